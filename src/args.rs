@@ -1,3 +1,4 @@
+use crate::util::io::ErrorFormat;
 use clap::{Parser, Subcommand};
 
 #[derive(Debug, Parser)]
@@ -12,19 +13,105 @@ pub struct FenvArgs {
     #[arg(long, global = true, action = clap::ArgAction::SetTrue)]
     pub info: bool,
 
+    /// Do not print any warning message, nor the error message of `fenv latest` on resolution
+    /// failure.
+    #[arg(short, long, global = true, action = clap::ArgAction::SetTrue)]
+    pub quiet: bool,
+
+    /// The format of warning and error messages. The JSON of a failed installation has `reason`
+    /// and `transient`, such as `"reason": "network", "transient": true`.
+    #[arg(long = "error-format", global = true, value_enum)]
+    pub error_format: Option<ErrorFormat>,
+
+    /// Forbid any network access. The remote Flutter SDKs are listed from the cache, and only
+    /// the release archives cached in `$FENV_ROOT/cache/downloads` are installed.
+    /// Same as `FENV_OFFLINE=1`.
+    #[arg(long, global = true, action = clap::ArgAction::SetTrue)]
+    pub offline: bool,
+
     #[command(subcommand)]
     pub command: FenvSubcommands,
 }
 
 #[derive(Debug, Subcommand)]
 pub enum FenvSubcommands {
+    /// Show, set, or delete the aliases of the version prefixes, such as `lts` of `3.19.6`.
+    /// An alias can be used wherever a version prefix is accepted, including the version files.
+    Alias(FenvAliasArgs),
+
+    /// Print the arguments to be prepended to the given tool, one argument per line.
+    /// Configured by `argsPrepend` of `$FENV_ROOT/config.json`. Used by the shims.
+    #[command(hide = true)]
+    ArgsPrepend(FenvArgsPrependArgs),
+
+    /// Show the records of the commands which changed the installed Flutter SDKs or the
+    /// selected versions, such as `install`, `uninstall`, and `global`, in `$FENV_ROOT/audit.log`.
+    #[command(subcommand)]
+    Audit(FenvAuditSubcommands),
+
+    /// Inspect and clean the caches in `$FENV_ROOT/cache`, such as the cached list of
+    /// the remote Flutter SDKs.
+    #[command(subcommand)]
+    Cache(FenvCacheSubcommands),
+
+    /// Fail if the given arguments of `flutter` are not allowed with the selected Flutter SDK,
+    /// such as `upgrade` with a specific version. Used by the `flutter` shim.
+    #[command(hide = true, alias = "__check-flutter-args")]
+    CheckFlutterArgs(FenvCheckFlutterArgsArgs),
+
+    /// Print the names of the installed Flutter SDKs, or the available ones with `--remote`,
+    /// one name per line. Used by the shell completion scripts.
+    #[command(hide = true, alias = "__complete-versions")]
+    CompleteVersions(FenvCompleteVersionsArgs),
+
+    /// Print the executables in `bin` of the selected Flutter SDK and `$PUB_CACHE/bin`,
+    /// one name per line. Used by the shell completion scripts for `exec --`.
+    #[command(hide = true, alias = "__complete-executables")]
+    CompleteExecutables,
+
+    /// Print the directories containing `pubspec.yaml` under the current directory, one path
+    /// per line. Used by the shell completion scripts for `workspace` and `setup`.
+    #[command(hide = true, alias = "__complete-workspaces")]
+    CompleteWorkspaces,
+
     /// Generate shell completion.
     Completions(FenvCompletionsArgs),
 
+    /// Show the selected Flutter SDK version and its source: `shell` (`$FENV_VERSION`),
+    /// `local` (`.flutter-version`), `asdf` (`.tool-versions`), or `global`.
+    Current(FenvCurrentArgs),
+
+    /// Show the directories and the environment variables which `fenv` resolved,
+    /// such as `$FENV_ROOT`, `$FENV_DIR`, and `$PUB_CACHE`.
+    Context(FenvContextArgs),
+
+    /// Print the environment variables of the selected Flutter SDK as shell code to be evaluated,
+    /// such as `eval "$(fenv env)"`: `FLUTTER_ROOT`, `DART_SDK`, `PUB_CACHE`, and `PATH`.
+    Env(FenvEnvArgs),
+
+    /// Run the given command with the selected Flutter SDK, such as `fenv exec -- flutter build`.
+    /// `bin` and `bin/cache/dart-sdk/bin` of the Flutter SDK are prepended to `PATH`.
+    Exec(FenvExecArgs),
+
+    /// Diagnose the environment and report the problems with their resolutions,
+    /// such as the shims missing on `PATH`, broken installations of the Flutter SDKs, and
+    /// the other Flutter version managers which conflict with `fenv`, and the IDE files of the
+    /// workspaces which refer to another Flutter SDK than their version files select.
+    /// Exits with 1 if any problem is found.
+    Doctor,
+
     /// Set the global Flutter version.
     /// The global version can be overridden by executing `fenv local`.
     Global(FenvGlobalArgs),
 
+    /// Show the help of a command, or the guide of a topic such as `version-files`, `shims`,
+    /// and `ci`. The guides are paged by `$PAGER` if stdout is a terminal.
+    Help(FenvHelpArgs),
+
+    /// Show the metadata of the latest installed Flutter SDK matching the given prefix:
+    /// the path, commit SHA, channel, Dart SDK version, disk usage, and install date.
+    Info(FenvInfoArgs),
+
     /// Help registering `fenv` to your `PATH` env. variable.
     Init(FenvInitArgs),
 
@@ -35,7 +122,7 @@ pub enum FenvSubcommands {
     Latest(FenvLatestArgs),
 
     /// List all installed Flutter SDKs. Alias of `versions` command.
-    List,
+    List(FenvVersionsArgs),
 
     /// Show the list of the available Flutter SDK versions.
     /// Alias of `install --list` command.
@@ -44,29 +131,99 @@ pub enum FenvSubcommands {
     /// Set the local Flutter version.
     Local(FenvLocalArgs),
 
+    /// Rename the installed Flutter SDKs which are named after legacy git tags such as
+    /// `v1.12.13+hotfix.8` to their canonical names such as `1.12.13+hotfix.8`.
+    /// Leaves a symbolic link at the legacy name and updates the version files which refer to it.
+    NormalizeNames(FenvNormalizeNamesArgs),
+
     /// Uninstall an installed Flutter SDK.
     Uninstall(FenvUninstallArgs),
 
+    /// Uninstall every installed Flutter SDK which is not referenced by the version files of
+    /// the given projects, the global version, or `$FENV_VERSION`.
+    Prune(FenvPruneArgs),
+
+    /// Verify that an installed Flutter SDK is complete and uncorrupted: `bin/flutter`,
+    /// `bin/dart`, the Dart SDK cache, no remaining installing marker, and the git HEAD at the
+    /// commit of its tag or branch. Exits with 1 if it is broken.
+    Verify(FenvVerifyArgs),
+
+    /// Fast-forward the installed channels such as `stable` to the latest commits of their
+    /// upstream branches by `git pull --ff-only`, and print the commits before and after.
+    Upgrade(FenvUpgradeArgs),
+
+    /// Rewrite the nearest version file with the exact version which its prefix currently
+    /// selects, such as `3.22.2` for `3`. A channel is pinned to the version it is at.
+    Pin(FenvPinArgs),
+
     /// Show the directory where the given flutter version is installed.
     Prefix(FenvPrefixArgs),
 
+    /// Set the Flutter version for the current shell session by `$FENV_VERSION`,
+    /// which takes precedence over the version files. Requires the shell function defined by
+    /// `fenv init -`, which evaluates the printed shell code.
+    Shell(FenvShellArgs),
+
+    /// Regenerate the `flutter` and `dart` shims in `$FENV_ROOT/shims`, and the shims of the other
+    /// executables of the installed Flutter SDKs such as `dartaotruntime`.
+    /// `install` and `uninstall` regenerate them too, and `exec` and `which` do if an executable
+    /// of the selected Flutter SDK has no shim.
+    Rehash,
+
+    /// Create a new Flutter project with the given Flutter SDK version: writes `.flutter-version`
+    /// into the new directory, installs the Flutter SDK if missing, runs its `flutter create`,
+    /// and generates the workspace files.
+    New(FenvNewArgs),
+
+    /// Set up a Flutter project in one go: resolves the Flutter SDK version from the local
+    /// version file or `pubspec.yaml`, installs it if missing, and generates the workspace files.
+    Setup(FenvSetupArgs),
+
+    /// Print a deterministic cache key of the currently selected Flutter SDK version.
+    /// The key consists of the version name and the commit SHA of the installed SDK,
+    /// such as `flutter-3.7.12-<sha>`, and can be used as a CI cache key or a Docker tag.
+    PrintCacheKey(FenvStartDirArgs),
+
+    /// Print the environment variables configured by `[env]` of the nearest `.fenv.toml`
+    /// as `KEY=VALUE`, one variable per line. Used by the shims.
+    #[command(hide = true)]
+    ProjectEnv,
+
+    /// Print the selected Flutter SDK version as parseable `key=value` lines:
+    /// `version`, `origin`, `installed`, and `path`. Never accesses the remote repository.
+    #[command(name = "__resolve-version", hide = true)]
+    ResolveVersion(FenvStartDirArgs),
+
+    /// Exit successfully only if the selected Flutter SDK is installed and the shims are placed.
+    /// Designed for readiness probes of containers such as Docker `HEALTHCHECK`.
+    Ready(FenvStartDirArgs),
+
     /// Show the fenv's root directory.
     /// Must be `$FENV_ROOT` or `$HOME/.fenv`.
     Root,
 
+    /// Manage the fenv itself.
+    #[command(name = "self", subcommand)]
+    SelfCommand(FenvSelfSubcommands),
+
+    /// Migrate from the other Flutter version managers.
+    #[command(subcommand)]
+    Migrate(FenvMigrateSubcommands),
+
     /// Show the name and the version file of the currently selected Flutter SDK version.
-    Version(FenvStartDirArgs),
+    Version(FenvVersionArgs),
 
     /// Show the file path of the nearest local version file or the global version file.
     VersionFile(FenvStartDirArgs),
 
     /// Show the name of the currently selected Flutter SDK version.
-    VersionName(FenvStartDirArgs),
+    VersionName(FenvVersionNameArgs),
 
     /// List all installed Flutter SDKs.
-    Versions,
+    Versions(FenvVersionsArgs),
 
-    /// Show the absolute path of the given command that is available is the current directory.
+    /// Show the absolute path of the given command that is available is the current directory:
+    /// in the selected Flutter SDK, `$PUB_CACHE/bin`, or the other directories on `PATH`.
     Which(FenvWhichArgs),
 
     /// Generates `.dart_tool/package_config.json` file and `.idea/libraries/Dart_SDK.xml` file
@@ -89,6 +246,70 @@ pub struct FenvInitArgs {
     pub path_mode: Option<String>,
 }
 
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvArgsPrependArgs {
+    /// The tool name such as `flutter` and `dart`.
+    pub tool: String,
+}
+
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvCheckFlutterArgsArgs {
+    /// The arguments which are given to `flutter`. Put them after `--`.
+    #[arg(allow_hyphen_values = true, trailing_var_arg = true)]
+    pub args: Vec<String>,
+}
+
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvCurrentArgs {
+    /// Print as a JSON object with `version`, `prefix`, `source`, `origin`, `installed`, and
+    /// `path` instead of human-readable text.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub json: bool,
+
+    #[command(flatten)]
+    pub start_dir: FenvStartDirOption,
+}
+
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvEnvArgs {
+    /// Specifies the shell type instead of detecting the running interactive shell.
+    #[arg(short, long, value_parser = ["bash", "zsh", "fish", "ksh"], conflicts_with = "json")]
+    pub shell: Option<String>,
+
+    /// Print as a JSON object instead of shell code. `PATH` is the list of the directories
+    /// to be prepended.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub json: bool,
+
+    #[command(flatten)]
+    pub start_dir: FenvStartDirOption,
+}
+
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvExecArgs {
+    #[command(flatten)]
+    pub start_dir: FenvStartDirOption,
+
+    /// Run the command in the given directory instead of the current directory. The nearest
+    /// version file is also found from it unless `--dir` is given.
+    #[arg(long, value_name = "DIR")]
+    pub cwd: Option<String>,
+
+    /// Set the environment variable of the command. Can be given multiple times, and is applied
+    /// after `--clean-env`.
+    #[arg(long = "env", value_name = "KEY=VALUE")]
+    pub envs: Vec<String>,
+
+    /// Run the command without the environment variables of `fenv` except `HOME` and `PATH`,
+    /// which is still prefixed with the directories of the Flutter SDK.
+    #[arg(long = "clean-env", action = clap::ArgAction::SetTrue)]
+    pub clean_env: bool,
+
+    /// The command to run and its arguments. Put them after `--`.
+    #[arg(required = true, allow_hyphen_values = true, trailing_var_arg = true)]
+    pub command: Vec<String>,
+}
+
 #[derive(Debug, clap::Args, Clone)]
 pub struct FenvInstallArgs {
     /// Show the all available Flutter SDK versions.
@@ -106,13 +327,89 @@ pub struct FenvInstallArgs {
     #[arg(name = "no-precache", long, action = clap::ArgAction::SetFalse)]
     pub should_precache: bool,
 
+    /// Download the artifacts of Android by `flutter precache`.
+    /// If any of `--precache-*` is given, the artifacts of the other platforms are not
+    /// downloaded, overriding `precachePlatforms` of `$FENV_ROOT/config.json`.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "no-precache")]
+    pub precache_android: bool,
+
+    /// Download the artifacts of iOS by `flutter precache`. See `--precache-android`.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "no-precache")]
+    pub precache_ios: bool,
+
+    /// Download the artifacts of the web by `flutter precache`. See `--precache-android`.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "no-precache")]
+    pub precache_web: bool,
+
+    /// Accept the all Android SDK licenses by `flutter doctor --android-licenses` after
+    /// installing, which is needed by the first Android build on the new CI machines.
+    /// Same as `acceptAndroidLicenses` of `$FENV_ROOT/config.json`. By default, disabled.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub accept_android_licenses: bool,
+
     /// If enabled, do not fail even if the specified sdk is already installed.
     /// If `--list` is given, will be ignored.
     /// By default, disabled.
     #[arg(name = "ignore-installed", long, action = clap::ArgAction::SetFalse)]
     pub fails_on_installed: bool,
 
-    /// A prefix of a version or a channel to install, such as `3`, `3.7`, `3.7.0`, `stable`, `beta`.
+    /// Remove the installed Flutter SDK matching the prefix, including an incomplete one, and
+    /// install it again from scratch. Same as `fenv uninstall` followed by `fenv install`.
+    /// By default, disabled.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "list")]
+    pub force: bool,
+
+    /// Include the pre-releases published only to the beta channel such as `3.23.0-0.1.pre`
+    /// when resolving the prefixes or listing the available versions. By default, disabled.
+    #[arg(long = "include-beta", action = clap::ArgAction::SetTrue)]
+    pub include_beta: bool,
+
+    /// Install up to this number of the given prefixes at once, prefixing their progress with
+    /// the prefix such as `[3.22] `. By default, 1.
+    #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub jobs: u32,
+
+    /// If given, find the nearest `.flutter-version` file in the given directory instead of
+    /// the current directory. Cannot be used with any prefix.
+    #[arg(long, conflicts_with = "prefixes")]
+    pub dir: Option<String>,
+
+    /// Install the Flutter SDK from the release archive downloaded beforehand, such as
+    /// `flutter_linux_3.22.2-stable.tar.xz`, without accessing the network. The only prefix is
+    /// the name to install it as, such as `3.22.2` and `stable`.
+    #[arg(long, value_name = "FILE", requires = "prefixes", conflicts_with_all = ["list", "jobs"])]
+    pub archive: Option<String>,
+
+    /// The SHA-256 of `--archive` listed by the release manifest such as `releases_linux.json`,
+    /// which the archive is verified against before it is extracted.
+    #[arg(long, value_name = "HEX", requires = "archive")]
+    pub sha256: Option<String>,
+
+    /// Install the latest version tagged in this git repository, such as a corporate fork or
+    /// mirror of Flutter, instead of the upstream one. It is installed as
+    /// `<NAMESPACE>-<VERSION>` such as `fork-3.19.6`, which is selected by the prefixes with
+    /// the namespace such as `fork-3.19` only.
+    #[arg(long = "git-url", value_name = "URL", requires = "prefixes", conflicts_with_all = ["list", "archive", "jobs"])]
+    pub git_url: Option<String>,
+
+    /// The namespace of the Flutter SDKs installed by `--git-url`, which consists of lowercase
+    /// letters, digits, and `_`. By default, `fork`.
+    #[arg(
+        long,
+        value_name = "NAME",
+        default_value = "fork",
+        requires = "git_url"
+    )]
+    pub namespace: String,
+
+    /// Cap the transfer rate of the release archives downloaded by `curl` in bytes per second,
+    /// such as `500K` or `10M`, overriding `download.limitRate` of `$FENV_ROOT/config.json`.
+    /// `git` is not throttled.
+    #[arg(long, value_name = "RATE")]
+    pub limit_rate: Option<String>,
+
+    /// A prefix of a version or a channel to install, such as `3`, `3.7`, `3.7.0`, `stable`, `beta`,
+    /// or the full SHA of a commit, which is installed under its first 10 characters.
     /// If omitted, attempts to install the version which is specified in the nearest `.flutter-version` file.
     /// Can be repeated.
     #[arg(action = clap::ArgAction::Append)]
@@ -121,9 +418,32 @@ pub struct FenvInstallArgs {
 
 #[derive(Debug, clap::Args, Clone)]
 pub struct FenvListRemoteArgs {
-    /// If set, do not mark installed Flutter SDK versions on the version list.
+    /// If set, do not mark the selected and installed Flutter SDK versions on the version list.
     #[arg(long, action = clap::ArgAction::SetTrue)]
     pub bare: bool,
+
+    /// Append the full commit SHA of each version after a tab, so that the build systems can
+    /// key their caches by the framework revision. Only with `--bare`.
+    #[arg(long = "with-sha", action = clap::ArgAction::SetTrue, requires = "bare")]
+    pub with_sha: bool,
+
+    /// Include the pre-releases published only to the beta channel such as `3.23.0-0.1.pre`.
+    /// By default, disabled.
+    #[arg(long = "include-beta", action = clap::ArgAction::SetTrue)]
+    pub include_beta: bool,
+
+    /// Print as a JSON array of objects with `name`, `sha`, `kind` (`tag` or `branch`), `ref`,
+    /// and `installed`, and with `channel`, `dart_sdk_version`, and `release_date` if listed by
+    /// the release manifest.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "bare")]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvCompleteVersionsArgs {
+    /// Print the available Flutter SDKs instead of the installed ones.
+    #[arg(short, long, action = clap::ArgAction::SetTrue)]
+    pub remote: bool,
 }
 
 #[derive(Debug, clap::Args, Clone)]
@@ -133,6 +453,41 @@ pub struct FenvCompletionsArgs {
     pub shell: String,
 }
 
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvContextArgs {
+    /// Print as a JSON object instead of human-readable text.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvVersionsArgs {
+    /// Print as tab-separated lines for scripts: `name`, `kind` (`channel`, `release`,
+    /// or `commit`),
+    /// `path`, `installed_at` (RFC 3339), and `dart_version`. The first line is the format version
+    /// `#fenv-versions/v1`, followed by the header line. Missing values are printed as `-`.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub machine: bool,
+
+    /// Print as a JSON array of objects with `name`, `kind` (`version`, `channel`, or
+    /// `commit`), `path`, and
+    /// `is_active`, which tells whether the Flutter SDK is the selected one.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "machine")]
+    pub json: bool,
+
+    /// Group the versions by their minor versions such as `3.22`, and mark the selected Flutter
+    /// SDK with `*`. The channels and the commits follow the groups.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with_all = ["machine", "json"])]
+    pub tree: bool,
+}
+
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvHelpArgs {
+    /// A command such as `install`, or a topic such as `version-files`.
+    /// If omitted, shows the help of `fenv`.
+    pub topic: Option<String>,
+}
+
 #[derive(Debug, clap::Args, Clone)]
 pub struct FenvGlobalArgs {
     /// A prefix of a specific version or a channel. For example, `3.7`, `3.0.0`, `stable`, `s` are valid.
@@ -141,10 +496,50 @@ pub struct FenvGlobalArgs {
 }
 
 #[derive(Debug, clap::Args, Clone)]
+pub struct FenvVersionArgs {
+    /// Print only the version written in the nearest version file.
+    /// Neither resolves it to an installed version nor accesses the remote repository.
+    #[arg(long = "version-only", action = clap::ArgAction::SetTrue)]
+    pub version_only: bool,
+
+    /// Print as a JSON object with `version`, `prefix`, `version_file`, and `is_global` instead
+    /// of human-readable text. `version_file` is `null` if `$FENV_VERSION` selects the version.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "version_only")]
+    pub json: bool,
+
+    #[command(flatten)]
+    pub start_dir: FenvStartDirArgs,
+}
+
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvVersionNameArgs {
+    /// Print as a JSON object with `version`, `prefix`, `version_file`, and `is_global` instead
+    /// of the version name only. `version_file` is `null` if `$FENV_VERSION` selects the version.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub json: bool,
+
+    #[command(flatten)]
+    pub start_dir: FenvStartDirArgs,
+}
+
+#[derive(Debug, clap::Args, Clone, Default)]
 pub struct FenvStartDirArgs {
     /// If given, find the nearest version file in the given directory.
     /// Otherwise, find the nearest version file in the current directory.
+    #[arg(value_name = "DIR", conflicts_with = "start_dir")]
     pub dir: Option<String>,
+
+    #[command(flatten)]
+    pub option: FenvStartDirOption,
+}
+
+/// The `--dir` option shared by the commands which read the nearest version file.
+#[derive(Debug, clap::Args, Clone, Default)]
+pub struct FenvStartDirOption {
+    /// If given, find the nearest version file in the given directory instead of
+    /// the current directory. A relative path is resolved from the current directory.
+    #[arg(long = "dir", value_name = "DIR")]
+    pub start_dir: Option<String>,
 }
 
 #[derive(Debug, clap::Args, Clone)]
@@ -159,12 +554,46 @@ pub struct FenvLatestArgs {
     #[deprecated(note = "Use --remote instead")]
     pub known: bool,
 
-    /// Do not print an error message on resolution failure.
-    #[arg(short, long, action = clap::ArgAction::SetTrue)]
-    pub quiet: bool,
+    /// Exit with a non-zero code on resolution failure even if `--quiet` is given,
+    /// so that scripts can branch on the availability without parsing stderr.
+    #[arg(long = "exit-code", action = clap::ArgAction::SetTrue)]
+    pub exit_code: bool,
+
+    /// Include the pre-releases published only to the beta channel such as `3.23.0-0.1.pre`.
+    /// Only effective with `--remote`. By default, disabled.
+    #[arg(long = "include-beta", action = clap::ArgAction::SetTrue)]
+    pub include_beta: bool,
+
+    /// If the remote repository is unreachable, answer from the cached list of the available
+    /// versions even if it is expired, noting its age on stderr. Only effective with `--remote`.
+    #[arg(long = "allow-stale", action = clap::ArgAction::SetTrue)]
+    pub allow_stale: bool,
+
+    /// Print a JSON object from each prefix to its latest version, or `null` if not found,
+    /// instead of failing on the first prefix which is not resolved.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "quiet")]
+    pub json: bool,
 
     /// A prefix of a specific version or a channel. For example, `3.7`, `3.0.0`, `stable`, `s` are valid.
-    pub prefix: String,
+    /// Can be repeated to print the latest versions line by line in the given order.
+    #[arg(action = clap::ArgAction::Append, required = true)]
+    pub prefixes: Vec<String>,
+}
+
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvAliasArgs {
+    /// The name of the alias. If omitted, lists all aliases.
+    #[arg(required_if_eq("delete", "true"))]
+    pub name: Option<String>,
+
+    /// A prefix of a specific version or a channel which the alias refers to.
+    /// If omitted, shows the current target of the alias.
+    #[arg(conflicts_with = "delete")]
+    pub prefix: Option<String>,
+
+    /// Delete the alias.
+    #[arg(short, long, action = clap::ArgAction::SetTrue)]
+    pub delete: bool,
 }
 
 #[derive(Debug, clap::Args, Clone)]
@@ -176,27 +605,229 @@ pub struct FenvLocalArgs {
     /// [deprecated] No longer install a symbol link to the Flutter SDK.
     #[arg(short, long, action = clap::ArgAction::SetTrue)]
     pub symlink: bool,
+
+    #[command(flatten)]
+    pub start_dir: FenvStartDirOption,
+}
+
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvNormalizeNamesArgs {
+    /// If enabled, do not rename the installed Flutter SDKs but create symbolic links
+    /// named after their canonical names.
+    #[arg(long = "link-only", action = clap::ArgAction::SetTrue)]
+    pub link_only: bool,
+}
+
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvVerifyArgs {
+    /// A prefix of a version or a channel to verify, such as `3.7`, `3.7.0`, `stable`.
+    pub prefix: String,
 }
 
 #[derive(Debug, clap::Args, Clone)]
 pub struct FenvUninstallArgs {
+    /// Uninstall every installation which was not completed or does not have `bin/flutter`,
+    /// after waiting for the ones being installed by another `fenv`.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub broken: bool,
+
+    /// Uninstall every installed Flutter SDK, and print the reclaimed disk space.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "prefixes")]
+    pub all: bool,
+
+    /// Same as `--all`, but keep every installed Flutter SDK matching the given prefix, such as
+    /// `3.22` and `stable`. Can be specified multiple times.
+    #[arg(
+        long = "all-except",
+        value_name = "PREFIX",
+        action = clap::ArgAction::Append,
+        conflicts_with_all = ["all", "prefixes"],
+    )]
+    pub all_except: Vec<String>,
+
     /// A prefix of a version or a channel to uninstall, such as `3`, `3.7`, `3.7.0`, `stable`, `beta`.
-    /// Must be specified once or more.
-    #[arg(action = clap::ArgAction::Append)]
+    /// A path to a directory in `$FENV_ROOT/versions`, such as `~/.fenv/versions/foo`, is also
+    /// accepted to remove an installation whose name is not a valid version.
+    /// Must be specified once or more unless `--broken`, `--all`, or `--all-except` is given.
+    #[arg(
+        action = clap::ArgAction::Append,
+        required_unless_present_any = ["broken", "all", "all_except"],
+    )]
     pub prefixes: Vec<String>,
 }
 
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvPruneArgs {
+    /// The root directories of the projects to scan for `.flutter-version` and `.tool-versions`.
+    /// If omitted, scans the workspaces which `fenv workspace` has set up.
+    #[arg(action = clap::ArgAction::Append)]
+    pub projects: Vec<String>,
+
+    /// Print the Flutter SDKs to be uninstalled without uninstalling them.
+    #[arg(long = "dry-run", action = clap::ArgAction::SetTrue)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvUpgradeArgs {
+    /// A prefix of an installed channel to upgrade, such as `stable`, `beta`, `s`.
+    /// If omitted, upgrades every installed channel.
+    #[arg(action = clap::ArgAction::Append)]
+    pub channels: Vec<String>,
+
+    /// Run `flutter precache` after the channel is upgraded.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub precache: bool,
+}
+
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvInfoArgs {
+    /// A prefix of a specific version or a channel. For example, `3.7`, `3.0.0`, `stable`, `s` are valid.
+    pub prefix: String,
+
+    /// Print as a JSON object instead of human-readable text.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvPinArgs {
+    #[command(flatten)]
+    pub start_dir: FenvStartDirOption,
+}
+
 #[derive(Debug, clap::Args, Clone)]
 pub struct FenvPrefixArgs {
     /// A prefix of a specific version or a channel. For example, `3.7`, `3.0.0`, `stable`, `s` are valid.
     /// If omitted, uses the current version.
+    #[arg(conflicts_with = "start_dir")]
+    pub prefix: Option<String>,
+
+    /// Fail with the exit code 3 if the installation is incomplete or does not have `bin/flutter`.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub validate: bool,
+
+    #[command(flatten)]
+    pub start_dir: FenvStartDirOption,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+pub enum FenvAuditSubcommands {
+    /// Show the records of `$FENV_ROOT/audit.log`: the time, the user, the result, and the
+    /// command line, the oldest first.
+    Show(FenvAuditShowArgs),
+}
+
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvAuditShowArgs {
+    /// Show only the latest N records.
+    #[arg(short = 'n', long, value_name = "N")]
+    pub limit: Option<usize>,
+
+    /// Print the records as they are recorded, one JSON object per line with `timestamp`,
+    /// `command`, `version`, `user`, `result`, and `error`.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub json: bool,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+pub enum FenvCacheSubcommands {
+    /// Show the size and the age of each entry in `$FENV_ROOT/cache`.
+    Info,
+
+    /// Remove the entries in `$FENV_ROOT/cache`.
+    Clear(FenvCacheClearArgs),
+}
+
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvCacheClearArgs {
+    /// Remove only the cached list of the remote Flutter SDKs, `$FENV_ROOT/cache/.remote_list`.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub remote_list_only: bool,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+pub enum FenvSelfSubcommands {
+    /// Replace the fenv executable with the latest release on GitHub for the current
+    /// operating system and CPU architecture, after verifying its SHA-256 checksum.
+    Update(FenvSelfUpdateArgs),
+}
+
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvSelfUpdateArgs {
+    /// Only show whether a newer release is available. Exits with 1 if available.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub check: bool,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+pub enum FenvMigrateSubcommands {
+    /// Convert the Flutter version pinned by FVM in `.fvmrc` or `.fvm/fvm_config.json`
+    /// into `.flutter-version`, and optionally copy the Flutter SDKs downloaded by FVM.
+    Fvm(FenvMigrateFvmArgs),
+}
+
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvMigrateFvmArgs {
+    /// The project directory which FVM manages. The current directory if omitted.
+    pub project: Option<String>,
+
+    /// Copy the Flutter SDKs in the FVM cache directory into `$FENV_ROOT/versions`
+    /// unless they are already installed, so that they are not downloaded again.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub copy_sdks: bool,
+
+    /// The cache directory of FVM, which has the `versions` directory.
+    /// `$FVM_CACHE_PATH`, or `$HOME/fvm` if not set, if omitted.
+    #[arg(long)]
+    pub fvm_cache: Option<String>,
+}
+
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvShellArgs {
+    /// A prefix of a specific version or a channel. For example, `3.7`, `3.0.0`, `stable`, `s` are valid.
+    /// Must be installed.
+    #[arg(required_unless_present = "unset", conflicts_with = "unset")]
     pub prefix: Option<String>,
+
+    /// Clear the Flutter version of the current shell session.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub unset: bool,
+
+    /// Specifies the shell type instead of detecting the running interactive shell.
+    #[arg(short, long, value_parser = ["bash", "zsh", "fish", "ksh"])]
+    pub shell: Option<String>,
 }
 
 #[derive(Debug, clap::Args, Clone)]
 pub struct FenvWhichArgs {
     /// The executable name to find where. For example, `flutter`, `dart`, `melos` etc.
     pub executable: String,
+
+    /// Do not fail even if the installation of the selected Flutter SDK is incomplete or does not
+    /// have `bin/flutter`. By default, fails with the exit code 3.
+    #[arg(name = "no-validate", long, action = clap::ArgAction::SetFalse)]
+    pub validate: bool,
+
+    /// Print the path to the shim in `$FENV_ROOT/shims` instead of the executable of the selected
+    /// Flutter SDK, which stays valid across the version switches. Fails if the shim is missing
+    /// or outdated. Falls back to the executable itself if it has no shim, such as `melos`.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub shim: bool,
+
+    /// Print every installed Flutter SDK which provides the executable with its full path,
+    /// instead of the one of the selected Flutter SDK.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "shim")]
+    pub all: bool,
+
+    /// Print as a JSON object with `path`, `origin`, `version`, and `executable` instead of the
+    /// path only. `origin` is `sdk`, `pub-cache`, or `system`, and `version` is the name of the
+    /// selected Flutter SDK if `origin` is `sdk`, or `null` otherwise.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "all")]
+    pub json: bool,
+
+    #[command(flatten)]
+    pub start_dir: FenvStartDirOption,
 }
 
 #[derive(Debug, clap::Args, Clone, PartialEq, Eq)]
@@ -215,7 +846,71 @@ pub struct FenvWorkspaceArgs {
     pub should_pub_get: bool,
 
     /// Re-generate `.dart_tool/package_config.json` and `.idea/libraries/Dart_SDK.xml`
-    /// if they are not needed to re-generate. By default, disabled.
+    /// if they are not needed to re-generate, except the files modified since `fenv` generated
+    /// them. By default, disabled.
     #[arg(short = 'f', long = "force", action = clap::ArgAction::SetTrue)]
     pub force: bool,
+
+    /// Writes the path to the selected Flutter SDK into the given plain file,
+    /// which can be tracked by external build systems such as Bazel and Make.
+    /// The file is re-written only if the selected Flutter SDK is changed.
+    #[arg(long = "emit-path-file", value_name = "FILE")]
+    pub emit_path_file: Option<String>,
+
+    /// Remove the files which `fenv workspace` generated, as listed in
+    /// `.fenv/workspace-manifest.json`. The files modified since the generation are left.
+    #[arg(
+        long,
+        action = clap::ArgAction::SetTrue,
+        conflicts_with_all = ["prefix", "should_pub_get", "force", "emit_path_file"]
+    )]
+    pub clean: bool,
+}
+
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvSetupArgs {
+    /// The path to the Flutter project directory, which must contain `pubspec.yaml` file.
+    pub project: String,
+
+    /// Executes `flutter pub get` to generate `.dart_tool/package_config.json` file.
+    /// If set, the minimum `.dart_tool/package_config.json` file is generated. By default, disabled.
+    #[arg(short = 'g', long = "pub-get", action = clap::ArgAction::SetTrue)]
+    pub should_pub_get: bool,
+
+    /// If enabled, do not execute `flutter precache` command after downloading Flutter SDK.
+    /// By default, disabled.
+    #[arg(name = "no-precache", long, action = clap::ArgAction::SetFalse)]
+    pub should_precache: bool,
+}
+
+#[derive(Debug, clap::Args, Clone)]
+pub struct FenvNewArgs {
+    /// The path to the directory of the new Flutter project, which must not exist or be empty.
+    pub dir: String,
+
+    /// The prefix of the Flutter SDK version which the new project uses, such as `3.22`.
+    /// The latest installed version matching it is used, or the latest available one is
+    /// installed if none is installed.
+    #[arg(long = "version", value_name = "PREFIX")]
+    pub prefix: String,
+
+    /// If enabled, only write `.flutter-version` without running `flutter create` nor
+    /// generating the workspace files. By default, disabled.
+    #[arg(name = "no-create", long, action = clap::ArgAction::SetFalse)]
+    pub should_create: bool,
+
+    /// Executes `flutter pub get` to generate `.dart_tool/package_config.json` file.
+    /// If set, the minimum `.dart_tool/package_config.json` file is generated. By default, disabled.
+    #[arg(short = 'g', long = "pub-get", action = clap::ArgAction::SetTrue)]
+    pub should_pub_get: bool,
+
+    /// If enabled, do not execute `flutter precache` command after downloading Flutter SDK.
+    /// By default, disabled.
+    #[arg(name = "no-precache", long, action = clap::ArgAction::SetFalse)]
+    pub should_precache: bool,
+
+    /// The arguments passed to `flutter create` as they are, given after `--`,
+    /// such as `--org com.example --platforms ios,android`.
+    #[arg(last = true, conflicts_with = "no-create")]
+    pub create_args: Vec<String>,
 }
@@ -0,0 +1,120 @@
+use crate::{
+    args::{FenvMigrateSubcommands, FenvSelfSubcommands, FenvSubcommands},
+    context::FenvContext,
+    util::{
+        chrono_wrapper::{Clock, SystemClock},
+        path_like::PathLike,
+    },
+};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+/// A record of a command which changed the installed Flutter SDKs or the selected versions,
+/// written into `{fenv_root}/audit.log` as a line of JSON.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AuditRecord {
+    /// When the command finished in RFC 3339.
+    pub timestamp: String,
+
+    /// The command line such as `fenv install 3.22`.
+    pub command: String,
+
+    /// The version prefixes given to the command, such as `3.22`, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    /// The user who ran the command, or `unknown` if `$USER` is not set.
+    pub user: String,
+
+    /// `ok`, or `error` if the command failed.
+    pub result: String,
+
+    /// The message of the failure.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The file where the audit records are appended, one record per line.
+///
+/// `{fenv_root}/audit.log`.
+pub fn audit_log_file(context: &impl FenvContext) -> PathLike {
+    context.fenv_root().join("audit.log")
+}
+
+/// The version prefixes given to `command` if it changes the installed Flutter SDKs or the
+/// selected versions, or `None` if it only reads them.
+fn audited_versions(command: &FenvSubcommands) -> Option<Vec<String>> {
+    match command {
+        FenvSubcommands::Install(args) if !args.list => Some(args.prefixes.clone()),
+        FenvSubcommands::Uninstall(args) => {
+            Some([&args.prefixes[..], &args.all_except[..]].concat())
+        }
+        FenvSubcommands::Prune(args) if !args.dry_run => Some(vec![]),
+        FenvSubcommands::Upgrade(args) => Some(args.channels.clone()),
+        // Both install the Flutter SDK if missing, and `new` writes `.flutter-version`.
+        FenvSubcommands::Setup(_) => Some(vec![]),
+        FenvSubcommands::New(args) => Some(vec![args.prefix.clone()]),
+        FenvSubcommands::Global(args) => args.prefix.clone().map(|prefix| vec![prefix]),
+        FenvSubcommands::Local(args) => args.prefix.clone().map(|prefix| vec![prefix]),
+        FenvSubcommands::Alias(args) if args.prefix.is_some() || args.delete => {
+            Some(args.prefix.iter().cloned().collect())
+        }
+        FenvSubcommands::Pin(_) | FenvSubcommands::NormalizeNames(_) => Some(vec![]),
+        FenvSubcommands::Migrate(FenvMigrateSubcommands::Fvm(_)) => Some(vec![]),
+        FenvSubcommands::SelfCommand(FenvSelfSubcommands::Update(args)) if !args.check => {
+            Some(vec![])
+        }
+        _ => None,
+    }
+}
+
+/// Appends the record of `command` with its `result` to `{fenv_root}/audit.log` if it changes
+/// the installed Flutter SDKs or the selected versions, so that the changes of the toolchains on
+/// the shared build machines can be traced.
+pub fn record<T>(
+    context: &impl FenvContext,
+    command: &FenvSubcommands,
+    command_line: &str,
+    result: &anyhow::Result<T>,
+) -> anyhow::Result<()> {
+    let Some(versions) = audited_versions(command) else {
+        return anyhow::Ok(());
+    };
+    let record = AuditRecord {
+        timestamp: SystemClock::new().utc_now().to_rfc3339(),
+        command: command_line.to_string(),
+        version: Some(versions.join(" ")).filter(|versions| !versions.is_empty()),
+        user: context.user().unwrap_or_else(|| String::from("unknown")),
+        result: String::from(if result.is_ok() { "ok" } else { "error" }),
+        error: result.as_ref().err().map(|e| e.to_string()),
+    };
+    let file = audit_log_file(context);
+    let mut line = serde_json::to_string(&record)?;
+    line.push('\n');
+    // A single write of the whole line keeps the records of the concurrent commands apart.
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&file)
+        .and_then(|mut log| log.write_all(line.as_bytes()))
+        .with_context(|| format!("Failed to write the audit record: `{file}`"))
+}
+
+/// Reads the records of `{fenv_root}/audit.log` in the order they were appended, skipping the
+/// lines which cannot be parsed.
+pub fn read_records(context: &impl FenvContext) -> anyhow::Result<Vec<AuditRecord>> {
+    let file = audit_log_file(context);
+    if !file.is_file() {
+        return anyhow::Ok(vec![]);
+    }
+    let content = file
+        .read_to_string()
+        .with_context(|| format!("Failed to read `{file}`"))?;
+    anyhow::Ok(
+        content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect(),
+    )
+}
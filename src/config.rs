@@ -0,0 +1,557 @@
+use crate::{
+    context::FenvContext,
+    external::{downloader::host_of, flutter_command::PrecachePlatform},
+    sdk_service::release_manifest::DEFAULT_STORAGE_BASE_URL,
+    util::path_like::PathLike,
+};
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A definition of format of `{fenv_root}/config.json` file.
+///
+/// Every field is optional, so an empty JSON object `{}` is a valid configuration.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FenvConfig {
+    /// Arguments which are prepended to every invocation of the given tool
+    /// through the shims. For example, `{"flutter": ["--suppress-analytics"]}`.
+    #[serde(default)]
+    pub args_prepend: BTreeMap<String, Vec<String>>,
+
+    /// How to name the directory where a tagged Flutter SDK is installed.
+    #[serde(default)]
+    pub install_name_policy: InstallNamePolicy,
+
+    /// The pub packages to be activated globally right after a Flutter SDK is installed.
+    /// A version constraint may follow the package name, such as `"melos ^3.0.0"`.
+    #[serde(default)]
+    pub pub_global_activate: Vec<String>,
+
+    /// The platforms whose artifacts `flutter precache` downloads after a Flutter SDK is
+    /// installed, such as `["android"]` for the CI images building Android apps only.
+    /// `--precache-android`, `--precache-ios`, and `--precache-web` of `fenv install` take
+    /// precedence over this. If empty, the default platforms of `flutter` are downloaded.
+    #[serde(default)]
+    pub precache_platforms: Vec<PrecachePlatform>,
+
+    /// If `true`, the Android SDK licenses are accepted by `flutter doctor --android-licenses`
+    /// after a Flutter SDK is installed, as `--accept-android-licenses` of `fenv install` does.
+    #[serde(default)]
+    pub accept_android_licenses: bool,
+
+    /// Where to emit the metrics. Only effective if `fenv` is built with `metrics` feature.
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+
+    /// How to access the remote repository over TLS.
+    #[serde(default)]
+    pub tls: TlsConfig,
+
+    /// Which proxy to access the remote repository and the downloads through.
+    #[serde(default)]
+    pub proxy: ProxyConfig,
+
+    /// How to download the release archives and the releases of `fenv`.
+    #[serde(default)]
+    pub download: DownloadConfig,
+
+    /// How to retry the downloads and `git ls-remote` failed by the network.
+    #[serde(default)]
+    pub retry: RetryConfig,
+
+    /// Which kind of the shims `fenv rehash` places in `{fenv_shims}`.
+    #[serde(default)]
+    pub shim_kind: ShimKind,
+}
+
+/// A configuration of TLS to access the remote repository through a TLS-intercepting proxy.
+///
+/// `$FENV_CA_BUNDLE` and `$FENV_INSECURE_SKIP_TLS_VERIFY` take precedence over this.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TlsConfig {
+    /// The CA bundle file to verify the server certificate instead of the system's one.
+    /// Relative to `$FENV_ROOT` unless absolute.
+    pub ca_bundle: Option<String>,
+
+    /// If `true`, skips the verification of the server certificate. Insecure.
+    #[serde(default)]
+    pub insecure_skip_verify: bool,
+}
+
+/// A configuration of the proxy for the networks where `$HTTPS_PROXY` cannot be set, such as
+/// the services started without the login environment.
+///
+/// `$HTTPS_PROXY` (or `$HTTP_PROXY`) and `$NO_PROXY` take precedence over this.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyConfig {
+    /// The URL of the proxy such as `http://proxy.example.com:8080`.
+    pub url: Option<String>,
+
+    /// The comma-separated hosts accessed without the proxy, such as `localhost,.example.com`.
+    pub no_proxy: Option<String>,
+}
+
+/// A configuration of the backend which downloads the remote resources.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadConfig {
+    /// The command which downloads `{url}` into `{destination}` instead of `curl`, such as
+    /// `["artifact-fetch", "--out", "{destination}", "{url}"]` for an internal artifact service.
+    /// `tls` and `proxy` are not applied to the command.
+    pub command: Option<Vec<String>>,
+
+    /// The hosts trusted to serve the Flutter SDKs besides `storage.googleapis.com` and the host
+    /// of `storage-base-url` of `.fenv.toml`, such as the CDN which the mirror redirects to.
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+
+    /// The maximum transfer rate of `curl` in bytes per second, such as `10M`, so that
+    /// `fenv install` does not saturate a shared network. Not applied to `command`.
+    pub limit_rate: Option<String>,
+}
+
+/// A configuration of the retries of the network operations.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryConfig {
+    /// The number of the attempts including the first one. 3 if omitted, and 1 disables retrying.
+    pub attempts: Option<u32>,
+
+    /// The milliseconds to wait before the first retry, which is doubled after every retry.
+    /// 1000 if omitted.
+    pub backoff_millis: Option<u64>,
+}
+
+/// A configuration of the opt-in metrics sink.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsConfig {
+    /// The `host:port` of the statsd server which receives the metrics over UDP.
+    /// If omitted, no metrics are emitted.
+    pub statsd_endpoint: Option<String>,
+
+    /// The prefix of the metric names. `fenv` if omitted.
+    pub prefix: Option<String>,
+}
+
+/// A kind of the shims placed in `{fenv_shims}`.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum ShimKind {
+    /// The bash scripts which run `fenv` to resolve the Flutter SDK.
+    #[default]
+    Script,
+
+    /// The hardlinks to the copy of the `fenv` binary, which resolve the Flutter SDK in-process.
+    /// They need no POSIX shell, such as in the minimal containers and the IDEs on Windows.
+    Native,
+}
+
+/// A naming policy of the directories where tagged Flutter SDKs are installed.
+///
+/// Channels are always installed as their channel names.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum InstallNamePolicy {
+    /// Uses the git tag as it is. For example, `v1.12.13+hotfix.4`.
+    #[default]
+    AsIs,
+
+    /// Strips the leading `v` of the git tag. For example, `1.12.13+hotfix.4`.
+    StripV,
+
+    /// Uses the canonical form of the version such as `1.12.13` and `1.4.5+hotfix.2`
+    /// regardless of the format of the git tag.
+    Canonical,
+}
+
+impl FenvConfig {
+    /// Loads `{fenv_root}/config.json`.
+    ///
+    /// If the file does not exist, returns the default configuration.
+    pub fn load(context: &impl FenvContext) -> anyhow::Result<Self> {
+        let config_file = context.fenv_config_file();
+        if !config_file.is_file() {
+            return anyhow::Ok(Self::default());
+        }
+        let content = config_file
+            .read_to_string()
+            .with_context(|| format!("Failed to read the config file: `{config_file}`"))?;
+        Self::parse(&content)
+            .with_context(|| format!("Failed to parse the config file: `{config_file}`"))
+    }
+
+    pub fn parse(raw_json: &str) -> anyhow::Result<Self> {
+        serde_json::from_str(raw_json).map_err(|e| anyhow::anyhow!(e))
+    }
+
+    /// The arguments to be prepended to the given `tool` such as `flutter` and `dart`.
+    pub fn args_prepend_of(&self, tool: &str) -> &[String] {
+        self.args_prepend
+            .get(tool)
+            .map(|args| &args[..])
+            .unwrap_or_default()
+    }
+}
+
+/// The name of the configuration file of a project.
+pub const PROJECT_CONFIG_FILE_NAME: &str = ".fenv.toml";
+
+/// A definition of format of `.fenv.toml` file of a project, which applies to the commands
+/// running in the directory where the file is and its subdirectories.
+///
+/// ```toml
+/// [flutter]
+/// git-url = "https://git.example.com/mobile/flutter.git"
+/// storage-base-url = "https://flutter-storage.example.com"
+///
+/// [env]
+/// PUB_HOSTED_URL = "https://pub.example.com"
+/// ```
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct FenvProjectConfig {
+    /// Where the Flutter SDKs of the project come from.
+    #[serde(default)]
+    pub flutter: FlutterSourceConfig,
+
+    /// The environment variables of the commands run by `fenv exec` and the shims in the
+    /// project, which take precedence over the inherited ones.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+}
+
+/// A configuration of the source of the Flutter SDKs, such as a company fork of Flutter.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct FlutterSourceConfig {
+    /// The git repository to install the Flutter SDKs from instead of the upstream one.
+    /// `$FLUTTER_GIT_URL` if omitted.
+    pub git_url: Option<String>,
+
+    /// The mirror of the release archives and the Flutter artifacts, which is given to `flutter`
+    /// as `$FLUTTER_STORAGE_BASE_URL` while installing the Flutter SDKs.
+    /// `$FLUTTER_STORAGE_BASE_URL` if omitted, whose host must be trusted by
+    /// `download.allowedHosts` of `{fenv_root}/config.json`.
+    pub storage_base_url: Option<String>,
+}
+
+impl FenvProjectConfig {
+    /// Loads the nearest `.fenv.toml` from `{fenv_dir}` to the root directory.
+    ///
+    /// If no such file exists, returns the default configuration. `$FLUTTER_GIT_URL` and
+    /// `$FLUTTER_STORAGE_BASE_URL` fill the sources which the file does not give, so that a
+    /// mirror applies to every project.
+    pub fn load(context: &impl FenvContext) -> anyhow::Result<Self> {
+        Self::load_from(context, &context.fenv_dir())
+    }
+
+    /// Same as [`FenvProjectConfig::load`], but from `start_dir` instead of `{fenv_dir}`.
+    pub fn load_from(context: &impl FenvContext, start_dir: &PathLike) -> anyhow::Result<Self> {
+        let mut config = match find_nearest_project_config_file(start_dir) {
+            Some(config_file) => {
+                let content = config_file
+                    .read_to_string()
+                    .with_context(|| format!("Failed to read the config file: `{config_file}`"))?;
+                Self::parse(&content)
+                    .with_context(|| format!("Failed to parse the config file: `{config_file}`"))?
+            }
+            None => Self::default(),
+        };
+        let flutter = &mut config.flutter;
+        flutter.git_url = flutter.git_url.take().or_else(|| context.flutter_git_url());
+        if flutter.storage_base_url.is_none() {
+            flutter.storage_base_url = context
+                .flutter_storage_base_url()
+                .map(|url| verify_storage_base_url_of_env(context, url))
+                .transpose()?;
+        }
+        anyhow::Ok(config)
+    }
+
+    pub fn parse(raw_toml: &str) -> anyhow::Result<Self> {
+        toml::from_str(raw_toml).map_err(|e| anyhow::anyhow!(e))
+    }
+}
+
+/// Fails unless the host of `$FLUTTER_STORAGE_BASE_URL` is `storage.googleapis.com` or one of
+/// `download.allowedHosts` of `{fenv_root}/config.json`, because the release archives are
+/// downloaded only from the trusted hosts, which an environment variable must not extend.
+fn verify_storage_base_url_of_env(
+    context: &impl FenvContext,
+    url: String,
+) -> anyhow::Result<String> {
+    let Some(host) = host_of(&url) else {
+        bail!("`$FLUTTER_STORAGE_BASE_URL` does not have the host: `{url}`")
+    };
+    let is_trusted = host_of(DEFAULT_STORAGE_BASE_URL).as_ref() == Some(&host)
+        || FenvConfig::load(context)?
+            .download
+            .allowed_hosts
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(&host));
+    if !is_trusted {
+        bail!(
+            "`$FLUTTER_STORAGE_BASE_URL` names `{host}`, which is not trusted to serve the \
+            Flutter SDKs: add it to `download.allowedHosts` of `{}`, or give \
+            `storage-base-url` in `.fenv.toml`",
+            context.fenv_config_file()
+        )
+    }
+    anyhow::Ok(url)
+}
+
+fn find_nearest_project_config_file(start_dir: &PathLike) -> Option<PathLike> {
+    let mut current = Some(start_dir.clone());
+    while let Some(dir) = current {
+        let config_file = dir.join(PROJECT_CONFIG_FILE_NAME);
+        if config_file.is_file() {
+            return Some(config_file);
+        }
+        current = dir.parent();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        FenvConfig, FenvProjectConfig, FlutterSourceConfig, InstallNamePolicy, MetricsConfig,
+        TlsConfig,
+    };
+    use crate::{
+        context::{FenvContext, RealFenvContext},
+        external::flutter_command::PrecachePlatform,
+        service::macros::test_with_context,
+    };
+    use indoc::indoc;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_parse_empty_object() {
+        assert_eq!(FenvConfig::parse("{}").unwrap(), FenvConfig::default());
+    }
+
+    #[test]
+    fn test_parse_args_prepend() {
+        let config = FenvConfig::parse(indoc! {r#"
+            {
+              "argsPrepend": {
+                "flutter": ["--suppress-analytics"],
+                "dart": ["--disable-analytics"]
+              }
+            }
+        "#})
+        .unwrap();
+        assert_eq!(config.args_prepend_of("flutter"), ["--suppress-analytics"]);
+        assert_eq!(config.args_prepend_of("dart"), ["--disable-analytics"]);
+        assert!(config.args_prepend_of("melos").is_empty());
+    }
+
+    #[test]
+    fn test_parse_install_name_policy() {
+        assert_eq!(
+            FenvConfig::parse("{}").unwrap().install_name_policy,
+            InstallNamePolicy::AsIs
+        );
+        assert_eq!(
+            FenvConfig::parse(r#"{"installNamePolicy": "strip-v"}"#)
+                .unwrap()
+                .install_name_policy,
+            InstallNamePolicy::StripV
+        );
+        assert_eq!(
+            FenvConfig::parse(r#"{"installNamePolicy": "canonical"}"#)
+                .unwrap()
+                .install_name_policy,
+            InstallNamePolicy::Canonical
+        );
+        assert!(FenvConfig::parse(r#"{"installNamePolicy": "unknown"}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_pub_global_activate() {
+        assert!(FenvConfig::parse("{}")
+            .unwrap()
+            .pub_global_activate
+            .is_empty());
+        assert_eq!(
+            FenvConfig::parse(r#"{"pubGlobalActivate": ["melos ^3.0.0", "very_good_cli"]}"#)
+                .unwrap()
+                .pub_global_activate,
+            ["melos ^3.0.0", "very_good_cli"]
+        );
+    }
+
+    #[test]
+    fn test_parse_precache_platforms() {
+        assert!(FenvConfig::parse("{}")
+            .unwrap()
+            .precache_platforms
+            .is_empty());
+        assert_eq!(
+            FenvConfig::parse(r#"{"precachePlatforms": ["android", "web"]}"#)
+                .unwrap()
+                .precache_platforms,
+            [PrecachePlatform::Android, PrecachePlatform::Web]
+        );
+        assert!(FenvConfig::parse(r#"{"precachePlatforms": ["linux"]}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_accept_android_licenses() {
+        assert!(!FenvConfig::parse("{}").unwrap().accept_android_licenses);
+        assert!(
+            FenvConfig::parse(r#"{"acceptAndroidLicenses": true}"#)
+                .unwrap()
+                .accept_android_licenses
+        );
+    }
+
+    #[test]
+    fn test_parse_metrics() {
+        assert_eq!(
+            FenvConfig::parse("{}").unwrap().metrics,
+            MetricsConfig::default()
+        );
+        assert_eq!(
+            FenvConfig::parse(r#"{"metrics": {"statsdEndpoint": "127.0.0.1:8125"}}"#)
+                .unwrap()
+                .metrics,
+            MetricsConfig {
+                statsd_endpoint: Some(String::from("127.0.0.1:8125")),
+                prefix: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_tls() {
+        assert_eq!(FenvConfig::parse("{}").unwrap().tls, TlsConfig::default());
+        assert_eq!(
+            FenvConfig::parse(r#"{"tls": {"caBundle": "/etc/ssl/corp.pem"}}"#)
+                .unwrap()
+                .tls,
+            TlsConfig {
+                ca_bundle: Some(String::from("/etc/ssl/corp.pem")),
+                insecure_skip_verify: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_load_returns_default_if_no_config_file_exists() {
+        test_with_context(|context, _| {
+            assert_eq!(FenvConfig::load(context).unwrap(), FenvConfig::default());
+        })
+    }
+
+    #[test]
+    fn test_load_fails_if_config_file_is_malformed() {
+        test_with_context(|context, _| {
+            // setup
+            context.fenv_config_file().writeln("{").unwrap();
+
+            // execution
+            let result = FenvConfig::load(context);
+
+            // validation
+            assert!(result.is_err());
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                format!(
+                    "Failed to parse the config file: `{}`",
+                    context.fenv_config_file()
+                )
+            );
+        })
+    }
+
+    #[test]
+    fn test_load_project_config() {
+        test_with_context(|context, _| {
+            // setup
+            assert_eq!(
+                FenvProjectConfig::load(context).unwrap(),
+                FenvProjectConfig::default()
+            );
+            context
+                .fenv_dir()
+                .join(".fenv.toml")
+                .writeln(indoc! {r#"
+                    [flutter]
+                    git-url = "https://git.example.com/mobile/flutter.git"
+                "#})
+                .unwrap();
+
+            // execution
+            let config = FenvProjectConfig::load(context).unwrap();
+
+            // validation
+            assert_eq!(
+                config.flutter,
+                FlutterSourceConfig {
+                    git_url: Some(String::from("https://git.example.com/mobile/flutter.git")),
+                    storage_base_url: None,
+                }
+            );
+        })
+    }
+
+    #[test]
+    fn test_load_project_config_falls_back_to_env_vars() {
+        test_with_context(|context, _| {
+            // setup
+            context
+                .fenv_dir()
+                .join(".fenv.toml")
+                .writeln(indoc! {r#"
+                    [flutter]
+                    git-url = "https://git.example.com/mobile/flutter.git"
+                "#})
+                .unwrap();
+            let env_map: HashMap<String, String> = [
+                ("HOME", context.home().to_string()),
+                ("FENV_ROOT", context.fenv_root().to_string()),
+                ("FENV_DIR", context.fenv_dir().to_string()),
+                ("PWD", context.fenv_dir().to_string()),
+                ("SHELL", String::from("/bin/bash")),
+                (
+                    "FLUTTER_GIT_URL",
+                    String::from("https://mirror.example.com/flutter.git"),
+                ),
+                (
+                    "FLUTTER_STORAGE_BASE_URL",
+                    String::from("https://storage.flutter-io.cn"),
+                ),
+            ]
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect();
+            let context = RealFenvContext::from(&env_map).unwrap();
+
+            // execution
+            let untrusted = FenvProjectConfig::load(&context);
+            context
+                .fenv_config_file()
+                .writeln(r#"{"download": {"allowedHosts": ["storage.flutter-io.cn"]}}"#)
+                .unwrap();
+            let config = FenvProjectConfig::load(&context).unwrap();
+
+            // validation
+            assert!(untrusted.unwrap_err().to_string().starts_with(
+                "`$FLUTTER_STORAGE_BASE_URL` names `storage.flutter-io.cn`, which is not trusted"
+            ));
+            assert_eq!(
+                config.flutter,
+                FlutterSourceConfig {
+                    git_url: Some(String::from("https://git.example.com/mobile/flutter.git")),
+                    storage_base_url: Some(String::from("https://storage.flutter-io.cn")),
+                }
+            );
+        })
+    }
+}
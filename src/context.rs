@@ -1,9 +1,13 @@
-use crate::util::path_like::PathLike;
+use crate::util::{
+    path_like::PathLike,
+    platform::{Architecture, OperatingSystem},
+    retry::RetryPolicy,
+};
 use anyhow::{bail, Ok, Result};
 use log::{debug, info};
 use std::{collections::HashMap, path::Path};
 
-pub trait FenvContext: Clone {
+pub trait FenvContext: Clone + Sync {
     /// The home directory.
     ///
     /// Equivalent to `$HOME`.
@@ -52,6 +56,13 @@ pub trait FenvContext: Clone {
         self.fenv_root().join("cache")
     }
 
+    /// The directory where the aliases of the version prefixes are recorded, one file per alias.
+    ///
+    /// `{fenv_root}/aliases`.
+    fn fenv_aliases(&self) -> PathLike {
+        self.fenv_root().join("aliases")
+    }
+
     /// The file where the global flutter version is recorded.
     ///
     /// `{fenv_root}/version`.
@@ -59,6 +70,13 @@ pub trait FenvContext: Clone {
         self.fenv_root().join("version")
     }
 
+    /// The file where the user configuration of `fenv` is recorded.
+    ///
+    /// `{fenv_root}/config.json`.
+    fn fenv_config_file(&self) -> PathLike {
+        self.fenv_root().join("config.json")
+    }
+
     /// The directory where the given `version_or_channel` is installed.
     ///
     /// `{fenv_root}/versions/{version_or_channel}`.
@@ -68,6 +86,140 @@ pub trait FenvContext: Clone {
 
     /// `$PUB_CACHE` if the environment variable is set. Otherwise, `$HOME/.pub-cache`.
     fn pub_cache(&self) -> PathLike;
+
+    /// Whether the warnings about deprecated options are suppressed.
+    ///
+    /// `true` if `$FENV_SUPPRESS_DEPRECATIONS` is set to a non-empty value other than `0`.
+    fn suppresses_deprecations(&self) -> bool {
+        false
+    }
+
+    /// Whether `flutter channel` is allowed through the `flutter` shim.
+    ///
+    /// `true` if `$FENV_ALLOW_FLUTTER_CHANNEL` is set to a non-empty value other than `0`.
+    fn allows_flutter_channel(&self) -> bool {
+        false
+    }
+
+    /// The CA bundle file to verify the server certificate of the remote repository,
+    /// which is useful behind a TLS-intercepting proxy.
+    ///
+    /// `$FENV_CA_BUNDLE` if the environment variable is set to a non-empty value.
+    fn ca_bundle(&self) -> Option<PathLike> {
+        None
+    }
+
+    /// The directories in `$PATH` in the order of precedence.
+    fn path(&self) -> Vec<PathLike> {
+        vec![]
+    }
+
+    /// Whether the verification of the server certificate of the remote repository is skipped.
+    ///
+    /// `true` if `$FENV_INSECURE_SKIP_TLS_VERIFY` is set to a non-empty value other than `0`.
+    fn skips_tls_verify(&self) -> bool {
+        false
+    }
+
+    /// The version prefix selected for the current shell session by `fenv shell`,
+    /// which takes precedence over the version files.
+    ///
+    /// `$FENV_VERSION` if the environment variable is set to a non-empty value.
+    fn shell_version(&self) -> Option<String> {
+        None
+    }
+
+    /// The command which pages the long output such as the guides of `fenv help`.
+    ///
+    /// `$PAGER` if the environment variable is set to a non-empty value, or `less -FRX`.
+    fn pager(&self) -> Option<String> {
+        None
+    }
+
+    /// The name of the user running `fenv`, which is recorded in `{fenv_root}/audit.log`.
+    ///
+    /// `$USER`, or `$USERNAME` on Windows, if the environment variable is set to a non-empty value.
+    fn user(&self) -> Option<String> {
+        None
+    }
+
+    /// The proxy to access the remote resources through.
+    ///
+    /// `$HTTPS_PROXY`, or `$HTTP_PROXY` if not set, in upper or lower case, if the environment
+    /// variable is set to a non-empty value.
+    fn https_proxy(&self) -> Option<String> {
+        None
+    }
+
+    /// The comma-separated hosts accessed without the proxy.
+    ///
+    /// `$NO_PROXY`, in upper or lower case, if the environment variable is set to a non-empty
+    /// value.
+    fn no_proxy(&self) -> Option<String> {
+        None
+    }
+
+    /// The git repository to install the Flutter SDKs from unless `.fenv.toml` gives one, such
+    /// as a mirror of the upstream repository.
+    ///
+    /// `$FLUTTER_GIT_URL` if the environment variable is set to a non-empty value.
+    fn flutter_git_url(&self) -> Option<String> {
+        None
+    }
+
+    /// The mirror of the release archives and the Flutter artifacts unless `.fenv.toml` gives
+    /// one, such as `https://storage.flutter-io.cn`.
+    ///
+    /// `$FLUTTER_STORAGE_BASE_URL` if the environment variable is set to a non-empty value.
+    fn flutter_storage_base_url(&self) -> Option<String> {
+        None
+    }
+
+    /// Whether any network access is forbidden, so that the remote Flutter SDKs are answered
+    /// from the cache and only the cached archives are installed.
+    ///
+    /// `true` if `--offline` is given, or `$FENV_OFFLINE` is set to a non-empty value other than
+    /// `0`.
+    fn is_offline(&self) -> bool {
+        false
+    }
+
+    /// The same context but [`FenvContext::is_offline`], for `--offline`.
+    fn to_offline(&self) -> Self;
+
+    /// The maximum transfer rate of the downloads, which takes precedence over
+    /// `download.limitRate` of `{fenv_root}/config.json`.
+    ///
+    /// Given by `fenv install --limit-rate`.
+    fn limit_rate(&self) -> Option<String> {
+        None
+    }
+
+    /// The same context but [`FenvContext::limit_rate`] is `limit_rate`, for `--limit-rate`.
+    fn with_limit_rate(&self, limit_rate: &str) -> Self;
+
+    /// The retries of the network operations which take precedence over `retry` of
+    /// `{fenv_root}/config.json`, so that the tests do not wait for the backoff.
+    fn retry_policy(&self) -> Option<RetryPolicy> {
+        None
+    }
+
+    /// The cache directory of FVM, which has the Flutter SDKs downloaded by FVM.
+    ///
+    /// `$FVM_CACHE_PATH` if the environment variable is set to a non-empty value.
+    fn fvm_cache_path(&self) -> Option<PathLike> {
+        None
+    }
+
+    /// The operating system where `fenv` runs.
+    fn operating_system(&self) -> OperatingSystem {
+        OperatingSystem::current()
+    }
+
+    /// The CPU architecture where `fenv` runs.
+    fn architecture(&self) -> Architecture {
+        Architecture::current()
+    }
 }
 
 /// The real implementation of [`FenvContext`].
@@ -78,6 +230,22 @@ pub struct RealFenvContext {
     fenv_root: PathLike,
     fenv_dir: PathLike,
     pub_cache: PathLike,
+    suppresses_deprecations: bool,
+    allows_flutter_channel: bool,
+    ca_bundle: Option<PathLike>,
+    skips_tls_verify: bool,
+    path: Vec<PathLike>,
+    shell_version: Option<String>,
+    pager: Option<String>,
+    user: Option<String>,
+    https_proxy: Option<String>,
+    no_proxy: Option<String>,
+    flutter_git_url: Option<String>,
+    flutter_storage_base_url: Option<String>,
+    offline: bool,
+    limit_rate: Option<String>,
+    retry_policy: Option<RetryPolicy>,
+    fvm_cache_path: Option<PathLike>,
 }
 
 impl RealFenvContext {
@@ -94,6 +262,54 @@ impl RealFenvContext {
             home: PathLike::from(home),
             default_shell: String::from(default_shell),
             pub_cache: PathLike::from(pub_cache),
+            suppresses_deprecations: false,
+            allows_flutter_channel: false,
+            ca_bundle: None,
+            skips_tls_verify: false,
+            path: vec![],
+            shell_version: None,
+            pager: None,
+            user: None,
+            https_proxy: None,
+            no_proxy: None,
+            flutter_git_url: None,
+            flutter_storage_base_url: None,
+            offline: false,
+            limit_rate: None,
+            retry_policy: None,
+            fvm_cache_path: None,
+        }
+    }
+
+    /// Replaces `$PATH` with the given directories.
+    pub fn with_path(self, path: &[PathLike]) -> Self {
+        Self {
+            path: path.to_vec(),
+            ..self
+        }
+    }
+
+    /// Replaces `$FENV_VERSION` with the given version prefix.
+    pub fn with_shell_version(self, shell_version: &str) -> Self {
+        Self {
+            shell_version: Some(String::from(shell_version)),
+            ..self
+        }
+    }
+
+    /// Replaces `$FVM_CACHE_PATH` with the given directory.
+    pub fn with_fvm_cache_path(self, fvm_cache_path: &PathLike) -> Self {
+        Self {
+            fvm_cache_path: Some(fvm_cache_path.clone()),
+            ..self
+        }
+    }
+
+    /// Replaces the retries of the network operations with `retry_policy`.
+    pub fn with_retry_policy(self, retry_policy: RetryPolicy) -> Self {
+        Self {
+            retry_policy: Some(retry_policy),
+            ..self
         }
     }
 
@@ -128,13 +344,79 @@ impl RealFenvContext {
             info!("Config::from(): Could not find `$PUB_CACHE`. Fallback to `$HOME/.pub-cache`");
             PathLike::from(home.as_str()).join(".pub-cache").to_string()
         };
-        Ok(Self::new(
-            &fenv_root,
-            &fenv_dir,
-            &home,
-            &find_in_env_vars(&env_map, "SHELL")?,
-            &pub_cache,
-        ))
+        let suppresses_deprecations = is_flag_set(env_map, "FENV_SUPPRESS_DEPRECATIONS");
+        let allows_flutter_channel = is_flag_set(env_map, "FENV_ALLOW_FLUTTER_CHANNEL");
+        let ca_bundle = env_map
+            .get("FENV_CA_BUNDLE")
+            .filter(|value| !value.is_empty())
+            .map(|value| PathLike::from(value.as_str()));
+        let skips_tls_verify = is_flag_set(env_map, "FENV_INSECURE_SKIP_TLS_VERIFY");
+        let path = env_map
+            .get("PATH")
+            .map(|path| {
+                std::env::split_paths(path)
+                    .map(|dir| PathLike::from(&dir))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let shell_version = env_map
+            .get("FENV_VERSION")
+            .filter(|value| !value.is_empty())
+            .cloned();
+        let pager = env_map
+            .get("PAGER")
+            .filter(|value| !value.is_empty())
+            .cloned()
+            .unwrap_or_else(|| String::from("less -FRX"));
+        let user = ["USER", "USERNAME"]
+            .iter()
+            .filter_map(|key| env_map.get(*key))
+            .find(|value| !value.is_empty())
+            .cloned();
+        let first_of = |keys: &[&str]| {
+            keys.iter()
+                .filter_map(|key| env_map.get(*key))
+                .find(|value| !value.is_empty())
+                .cloned()
+        };
+        let https_proxy = first_of(&["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy"]);
+        let no_proxy = first_of(&["NO_PROXY", "no_proxy"]);
+        let flutter_git_url = env_map
+            .get("FLUTTER_GIT_URL")
+            .filter(|value| !value.is_empty())
+            .cloned();
+        let flutter_storage_base_url = env_map
+            .get("FLUTTER_STORAGE_BASE_URL")
+            .filter(|value| !value.is_empty())
+            .cloned();
+        let fvm_cache_path = env_map
+            .get("FVM_CACHE_PATH")
+            .filter(|value| !value.is_empty())
+            .map(|value| PathLike::from(value.as_str()));
+        Ok(Self {
+            suppresses_deprecations,
+            allows_flutter_channel,
+            ca_bundle,
+            skips_tls_verify,
+            path,
+            shell_version,
+            pager: Some(pager),
+            user,
+            https_proxy,
+            no_proxy,
+            flutter_git_url,
+            flutter_storage_base_url,
+            offline: is_flag_set(env_map, "FENV_OFFLINE"),
+            retry_policy: None,
+            fvm_cache_path,
+            ..Self::new(
+                &fenv_root,
+                &fenv_dir,
+                &home,
+                &find_in_env_vars(env_map, "SHELL")?,
+                &pub_cache,
+            )
+        })
     }
 }
 
@@ -158,6 +440,91 @@ impl FenvContext for RealFenvContext {
     fn pub_cache(&self) -> PathLike {
         self.pub_cache.clone()
     }
+
+    fn suppresses_deprecations(&self) -> bool {
+        self.suppresses_deprecations
+    }
+
+    fn allows_flutter_channel(&self) -> bool {
+        self.allows_flutter_channel
+    }
+
+    fn ca_bundle(&self) -> Option<PathLike> {
+        self.ca_bundle.clone()
+    }
+
+    fn skips_tls_verify(&self) -> bool {
+        self.skips_tls_verify
+    }
+
+    fn path(&self) -> Vec<PathLike> {
+        self.path.clone()
+    }
+
+    fn shell_version(&self) -> Option<String> {
+        self.shell_version.clone()
+    }
+
+    fn pager(&self) -> Option<String> {
+        self.pager.clone()
+    }
+
+    fn user(&self) -> Option<String> {
+        self.user.clone()
+    }
+
+    fn https_proxy(&self) -> Option<String> {
+        self.https_proxy.clone()
+    }
+
+    fn no_proxy(&self) -> Option<String> {
+        self.no_proxy.clone()
+    }
+
+    fn flutter_git_url(&self) -> Option<String> {
+        self.flutter_git_url.clone()
+    }
+
+    fn flutter_storage_base_url(&self) -> Option<String> {
+        self.flutter_storage_base_url.clone()
+    }
+
+    fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    fn to_offline(&self) -> Self {
+        Self {
+            offline: true,
+            ..self.clone()
+        }
+    }
+
+    fn limit_rate(&self) -> Option<String> {
+        self.limit_rate.clone()
+    }
+
+    fn with_limit_rate(&self, limit_rate: &str) -> Self {
+        Self {
+            limit_rate: Some(String::from(limit_rate)),
+            ..self.clone()
+        }
+    }
+
+    fn retry_policy(&self) -> Option<RetryPolicy> {
+        self.retry_policy
+    }
+
+    fn fvm_cache_path(&self) -> Option<PathLike> {
+        self.fvm_cache_path.clone()
+    }
+}
+
+/// Whether the given environment variable is set to a non-empty value other than `0`.
+fn is_flag_set(env_map: &HashMap<String, String>, env_key: &str) -> bool {
+    env_map
+        .get(env_key)
+        .is_some_and(|value| !value.is_empty() && value != "0")
 }
 
 fn find_in_env_vars(env_map: &HashMap<String, String>, lookup_target: &str) -> Result<String> {
@@ -187,7 +554,7 @@ fn requires_directory(env_map: &HashMap<String, String>, env_key: &str) -> Resul
 #[cfg(test)]
 mod tests {
     use super::RealFenvContext;
-    use crate::util::path_like::PathLike;
+    use crate::{context::FenvContext, util::path_like::PathLike};
     use std::collections::HashMap;
 
     fn generate_env_map(vars: &[(&str, &str)]) -> HashMap<String, String> {
@@ -244,6 +611,22 @@ mod tests {
                 fenv_root,
                 fenv_dir,
                 pub_cache,
+                suppresses_deprecations: false,
+                allows_flutter_channel: false,
+                ca_bundle: None,
+                skips_tls_verify: false,
+                path: vec![],
+                shell_version: None,
+                pager: Some(String::from("less -FRX")),
+                user: None,
+                https_proxy: None,
+                no_proxy: None,
+                flutter_git_url: None,
+                flutter_storage_base_url: None,
+                offline: false,
+                limit_rate: None,
+                retry_policy: None,
+                fvm_cache_path: None,
             }
         )
     }
@@ -272,7 +655,165 @@ mod tests {
                 fenv_root: PathLike::from("/fake_home/user/.fenv"),
                 fenv_dir: PathLike::from("/fake_pwd"),
                 pub_cache: PathLike::from("/fake_pub_cache"),
+                suppresses_deprecations: false,
+                allows_flutter_channel: false,
+                ca_bundle: None,
+                skips_tls_verify: false,
+                path: vec![],
+                shell_version: None,
+                pager: Some(String::from("less -FRX")),
+                user: None,
+                https_proxy: None,
+                no_proxy: None,
+                flutter_git_url: None,
+                flutter_storage_base_url: None,
+                offline: false,
+                limit_rate: None,
+                retry_policy: None,
+                fvm_cache_path: None,
             }
         )
     }
+
+    #[test]
+    fn test_from_reads_suppress_deprecations() {
+        let env_map_of = |value: &str| {
+            generate_env_map(&[
+                ("HOME", "/fake_home/user"),
+                ("PWD", "/fake_pwd"),
+                ("SHELL", "/bin/bash"),
+                ("FENV_SUPPRESS_DEPRECATIONS", value),
+            ])
+        };
+        let suppresses = |value: &str| {
+            RealFenvContext::from(&env_map_of(value))
+                .unwrap()
+                .suppresses_deprecations()
+        };
+        assert!(suppresses("1"));
+        assert!(suppresses("true"));
+        assert!(!suppresses("0"));
+        assert!(!suppresses(""));
+    }
+
+    #[test]
+    fn test_from_reads_allow_flutter_channel() {
+        let allows = |value: &str| {
+            RealFenvContext::from(&generate_env_map(&[
+                ("HOME", "/fake_home/user"),
+                ("PWD", "/fake_pwd"),
+                ("SHELL", "/bin/bash"),
+                ("FENV_ALLOW_FLUTTER_CHANNEL", value),
+            ]))
+            .unwrap()
+            .allows_flutter_channel()
+        };
+        assert!(allows("1"));
+        assert!(!allows("0"));
+    }
+
+    #[test]
+    fn test_from_reads_tls_settings() {
+        let context_of = |ca_bundle: &str, skip_verify: &str| {
+            RealFenvContext::from(&generate_env_map(&[
+                ("HOME", "/fake_home/user"),
+                ("PWD", "/fake_pwd"),
+                ("SHELL", "/bin/bash"),
+                ("FENV_CA_BUNDLE", ca_bundle),
+                ("FENV_INSECURE_SKIP_TLS_VERIFY", skip_verify),
+            ]))
+            .unwrap()
+        };
+        let context = context_of("/etc/ssl/corp.pem", "1");
+        assert_eq!(
+            context.ca_bundle().unwrap().to_string(),
+            "/etc/ssl/corp.pem"
+        );
+        assert!(context.skips_tls_verify());
+        let context = context_of("", "0");
+        assert!(context.ca_bundle().is_none());
+        assert!(!context.skips_tls_verify());
+    }
+
+    #[test]
+    fn test_from_reads_path() {
+        let context = RealFenvContext::from(&generate_env_map(&[
+            ("HOME", "/fake_home/user"),
+            ("PWD", "/fake_pwd"),
+            ("SHELL", "/bin/bash"),
+            ("PATH", "/fake_home/user/.fenv/shims:/usr/bin"),
+        ]))
+        .unwrap();
+        let path: Vec<String> = context.path().iter().map(|dir| dir.to_string()).collect();
+        assert_eq!(path, ["/fake_home/user/.fenv/shims", "/usr/bin"]);
+    }
+
+    #[test]
+    fn test_from_reads_pager() {
+        let pager_of = |value: &str| {
+            RealFenvContext::from(&generate_env_map(&[
+                ("HOME", "/fake_home/user"),
+                ("PWD", "/fake_pwd"),
+                ("SHELL", "/bin/bash"),
+                ("PAGER", value),
+            ]))
+            .unwrap()
+            .pager()
+        };
+        assert_eq!(pager_of("more"), Some(String::from("more")));
+        assert_eq!(pager_of(""), Some(String::from("less -FRX")));
+    }
+
+    #[test]
+    fn test_from_reads_user() {
+        let user_of = |env_vars: &[(&str, &str)]| {
+            let mut env_map = generate_env_map(&[
+                ("HOME", "/fake_home/user"),
+                ("PWD", "/fake_pwd"),
+                ("SHELL", "/bin/bash"),
+            ]);
+            env_map.extend(generate_env_map(env_vars));
+            RealFenvContext::from(&env_map).unwrap().user()
+        };
+        assert_eq!(user_of(&[("USER", "alice")]), Some(String::from("alice")));
+        assert_eq!(
+            user_of(&[("USER", ""), ("USERNAME", "bob")]),
+            Some(String::from("bob"))
+        );
+        assert_eq!(user_of(&[]), None);
+    }
+
+    #[test]
+    fn test_from_reads_proxy() {
+        let context = RealFenvContext::from(&generate_env_map(&[
+            ("HOME", "/fake_home/user"),
+            ("PWD", "/fake_pwd"),
+            ("SHELL", "/bin/bash"),
+            ("HTTPS_PROXY", ""),
+            ("http_proxy", "http://proxy.example.com:8080"),
+            ("no_proxy", "localhost"),
+        ]))
+        .unwrap();
+        assert_eq!(
+            context.https_proxy(),
+            Some(String::from("http://proxy.example.com:8080"))
+        );
+        assert_eq!(context.no_proxy(), Some(String::from("localhost")));
+    }
+
+    #[test]
+    fn test_from_reads_shell_version() {
+        let shell_version_of = |value: &str| {
+            RealFenvContext::from(&generate_env_map(&[
+                ("HOME", "/fake_home/user"),
+                ("PWD", "/fake_pwd"),
+                ("SHELL", "/bin/bash"),
+                ("FENV_VERSION", value),
+            ]))
+            .unwrap()
+            .shell_version()
+        };
+        assert_eq!(shell_version_of("3.7"), Some(String::from("3.7")));
+        assert_eq!(shell_version_of(""), None);
+    }
 }
@@ -0,0 +1,150 @@
+use crate::{context::FenvContext, util::path_like::PathLike};
+use anyhow::Context;
+use clap::{parser::ValueSource, ArgMatches};
+use std::collections::BTreeSet;
+
+/// A deprecated command line option.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Deprecation {
+    /// The subcommand which owns the option, such as `local`.
+    pub subcommand: &'static str,
+
+    /// The id of the clap argument, which is the field name of the `Fenv*Args` struct.
+    pub arg_id: &'static str,
+
+    /// The option as it is given in the command line, such as `--symlink`.
+    pub option: &'static str,
+
+    /// What to do instead.
+    pub advice: &'static str,
+}
+
+/// Every deprecated option of `fenv`.
+///
+/// Register an option here when deprecating it, and mark its doc comment in `args.rs`.
+pub const DEPRECATIONS: &[Deprecation] = &[
+    Deprecation {
+        subcommand: "local",
+        arg_id: "symlink",
+        option: "--symlink",
+        advice: "For IDE support, use `fenv workspace` instead.",
+    },
+    Deprecation {
+        subcommand: "latest",
+        arg_id: "known",
+        option: "--known",
+        advice: "Use `--remote` instead.",
+    },
+];
+
+impl Deprecation {
+    /// The command line usage such as `fenv local --symlink`.
+    pub fn usage(&self) -> String {
+        format!("fenv {} {}", self.subcommand, self.option)
+    }
+
+    pub fn message(&self) -> String {
+        format!("`{}` is deprecated. {}", self.usage(), self.advice)
+    }
+}
+
+/// Finds the deprecated options which are explicitly given in the command line.
+pub fn find_deprecated_usages(matches: &ArgMatches) -> Vec<&'static Deprecation> {
+    let Some((subcommand, sub_matches)) = matches.subcommand() else {
+        return vec![];
+    };
+    DEPRECATIONS
+        .iter()
+        .filter(|deprecation| deprecation.subcommand == subcommand)
+        .filter(|deprecation| {
+            sub_matches.value_source(deprecation.arg_id) == Some(ValueSource::CommandLine)
+        })
+        .collect()
+}
+
+/// The file where the deprecated usages are recorded, one usage per line.
+///
+/// `{fenv_cache}/deprecated_usages`.
+fn deprecated_usages_file(context: &impl FenvContext) -> PathLike {
+    context.fenv_cache().join("deprecated_usages")
+}
+
+/// Records the given deprecated usages so that `fenv doctor` can list the deprecated options
+/// which the user's scripts still pass.
+pub fn record_usages(
+    context: &impl FenvContext,
+    deprecations: &[&Deprecation],
+) -> anyhow::Result<()> {
+    let mut usages: BTreeSet<String> = recorded_usages(context).into_iter().collect();
+    let count = usages.len();
+    usages.extend(deprecations.iter().map(|deprecation| deprecation.usage()));
+    if usages.len() == count {
+        return anyhow::Ok(());
+    }
+    let file = deprecated_usages_file(context);
+    file.write(
+        usages
+            .iter()
+            .map(|usage| format!("{usage}\n"))
+            .collect::<String>(),
+    )
+    .with_context(|| format!("Failed to record the deprecated usages: `{file}`"))
+}
+
+/// Lists the deprecated usages which have been recorded by [`record_usages`].
+pub fn recorded_usages(context: &impl FenvContext) -> Vec<String> {
+    deprecated_usages_file(context)
+        .read_to_string()
+        .map(|content| content.lines().map(|line| line.to_owned()).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_deprecated_usages, record_usages, recorded_usages, DEPRECATIONS};
+    use crate::{build_command, service::macros::test_with_context};
+
+    #[test]
+    fn test_every_deprecation_refers_to_existing_arg() {
+        let command = build_command();
+        for deprecation in DEPRECATIONS {
+            let subcommand = command
+                .find_subcommand(deprecation.subcommand)
+                .unwrap_or_else(|| panic!("No subcommand: `{}`", deprecation.subcommand));
+            let arg = subcommand
+                .get_arguments()
+                .find(|arg| arg.get_id() == deprecation.arg_id)
+                .unwrap_or_else(|| panic!("No argument: `{}`", deprecation.usage()));
+            assert_eq!(
+                Some(&deprecation.option[2..]),
+                arg.get_long(),
+                "Mismatched option: `{}`",
+                deprecation.usage()
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_deprecated_usages() {
+        let matches = build_command().get_matches_from(["fenv", "latest", "--known", "3"]);
+        assert_eq!(find_deprecated_usages(&matches), [&DEPRECATIONS[1]]);
+
+        let matches = build_command().get_matches_from(["fenv", "latest", "--remote", "3"]);
+        assert!(find_deprecated_usages(&matches).is_empty());
+    }
+
+    #[test]
+    fn test_record_usages_without_duplication() {
+        test_with_context(|context, _| {
+            // execution
+            record_usages(context, &[&DEPRECATIONS[1]]).unwrap();
+            record_usages(context, &[&DEPRECATIONS[0], &DEPRECATIONS[1]]).unwrap();
+
+            // validation
+            assert_eq!(
+                recorded_usages(context),
+                ["fenv latest --known", "fenv local --symlink"]
+            );
+        })
+    }
+}
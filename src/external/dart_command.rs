@@ -0,0 +1,75 @@
+use crate::spawn_and_wait;
+use anyhow::{Context as _, Ok, Result};
+use mockall::automock;
+use std::process::Command;
+
+/// The `dart` CLI bundled with the Flutter SDK at `flutter_sdk_root`.
+#[automock]
+pub trait DartCommand: Sync {
+    /// Runs `dart pub get` on `working_dir`.
+    fn pub_get(&self, flutter_sdk_root: &str, working_dir: &str) -> Result<()>;
+    /// Runs `dart pub global activate` with the given `package`, which may be followed by
+    /// the version constraint such as `["melos", "^3.0.0"]`.
+    fn pub_global_activate(&self, flutter_sdk_root: &str, package: &[String]) -> Result<()>;
+    /// Runs `dart format` for the given `paths` on `working_dir`.
+    fn format(&self, flutter_sdk_root: &str, working_dir: &str, paths: &[String]) -> Result<()>;
+    /// Runs `dart analyze` on `working_dir`.
+    fn analyze(&self, flutter_sdk_root: &str, working_dir: &str) -> Result<()>;
+}
+
+#[derive(Default)]
+pub struct DartCommandImpl {}
+
+impl DartCommandImpl {
+    pub fn new() -> DartCommandImpl {
+        DartCommandImpl {}
+    }
+}
+
+impl DartCommand for DartCommandImpl {
+    fn pub_get(&self, flutter_sdk_root: &str, working_dir: &str) -> Result<()> {
+        let mut command = Command::new(dart_cli_path(flutter_sdk_root));
+        spawn_and_wait!(
+            command.current_dir(working_dir).args(["pub", "get"]),
+            "pub_get",
+            "Failed to execute `dart pub get` on `{working_dir}`",
+        );
+        Ok(())
+    }
+
+    fn pub_global_activate(&self, flutter_sdk_root: &str, package: &[String]) -> Result<()> {
+        let mut command = Command::new(dart_cli_path(flutter_sdk_root));
+        spawn_and_wait!(
+            command.args(["pub", "global", "activate"]).args(package),
+            "pub_global_activate",
+            "Failed to execute `dart pub global activate {}`",
+            package.join(" "),
+        );
+        Ok(())
+    }
+
+    fn format(&self, flutter_sdk_root: &str, working_dir: &str, paths: &[String]) -> Result<()> {
+        let mut command = Command::new(dart_cli_path(flutter_sdk_root));
+        spawn_and_wait!(
+            command.current_dir(working_dir).arg("format").args(paths),
+            "format",
+            "Failed to execute `dart format` on `{working_dir}`",
+        );
+        Ok(())
+    }
+
+    fn analyze(&self, flutter_sdk_root: &str, working_dir: &str) -> Result<()> {
+        let mut command = Command::new(dart_cli_path(flutter_sdk_root));
+        spawn_and_wait!(
+            command.current_dir(working_dir).arg("analyze"),
+            "analyze",
+            "Failed to execute `dart analyze` on `{working_dir}`",
+        );
+        Ok(())
+    }
+}
+
+/// `{flutter_sdk_root}/bin/dart`, which is used instead of `dart` on `PATH` to pin the SDK.
+fn dart_cli_path(flutter_sdk_root: &str) -> String {
+    [flutter_sdk_root, "bin", "dart"].join(std::path::MAIN_SEPARATOR_STR)
+}
@@ -1,10 +1,89 @@
-use crate::spawn_and_wait;
-use anyhow::{Context as _, Ok, Result};
-use std::{env, path::PathBuf, process::Command};
+use super::progress::{run_with_options, RunOptions};
+use anyhow::{bail, Context as _, Ok, Result};
+use serde::{Deserialize, Serialize};
+use std::{env, io::Write, path::PathBuf, process::Command, time::Duration};
 
-pub trait FlutterCommand {
-    fn doctor(&self, flutter_sdk_root: &str) -> Result<()>;
-    fn precache(&self, flutter_sdk_root: &str) -> Result<()>;
+/// How long `flutter doctor --android-licenses` may take before it is terminated, because it
+/// waits for the input forever if a prompt is not answered as expected.
+pub const ANDROID_LICENSES_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// A platform whose artifacts `flutter precache` downloads.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum PrecachePlatform {
+    Android,
+    Ios,
+    Web,
+}
+
+impl PrecachePlatform {
+    pub const ALL: [PrecachePlatform; 3] = [Self::Android, Self::Ios, Self::Web];
+
+    /// The name of the flag of `flutter precache`, such as `android` of `--android`.
+    pub fn flag_name(&self) -> &'static str {
+        match self {
+            PrecachePlatform::Android => "android",
+            PrecachePlatform::Ios => "ios",
+            PrecachePlatform::Web => "web",
+        }
+    }
+}
+
+/// The arguments of `flutter precache` which download the artifacts of `platforms` only.
+///
+/// If `platforms` is empty, no flag is given, so that `flutter` downloads the artifacts of its
+/// default platforms.
+pub fn precache_args(platforms: &[PrecachePlatform]) -> Vec<String> {
+    let mut args = vec![String::from("precache")];
+    if platforms.is_empty() {
+        return args;
+    }
+    for platform in PrecachePlatform::ALL {
+        let negation = if platforms.contains(&platform) {
+            ""
+        } else {
+            "no-"
+        };
+        args.push(format!("--{negation}{}", platform.flag_name()));
+    }
+    args
+}
+
+/// `flutter` of the Flutter SDK at `flutter_sdk_root`.
+///
+/// The output of the commands is relayed into `progress` because they may take minutes.
+/// If `storage_base_url` is given, the artifacts are downloaded from there by
+/// `$FLUTTER_STORAGE_BASE_URL`. `precache` downloads the artifacts of `platforms` only,
+/// or those of the default platforms of `flutter` if `platforms` is empty.
+/// `accept_android_licenses` answers `y` to every prompt of `flutter doctor --android-licenses`.
+/// `create` creates a Flutter project at `project_dir` by `flutter create` with `args`.
+pub trait FlutterCommand: Sync {
+    fn doctor(
+        &self,
+        flutter_sdk_root: &str,
+        storage_base_url: Option<&str>,
+        progress: &mut dyn Write,
+    ) -> Result<()>;
+    fn precache(
+        &self,
+        flutter_sdk_root: &str,
+        platforms: &[PrecachePlatform],
+        storage_base_url: Option<&str>,
+        progress: &mut dyn Write,
+    ) -> Result<()>;
+    fn accept_android_licenses(
+        &self,
+        flutter_sdk_root: &str,
+        storage_base_url: Option<&str>,
+        progress: &mut dyn Write,
+    ) -> Result<()>;
+    fn create(
+        &self,
+        flutter_sdk_root: &str,
+        project_dir: &str,
+        args: &[String],
+        progress: &mut dyn Write,
+    ) -> Result<()>;
 }
 
 pub struct FlutterCommandImpl {}
@@ -13,41 +92,113 @@ impl FlutterCommandImpl {
     pub fn new() -> FlutterCommandImpl {
         FlutterCommandImpl {}
     }
-}
 
-impl FlutterCommand for FlutterCommandImpl {
-    fn doctor(&self, flutter_sdk_root: &str) -> Result<()> {
+    fn run(
+        &self,
+        flutter_sdk_root: &str,
+        label: &str,
+        args: &[&str],
+        storage_base_url: Option<&str>,
+        options: RunOptions,
+        progress: &mut dyn Write,
+    ) -> Result<()> {
         let flutter_bin_directory = [flutter_sdk_root, "bin"].join(std::path::MAIN_SEPARATOR_STR);
         let mut command = Command::new("flutter");
-        spawn_and_wait!(
-            command
-                .current_dir(&flutter_bin_directory)
-                .env(
-                    "PATH",
-                    flutter_sdk_root_merged_env_path(&flutter_bin_directory)?
-                )
-                .args(["doctor", "--suppress-analytics", "--verbose"]),
-            "doctor",
-            "Failed to execute `flutter doctor` on `{flutter_bin_directory}`",
-        );
+        command
+            .current_dir(&flutter_bin_directory)
+            .env(
+                "PATH",
+                flutter_sdk_root_merged_env_path(&flutter_bin_directory)?,
+            )
+            .args(args);
+        if let Some(storage_base_url) = storage_base_url {
+            command.env("FLUTTER_STORAGE_BASE_URL", storage_base_url);
+        }
+        let status = run_with_options(&mut command, label, options, progress)
+            .with_context(|| format!("Failed to execute `{label}` on `{flutter_bin_directory}`"))?;
+        if !status.success() {
+            bail!(
+                "Failed to execute `{label}` on `{flutter_bin_directory}`: OS state code - {}",
+                status.code().unwrap_or(-1)
+            )
+        }
         Ok(())
     }
+}
 
-    fn precache(&self, flutter_sdk_root: &str) -> Result<()> {
-        let flutter_bin_directory = [flutter_sdk_root, "bin"].join(std::path::MAIN_SEPARATOR_STR);
-        let mut command = Command::new("flutter");
-        spawn_and_wait!(
-            command
-                .current_dir(&flutter_bin_directory)
-                .env(
-                    "PATH",
-                    flutter_sdk_root_merged_env_path(&flutter_bin_directory)?
-                )
-                .arg("precache"),
-            "doctor",
-            "Failed to execute `flutter precache` on `{flutter_bin_directory}`",
-        );
-        Ok(())
+impl FlutterCommand for FlutterCommandImpl {
+    fn doctor(
+        &self,
+        flutter_sdk_root: &str,
+        storage_base_url: Option<&str>,
+        progress: &mut dyn Write,
+    ) -> Result<()> {
+        self.run(
+            flutter_sdk_root,
+            "flutter doctor",
+            &["doctor", "--suppress-analytics", "--verbose"],
+            storage_base_url,
+            RunOptions::default(),
+            progress,
+        )
+    }
+
+    fn precache(
+        &self,
+        flutter_sdk_root: &str,
+        platforms: &[PrecachePlatform],
+        storage_base_url: Option<&str>,
+        progress: &mut dyn Write,
+    ) -> Result<()> {
+        let args = precache_args(platforms);
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        self.run(
+            flutter_sdk_root,
+            "flutter precache",
+            &args,
+            storage_base_url,
+            RunOptions::default(),
+            progress,
+        )
+    }
+
+    fn accept_android_licenses(
+        &self,
+        flutter_sdk_root: &str,
+        storage_base_url: Option<&str>,
+        progress: &mut dyn Write,
+    ) -> Result<()> {
+        self.run(
+            flutter_sdk_root,
+            "flutter doctor --android-licenses",
+            &["doctor", "--suppress-analytics", "--android-licenses"],
+            storage_base_url,
+            RunOptions {
+                timeout: Some(ANDROID_LICENSES_TIMEOUT),
+                repeated_input: Some("y\n"),
+            },
+            progress,
+        )
+    }
+
+    fn create(
+        &self,
+        flutter_sdk_root: &str,
+        project_dir: &str,
+        args: &[String],
+        progress: &mut dyn Write,
+    ) -> Result<()> {
+        let mut create_args = vec!["create", "--suppress-analytics"];
+        create_args.extend(args.iter().map(String::as_str));
+        create_args.push(project_dir);
+        self.run(
+            flutter_sdk_root,
+            "flutter create",
+            &create_args,
+            None,
+            RunOptions::default(),
+            progress,
+        )
     }
 }
 
@@ -60,3 +211,21 @@ fn flutter_sdk_root_merged_env_path(flutter_bin_directory: &str) -> Result<Strin
         .map(|s| s.to_string_lossy().to_string())
         .map_err(|e| anyhow::anyhow!(e))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{precache_args, PrecachePlatform};
+
+    #[test]
+    fn test_precache_args() {
+        assert_eq!(precache_args(&[]), ["precache"]);
+        assert_eq!(
+            precache_args(&[PrecachePlatform::Android]),
+            ["precache", "--android", "--no-ios", "--no-web"]
+        );
+        assert_eq!(
+            precache_args(&[PrecachePlatform::Web, PrecachePlatform::Ios]),
+            ["precache", "--no-android", "--ios", "--web"]
+        );
+    }
+}
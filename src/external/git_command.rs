@@ -1,14 +1,164 @@
-use crate::{spawn_and_capture, spawn_and_wait};
-use anyhow::{Context as _, Ok, Result};
+use super::proxy::ProxyOptions;
+use crate::{
+    config::{FenvConfig, FenvProjectConfig},
+    context::FenvContext,
+    sdk_service::results::InstallFailureReason,
+    spawn_and_capture, spawn_and_wait,
+    util::retry::RetryPolicy,
+};
+use anyhow::{bail, Context as _, Ok, Result};
+use is_executable::is_executable;
 use mockall::automock;
 use std::process::Command;
 
 #[automock]
-pub trait GitCommand {
-    fn clone_flutter_sdk_by_channel(&self, channel: &str, destination: &str) -> Result<()>;
-    fn clone_flutter_sdk_by_version(&self, version: &str, destination: &str) -> Result<()>;
-    fn list_remote_sdks_by_tags(&self) -> Result<String>;
-    fn list_remote_sdks_by_branches(&self) -> Result<String>;
+pub trait GitCommand: Sync {
+    fn clone_flutter_sdk_by_channel(
+        &self,
+        channel: &str,
+        destination: &str,
+        remote: &GitRemote,
+    ) -> Result<()>;
+    fn clone_flutter_sdk_by_version(
+        &self,
+        version: &str,
+        destination: &str,
+        remote: &GitRemote,
+    ) -> Result<()>;
+    /// Clones the repository and checks out `sha`, which may be on any branch.
+    fn clone_flutter_sdk_by_commit(
+        &self,
+        sha: &str,
+        destination: &str,
+        remote: &GitRemote,
+    ) -> Result<()>;
+    /// Resumes the clone of `channel` interrupted in `destination` by fetching the missing
+    /// objects, instead of cloning the whole repository again.
+    fn resume_flutter_sdk_by_channel(
+        &self,
+        channel: &str,
+        destination: &str,
+        remote: &GitRemote,
+    ) -> Result<()>;
+    /// Resumes the clone of `version` interrupted in `destination`.
+    fn resume_flutter_sdk_by_version(
+        &self,
+        version: &str,
+        destination: &str,
+        remote: &GitRemote,
+    ) -> Result<()>;
+    fn list_remote_sdks_by_tags(&self, remote: &GitRemote) -> Result<String>;
+    fn list_remote_sdks_by_branches(&self, remote: &GitRemote) -> Result<String>;
+    fn rev_parse_head(&self, working_dir: &str) -> Result<String>;
+    /// Reads the commit which `rev` such as `refs/tags/3.22.2` points to in `working_dir`.
+    fn rev_parse_commit(&self, working_dir: &str, rev: &str) -> Result<String>;
+    /// Fast-forwards the branch checked out in `working_dir` to its upstream branch.
+    fn pull_ff_only(
+        &self,
+        working_dir: &str,
+        tls: &GitTlsOptions,
+        proxy: &ProxyOptions,
+    ) -> Result<()>;
+}
+
+/// The upstream repository of the Flutter SDKs.
+pub const FLUTTER_GIT_URL: &str = "https://github.com/flutter/flutter.git";
+
+/// The repository where the Flutter SDKs are installed from, and how to access it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitRemote {
+    pub url: String,
+    pub tls: GitTlsOptions,
+    pub proxy: ProxyOptions,
+    /// How a failed `git ls-remote` is retried.
+    pub retry: RetryPolicy,
+}
+
+impl Default for GitRemote {
+    fn default() -> Self {
+        Self {
+            url: String::from(FLUTTER_GIT_URL),
+            tls: GitTlsOptions::default(),
+            proxy: ProxyOptions::default(),
+            retry: RetryPolicy::default(),
+        }
+    }
+}
+
+impl GitRemote {
+    /// Resolves the repository from `.fenv.toml` of the current project, which defaults to
+    /// the upstream repository, and the TLS options.
+    pub fn resolve(context: &impl FenvContext) -> Result<Self> {
+        let project_config = FenvProjectConfig::load(context)?;
+        Ok(Self {
+            url: project_config
+                .flutter
+                .git_url
+                .unwrap_or_else(|| String::from(FLUTTER_GIT_URL)),
+            tls: GitTlsOptions::resolve(context)?,
+            proxy: ProxyOptions::resolve(context)?,
+            retry: RetryPolicy::resolve(context)?,
+        })
+    }
+}
+
+/// Whether `git` is found on `PATH`.
+///
+/// Without `git`, the versions are installed from the release archives instead, and the
+/// commands which need the remote repository or the git history fail with the reason.
+/// Regarded as available if `PATH` is empty, where it cannot be looked up.
+pub fn is_git_available(context: &impl FenvContext) -> bool {
+    let path = context.path();
+    path.is_empty() || path.iter().any(|dir| is_executable(dir.join("git")))
+}
+
+/// How `git` verifies the server certificate of the remote repository.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GitTlsOptions {
+    /// The CA bundle file which is used instead of the system's one.
+    pub ca_bundle: Option<String>,
+
+    /// Whether to skip the verification of the server certificate.
+    pub skips_verify: bool,
+}
+
+impl GitTlsOptions {
+    /// Resolves the options from the environment variables and `{fenv_root}/config.json`.
+    /// The environment variables take precedence over the configuration file.
+    ///
+    /// A relative CA bundle is resolved against the working directory if given by
+    /// `$FENV_CA_BUNDLE`, or against `{fenv_root}` if given by the configuration file.
+    pub fn resolve(context: &impl FenvContext) -> Result<Self> {
+        let config = FenvConfig::load(context)?;
+        let ca_bundle = match context.ca_bundle() {
+            Some(ca_bundle) => Some(context.fenv_dir().join(ca_bundle.path())),
+            None => config
+                .tls
+                .ca_bundle
+                .map(|ca_bundle| context.fenv_root().join(ca_bundle)),
+        };
+        if let Some(ca_bundle) = &ca_bundle {
+            if !ca_bundle.is_file() {
+                bail!("The CA bundle does not exist: `{ca_bundle}`")
+            }
+        }
+        Ok(Self {
+            ca_bundle: ca_bundle.map(|ca_bundle| ca_bundle.to_string()),
+            skips_verify: context.skips_tls_verify() || config.tls.insecure_skip_verify,
+        })
+    }
+
+    /// The `-c <name>=<value>` arguments of `git`, which must precede the subcommand.
+    fn config_args(&self) -> Vec<String> {
+        let mut args = vec![];
+        if let Some(ca_bundle) = &self.ca_bundle {
+            args.extend([String::from("-c"), format!("http.sslCAInfo={ca_bundle}")]);
+        }
+        if self.skips_verify {
+            args.extend([String::from("-c"), String::from("http.sslVerify=false")]);
+        }
+        args
+    }
 }
 
 pub struct GitCommandImpl {}
@@ -31,53 +181,290 @@ impl GitCommandImpl {
         );
         Ok(())
     }
-}
 
-impl GitCommand for GitCommandImpl {
-    fn clone_flutter_sdk_by_channel(&self, channel: &str, destination: &str) -> Result<()> {
+    fn clone_branch(&self, branch: &str, destination: &str, remote: &GitRemote) -> Result<()> {
         let mut command = Command::new("git");
         spawn_and_wait!(
             command
+                .args(remote.tls.config_args())
+                .args(remote.proxy.git_config_args())
+                .envs(remote.proxy.git_envs())
                 .arg("clone")
-                .args(["-c", "advice.detachedHead=false", "-b", channel])
-                .arg("https://github.com/flutter/flutter.git")
+                .args(["-c", "advice.detachedHead=false", "-b", branch])
+                .arg(&remote.url)
                 .arg(destination),
             "clone_flutter_sdk_by_channel",
-            "Failed to execute `git clone https://github.com/flutter/flutter.git`"
+            "Failed to execute `git clone {url}`",
+            url = remote.url
+        );
+        Ok(())
+    }
+
+    fn fetch_branch(&self, branch: &str, destination: &str, remote: &GitRemote) -> Result<()> {
+        let mut command = Command::new("git");
+        spawn_and_wait!(
+            command
+                .current_dir(destination)
+                .args(remote.tls.config_args())
+                .args(remote.proxy.git_config_args())
+                .envs(remote.proxy.git_envs())
+                .args(["fetch", "--tags"])
+                .arg(&remote.url)
+                .arg(format!("+refs/heads/{branch}:refs/remotes/origin/{branch}")),
+            "resume_flutter_sdk_by_channel",
+            "Failed to fetch `{url}` into `{destination}`",
+            url = remote.url
         );
         Ok(())
     }
+}
+
+impl GitCommand for GitCommandImpl {
+    fn clone_flutter_sdk_by_channel(
+        &self,
+        channel: &str,
+        destination: &str,
+        remote: &GitRemote,
+    ) -> Result<()> {
+        self.clone_branch(channel, destination, remote)
+            .map_err(|e| InstallFailureReason::Network.wrap(e))
+    }
 
-    fn clone_flutter_sdk_by_version(&self, version: &str, destination: &str) -> Result<()> {
-        self.clone_flutter_sdk_by_channel("stable", destination)?;
+    fn clone_flutter_sdk_by_version(
+        &self,
+        version: &str,
+        destination: &str,
+        remote: &GitRemote,
+    ) -> Result<()> {
+        self.clone_flutter_sdk_by_channel("stable", destination, remote)?;
         self.hard_reset_to_refs(destination, version)
+            .map_err(|e| InstallFailureReason::GitRefMissing.wrap(e))
     }
 
-    fn list_remote_sdks_by_tags(&self) -> Result<String> {
+    fn clone_flutter_sdk_by_commit(
+        &self,
+        sha: &str,
+        destination: &str,
+        remote: &GitRemote,
+    ) -> Result<()> {
+        // `git clone` fetches every branch, so the commits only on `master` are reachable too.
+        self.clone_flutter_sdk_by_channel("master", destination, remote)?;
+        self.hard_reset_to_refs(destination, sha)
+            .map_err(|e| InstallFailureReason::GitRefMissing.wrap(e))
+    }
+
+    fn resume_flutter_sdk_by_channel(
+        &self,
+        channel: &str,
+        destination: &str,
+        remote: &GitRemote,
+    ) -> Result<()> {
+        self.fetch_branch(channel, destination, remote)
+            .map_err(|e| InstallFailureReason::Network.wrap(e))?;
         let mut command = Command::new("git");
-        let git_output = spawn_and_capture!(
+        spawn_and_wait!(
             command
-                .arg("ls-remote")
-                .arg("--tags")
-                .arg("https://github.com/flutter/flutter.git")
-                .arg("**/*.*.*"),
-            "list_remote_sdks_by_tags",
-            "Failed to fetch remote tags from `https://github.com/flutter/flutter.git`"
+                .current_dir(destination)
+                .args(["checkout", "--force", "-B", channel, "--track"])
+                .arg(format!("origin/{channel}")),
+            "resume_flutter_sdk_by_channel",
+            "Failed to check out `{channel}` in `{destination}`"
         );
-        Ok(git_output)
+        Ok(())
+    }
+
+    fn resume_flutter_sdk_by_version(
+        &self,
+        version: &str,
+        destination: &str,
+        remote: &GitRemote,
+    ) -> Result<()> {
+        self.resume_flutter_sdk_by_channel("stable", destination, remote)?;
+        self.hard_reset_to_refs(destination, version)
+            .map_err(|e| InstallFailureReason::GitRefMissing.wrap(e))
     }
 
-    fn list_remote_sdks_by_branches(&self) -> Result<String> {
+    fn list_remote_sdks_by_tags(&self, remote: &GitRemote) -> Result<String> {
+        remote.retry.run("list_remote_sdks_by_tags", || {
+            list_remote_sdks_by_tags_once(remote).map_err(|e| InstallFailureReason::Network.wrap(e))
+        })
+    }
+
+    fn list_remote_sdks_by_branches(&self, remote: &GitRemote) -> Result<String> {
+        remote.retry.run("list_remote_sdks_by_branches", || {
+            list_remote_sdks_by_branches_once(remote)
+                .map_err(|e| InstallFailureReason::Network.wrap(e))
+        })
+    }
+
+    fn rev_parse_head(&self, working_dir: &str) -> Result<String> {
+        let mut command = Command::new("git");
+        let git_output = spawn_and_capture!(
+            command.current_dir(working_dir).args(["rev-parse", "HEAD"]),
+            "rev_parse_head",
+            "Failed to read the current commit of `{working_dir}`"
+        );
+        Ok(git_output.trim().to_string())
+    }
+
+    fn rev_parse_commit(&self, working_dir: &str, rev: &str) -> Result<String> {
         let mut command = Command::new("git");
         let git_output = spawn_and_capture!(
             command
-                .arg("ls-remote")
-                .args(["--heads", "--refs"])
-                .arg("https://github.com/flutter/flutter.git")
-                .args(["stable", "dev", "beta", "master"]),
-            "list_remote_sdks_by_branches",
-            "Failed to fetch remote branches from `https://github.com/flutter/flutter.git`"
+                .current_dir(working_dir)
+                .args(["rev-parse", "--verify", "--quiet"])
+                .arg(format!("{rev}^{{commit}}")),
+            "rev_parse_commit",
+            "Failed to read the commit of `{rev}` in `{working_dir}`"
+        );
+        Ok(git_output.trim().to_string())
+    }
+
+    fn pull_ff_only(
+        &self,
+        working_dir: &str,
+        tls: &GitTlsOptions,
+        proxy: &ProxyOptions,
+    ) -> Result<()> {
+        let mut command = Command::new("git");
+        spawn_and_wait!(
+            command
+                .current_dir(working_dir)
+                .args(tls.config_args())
+                .args(proxy.git_config_args())
+                .envs(proxy.git_envs())
+                .args(["pull", "--ff-only"]),
+            "pull_ff_only",
+            "Failed to fast-forward `{working_dir}`"
         );
-        Ok(git_output)
+        Ok(())
+    }
+}
+
+/// Lists the tags of the versions in `remote` by `git ls-remote` once.
+fn list_remote_sdks_by_tags_once(remote: &GitRemote) -> Result<String> {
+    let mut command = Command::new("git");
+    let git_output = spawn_and_capture!(
+        command
+            .args(remote.tls.config_args())
+            .args(remote.proxy.git_config_args())
+            .envs(remote.proxy.git_envs())
+            .arg("ls-remote")
+            .arg("--tags")
+            .arg(&remote.url)
+            .arg("**/*.*.*"),
+        "list_remote_sdks_by_tags",
+        "Failed to fetch remote tags from `{url}`",
+        url = remote.url
+    );
+    Ok(git_output)
+}
+
+/// Lists the branches of the channels in `remote` by `git ls-remote` once.
+fn list_remote_sdks_by_branches_once(remote: &GitRemote) -> Result<String> {
+    let mut command = Command::new("git");
+    let git_output = spawn_and_capture!(
+        command
+            .args(remote.tls.config_args())
+            .args(remote.proxy.git_config_args())
+            .envs(remote.proxy.git_envs())
+            .arg("ls-remote")
+            .args(["--heads", "--refs"])
+            .arg(&remote.url)
+            .args(["stable", "dev", "beta", "master"]),
+        "list_remote_sdks_by_branches",
+        "Failed to fetch remote branches from `{url}`",
+        url = remote.url
+    );
+    Ok(git_output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GitTlsOptions;
+    use crate::{context::FenvContext, service::macros::test_with_context};
+
+    #[test]
+    fn test_config_args() {
+        assert!(GitTlsOptions::default().config_args().is_empty());
+        assert_eq!(
+            GitTlsOptions {
+                ca_bundle: Some(String::from("/etc/ssl/corp.pem")),
+                skips_verify: true,
+            }
+            .config_args(),
+            [
+                "-c",
+                "http.sslCAInfo=/etc/ssl/corp.pem",
+                "-c",
+                "http.sslVerify=false"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_reads_config_file() {
+        test_with_context(|context, _| {
+            // setup
+            let ca_bundle = context.fenv_root().join("corp.pem");
+            ca_bundle.writeln("").unwrap();
+            context
+                .fenv_config_file()
+                .writeln(format!(
+                    r#"{{"tls": {{"caBundle": "{ca_bundle}", "insecureSkipVerify": true}}}}"#
+                ))
+                .unwrap();
+
+            // execution
+            let options = GitTlsOptions::resolve(context).unwrap();
+
+            // validation
+            assert_eq!(
+                options,
+                GitTlsOptions {
+                    ca_bundle: Some(ca_bundle.to_string()),
+                    skips_verify: true,
+                }
+            );
+        })
+    }
+
+    #[test]
+    fn test_resolve_relative_ca_bundle_of_config_file_against_fenv_root() {
+        test_with_context(|context, _| {
+            // setup
+            let ca_bundle = context.fenv_root().join("certs").join("corp.pem");
+            ca_bundle.writeln("").unwrap();
+            context
+                .fenv_config_file()
+                .writeln(r#"{"tls": {"caBundle": "certs/corp.pem"}}"#)
+                .unwrap();
+
+            // execution
+            let options = GitTlsOptions::resolve(context).unwrap();
+
+            // validation
+            assert_eq!(options.ca_bundle, Some(ca_bundle.to_string()));
+        })
+    }
+
+    #[test]
+    fn test_resolve_fails_if_ca_bundle_does_not_exist() {
+        test_with_context(|context, _| {
+            // setup
+            context
+                .fenv_config_file()
+                .writeln(r#"{"tls": {"caBundle": "/no/such/corp.pem"}}"#)
+                .unwrap();
+
+            // execution
+            let result = GitTlsOptions::resolve(context);
+
+            // validation
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                "The CA bundle does not exist: `/no/such/corp.pem`"
+            );
+        })
     }
 }
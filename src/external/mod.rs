@@ -1,2 +1,6 @@
+pub mod dart_command;
+pub mod downloader;
 pub mod flutter_command;
 pub mod git_command;
+pub mod progress;
+pub mod proxy;
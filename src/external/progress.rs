@@ -0,0 +1,352 @@
+use anyhow::{bail, Context as _, Result};
+use std::{
+    io::{BufRead, BufReader, IsTerminal, Write},
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::mpsc::{self, RecvTimeoutError},
+    time::{Duration, Instant},
+};
+
+const SPINNER_FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+
+/// How often the spinner is redrawn and the interruption is checked.
+const TICK: Duration = Duration::from_millis(100);
+
+/// How long the interrupted child process is given to exit before it is killed.
+const GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// How [`run_with_options`] runs a child process besides [`run_with_progress`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RunOptions {
+    /// The child process is terminated if it does not exit in time.
+    pub timeout: Option<Duration>,
+
+    /// Written into stdin of the child process over and over until it is closed, such as `y\n`
+    /// to answer every prompt as `yes` does.
+    pub repeated_input: Option<&'static str>,
+}
+
+/// Runs `command` until it exits, relaying its stdout lines into `progress`.
+///
+/// If stderr of `fenv` is a terminal, a spinner with the elapsed time is drawn below the relayed
+/// lines. The child process runs in its own process group, and the whole group is terminated
+/// if `fenv` is interrupted by `SIGINT` or `SIGTERM`, so that no `dart` process is left behind.
+pub fn run_with_progress(
+    command: &mut Command,
+    label: &str,
+    progress: &mut dyn Write,
+) -> Result<ExitStatus> {
+    run_with_options(command, label, RunOptions::default(), progress)
+}
+
+/// Same as [`run_with_progress`], but with the timeout and the input of the child process.
+pub fn run_with_options(
+    command: &mut Command,
+    label: &str,
+    options: RunOptions,
+    progress: &mut dyn Write,
+) -> Result<ExitStatus> {
+    let animates = std::io::stderr().is_terminal();
+    command.stdout(Stdio::piped());
+    if options.repeated_input.is_some() {
+        command.stdin(Stdio::piped());
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    log::info!(
+        "run_with_progress(): command: program={:?}: args={:?}",
+        command.get_program(),
+        command.get_args()
+    );
+
+    let guard = interruption::Guard::install();
+    let mut child = command
+        .spawn()
+        .with_context(|| format!("Failed to execute `{label}`"))?;
+    if let (Some(input), Some(mut stdin)) = (options.repeated_input, child.stdin.take()) {
+        // Stops when the child process closes stdin or exits.
+        std::thread::spawn(move || while stdin.write_all(input.as_bytes()).is_ok() {});
+    }
+    let stdout = child.stdout.take().expect("stdout must be piped");
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout)
+            .lines()
+            .map_while(std::io::Result::ok)
+        {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let started_at = Instant::now();
+    let mut frame = 0;
+    let mut stdout_is_closed = false;
+    let status = loop {
+        if stdout_is_closed {
+            std::thread::sleep(TICK);
+        } else {
+            match receiver.recv_timeout(TICK) {
+                Ok(line) => {
+                    if animates {
+                        write!(progress, "\r\x1b[2K")?;
+                    }
+                    writeln!(progress, "{line}")?;
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => stdout_is_closed = true,
+            }
+        }
+        if guard.is_interrupted() {
+            if animates {
+                write!(progress, "\r\x1b[2K")?;
+            }
+            terminate(&mut child);
+            bail!("`{label}` is interrupted")
+        }
+        if let Some(timeout) = options
+            .timeout
+            .filter(|timeout| started_at.elapsed() > *timeout)
+        {
+            if animates {
+                write!(progress, "\r\x1b[2K")?;
+            }
+            terminate(&mut child);
+            bail!("`{label}` timed out after {}s", timeout.as_secs())
+        }
+        if let Some(status) = child
+            .try_wait()
+            .with_context(|| format!("Failed to wait for `{label}`"))?
+        {
+            break status;
+        }
+        if animates {
+            let elapsed = started_at.elapsed().as_secs();
+            let spinner = SPINNER_FRAMES[frame % SPINNER_FRAMES.len()];
+            write!(progress, "\r\x1b[2K{spinner} {label} ({elapsed}s)")?;
+            progress.flush()?;
+            frame += 1;
+        }
+    };
+    // Relays the lines which are written just before the exit. The pipe may be kept open by the
+    // detached grandchildren, so it is not waited to be closed.
+    let remaining = std::iter::from_fn(|| receiver.recv_timeout(TICK).ok());
+    for line in remaining {
+        if animates {
+            write!(progress, "\r\x1b[2K")?;
+        }
+        writeln!(progress, "{line}")?;
+    }
+    if animates {
+        write!(progress, "\r\x1b[2K")?;
+        writeln!(progress, "{label} took {}s", started_at.elapsed().as_secs())?;
+    }
+    Ok(status)
+}
+
+/// Terminates the process group of `child`, and kills it if it does not exit in time.
+fn terminate(child: &mut Child) {
+    #[cfg(unix)]
+    {
+        use nix::{
+            sys::signal::{killpg, Signal},
+            unistd::Pid,
+        };
+        let group = Pid::from_raw(child.id() as i32);
+        if let Err(e) = killpg(group, Signal::SIGTERM) {
+            log::debug!("terminate(): Failed to send SIGTERM to `{group}`: {e}");
+        }
+        let started_at = Instant::now();
+        while started_at.elapsed() < GRACE_PERIOD {
+            if let Ok(Some(_)) = child.try_wait() {
+                // Kills the remaining processes of the group such as the orphaned `dart`.
+                let _ = killpg(group, Signal::SIGKILL);
+                return;
+            }
+            std::thread::sleep(TICK);
+        }
+        let _ = killpg(group, Signal::SIGKILL);
+    }
+    if let Err(e) = child.kill() {
+        log::debug!("terminate(): Failed to kill the child process: {e}");
+    }
+    let _ = child.wait();
+}
+
+/// Records `SIGINT` and `SIGTERM` instead of terminating `fenv` while a child process runs.
+///
+/// The handlers are shared by the child processes running in parallel: they are installed by the
+/// first guard, and the previous ones are restored when the last guard is dropped.
+pub(crate) mod interruption {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    #[cfg(unix)]
+    use std::sync::Mutex;
+
+    static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+    /// The number of the living guards, and the handlers installed before the first one.
+    #[cfg(unix)]
+    static INSTALLED: Mutex<(
+        usize,
+        Vec<(nix::sys::signal::Signal, nix::sys::signal::SigAction)>,
+    )> = Mutex::new((0, Vec::new()));
+
+    /// Installs the signal handlers, and restores the previous ones when dropped.
+    pub struct Guard;
+
+    impl Guard {
+        pub fn install() -> Self {
+            #[cfg(unix)]
+            {
+                use nix::sys::signal::{sigaction, SaFlags, SigAction, SigHandler, SigSet, Signal};
+
+                extern "C" fn on_signal(_: nix::libc::c_int) {
+                    INTERRUPTED.store(true, Ordering::SeqCst);
+                }
+
+                let mut installed = INSTALLED.lock().unwrap_or_else(|e| e.into_inner());
+                let (count, previous) = &mut *installed;
+                if *count == 0 {
+                    INTERRUPTED.store(false, Ordering::SeqCst);
+                    let action = SigAction::new(
+                        SigHandler::Handler(on_signal),
+                        SaFlags::SA_RESTART,
+                        SigSet::empty(),
+                    );
+                    *previous = [Signal::SIGINT, Signal::SIGTERM]
+                        .into_iter()
+                        .filter_map(|signal| {
+                            // SAFETY: `on_signal` only stores into an atomic, which is signal-safe.
+                            unsafe { sigaction(signal, &action) }
+                                .ok()
+                                .map(|previous| (signal, previous))
+                        })
+                        .collect();
+                }
+                *count += 1;
+            }
+            #[cfg(not(unix))]
+            INTERRUPTED.store(false, Ordering::SeqCst);
+            Self
+        }
+
+        pub fn is_interrupted(&self) -> bool {
+            INTERRUPTED.load(Ordering::SeqCst)
+        }
+    }
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            #[cfg(unix)]
+            {
+                let mut installed = INSTALLED.lock().unwrap_or_else(|e| e.into_inner());
+                let (count, previous) = &mut *installed;
+                *count -= 1;
+                if *count == 0 {
+                    INTERRUPTED.store(false, Ordering::SeqCst);
+                    for (signal, previous) in previous.drain(..) {
+                        // SAFETY: restores the handler which was installed before the first guard.
+                        let _ = unsafe { nix::sys::signal::sigaction(signal, &previous) };
+                    }
+                }
+            }
+        }
+    }
+
+    /// Behaves as if `SIGINT` is received.
+    #[cfg(test)]
+    pub fn interrupt() {
+        INTERRUPTED.store(true, Ordering::SeqCst);
+    }
+
+    /// Serializes the tests which interrupt or hold a guard while the other ones run, because
+    /// the interruption is shared by the whole process.
+    #[cfg(test)]
+    pub static SERIAL: std::sync::Mutex<()> = std::sync::Mutex::new(());
+}
+
+#[cfg(unix)]
+#[cfg(test)]
+mod tests_unix {
+    use super::{interruption, run_with_options, run_with_progress, RunOptions};
+    use std::{
+        process::Command,
+        time::{Duration, Instant},
+    };
+
+    #[test]
+    fn test_run_with_progress() {
+        // relays the stdout lines.
+        let mut progress: Vec<u8> = vec![];
+        let status = run_with_progress(
+            Command::new("sh").args(["-c", "echo first; echo second; exit 3"]),
+            "sh",
+            &mut progress,
+        )
+        .unwrap();
+        assert_eq!(status.code(), Some(3));
+        assert_eq!(String::from_utf8(progress).unwrap(), "first\nsecond\n");
+
+        // answers every prompt.
+        let mut progress: Vec<u8> = vec![];
+        let options = RunOptions {
+            repeated_input: Some("y\n"),
+            ..RunOptions::default()
+        };
+        let status = run_with_options(
+            Command::new("sh").args(["-c", "read a; read b; echo \"$a$b\""]),
+            "sh",
+            options,
+            &mut progress,
+        )
+        .unwrap();
+        assert!(status.success());
+        assert_eq!(String::from_utf8(progress).unwrap(), "yy\n");
+
+        // terminates the child process on the timeout.
+        let options = RunOptions {
+            timeout: Some(Duration::from_millis(300)),
+            ..RunOptions::default()
+        };
+        let started_at = Instant::now();
+        let result = run_with_options(
+            Command::new("sh").args(["-c", "sleep 30"]),
+            "sh",
+            options,
+            &mut vec![],
+        );
+        assert_eq!(result.unwrap_err().to_string(), "`sh` timed out after 0s");
+        assert!(started_at.elapsed() < Duration::from_secs(10));
+
+        // terminates the process group on the interruption.
+        let _serial = interruption::SERIAL
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        std::thread::spawn(|| {
+            std::thread::sleep(Duration::from_millis(500));
+            interruption::interrupt();
+        });
+        let mut progress: Vec<u8> = vec![];
+        let started_at = Instant::now();
+        let result = run_with_progress(
+            Command::new("sh").args(["-c", "sleep 30 & echo $!; wait"]),
+            "sh",
+            &mut progress,
+        );
+        assert_eq!(result.unwrap_err().to_string(), "`sh` is interrupted");
+        assert!(started_at.elapsed() < Duration::from_secs(10));
+        #[cfg(target_os = "linux")]
+        {
+            let pid = String::from_utf8(progress).unwrap().trim().to_string();
+            std::thread::sleep(Duration::from_millis(200));
+            let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).unwrap_or_default();
+            assert!(
+                stat.is_empty() || stat.contains(") Z "),
+                "the grandchild must be terminated: {stat}"
+            );
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{config::FenvConfig, context::FenvContext};
+use anyhow::{Ok, Result};
+
+/// Which proxy `curl` and `git` access the remote resources through.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ProxyOptions {
+    /// The URL of the proxy such as `http://proxy.example.com:8080`.
+    pub url: Option<String>,
+
+    /// The comma-separated hosts accessed without the proxy, such as `localhost,.example.com`.
+    pub no_proxy: Option<String>,
+}
+
+impl ProxyOptions {
+    /// Resolves the options from the environment variables and `{fenv_root}/config.json`.
+    /// The environment variables take precedence over the configuration file.
+    pub fn resolve(context: &impl FenvContext) -> Result<Self> {
+        let config = FenvConfig::load(context)?;
+        Ok(Self {
+            url: context.https_proxy().or(config.proxy.url),
+            no_proxy: context.no_proxy().or(config.proxy.no_proxy),
+        })
+    }
+
+    /// The arguments of `curl` to go through the proxy.
+    pub fn curl_args(&self) -> Vec<String> {
+        let mut args = vec![];
+        if let Some(url) = &self.url {
+            args.extend([String::from("--proxy"), url.clone()]);
+        }
+        if let Some(no_proxy) = &self.no_proxy {
+            args.extend([String::from("--noproxy"), no_proxy.clone()]);
+        }
+        args
+    }
+
+    /// The `-c <name>=<value>` arguments of `git`, which must precede the subcommand.
+    pub fn git_config_args(&self) -> Vec<String> {
+        match &self.url {
+            Some(url) => vec![String::from("-c"), format!("http.proxy={url}")],
+            None => vec![],
+        }
+    }
+
+    /// The environment variables of `git`, which has no configuration of the hosts to access
+    /// without the proxy.
+    pub fn git_envs(&self) -> Vec<(&'static str, String)> {
+        match &self.no_proxy {
+            Some(no_proxy) => vec![
+                ("NO_PROXY", no_proxy.clone()),
+                ("no_proxy", no_proxy.clone()),
+            ],
+            None => vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProxyOptions;
+    use crate::{context::FenvContext, service::macros::test_with_context};
+
+    #[test]
+    fn test_resolve_reads_config_file() {
+        test_with_context(|context, _| {
+            // setup
+            context
+                .fenv_config_file()
+                .writeln(
+                    r#"{"proxy": {"url": "http://proxy.example.com:8080", "noProxy": "localhost"}}"#,
+                )
+                .unwrap();
+
+            // execution
+            let options = ProxyOptions::resolve(context).unwrap();
+
+            // validation
+            assert_eq!(
+                options.curl_args(),
+                [
+                    "--proxy",
+                    "http://proxy.example.com:8080",
+                    "--noproxy",
+                    "localhost"
+                ]
+            );
+            assert_eq!(
+                options.git_config_args(),
+                ["-c", "http.proxy=http://proxy.example.com:8080"]
+            );
+            assert_eq!(
+                options.git_envs(),
+                [
+                    ("NO_PROXY", String::from("localhost")),
+                    ("no_proxy", String::from("localhost"))
+                ]
+            );
+        })
+    }
+}
@@ -1,24 +1,67 @@
 pub mod args;
+pub mod audit;
+pub mod config;
 pub mod context;
+pub mod deprecation;
 pub mod external;
+pub mod metrics;
+pub mod native_shim;
 pub mod sdk_service;
 pub mod service;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
 pub mod util;
+pub mod workspaces;
 
 use crate::{
-    args::FenvSubcommands,
+    args::{FenvMigrateSubcommands, FenvSelfSubcommands, FenvSubcommands},
     service::{
+        alias::alias_service::FenvAliasService,
+        args_prepend::args_prepend_service::FenvArgsPrependService,
+        audit::audit_service::FenvAuditService,
+        cache::cache_service::FenvCacheService,
+        check_flutter_args::check_flutter_args_service::FenvCheckFlutterArgsService,
+        complete_executables::complete_executables_service::FenvCompleteExecutablesService,
+        complete_versions::complete_versions_service::FenvCompleteVersionsService,
+        complete_workspaces::complete_workspaces_service::FenvCompleteWorkspacesService,
         completions::completions_service::FenvCompletionsService,
-        global::global_service::FenvGlobalService, init::init_service::FenvInitService,
-        install::install_service::FenvInstallService, latest::latest_service::FenvLatestService,
+        context::context_service::FenvContextService,
+        current::current_service::FenvCurrentService,
+        doctor::doctor_service::FenvDoctorService,
+        env::env_service::FenvEnvService,
+        exec::exec_service::FenvExecService,
+        global::global_service::FenvGlobalService,
+        help::help_service::{self, FenvHelpService},
+        info::info_service::FenvInfoService,
+        init::init_service::FenvInitService,
+        install::install_service::FenvInstallService,
+        latest::latest_service::FenvLatestService,
         list_remote::list_remote_service::FenvListRemoteService,
-        local::local_service::FenvLocalService, prefix::prefix_service::FenvPrefixService,
-        root::root_service::FenvRootService, service::Service,
+        local::local_service::FenvLocalService,
+        migrate_fvm::migrate_fvm_service::FenvMigrateFvmService,
+        new::new_service::FenvNewService,
+        normalize_names::normalize_names_service::FenvNormalizeNamesService,
+        pin::pin_service::FenvPinService,
+        prefix::prefix_service::FenvPrefixService,
+        print_cache_key::print_cache_key_service::FenvPrintCacheKeyService,
+        project_env::project_env_service::FenvProjectEnvService,
+        prune::prune_service::FenvPruneService,
+        ready::ready_service::FenvReadyService,
+        rehash::rehash_service::FenvRehashService,
+        resolve_version::resolve_version_service::FenvResolveVersionService,
+        root::root_service::FenvRootService,
+        self_update::self_update_service::FenvSelfUpdateService,
+        service::Service,
+        setup::setup_service::FenvSetupService,
+        shell::shell_service::FenvShellService,
         uninstall::uninstall_service::FenvUninstallService,
+        upgrade::upgrade_service::FenvUpgradeService,
+        verify::verify_service::FenvVerifyService,
         version::version_service::FenvVersionService,
         version_file::version_file_service::FenvVersionFileService,
         version_name::version_name_service::FenvVersionNameService,
-        versions::versions_service::FenvVersionsService, which::which_service::FenvWhichService,
+        versions::versions_service::FenvVersionsService,
+        which::which_service::FenvWhichService,
         workspace::workspace_service::FenvWorkspaceService,
     },
 };
@@ -26,12 +69,24 @@ use anyhow::Result;
 use args::FenvArgs;
 use clap::{Command, CommandFactory, FromArgMatches};
 use context::FenvContext;
+use deprecation::Deprecation;
+use external::git_command::GitTlsOptions;
 use indoc::indoc;
 use log::debug;
 use sdk_service::sdk_service::SdkService;
 use std::ffi::OsString;
 use util::io::ConsoleOutput;
 
+/// The command line of `fenv` parsed by [`parse_command_line`].
+pub struct FenvCommandLine {
+    pub args: FenvArgs,
+    /// The name of the subcommand, such as `install` of `fenv install 3.22`.
+    pub subcommand: String,
+    deprecations: Vec<&'static Deprecation>,
+    /// The command line recorded in the audit log.
+    line: String,
+}
+
 pub fn try_run<I, T, C: FenvContext, S: SdkService, OUT: std::io::Write, ERR: std::io::Write>(
     args: I,
     context: &C,
@@ -42,10 +97,57 @@ where
     I: IntoIterator<Item = T>,
     T: Into<OsString> + Clone,
 {
-    let args = matches_args(args);
+    run(&parse_command_line(args), context, sdk_service, output)
+}
+
+/// Runs the command line parsed by [`parse_command_line`].
+pub fn run<C: FenvContext, S: SdkService, OUT: std::io::Write, ERR: std::io::Write>(
+    command_line: &FenvCommandLine,
+    context: &C,
+    sdk_service: &S,
+    output: &mut dyn ConsoleOutput<OUT, ERR>,
+) -> Result<()> {
+    let FenvCommandLine {
+        args, deprecations, ..
+    } = command_line;
 
     debug!("arguments = {args:?}");
 
+    if args.quiet {
+        output.options_mut().quiet = true;
+    }
+    if let Some(error_format) = args.error_format {
+        output.options_mut().error_format = error_format;
+    }
+    if !deprecations.is_empty() {
+        if !context.suppresses_deprecations() {
+            for deprecation in deprecations {
+                output.warning(&deprecation.message())?;
+            }
+        }
+        if let Err(e) = deprecation::record_usages(context, deprecations) {
+            debug!("{e}");
+        }
+    }
+
+    let offline_context;
+    let context = if args.offline && !context.is_offline() {
+        offline_context = context.to_offline();
+        &offline_context
+    } else {
+        context
+    };
+
+    if !context.is_offline()
+        && accesses_network(&args.command)
+        && GitTlsOptions::resolve(context).is_ok_and(|tls| tls.skips_verify)
+    {
+        output.security_warning(
+            "TLS certificate verification is disabled. \
+            The Flutter SDK may be tampered with by anyone on the network",
+        )?;
+    }
+
     macro_rules! execute_service {
         ($name: ty, $args: expr) => {
             <$name>::new($args.clone()).execute(context, sdk_service, output)
@@ -55,14 +157,37 @@ where
         };
     }
 
-    match &args.command {
+    let result = match &args.command {
+        FenvSubcommands::Alias(sub_args) => execute_service!(FenvAliasService, sub_args),
+        FenvSubcommands::ArgsPrepend(sub_args) => {
+            execute_service!(FenvArgsPrependService, sub_args)
+        }
+        FenvSubcommands::Audit(sub_args) => execute_service!(FenvAuditService, sub_args),
+        FenvSubcommands::Cache(sub_args) => execute_service!(FenvCacheService, sub_args),
+        FenvSubcommands::Info(sub_args) => execute_service!(FenvInfoService, sub_args),
         FenvSubcommands::Init(sub_args) => execute_service!(FenvInitService, sub_args),
         FenvSubcommands::Install(sub_args) => execute_service!(FenvInstallService, sub_args),
-        FenvSubcommands::Versions | FenvSubcommands::List => execute_service!(FenvVersionsService),
+        FenvSubcommands::Versions(sub_args) | FenvSubcommands::List(sub_args) => {
+            execute_service!(FenvVersionsService, sub_args)
+        }
+        FenvSubcommands::CheckFlutterArgs(sub_args) => {
+            execute_service!(FenvCheckFlutterArgsService, sub_args)
+        }
+        FenvSubcommands::CompleteExecutables => execute_service!(FenvCompleteExecutablesService),
+        FenvSubcommands::CompleteWorkspaces => execute_service!(FenvCompleteWorkspacesService),
+        FenvSubcommands::CompleteVersions(sub_args) => {
+            execute_service!(FenvCompleteVersionsService, sub_args)
+        }
         FenvSubcommands::Completions(sub_args) => {
             execute_service!(FenvCompletionsService, sub_args)
         }
+        FenvSubcommands::Context(sub_args) => execute_service!(FenvContextService, sub_args),
+        FenvSubcommands::Current(sub_args) => execute_service!(FenvCurrentService, sub_args),
+        FenvSubcommands::Doctor => execute_service!(FenvDoctorService),
+        FenvSubcommands::Env(sub_args) => execute_service!(FenvEnvService, sub_args),
+        FenvSubcommands::Exec(sub_args) => execute_service!(FenvExecService, sub_args),
         FenvSubcommands::Global(sub_args) => execute_service!(FenvGlobalService, sub_args),
+        FenvSubcommands::Help(sub_args) => execute_service!(FenvHelpService, sub_args),
         FenvSubcommands::VersionFile(sub_args) => {
             execute_service!(FenvVersionFileService, sub_args)
         }
@@ -72,12 +197,56 @@ where
         FenvSubcommands::Latest(sub_args) => execute_service!(FenvLatestService, sub_args),
         FenvSubcommands::ListRemote(sub_args) => execute_service!(FenvListRemoteService, sub_args),
         FenvSubcommands::Local(sub_args) => execute_service!(FenvLocalService, sub_args),
+        FenvSubcommands::NormalizeNames(sub_args) => {
+            execute_service!(FenvNormalizeNamesService, sub_args)
+        }
         FenvSubcommands::Uninstall(sub_args) => execute_service!(FenvUninstallService, sub_args),
+        FenvSubcommands::Prune(sub_args) => execute_service!(FenvPruneService, sub_args),
+        FenvSubcommands::Upgrade(sub_args) => execute_service!(FenvUpgradeService, sub_args),
+        FenvSubcommands::Verify(sub_args) => execute_service!(FenvVerifyService, sub_args),
         FenvSubcommands::Version(sub_args) => execute_service!(FenvVersionService, sub_args),
+        FenvSubcommands::Pin(sub_args) => execute_service!(FenvPinService, sub_args),
         FenvSubcommands::Prefix(sub_args) => execute_service!(FenvPrefixService, sub_args),
+        FenvSubcommands::PrintCacheKey(sub_args) => {
+            execute_service!(FenvPrintCacheKeyService, sub_args)
+        }
+        FenvSubcommands::ProjectEnv => execute_service!(FenvProjectEnvService),
+        FenvSubcommands::New(sub_args) => execute_service!(FenvNewService, sub_args),
+        FenvSubcommands::Setup(sub_args) => execute_service!(FenvSetupService, sub_args),
+        FenvSubcommands::Shell(sub_args) => execute_service!(FenvShellService, sub_args),
         FenvSubcommands::Which(sub_args) => execute_service!(FenvWhichService, sub_args),
         FenvSubcommands::Workspace(sub_args) => execute_service!(FenvWorkspaceService, sub_args),
+        FenvSubcommands::Ready(sub_args) => execute_service!(FenvReadyService, sub_args),
+        FenvSubcommands::Rehash => execute_service!(FenvRehashService),
+        FenvSubcommands::ResolveVersion(sub_args) => {
+            execute_service!(FenvResolveVersionService, sub_args)
+        }
         FenvSubcommands::Root => execute_service!(FenvRootService),
+        FenvSubcommands::Migrate(FenvMigrateSubcommands::Fvm(sub_args)) => {
+            execute_service!(FenvMigrateFvmService, sub_args)
+        }
+        FenvSubcommands::SelfCommand(FenvSelfSubcommands::Update(sub_args)) => {
+            execute_service!(FenvSelfUpdateService, sub_args)
+        }
+    };
+    if let Err(e) = audit::record(context, &args.command, &command_line.line, &result) {
+        debug!("{e}");
+    }
+    result
+}
+
+/// Whether the command may clone, fetch, or download from the remote repository.
+fn accesses_network(command: &FenvSubcommands) -> bool {
+    #[allow(deprecated)]
+    match command {
+        FenvSubcommands::Install(_)
+        | FenvSubcommands::ListRemote(_)
+        | FenvSubcommands::Upgrade(_)
+        | FenvSubcommands::New(_)
+        | FenvSubcommands::Setup(_)
+        | FenvSubcommands::SelfCommand(FenvSelfSubcommands::Update(_)) => true,
+        FenvSubcommands::Latest(args) => args.from_remote || args.known,
+        _ => false,
     }
 }
 
@@ -95,20 +264,49 @@ pub fn build_command() -> Command {
             Show the list of the available Flutter SDKs
         fenv list-remote
             Same as `fenv install --list`
+            `>` marks the selected version and `*` marks the other installed versions
         fenv latest [--remote|-r] 3
             Show the latest version name of Flutter `3.x.y`
+        fenv latest --remote --json 3.19 3.22 stable
+            Show a JSON object from each prefix to its latest version, or `null` if not found
+        fenv latest --remote --allow-stale 3
+            Same as `fenv latest --remote 3`, but answer from the expired cache when offline
+        fenv list-remote --include-beta
+            Also show the pre-releases published only to the beta channel such as `3.23.0-0.1.pre`
+        fenv list-remote --json
+            Show the available Flutter SDKs as a JSON array with their commits, refs,
+              and whether each of them is installed
+        fenv list-remote --bare --with-sha
+            Show the available Flutter SDKs with their full commit SHAs after a tab
 
       [List up installed Flutter SDK]
         fenv versions
             Show the list of the installed Flutter SDKs
         fenv list
             Same as `fenv versions`
+        fenv versions --machine
+            Show the installed Flutter SDKs as tab-separated lines for scripts, with
+              their kinds, paths, installation times, and Dart versions
+        fenv versions --json
+            Show the installed Flutter SDKs as a JSON array with their kinds, paths,
+              and whether each of them is the selected one
+        fenv versions --tree
+            Show the installed Flutter SDKs grouped by their minor versions, marking
+              the selected one with `*`
         fenv latest 3
             Show the latest installed version name of the Flutter `3.x.y`
+        fenv latest --quiet --exit-code 3
+            Print nothing but exit with a non-zero code if no Flutter `3.x.y` is installed
+        fenv latest '>=3.7 <3.16'
+            Show the latest installed version name in the range. `^3.10` and `3.*` are
+              also accepted by the commands and the version files which take a prefix
 
       [Install Flutter SDK]
         fenv install
             Install the Flutter version specified in the nearest `.flutter-version` file
+        fenv install --dir packages/app
+            Install the Flutter version specified in the nearest `.flutter-version` file
+              from `packages/app`
         fenv install stable
             Install the latest snapshot of `stable` channel
         fenv install s
@@ -119,6 +317,36 @@ pub fn build_command() -> Command {
             Install the latest version of Flutter `3.7.x`
         fenv install 3
             Install the latest version of Flutter `3.x.y`
+        fenv install 761747bfc538b5af34aa0d3fac380f1bc331ec49
+            Install Flutter at the commit as `761747bfc5`, which may be on any branch
+        fenv install --include-beta 3.23
+            Install the latest pre-release of Flutter `3.23` such as `3.23.0-0.1.pre`
+              if it is not released to the stable channel yet
+        fenv install --precache-android 3.22
+            Install Flutter `3.22.x` and download the artifacts of Android only.
+              `--precache-ios` and `--precache-web` can be combined
+        fenv install --accept-android-licenses 3.22
+            Install Flutter `3.22.x` and accept the Android SDK licenses for CI machines
+        fenv install --force 3.22
+            Remove the installed Flutter `3.22.x`, even if broken, and install it again
+        fenv install --jobs 3 3.19 3.22 stable
+            Install Flutter `3.19.x`, `3.22.x`, and `stable` at once, prefixing their
+              progress with each prefix such as `[3.22] `
+        fenv install --archive flutter_linux_3.22.2-stable.tar.xz 3.22.2
+            Install the release archive downloaded beforehand as `3.22.2` without the network
+        fenv --offline install 3.22
+            Install Flutter `3.22.x` from the release archives in `$FENV_ROOT/cache/downloads`
+              without the network. `fenv --offline list-remote` and
+              `fenv --offline latest --remote` answer from the cached list
+        fenv install --git-url <URL> [--namespace <NAME>] 3.19
+            Install the latest version of Flutter `3.19.x` tagged in the fork at <URL>
+              as `fork-3.19.x`, or `<NAME>-3.19.x`, which `fork-3.19` selects
+
+      [Upgrade Flutter SDK]
+        fenv upgrade
+            Fast-forward every installed channel such as `stable` to its latest commit
+        fenv upgrade --precache beta
+            Fast-forward `beta` and run `flutter precache` if it is advanced
 
       [Uninstall Flutter SDK]
         fenv uninstall stable
@@ -129,6 +357,26 @@ pub fn build_command() -> Command {
             Uninstall every installed version of Flutter `3.7.x`
         fenv 3
             Uninstall every installed version of Flutter `3.x.y`
+        fenv uninstall --broken
+            Uninstall every incomplete installation and every installation without `bin/flutter`
+        fenv uninstall ~/.fenv/versions/<DIR>
+            Uninstall the given directory even if its name is not a valid version
+        fenv uninstall --all
+            Uninstall every installed version and channel, and print the reclaimed disk space
+        fenv uninstall --all-except 3.22 --all-except stable
+            Same as above, but keep `stable` and every installed version of Flutter `3.22.x`
+        fenv prune ~/projects/app ~/projects/lib
+            Uninstall every version which is not used by the given projects,
+              the global version, or `$FENV_VERSION`
+        fenv prune --dry-run
+            Print the versions which are not used by the workspaces set up by `fenv workspace`
+
+      [Normalize the names of installed Flutter SDK]
+        fenv normalize-names
+            Rename installed SDKs such as `v1.12.13+hotfix.8` to `1.12.13+hotfix.8`
+              and leave a symbolic link at the old name
+        fenv normalize-names --link-only
+            Create symbolic links such as `1.12.13+hotfix.8` without renaming
 
       [Select Flutter SDK]
         fenv global stable
@@ -144,6 +392,26 @@ pub fn build_command() -> Command {
         fenv local 3
             Use the latest version of Flutter `3.x.y`
               in the current directory and its child directories
+        fenv global latest
+            Use the newest installed release, resolved whenever the version file is read.
+              `latest:3.22` limits it to `3.22.x`. Not reproducible across machines
+        fenv pin
+            Rewrite the nearest version file with the exact version which it currently selects,
+              such as `3` to `3.22.2`. A channel is pinned to the version it is at
+        fenv shell 3.7
+            Use the latest version of Flutter `3.7.x` in the current shell session
+              by setting `$FENV_VERSION`, which takes precedence over the version files
+        fenv shell --unset
+            Stop using the Flutter version set by `fenv shell`
+
+      [Name Flutter SDK]
+        fenv alias lts 3.19.6
+            Make `lts` an alias of `3.19.6`, which can be used wherever a version prefix is
+              accepted, such as `fenv local lts` and `.flutter-version` containing `lts`
+        fenv alias [lts]
+            Show all aliases, or the version which `lts` refers to
+        fenv alias --delete lts
+            Delete the alias `lts`
 
       [See selected Flutter SDK]
         fenv global
@@ -152,14 +420,75 @@ pub fn build_command() -> Command {
             Show the Flutter version specified in the nearest `.flutter-version` file
         fenv version
             Show the selected Flutter SDK version and where its version file is located
+        fenv version --version-only
+            Show the version written in the nearest version file only, without resolving it
         fenv version-name
             Show the selected Flutter SDK version only
+        fenv version-name --json
+            Show the selected Flutter SDK version, the version prefix, and the version file as
+              a JSON object for IDE plugins. `fenv version --json` prints the same
+        fenv current [--json]
+            Show the selected Flutter SDK version and the kind of its source:
+              `shell`, `local`, `asdf`, or `global`
         fenv version-file
             Show where the selected Flutter SDK version file is located
         fenv which flutter
             Show the full path to the selected `flutter` executable
         fenv which dart
             Show the full path to the selected `dart` executable
+        fenv which --shim flutter
+            Show the full path to the `flutter` shim, which stays valid across the version switches
+        fenv which --all melos
+            Show every installed Flutter SDK which provides `melos` with the full path to it
+        fenv which --json melos
+            Show the path to `melos` with its origin, `sdk`, `pub-cache`, or `system`, as JSON
+        fenv which --dir <DIR> flutter
+            Same as above, but find the nearest version file from <DIR>.
+              `local`, `prefix`, `version`, `version-name`, `version-file` accept `--dir` too
+        fenv prefix --validate 3.19
+            Show the directory of the latest installed `3.19.x`,
+              but exit with 3 if the installation is broken. `which` validates by default
+        fenv info 3.22 [--json]
+            Show the path, commit, channel, Dart SDK version, disk usage, and install date
+              of the latest installed `3.22.x`
+        fenv verify 3.22
+            Verify that the latest installed `3.22.x` is complete and at the commit of its tag,
+              and exit with 1 if it is broken
+        fenv print-cache-key
+            Show a cache key of the selected Flutter SDK, such as `flutter-3.7.12-<sha>`,
+              which is suitable for CI cache keys and Docker image tags
+        fenv context [--json]
+            Show the directories and the environment variables which fenv resolved
+              such as `$FENV_ROOT`, `$FENV_DIR`, and `$PUB_CACHE`
+        fenv exec -- flutter build apk
+            Run the given command with the selected Flutter SDK on `PATH` without the shims
+        fenv exec --cwd app --clean-env --env CI=true -- flutter test
+            Same as above, but in `app`, only with `HOME`, `PATH`, and `CI=true`
+        eval \"$(fenv env)\"
+            Export `FLUTTER_ROOT`, `DART_SDK`, `PUB_CACHE`, and `PATH` of the selected Flutter SDK
+              to the current shell. `fenv env --json` prints them as a JSON object
+        fenv doctor
+            Report the problems of the environment such as the shims missing on `PATH`, broken
+              installations, the conflicting Flutter version managers (FVM, asdf, snap), and
+              the IDE files of the workspaces which refer to another Flutter SDK than selected,
+              with their resolutions, and exit with 1 if any problem is found
+        fenv ready
+            Exit with 0 only if the selected Flutter SDK is installed and the shims are placed,
+              which is suitable for Docker `HEALTHCHECK` and init containers
+        fenv rehash
+            Regenerate the `flutter` and `dart` shims in `$FENV_ROOT/shims`
+        fenv audit show [-n <N>] [--json]
+            Show who installed, uninstalled, or selected which Flutter SDK when, recorded in
+              `$FENV_ROOT/audit.log`
+        fenv cache info
+            Show the size and the age of the caches in `$FENV_ROOT/cache`
+        fenv cache clear [--remote-list-only]
+            Remove the caches, or only the cached list of the remote Flutter SDKs
+        fenv self update [--check]
+            Replace fenv with the latest release on GitHub after verifying its checksum
+        fenv migrate fvm [--copy-sdks] [<DIR>]
+            Convert the Flutter version pinned by FVM into `.flutter-version`, and copy
+              the Flutter SDKs downloaded by FVM into `$FENV_ROOT/versions` with `--copy-sdks`
 
       [Support for IDE]
         fenv workspace <DIR>
@@ -168,6 +497,20 @@ pub fn build_command() -> Command {
         fenv workspace [--pub-get|-g] <DIR>
             Generate some files, which are set to the selected Flutter SDK, to be used by
               IDEs such as VS Code and IntelliJ IDEA with running `dart pub get`
+        fenv workspace --emit-path-file <FILE> <DIR>
+            Additionally write the path to the selected Flutter SDK into <FILE>
+              for external build systems such as Bazel and Make
+        fenv workspace --clean <DIR>
+            Remove the files which `fenv workspace` generated, except the modified ones
+        fenv setup [--pub-get|-g] <DIR>
+            Install the Flutter SDK which the project in <DIR> requires if missing,
+              and then work like `fenv workspace`
+        fenv new --version <PREFIX> <DIR> [-- <FLUTTER_CREATE_ARGS>...]
+            Create a Flutter project in <DIR> by `flutter create` of the latest Flutter
+              matching <PREFIX>, which is installed if missing, with `.flutter-version`
+              and the workspace files
+        fenv new --version <PREFIX> --no-create <DIR>
+            Only create <DIR> with `.flutter-version`
 
       To see more command-specific options, `fenv <COMMAND> [-h|--help]`"};
 
@@ -176,6 +519,10 @@ pub fn build_command() -> Command {
       [Since 0.1.0]
         fenv local --symlink
             Works like `fenv local` but doesn't create a symlink anymore.
+      [Other]
+        fenv latest --known
+            Same as `fenv latest --remote`.
+      Set `FENV_SUPPRESS_DEPRECATIONS=1` to suppress the warnings of the deprecated options.
 
     Note:
       - `flutter upgrade/downgrade/channel` commands are not permitted by `fenv`
@@ -197,21 +544,35 @@ pub fn build_command() -> Command {
         {usage}{after-help}
         "#})
         .override_usage(USAGE)
-        .after_help(NOTE)
+        .after_help(format!("{NOTE}\n{}", help_service::guides_note()))
+        .disable_help_subcommand(true)
         .color(clap::ColorChoice::Never)
 }
 
-fn matches_args<I, T>(args: I) -> FenvArgs
+/// Parses the command line of `fenv`, exiting with the usage if it is invalid.
+pub fn parse_command_line<I, T>(args: I) -> FenvCommandLine
 where
     I: IntoIterator<Item = T>,
     T: Into<OsString> + Clone,
 {
+    let args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+    let line = args.iter().skip(1).fold(String::from("fenv"), |line, arg| {
+        format!("{line} {}", arg.to_string_lossy())
+    });
     let command = build_command();
-    let mut matches = &mut command.get_matches_from(args);
-    args::FenvArgs::from_arg_matches_mut(&mut matches)
+    let mut matches = command.get_matches_from(args);
+    let subcommand = matches.subcommand_name().unwrap_or_default().to_owned();
+    let deprecations = deprecation::find_deprecated_usages(&matches);
+    let args = args::FenvArgs::from_arg_matches_mut(&mut matches)
         .map_err(|err| {
             let mut cmd = args::FenvArgs::command();
             err.format(&mut cmd)
         })
-        .unwrap()
+        .unwrap();
+    FenvCommandLine {
+        args,
+        subcommand,
+        deprecations,
+        line,
+    }
 }
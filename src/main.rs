@@ -1,8 +1,14 @@
 use anyhow::Error;
 use fenv::{
-    context::RealFenvContext, sdk_service::sdk_service::RealSdkService, util::io::StdOutput,
+    context::RealFenvContext,
+    metrics, native_shim,
+    sdk_service::{
+        results::{BrokenInstallation, InstallFailure},
+        sdk_service::RealSdkService,
+    },
+    util::io::{ErrorFormat, SilentFailure, StdOutput},
 };
-use std::{collections::HashMap, env};
+use std::{collections::HashMap, env, time::Instant};
 
 fn main() {
     let args: Vec<String> = env::args().collect();
@@ -18,8 +24,23 @@ fn main() {
         );
     }
 
-    let debug = args.contains(&String::from("--debug"));
-    let info = args.contains(&String::from("--info"));
+    // A native shim is a hardlink to the copy of `fenv` named after the executable it delegates.
+    // Its arguments belong to the executable, so `$FENV_DEBUG` enables the logs as the shim
+    // scripts do.
+    let shim_name = env::current_exe()
+        .ok()
+        .and_then(|exe| native_shim::invoked_name(&exe));
+    let command_line = match shim_name {
+        Some(_) => None,
+        None => Some(fenv::parse_command_line(&args)),
+    };
+    let fenv_args = command_line.as_ref().map(|command_line| &command_line.args);
+    let debug = match fenv_args {
+        Some(fenv_args) => fenv_args.debug,
+        None => env_vars.contains_key("FENV_DEBUG"),
+    };
+    let info = fenv_args.is_some_and(|fenv_args| fenv_args.info);
+    let json = fenv_args.is_some_and(|fenv_args| fenv_args.error_format == Some(ErrorFormat::Json));
     if debug {
         env::set_var("RUST_BACKTRACE", "1");
         env::set_var("RUST_LOG", "debug");
@@ -40,28 +61,74 @@ fn main() {
     let context = match RealFenvContext::from(&env_vars) {
         Ok(context) => context,
         Err(err) => {
-            print_error(err, debug);
+            print_error(err, debug, json);
             std::process::exit(1);
         }
     };
     log::debug!("context = {context:?}");
-    if let Err(err) = fenv::try_run(
-        &args,
-        &context,
-        &RealSdkService::new(),
-        &mut StdOutput::new(),
-    ) {
-        print_error(err, debug);
-        std::process::exit(1);
+    let started_at = Instant::now();
+    let result = match (&shim_name, &command_line) {
+        (Some(name), _) => native_shim::run(
+            name,
+            &args[1..],
+            &context,
+            &RealSdkService::new(),
+            &mut StdOutput::new(),
+        ),
+        (None, Some(command_line)) => {
+            let result = fenv::run(
+                command_line,
+                &context,
+                &RealSdkService::new(),
+                &mut StdOutput::new(),
+            );
+            metrics::record_command(
+                &context,
+                &command_line.subcommand,
+                started_at.elapsed(),
+                &result,
+            );
+            result
+        }
+        (None, None) => unreachable!("`fenv` is invoked as either a shim or itself"),
+    };
+    if let Err(err) = result {
+        if let Some(SilentFailure(exit_code)) = err.downcast_ref::<SilentFailure>() {
+            std::process::exit(*exit_code);
+        }
+        let exit_code = if err.downcast_ref::<BrokenInstallation>().is_some() {
+            BrokenInstallation::EXIT_CODE
+        } else if let Some(reason) = InstallFailure::reason_of(&err) {
+            reason.exit_code()
+        } else {
+            1
+        };
+        print_error(err, debug, json);
+        std::process::exit(exit_code);
     }
 }
 
-fn print_error(err: Error, debug: bool) {
+fn print_error(err: Error, debug: bool, json: bool) {
     if debug {
         eprintln!("{:?}", err);
         return;
     }
 
+    if json {
+        let causes: Vec<String> = err.chain().skip(1).map(|cause| cause.to_string()).collect();
+        let mut json = serde_json::json!({
+            "level": "error",
+            "message": err.to_string(),
+            "causes": causes,
+        });
+        if let Some(reason) = InstallFailure::reason_of(&err) {
+            json["reason"] = serde_json::json!(reason.name());
+            json["transient"] = serde_json::json!(reason.is_transient());
+        }
+        eprintln!("{json}");
+        return;
+    }
+
     eprintln!("fenv: {}", err);
     let error_chain = err.chain().skip(1);
     if error_chain.len() > 0 {
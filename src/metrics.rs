@@ -0,0 +1,190 @@
+use crate::context::FenvContext;
+use std::time::Duration;
+
+/// The default prefix of the metric names.
+#[cfg(feature = "metrics")]
+const DEFAULT_PREFIX: &str = "fenv";
+
+/// A metric to be emitted to the metrics sink configured by `metrics` of `config.json`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Metric {
+    /// A duration such as how long it takes to install a Flutter SDK.
+    Timing { name: String, duration: Duration },
+
+    /// An occurrence such as a cache hit or a failure.
+    Count { name: String },
+}
+
+impl Metric {
+    pub fn timing(name: &str, duration: Duration) -> Self {
+        Self::Timing {
+            name: name.to_string(),
+            duration,
+        }
+    }
+
+    pub fn count(name: &str) -> Self {
+        Self::Count {
+            name: name.to_string(),
+        }
+    }
+
+    /// Formats this metric in the statsd line protocol such as `fenv.install.duration:1234|ms`.
+    pub fn to_statsd_line(&self, prefix: &str) -> String {
+        match self {
+            Metric::Timing { name, duration } => {
+                format!("{prefix}.{name}:{}|ms", duration.as_millis())
+            }
+            Metric::Count { name } => format!("{prefix}.{name}:1|c"),
+        }
+    }
+}
+
+/// Classifies the given error into a coarse category, which is used as a part of the metric name
+/// of failures: `io`, `config`, or `other`.
+pub fn failure_category(err: &anyhow::Error) -> &'static str {
+    for cause in err.chain() {
+        if cause.is::<std::io::Error>() {
+            return "io";
+        }
+        if cause.is::<serde_json::Error>() {
+            return "config";
+        }
+    }
+    "other"
+}
+
+/// Records the duration and the result of the top-level `fenv` command `subcommand`.
+///
+/// Emits `command.<SUBCOMMAND>.duration` and either `command.<SUBCOMMAND>.success` or
+/// `command.<SUBCOMMAND>.failure.<CATEGORY>`.
+#[cfg(feature = "metrics")]
+pub fn record_command(
+    context: &impl FenvContext,
+    subcommand: &str,
+    duration: Duration,
+    result: &anyhow::Result<()>,
+) {
+    let result_metric = match result {
+        Ok(_) => Metric::count(&format!("command.{subcommand}.success")),
+        Err(err) => Metric::count(&format!(
+            "command.{subcommand}.failure.{}",
+            failure_category(err)
+        )),
+    };
+    emit(
+        context,
+        &[
+            Metric::timing(&format!("command.{subcommand}.duration"), duration),
+            result_metric,
+        ],
+    );
+}
+
+/// Does nothing because `fenv` is built without `metrics` feature.
+#[cfg(not(feature = "metrics"))]
+pub fn record_command(_: &impl FenvContext, _: &str, _: Duration, _: &anyhow::Result<()>) {}
+
+/// Emits the given metrics to the statsd endpoint configured by `metrics.statsdEndpoint` of
+/// `config.json`.
+///
+/// Metrics are best effort: any failure is logged and never fails the command.
+#[cfg(feature = "metrics")]
+pub fn emit(context: &impl FenvContext, metrics: &[Metric]) {
+    use crate::config::FenvConfig;
+    use log::debug;
+
+    let config = match FenvConfig::load(context) {
+        Ok(config) => config.metrics,
+        Err(e) => {
+            debug!("metrics::emit(): {e}");
+            return;
+        }
+    };
+    let Some(endpoint) = config.statsd_endpoint else {
+        return;
+    };
+    let prefix = config.prefix.as_deref().unwrap_or(DEFAULT_PREFIX);
+    let payload = metrics
+        .iter()
+        .map(|metric| metric.to_statsd_line(prefix))
+        .collect::<Vec<String>>()
+        .join("\n");
+    let sent = std::net::UdpSocket::bind("0.0.0.0:0")
+        .and_then(|socket| socket.send_to(payload.as_bytes(), &endpoint));
+    if let Err(e) = sent {
+        debug!("metrics::emit(): Failed to send metrics to `{endpoint}`: {e}");
+    }
+}
+
+/// Does nothing because `fenv` is built without `metrics` feature.
+#[cfg(not(feature = "metrics"))]
+pub fn emit(_: &impl FenvContext, _: &[Metric]) {}
+
+#[cfg(test)]
+mod tests {
+    use super::{failure_category, Metric};
+    use anyhow::Context;
+    use std::time::Duration;
+
+    #[test]
+    fn test_to_statsd_line() {
+        assert_eq!(
+            Metric::timing("install.duration", Duration::from_millis(1234)).to_statsd_line("fenv"),
+            "fenv.install.duration:1234|ms"
+        );
+        assert_eq!(
+            Metric::count("install.cache.hit").to_statsd_line("fleet.fenv"),
+            "fleet.fenv.install.cache.hit:1|c"
+        );
+    }
+
+    #[test]
+    fn test_failure_category() {
+        let io_error: anyhow::Result<()> =
+            Err(std::io::Error::from(std::io::ErrorKind::NotFound)).context("Failed to read");
+        assert_eq!(failure_category(&io_error.unwrap_err()), "io");
+        let config_error = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        assert_eq!(failure_category(&anyhow::anyhow!(config_error)), "config");
+        assert_eq!(failure_category(&anyhow::anyhow!("Not found")), "other");
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_emit_sends_statsd_lines() {
+        use super::emit;
+        use crate::{context::FenvContext, service::macros::test_with_context};
+
+        test_with_context(|context, _| {
+            // setup
+            let server = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+            server
+                .set_read_timeout(Some(Duration::from_secs(5)))
+                .unwrap();
+            context
+                .fenv_config_file()
+                .writeln(format!(
+                    r#"{{"metrics": {{"statsdEndpoint": "{}"}}}}"#,
+                    server.local_addr().unwrap()
+                ))
+                .unwrap();
+
+            // execution
+            emit(
+                context,
+                &[
+                    Metric::count("install.cache.miss"),
+                    Metric::timing("install.duration", Duration::from_millis(42)),
+                ],
+            );
+
+            // validation
+            let mut buffer = [0u8; 1024];
+            let size = server.recv(&mut buffer).unwrap();
+            assert_eq!(
+                String::from_utf8_lossy(&buffer[..size]),
+                "fenv.install.cache.miss:1|c\nfenv.install.duration:42|ms"
+            );
+        })
+    }
+}
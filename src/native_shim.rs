@@ -0,0 +1,143 @@
+use crate::{
+    config::{FenvConfig, FenvProjectConfig},
+    context::FenvContext,
+    sdk_service::sdk_service::SdkService,
+    service::{
+        exec::exec_service::{path_with_sdk, resolve_sdk_root_to_run},
+        rehash::rehash_service::NATIVE_SHIM_BINARY,
+    },
+    util::io::{ConsoleOutput, SilentFailure},
+};
+use anyhow::Context;
+use log::debug;
+use std::{path::Path, process::Command};
+
+/// The name of the executable which the native shim at `exe` delegates, or `None` if `exe` is
+/// not a native shim, which is placed next to [`NATIVE_SHIM_BINARY`].
+///
+/// `exe` is the path of the running executable, which names the hardlink it is invoked through.
+pub fn invoked_name(exe: &Path) -> Option<String> {
+    let name = exe.file_name()?.to_str()?;
+    if name == NATIVE_SHIM_BINARY || !exe.with_file_name(NATIVE_SHIM_BINARY).is_file() {
+        return None;
+    }
+    Some(String::from(name))
+}
+
+/// Runs the executable `name` of the selected Flutter SDK with `args` as the shim scripts do,
+/// without spawning `fenv` to resolve the Flutter SDK.
+///
+/// The Flutter SDK is guarded in the same way as `fenv exec`, see [`resolve_sdk_root_to_run`].
+pub fn run<OUT: std::io::Write, ERR: std::io::Write>(
+    name: &str,
+    args: &[String],
+    context: &impl FenvContext,
+    sdk_service: &impl SdkService,
+    output: &mut dyn ConsoleOutput<OUT, ERR>,
+) -> anyhow::Result<()> {
+    let sdk_root = resolve_sdk_root_to_run(
+        context,
+        sdk_service,
+        output,
+        &context.fenv_dir(),
+        name,
+        args,
+    )?;
+
+    let config = FenvConfig::load(context)?;
+    let project_config = FenvProjectConfig::load(context)?;
+    let path = path_with_sdk(context, &sdk_root)?;
+    debug!("native_shim::run(): program={name}: args={args:?}: PATH={path:?}");
+    let status = Command::new(name)
+        .args(config.args_prepend_of(name))
+        .args(args)
+        .envs(&project_config.env)
+        .env("PATH", path)
+        .current_dir(context.fenv_dir())
+        .status()
+        .with_context(|| format!("Failed to execute `{name}`"))?;
+    if !status.success() {
+        return Err(SilentFailure(status.code().unwrap_or(1)).into());
+    }
+    anyhow::Ok(())
+}
+
+#[cfg(unix)]
+#[cfg(test)]
+mod tests {
+    use super::{invoked_name, run};
+    use crate::{
+        context::FenvContext, sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context, util::io::SilentFailure,
+    };
+    use std::os::unix::prelude::PermissionsExt;
+
+    #[test]
+    fn test_invoked_name_is_given_only_in_shims() {
+        test_with_context(|context, _| {
+            // setup
+            let shims = context.fenv_shims();
+            shims.join(".fenv-shim").writeln("").unwrap();
+
+            // execution & validation
+            assert_eq!(
+                invoked_name(shims.join("flutter").path()),
+                Some(String::from("flutter"))
+            );
+            assert_eq!(invoked_name(shims.join(".fenv-shim").path()), None);
+            assert_eq!(
+                invoked_name(context.fenv_root().join("bin/fenv").path()),
+                None
+            );
+        })
+    }
+
+    #[test]
+    fn test_run_executes_selected_sdk() {
+        test_with_context(|context, output| {
+            // setup
+            let sdk_root = context.fenv_versions().join("3.7.12");
+            let flutter = sdk_root.join("bin/flutter");
+            flutter
+                .writeln("#!/bin/sh\necho \"$@\" > args.txt\nexit 3")
+                .unwrap();
+            std::fs::set_permissions(&flutter, std::fs::Permissions::from_mode(0o755)).unwrap();
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("3.7.12")
+                .unwrap();
+            context
+                .fenv_config_file()
+                .writeln(r#"{"argsPrepend": {"flutter": ["--suppress-analytics"]}}"#)
+                .unwrap();
+            let args = [String::from("--version")];
+
+            // execution
+            let polluted = run("flutter", &args, context, &RealSdkService::new(), output);
+            sdk_root.join("version").writeln("3.7.12").unwrap();
+            let result = run("flutter", &args, context, &RealSdkService::new(), output);
+
+            // validation
+            let polluted = polluted.unwrap_err();
+            assert!(matches!(
+                polluted.downcast_ref::<SilentFailure>(),
+                Some(SilentFailure(2))
+            ));
+            assert!(output.stderr_to_string().contains("is polluted"));
+            let e = result.unwrap_err();
+            assert!(matches!(
+                e.downcast_ref::<SilentFailure>(),
+                Some(SilentFailure(3))
+            ));
+            assert_eq!(
+                context
+                    .fenv_dir()
+                    .join("args.txt")
+                    .read_to_string()
+                    .unwrap(),
+                "--suppress-analytics --version\n"
+            );
+        })
+    }
+}
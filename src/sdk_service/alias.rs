@@ -0,0 +1,139 @@
+use super::latest_keyword;
+use crate::{context::FenvContext, util::path_like::PathLike};
+use anyhow::{bail, Context};
+use log::debug;
+
+/// The channels, which cannot be shadowed by aliases.
+const CHANNELS: [&str; 4] = ["stable", "beta", "dev", "master"];
+
+/// Resolves `prefix` to the version prefix which the alias of the same name refers to.
+///
+/// If no such alias exists, returns `prefix` as it is. Aliases are not resolved recursively.
+pub fn resolve(context: &impl FenvContext, prefix: &str) -> String {
+    match read(context, prefix) {
+        Some(target) => {
+            debug!("resolve(): `{prefix}` is an alias of `{target}`");
+            target
+        }
+        None => prefix.to_string(),
+    }
+}
+
+/// Reads the version prefix which the alias `name` refers to.
+pub fn read(context: &impl FenvContext, name: &str) -> Option<String> {
+    if validate_name(name).is_err() {
+        return None;
+    }
+    let target = alias_file_of(context, name).read_to_string().ok()?;
+    Some(target.trim().to_string()).filter(|target| !target.is_empty())
+}
+
+/// Makes the alias `name` refer to `prefix`, overwriting the existing one.
+pub fn write(context: &impl FenvContext, name: &str, prefix: &str) -> anyhow::Result<()> {
+    validate_name(name)?;
+    if prefix.trim().is_empty() {
+        bail!("The target of an alias must not be empty")
+    }
+    let alias_file = alias_file_of(context, name);
+    alias_file
+        .writeln(prefix.trim())
+        .with_context(|| format!("Failed to write the alias: `{alias_file}`"))
+}
+
+/// Removes the alias `name`. Fails if it does not exist.
+pub fn remove(context: &impl FenvContext, name: &str) -> anyhow::Result<()> {
+    validate_name(name)?;
+    let alias_file = alias_file_of(context, name);
+    if !alias_file.is_file() {
+        bail!("No such alias: `{name}`")
+    }
+    alias_file
+        .remove_file()
+        .with_context(|| format!("Failed to remove the alias: `{alias_file}`"))
+}
+
+/// Lists the pairs of the alias names and their targets sorted by the names.
+pub fn list(context: &impl FenvContext) -> anyhow::Result<Vec<(String, String)>> {
+    let aliases_dir = context.fenv_aliases();
+    if !aliases_dir.is_dir() {
+        return anyhow::Ok(vec![]);
+    }
+    let entries = aliases_dir
+        .read_dir()
+        .with_context(|| format!("Failed to read `{aliases_dir}`"))?;
+    let mut aliases: Vec<(String, String)> = entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str().map(String::from))
+        .filter_map(|name| read(context, &name).map(|target| (name, target)))
+        .collect();
+    aliases.sort();
+    anyhow::Ok(aliases)
+}
+
+/// Fails if `name` cannot be an alias because it may be taken for a version or a channel.
+pub fn validate_name(name: &str) -> anyhow::Result<()> {
+    let is_well_formed = name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    let looks_like_version =
+        name.starts_with('v') && name[1..].starts_with(|c: char| c.is_ascii_digit());
+    if !is_well_formed
+        || looks_like_version
+        || CHANNELS.contains(&name)
+        || name == latest_keyword::LATEST
+    {
+        bail!(
+            "`{name}` cannot be an alias: start with a letter, use letters, digits, `-`, and `_` \
+            only, and avoid the channel names and `latest`"
+        )
+    }
+    anyhow::Ok(())
+}
+
+fn alias_file_of(context: &impl FenvContext, name: &str) -> PathLike {
+    context.fenv_aliases().join(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{list, remove, resolve, validate_name, write};
+    use crate::service::macros::test_with_context;
+
+    #[test]
+    fn test_validate_name() {
+        for name in ["lts", "team-a", "release_2024", "v"] {
+            assert!(validate_name(name).is_ok(), "{name}");
+        }
+        for name in [
+            "", "3.19", "v3", "stable", "latest", "-lts", "lts/1", "lts.1",
+        ] {
+            assert!(validate_name(name).is_err(), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_write_resolve_and_remove() {
+        test_with_context(|context, _| {
+            // execution & validation
+            write(context, "lts", "3.19.6").unwrap();
+            write(context, "next", "beta").unwrap();
+            assert_eq!(resolve(context, "lts"), "3.19.6");
+            assert_eq!(resolve(context, "3.7"), "3.7");
+            assert_eq!(
+                list(context).unwrap(),
+                [
+                    (String::from("lts"), String::from("3.19.6")),
+                    (String::from("next"), String::from("beta"))
+                ]
+            );
+
+            remove(context, "lts").unwrap();
+            assert_eq!(resolve(context, "lts"), "lts");
+            assert_eq!(
+                remove(context, "lts").unwrap_err().to_string(),
+                "No such alias: `lts`"
+            );
+        })
+    }
+}
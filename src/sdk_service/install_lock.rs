@@ -0,0 +1,78 @@
+use std::{
+    collections::HashSet,
+    sync::{Condvar, Mutex},
+};
+
+/// The names of the Flutter SDKs being installed, so that the prefixes installed in parallel and
+/// resolved to the same SDK such as `3.22` and `3.22.2` do not install it at once.
+#[derive(Default)]
+pub struct InstallLocks {
+    installing: Mutex<HashSet<String>>,
+    released: Condvar,
+}
+
+impl InstallLocks {
+    /// Waits until no one installs `name`, and holds it until the returned guard is dropped.
+    pub fn lock(&self, name: &str) -> InstallGuard<'_> {
+        let mut installing = self.installing.lock().unwrap_or_else(|e| e.into_inner());
+        while installing.contains(name) {
+            installing = self
+                .released
+                .wait(installing)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+        installing.insert(name.to_string());
+        InstallGuard {
+            locks: self,
+            name: name.to_string(),
+        }
+    }
+}
+
+pub struct InstallGuard<'a> {
+    locks: &'a InstallLocks,
+    name: String,
+}
+
+impl Drop for InstallGuard<'_> {
+    fn drop(&mut self) {
+        let mut installing = self
+            .locks
+            .installing
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        installing.remove(&self.name);
+        self.locks.released.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InstallLocks;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    #[test]
+    fn test_lock_excludes_same_name_only() {
+        let locks = InstallLocks::default();
+        let running = AtomicUsize::new(0);
+        let max_running = AtomicUsize::new(0);
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    let _guard = locks.lock("3.22.2");
+                    let current = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_running.fetch_max(current, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(20));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+        assert_eq!(max_running.load(Ordering::SeqCst), 1);
+
+        let _stable = locks.lock("stable");
+        let _version = locks.lock("3.22.2");
+    }
+}
@@ -0,0 +1,74 @@
+use super::model::{flutter_sdk::FlutterSdk, flutter_version::FlutterVersion};
+use std::fmt::Display;
+
+/// The keyword which selects the newest release, such as `latest` or `latest:3.22`.
+pub const LATEST: &str = "latest";
+
+/// Parses `prefix` as the `latest` keyword and returns the version prefix which the release must
+/// match. `latest` returns an empty prefix, and `latest:3.22` returns `3.22`.
+pub fn parse(prefix: &str) -> Option<&str> {
+    match prefix.strip_prefix(LATEST)? {
+        "" => Some(""),
+        rest => rest.strip_prefix(':').map(str::trim),
+    }
+}
+
+/// Whether `prefix` is the `latest` keyword, which selects a different release as new ones are
+/// installed.
+pub fn is_latest(prefix: &str) -> bool {
+    parse(prefix).is_some()
+}
+
+/// Whether the Flutter SDK named `display_name` is a release, which is neither a channel nor a
+/// pre-release.
+pub fn is_release(display_name: &str) -> bool {
+    FlutterVersion::parse(display_name).is_some_and(|version| !version.is_prerelease())
+}
+
+/// The warning shown wherever the `latest` keyword is written or selected.
+pub fn warning_of(prefix: &str) -> String {
+    format!(
+        "`{prefix}` selects the newest installed release when it is read, \
+        so the selected Flutter SDK is not reproducible"
+    )
+}
+
+/// The `latest` keyword written into a version file as it is, instead of the release which it
+/// currently selects.
+#[derive(Debug, Clone)]
+pub struct LatestKeyword(pub String);
+
+impl Display for LatestKeyword {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FlutterSdk for LatestKeyword {
+    fn display_name(&self) -> String {
+        self.0.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_release, parse};
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("latest"), Some(""));
+        assert_eq!(parse("latest:3.22"), Some("3.22"));
+        assert_eq!(parse("latest: 3"), Some("3"));
+        assert_eq!(parse("latest3"), None);
+        assert_eq!(parse("3.22"), None);
+        assert_eq!(parse("stable"), None);
+    }
+
+    #[test]
+    fn test_is_release() {
+        assert!(is_release("3.22.2"));
+        assert!(is_release("v1.17.5"));
+        assert!(!is_release("3.23.0-0.1.pre"));
+        assert!(!is_release("stable"));
+    }
+}
@@ -1,9 +1,15 @@
 use super::{
-    model::flutter_sdk::FlutterSdk, results::LookupResult, version_prefix_match::matches_prefix,
+    alias, model::flutter_sdk::FlutterSdk, results::LookupResult, version_file,
+    version_prefix_match::matches_prefix,
 };
 use crate::{
-    context::FenvContext, sdk_service::model::local_flutter_sdk::LocalFlutterSdk, unwrap_or_return,
-    util::path_like::PathLike,
+    context::FenvContext,
+    sdk_service::model::local_flutter_sdk::LocalFlutterSdk,
+    unwrap_or_return,
+    util::{
+        network_fs::{move_dir, FileLock},
+        path_like::PathLike,
+    },
 };
 use anyhow::Context as _;
 use indoc::formatdoc;
@@ -39,25 +45,43 @@ impl LocalSdkRepository {
         dir.join(".flutter-version")
     }
 
+    /// Finds the nearest `.flutter-version` from `start_dir` to the root directory.
+    ///
+    /// In a directory without `.flutter-version`, an asdf `.tool-versions` having a `flutter`
+    /// line is used instead.
     pub fn find_nearest_local_version_file(&self, start_dir: &PathLike) -> Option<PathLike> {
-        debug!("Looking up version file in `{start_dir}`");
-        if self.version_file_of(start_dir).is_file() {
-            debug!("Found version file in `{start_dir}`");
-            return Some(self.version_file_of(start_dir));
-        }
-
-        let mut current = start_dir.parent();
+        let mut current = Some(start_dir.clone());
         while let Some(dir) = &current {
             debug!("Looking up version file in `{dir}`");
-            if self.version_file_of(&dir).is_file() {
+            if self.version_file_of(dir).is_file() {
                 debug!("Found version file in `{dir}`");
                 return Some(self.version_file_of(dir));
             }
+            let tool_versions = dir.join(version_file::TOOL_VERSIONS_FILE_NAME);
+            if self.has_flutter_in_tool_versions(&tool_versions) {
+                debug!(
+                    "Found `{}` in `{dir}`",
+                    version_file::TOOL_VERSIONS_FILE_NAME
+                );
+                return Some(tool_versions);
+            }
             current = dir.parent();
         }
         None
     }
 
+    fn has_flutter_in_tool_versions(&self, path: &PathLike) -> bool {
+        path.is_file()
+            && path
+                .read_to_string()
+                .is_ok_and(|content| !version_file::parse_tool_versions(&content).is_empty())
+    }
+
+    /// Whether the given version file is an asdf `.tool-versions`, which `fenv` never writes.
+    pub fn is_tool_versions_file(&self, path: &PathLike) -> bool {
+        path.path().file_name() == Some(std::ffi::OsStr::new(version_file::TOOL_VERSIONS_FILE_NAME))
+    }
+
     pub fn find_global_version_file(&self, context: &impl FenvContext) -> Option<PathLike> {
         debug!("Looking up the global version file");
         let global_version_file = context.fenv_global_version_file();
@@ -74,8 +98,9 @@ impl LocalSdkRepository {
         context: &impl FenvContext,
         prefix: &str,
     ) -> LookupResult<LocalFlutterSdk> {
+        let prefix = alias::resolve(context, prefix);
         let sdks: Vec<LocalFlutterSdk> = unwrap_or_return!(self.get_installed_sdk_list(context));
-        let filtered_sdks = matches_prefix(&sdks, prefix);
+        let filtered_sdks = matches_prefix(&sdks, &prefix);
         filtered_sdks.last().map(|sdk| sdk.to_owned()).into()
     }
 
@@ -83,14 +108,26 @@ impl LocalSdkRepository {
         path.path() == context.fenv_global_version_file().path()
     }
 
+    /// Reads the version prefix in the given version file, ignoring the comments.
+    /// An asdf `.tool-versions` is read for the version of `flutter`.
     pub fn read_version_file(&self, path: &PathLike) -> anyhow::Result<String> {
+        let is_tool_versions = self.is_tool_versions_file(path);
         path.read_to_string()
-            .map(|s| s.trim().to_owned())
+            .map(|content| match is_tool_versions {
+                true => version_file::parse_tool_versions(&content),
+                false => version_file::parse_version_prefix(&content),
+            })
             .map_err(|e| anyhow::anyhow!(e))
     }
 
+    /// Writes the given `sdk` to the version file.
+    /// If the version file already exists, preserves its comments.
     pub fn write_version_file(&self, path: &PathLike, sdk: &impl FlutterSdk) -> anyhow::Result<()> {
-        path.writeln(sdk.display_name()).with_context(|| {
+        let content = match path.read_to_string() {
+            Ok(existing) => version_file::replace_version_prefix(&existing, &sdk.display_name()),
+            Err(_) => sdk.display_name(),
+        };
+        path.writeln(content).with_context(|| {
             format!(
                 "Failed to write `{}` to the version file: `{path}`",
                 sdk.display_name()
@@ -98,6 +135,203 @@ impl LocalSdkRepository {
         })
     }
 
+    /// Triggers a failure if a non-directory entry such as a stray file occupies the location
+    /// where `version_or_channel` is going to be installed.
+    pub fn ensure_no_conflicting_entry(
+        &self,
+        context: &impl FenvContext,
+        version_or_channel: &str,
+    ) -> anyhow::Result<()> {
+        let install_destination = context.fenv_sdk_root(version_or_channel);
+        let is_conflicting = install_destination
+            .path()
+            .symlink_metadata()
+            .map(|metadata| !metadata.is_dir())
+            .unwrap_or(false);
+        if is_conflicting {
+            anyhow::bail!(
+                "`{install_destination}` already exists but is not a directory: \
+                remove it by `rm {install_destination}` and try again"
+            )
+        }
+        anyhow::Ok(())
+    }
+
+    /// Creates a symbolic link `{fenv_root}/versions/{link_name}` which points to
+    /// `{fenv_root}/versions/{target_name}`.
+    ///
+    /// Does nothing if anything already exists at `link_name`.
+    pub fn create_compatibility_symlink(
+        &self,
+        context: &impl FenvContext,
+        link_name: &str,
+        target_name: &str,
+    ) -> anyhow::Result<()> {
+        let link = context.fenv_sdk_root(link_name);
+        if link.path().symlink_metadata().is_ok() {
+            debug!("`{link}` already exists: skip creating a compatibility symlink");
+            return anyhow::Ok(());
+        }
+        std::os::unix::fs::symlink(target_name, &link).with_context(|| {
+            format!("Failed to create a compatibility symlink: `{link}` -> `{target_name}`")
+        })
+    }
+
+    /// Renames the installed `{fenv_root}/versions/{from}` to `{fenv_root}/versions/{to}`.
+    pub fn rename_sdk(
+        &self,
+        context: &impl FenvContext,
+        from: &str,
+        to: &str,
+    ) -> anyhow::Result<()> {
+        let source = context.fenv_sdk_root(from);
+        let destination = context.fenv_sdk_root(to);
+        if destination.path().symlink_metadata().is_ok() {
+            anyhow::bail!("`{destination}` already exists")
+        }
+        move_dir(&source, &destination)
+    }
+
+    /// Locks `install_name` against the other `fenv` processes installing it, which may run on
+    /// the other machines sharing `{fenv_root}`.
+    pub fn lock_installation(
+        &self,
+        context: &impl FenvContext,
+        install_name: &str,
+    ) -> anyhow::Result<FileLock> {
+        FileLock::acquire(
+            &context
+                .fenv_versions()
+                .join(format!(".lock_{install_name}")),
+        )
+    }
+
+    /// Removes every symbolic link in `{fenv_root}/versions` which points to `install_name`.
+    pub fn remove_compatibility_symlinks(
+        &self,
+        context: &impl FenvContext,
+        install_name: &str,
+    ) -> anyhow::Result<()> {
+        let versions_directory = context.fenv_versions();
+        if !versions_directory.is_dir() {
+            return anyhow::Ok(());
+        }
+        let children = versions_directory
+            .read_dir()
+            .with_context(|| anyhow::anyhow!("Could not read `{versions_directory}`"))?;
+        for child in children.flatten() {
+            let is_symlink = child
+                .file_type()
+                .map(|file_type| file_type.is_symlink())
+                .unwrap_or(false);
+            if !is_symlink {
+                continue;
+            }
+            let points_to_install_name = std::fs::read_link(child.path())
+                .map(|target| target == std::path::Path::new(install_name))
+                .unwrap_or(false);
+            if points_to_install_name {
+                debug!("Removing the compatibility symlink `{:?}`", child.path());
+                std::fs::remove_file(child.path()).with_context(|| {
+                    format!(
+                        "Failed to remove a compatibility symlink: `{:?}`",
+                        child.path()
+                    )
+                })?;
+            }
+        }
+        anyhow::Ok(())
+    }
+
+    /// Lists the names of the directories in `{fenv_root}/versions` which are left by
+    /// unsuccessful installations or do not have `bin/flutter`.
+    pub fn find_broken_installations(
+        &self,
+        context: &impl FenvContext,
+    ) -> anyhow::Result<Vec<String>> {
+        let versions_directory = context.fenv_versions();
+        if !versions_directory.is_dir() {
+            return anyhow::Ok(vec![]);
+        }
+        let children = versions_directory
+            .read_dir()
+            .with_context(|| anyhow::anyhow!("Could not read `{versions_directory}`"))?;
+        let mut names: Vec<String> = children
+            .flatten()
+            .filter_map(|child| {
+                let child_name = child.file_name().to_str()?.to_owned();
+                if is_directory(&child) {
+                    return Some(child_name);
+                }
+                // The marker whose installation has been removed by hand.
+                let name = child_name.strip_prefix(&installing_marker_of(""))?;
+                (!versions_directory.join(name).exists()).then(|| name.to_owned())
+            })
+            .filter(|child_name| self.is_broken_installation(context, child_name))
+            .collect();
+        names.sort();
+        names.dedup();
+        anyhow::Ok(names)
+    }
+
+    /// Whether the installation `{fenv_root}/versions/{name}` was not completed
+    /// or does not have `bin/flutter`.
+    pub fn is_broken_installation(&self, context: &impl FenvContext, name: &str) -> bool {
+        let versions_directory = context.fenv_versions();
+        let is_installation_incomplete =
+            versions_directory.join(installing_marker_of(name)).exists();
+        let has_flutter_executable = versions_directory
+            .join(name)
+            .join("bin")
+            .join("flutter")
+            .is_file();
+        is_installation_incomplete || !has_flutter_executable
+    }
+
+    /// Lists the problems of the files of the installation `{fenv_root}/versions/{name}`:
+    /// the remaining installing marker, and the missing `bin/flutter`, `bin/dart`, and the
+    /// Dart SDK cache.
+    pub fn find_missing_files(&self, context: &impl FenvContext, name: &str) -> Vec<String> {
+        let versions_directory = context.fenv_versions();
+        let sdk_root = versions_directory.join(name);
+        let mut problems = vec![];
+        let marker = versions_directory.join(installing_marker_of(name));
+        if marker.exists() {
+            problems.push(format!(
+                "The installation was not completed: `{marker}` remains"
+            ));
+        }
+        for executable in ["flutter", "dart"] {
+            let path = sdk_root.join("bin").join(executable);
+            if !path.is_file() {
+                problems.push(format!("Missing `{path}`"));
+            }
+        }
+        let dart_sdk = sdk_root.join("bin").join("cache").join("dart-sdk");
+        if !dart_sdk.join("bin").join("dart").is_file() {
+            problems.push(format!("Missing the Dart SDK cache: `{dart_sdk}`"));
+        }
+        problems
+    }
+
+    /// Removes `{fenv_root}/versions/{name}` together with its installing marker and the
+    /// compatibility symlinks which point to it.
+    pub fn remove_sdk(&self, context: &impl FenvContext, name: &str) -> anyhow::Result<()> {
+        let sdk_location = context.fenv_sdk_root(name);
+        if sdk_location.path().symlink_metadata().is_ok() {
+            sdk_location
+                .remove_dir_all()
+                .with_context(|| format!("Failed to remove `{sdk_location}`"))?;
+        }
+        let marker = context.fenv_versions().join(installing_marker_of(name));
+        if marker.exists() {
+            marker
+                .remove_file()
+                .with_context(|| format!("Failed to remove an installing marker: `{marker}`"))?;
+        }
+        self.remove_compatibility_symlinks(context, name)
+    }
+
     pub fn remove_installation_garbages(
         &self,
         context: &impl FenvContext,
@@ -120,6 +354,29 @@ impl LocalSdkRepository {
         anyhow::Ok(())
     }
 
+    /// Whether `{fenv_root}/versions/{version_or_channel}` is installed completely, which is
+    /// checked again after waiting for another install of the same SDK in parallel.
+    pub fn is_installed(&self, context: &impl FenvContext, version_or_channel: &str) -> bool {
+        let versions_directory = context.fenv_versions();
+        versions_directory.join(version_or_channel).is_dir()
+            && !versions_directory
+                .join(installing_marker_of(version_or_channel))
+                .exists()
+    }
+
+    /// Whether a previous trial to install `version_or_channel` was interrupted after `git clone`
+    /// had created the repository, so that the installation can be resumed.
+    pub fn has_interrupted_clone(
+        &self,
+        context: &impl FenvContext,
+        version_or_channel: &str,
+    ) -> bool {
+        let versions_directory = context.fenv_versions();
+        let marker = versions_directory.join(installing_marker_of(version_or_channel));
+        let git_dir = versions_directory.join(version_or_channel).join(".git");
+        marker.exists() && git_dir.join("HEAD").is_file() && git_dir.join("objects").is_dir()
+    }
+
     pub fn create_installing_marker(
         &self,
         context: &impl FenvContext,
@@ -1,7 +1,15 @@
-mod local_repository;
+pub mod alias;
+mod install_lock;
+pub mod latest_keyword;
+pub(crate) mod local_repository;
 pub mod model;
+mod release_archive;
+pub(crate) mod release_manifest;
 mod remote_repository;
-mod remote_sdk_list_cache;
+pub mod remote_sdk_list_cache;
 pub mod results;
+pub mod sdk_metadata;
 pub mod sdk_service;
-mod version_prefix_match;
+pub mod version_file;
+pub mod version_prefix_match;
+mod version_range;
@@ -1,13 +1,18 @@
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Clone)]
 pub struct FlutterVersion {
     pub major: u8,
     pub minor: u8,
     pub patch: u8,
     pub hotfix: u8,
+
+    /// The pre-release numbers of beta releases such as `(0, 1)` of `3.23.0-0.1.pre`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre: Option<(u16, u16)>,
 }
 
 impl FlutterVersion {
@@ -17,13 +22,37 @@ impl FlutterVersion {
             minor,
             patch,
             hotfix,
+            pre: None,
+        }
+    }
+
+    /// Whether this version is a pre-release which is published only to the beta channel,
+    /// such as `3.23.0-0.1.pre`.
+    pub fn is_prerelease(&self) -> bool {
+        self.pre.is_some()
+    }
+
+    /// The canonical name of this version such as `1.12.13` and `1.12.13+hotfix.4`.
+    pub fn canonical_name(&self) -> String {
+        if let Some((pre_major, pre_minor)) = self.pre {
+            format!(
+                "{}.{}.{}-{pre_major}.{pre_minor}.pre",
+                self.major, self.minor, self.patch
+            )
+        } else if self.hotfix == 0 {
+            format!("{}.{}.{}", self.major, self.minor, self.patch)
+        } else {
+            format!(
+                "{}.{}.{}+hotfix.{}",
+                self.major, self.minor, self.patch, self.hotfix
+            )
         }
     }
 
     pub fn parse(flutter_version_string: &str) -> Option<Self> {
         lazy_static! {
           static ref PATTERN: Regex = Regex::new(
-            r"^v?(?P<major>\d+)\.(?P<minor>\d+)\.(?P<patch>\d+)(?:(?:\+|-)hotfix\.(?P<hotfix>\d+))?$"
+            r"^v?(?P<major>\d+)\.(?P<minor>\d+)\.(?P<patch>\d+)(?:(?:\+|-)hotfix\.(?P<hotfix>\d+)|-(?P<pre_major>\d+)\.(?P<pre_minor>\d+)\.pre)?$"
           )
           .unwrap();
         }
@@ -46,13 +75,45 @@ impl FlutterVersion {
                     .name("hotfix")
                     .map(|s| s.as_str().parse::<u8>().unwrap())
                     .unwrap_or(0);
-                Some(FlutterVersion::new(major, minor, patch, hotfix))
+                let pre = match (capture.name("pre_major"), capture.name("pre_minor")) {
+                    (Some(pre_major), Some(pre_minor)) => Some((
+                        pre_major.as_str().parse::<u16>().ok()?,
+                        pre_minor.as_str().parse::<u16>().ok()?,
+                    )),
+                    _ => None,
+                };
+                Some(FlutterVersion {
+                    pre,
+                    ..FlutterVersion::new(major, minor, patch, hotfix)
+                })
             }
             None => None,
         };
     }
 }
 
+impl Ord for FlutterVersion {
+    /// Orders versions numerically, where a pre-release precedes the release of the same version:
+    /// `3.22.2` < `3.23.0-0.1.pre` < `3.23.0-0.2.pre` < `3.23.0` < `3.23.0+hotfix.1`.
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre, other.pre) {
+                (Some(me), Some(you)) => me.cmp(&you),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            })
+            .then_with(|| self.hotfix.cmp(&other.hotfix))
+    }
+}
+
+impl PartialOrd for FlutterVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use rand::seq::SliceRandom;
@@ -68,6 +129,7 @@ mod tests {
                 minor: 231,
                 patch: 5,
                 hotfix: 2,
+                pre: None,
             })
         );
         assert_eq!(
@@ -77,6 +139,7 @@ mod tests {
                 minor: 0,
                 patch: 0,
                 hotfix: 0,
+                pre: None,
             })
         );
         assert_eq!(
@@ -86,6 +149,7 @@ mod tests {
                 minor: 23,
                 patch: 40,
                 hotfix: 10,
+                pre: None,
             })
         );
         assert_eq!(
@@ -95,11 +159,64 @@ mod tests {
                 minor: 231,
                 patch: 5,
                 hotfix: 0,
+                pre: None,
+            })
+        );
+        assert_eq!(
+            FlutterVersion::parse("3.23.0-0.1.pre"),
+            Some(FlutterVersion {
+                major: 3,
+                minor: 23,
+                patch: 0,
+                hotfix: 0,
+                pre: Some((0, 1)),
             })
         );
         assert_eq!(FlutterVersion::parse("unknown"), None);
     }
 
+    #[test]
+    fn test_canonical_name() {
+        assert_eq!(
+            FlutterVersion::parse("v1.12.13+hotfix.4")
+                .unwrap()
+                .canonical_name(),
+            "1.12.13+hotfix.4"
+        );
+        assert_eq!(
+            FlutterVersion::parse("v1.4.5-hotfix.2")
+                .unwrap()
+                .canonical_name(),
+            "1.4.5+hotfix.2"
+        );
+        assert_eq!(
+            FlutterVersion::parse("v1.12.0").unwrap().canonical_name(),
+            "1.12.0"
+        );
+        assert_eq!(
+            FlutterVersion::parse("3.23.0-0.1.pre")
+                .unwrap()
+                .canonical_name(),
+            "3.23.0-0.1.pre"
+        );
+    }
+
+    #[test]
+    fn test_prerelease_precedes_release() {
+        let ordered = [
+            "3.22.2",
+            "3.23.0-0.1.pre",
+            "3.23.0-0.2.pre",
+            "3.23.0-12.0.pre",
+            "3.23.0",
+            "3.23.0+hotfix.1",
+        ]
+        .map(|version| FlutterVersion::parse(version).unwrap());
+        for pair in ordered.windows(2) {
+            assert!(pair[0] < pair[1], "{:?} < {:?}", pair[0], pair[1]);
+        }
+    }
+
     #[test]
     fn parse_and_order() {
         let mut versions = vec![
@@ -4,39 +4,142 @@ use super::{
     flutter_channel::FlutterChannel, flutter_sdk::FlutterSdk, flutter_version::FlutterVersion,
 };
 use anyhow::{bail, Ok, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::cmp::Ordering;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+/// Splits a name such as `fork-3.19.6` into the namespace `fork` and the version `3.19.6`.
+/// The Flutter SDKs installed from another repository by `fenv install --git-url` are named so,
+/// which never collide with nor are selected by the plain versions of the upstream ones.
+pub fn split_namespace(name: &str) -> Option<(&str, &str)> {
+    lazy_static! {
+        static ref PATTERN: Regex = Regex::new(r"^([a-z][a-z0-9_]*)-(v?\d.*)$").unwrap();
+    }
+    let captures = PATTERN.captures(name)?;
+    Some((captures.get(1)?.as_str(), captures.get(2)?.as_str()))
+}
+
+/// How many leading characters of a commit SHA the Flutter SDK installed at the commit is named
+/// after, which is as long as the revision printed by `flutter --version`.
+pub const SHORT_SHA_LENGTH: usize = 10;
+
+/// Whether `name` is an abbreviated or full commit SHA such as `761747bfc5`, which is at least
+/// 7 hexadecimal digits as `git` abbreviates.
+pub fn is_commit_sha(name: &str) -> bool {
+    (7..=40).contains(&name.len()) && name.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'))
+}
+
+/// Whether `namespace` can be given to `fenv install --namespace`, such as `fork` and `acme`.
+pub fn is_valid_namespace(namespace: &str) -> bool {
+    split_namespace(&format!("{namespace}-0")).is_some_and(|(parsed, _)| parsed == namespace)
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub enum LocalFlutterSdk {
     Version {
         version: FlutterVersion,
         display_name: String,
     },
     Channel(FlutterChannel),
+    /// Installed at a commit by `fenv install <SHA>`, named after the short SHA.
+    Commit(String),
 }
 
 impl LocalFlutterSdk {
     pub fn parse(channel_or_version: &str) -> Result<LocalFlutterSdk> {
         if let Some(channel) = FlutterChannel::parse(channel_or_version) {
             Ok(LocalFlutterSdk::Channel(channel))
-        } else if let Some(version) = FlutterVersion::parse(channel_or_version) {
+        } else if let Some(version) = FlutterVersion::parse(channel_or_version).or_else(|| {
+            split_namespace(channel_or_version).and_then(|(_, tag)| FlutterVersion::parse(tag))
+        }) {
             Ok(LocalFlutterSdk::Version {
                 version,
                 display_name: channel_or_version.to_owned(),
             })
+        } else if is_commit_sha(channel_or_version) {
+            Ok(LocalFlutterSdk::Commit(channel_or_version.to_owned()))
         } else {
             bail!("Invalid Flutter SDK: `{channel_or_version}`")
         }
     }
 
+    /// The namespace of the SDK installed from another repository, such as `fork` of
+    /// `fork-3.19.6`. See [`split_namespace`].
+    pub fn namespace(&self) -> Option<&str> {
+        match self {
+            LocalFlutterSdk::Version { display_name, .. } => {
+                split_namespace(display_name).map(|(namespace, _)| namespace)
+            }
+            LocalFlutterSdk::Channel(_) | LocalFlutterSdk::Commit(_) => None,
+        }
+    }
+
+    /// The git tag or branch which this SDK is checked out at: the name without the namespace.
+    pub fn tag_name(&self) -> String {
+        match self {
+            LocalFlutterSdk::Version { display_name, .. } => match split_namespace(display_name) {
+                Some((_, tag)) => tag.to_string(),
+                None => display_name.clone(),
+            },
+            LocalFlutterSdk::Channel(channel) => channel.channel_name().to_string(),
+            LocalFlutterSdk::Commit(sha) => sha.clone(),
+        }
+    }
+
+    /// Whether this SDK is named after the canonical name of its version, such as `1.17.5`
+    /// rather than `v1.17.5`. Always `true` for a channel and a commit.
+    pub fn is_canonical(&self) -> bool {
+        match self {
+            LocalFlutterSdk::Version { version, .. } => self.tag_name() == version.canonical_name(),
+            LocalFlutterSdk::Channel(_) | LocalFlutterSdk::Commit(_) => true,
+        }
+    }
+
+    /// Whether this SDK and `other` are the same release of the same namespace regardless of
+    /// their names, such as `v1.17.5` and `1.17.5`.
+    pub fn is_equivalent(&self, other: &LocalFlutterSdk) -> bool {
+        match (self, other) {
+            (
+                LocalFlutterSdk::Version { version: me, .. },
+                LocalFlutterSdk::Version { version: you, .. },
+            ) => me == you && self.namespace() == other.namespace(),
+            (LocalFlutterSdk::Channel(me), LocalFlutterSdk::Channel(you)) => me == you,
+            (LocalFlutterSdk::Commit(me), LocalFlutterSdk::Commit(you)) => me == you,
+            _ => false,
+        }
+    }
+
+    /// Keeps only one of the equivalent SDKs in the sorted `sdks`, which is the one named after
+    /// the canonical name if any.
+    pub fn dedup_equivalents(sdks: Vec<LocalFlutterSdk>) -> Vec<LocalFlutterSdk> {
+        let mut deduped: Vec<LocalFlutterSdk> = Vec::with_capacity(sdks.len());
+        for sdk in sdks {
+            match deduped.last_mut() {
+                // The canonical one is sorted after the others of the same release.
+                Some(last) if last.is_equivalent(&sdk) => *last = sdk,
+                _ => deduped.push(sdk),
+            }
+        }
+        deduped
+    }
+
+    /// The git refs of this SDK such as `refs/tags/3.22.2`, or the short SHA of a commit.
     pub fn refs_name(&self) -> String {
         match self {
-            LocalFlutterSdk::Version {
-                version: _,
-                display_name,
-            } => format!("refs/tags/{display_name}"),
+            LocalFlutterSdk::Version { .. } => format!("refs/tags/{}", self.tag_name()),
             LocalFlutterSdk::Channel(channel) => {
                 format!("refs/heads/{channel}", channel = channel.channel_name())
             }
+            LocalFlutterSdk::Commit(sha) => sha.clone(),
+        }
+    }
+
+    /// The order of the kinds: the versions, the commits, and then the channels.
+    fn kind_order(&self) -> u8 {
+        match self {
+            LocalFlutterSdk::Version { .. } => 0,
+            LocalFlutterSdk::Commit(_) => 1,
+            LocalFlutterSdk::Channel(_) => 2,
         }
     }
 }
@@ -46,15 +149,48 @@ impl Display for LocalFlutterSdk {
         match self {
             LocalFlutterSdk::Version { display_name, .. } => write!(f, "{}", display_name),
             LocalFlutterSdk::Channel(channel) => write!(f, "{}", channel.channel_name()),
+            LocalFlutterSdk::Commit(sha) => write!(f, "{}", sha),
+        }
+    }
+}
+
+impl Ord for LocalFlutterSdk {
+    /// Orders versions before commits, and commits before channels. The equivalent versions such as `v1.17.5` and `1.17.5`
+    /// are ordered so that the canonical one comes last, which is selected as the latest.
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (
+                LocalFlutterSdk::Version {
+                    version: my_version,
+                    display_name: my_name,
+                },
+                LocalFlutterSdk::Version {
+                    version: your_version,
+                    display_name: your_name,
+                },
+            ) => my_version
+                .cmp(your_version)
+                .then_with(|| self.is_canonical().cmp(&other.is_canonical()))
+                .then_with(|| my_name.cmp(your_name)),
+            (LocalFlutterSdk::Channel(me), LocalFlutterSdk::Channel(you)) => me.cmp(you),
+            (LocalFlutterSdk::Commit(me), LocalFlutterSdk::Commit(you)) => me.cmp(you),
+            _ => self.kind_order().cmp(&other.kind_order()),
         }
     }
 }
 
+impl PartialOrd for LocalFlutterSdk {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl FlutterSdk for LocalFlutterSdk {
     fn display_name(&self) -> String {
         match self {
             LocalFlutterSdk::Version { display_name, .. } => display_name.clone(),
             LocalFlutterSdk::Channel(channel) => channel.channel_name().to_string(),
+            LocalFlutterSdk::Commit(sha) => sha.clone(),
         }
     }
 }
@@ -80,6 +216,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_namespaced() {
+        let sdk = LocalFlutterSdk::parse("fork-v3.19.6").unwrap();
+        assert_eq!(
+            sdk,
+            LocalFlutterSdk::Version {
+                version: FlutterVersion::new(3, 19, 6, 0),
+                display_name: "fork-v3.19.6".to_owned()
+            }
+        );
+        assert_eq!(sdk.namespace(), Some("fork"));
+        assert_eq!(sdk.refs_name(), "refs/tags/v3.19.6");
+        assert!(!sdk.is_equivalent(&LocalFlutterSdk::parse("v3.19.6").unwrap()));
+        assert_eq!(LocalFlutterSdk::parse("v3.19.6").unwrap().namespace(), None);
+        assert!(LocalFlutterSdk::parse("fork-stable").is_err());
+        assert!(is_valid_namespace("acme_2"));
+        assert!(!is_valid_namespace("Fork"));
+        assert!(!is_valid_namespace("fork-3"));
+    }
+
+    #[test]
+    fn test_parse_commit() {
+        let sdk = LocalFlutterSdk::parse("761747bfc5").unwrap();
+        assert_eq!(sdk, LocalFlutterSdk::Commit("761747bfc5".to_owned()));
+        assert!(sdk > LocalFlutterSdk::parse("3.22.2").unwrap());
+        assert!(sdk < LocalFlutterSdk::parse("beta").unwrap());
+        assert!(LocalFlutterSdk::parse("761747").is_err());
+        assert!(LocalFlutterSdk::parse("761747BFC5").is_err());
+    }
+
     #[test]
     fn test_parse_invalid() {
         let result = LocalFlutterSdk::parse("invalid");
@@ -123,4 +289,26 @@ mod tests {
             sdks
         );
     }
+
+    #[test]
+    fn test_equivalent_versions() {
+        let mut sdks = vec![
+            LocalFlutterSdk::parse("1.17.5").unwrap(),
+            LocalFlutterSdk::parse("v1.17.5").unwrap(),
+            LocalFlutterSdk::parse("v1.4.5-hotfix.2").unwrap(),
+            LocalFlutterSdk::parse("1.4.5+hotfix.2").unwrap(),
+            LocalFlutterSdk::parse("v1.4.19").unwrap(),
+        ];
+        sdks.shuffle(&mut rand::thread_rng());
+
+        sdks.sort();
+        assert_eq!(
+            LocalFlutterSdk::dedup_equivalents(sdks),
+            vec![
+                LocalFlutterSdk::parse("1.4.5+hotfix.2").unwrap(),
+                LocalFlutterSdk::parse("v1.4.19").unwrap(),
+                LocalFlutterSdk::parse("1.17.5").unwrap(),
+            ]
+        );
+    }
 }
@@ -1,6 +1,7 @@
 use std::fmt::Display;
 
 use super::{flutter_sdk::FlutterSdk, flutter_version::FlutterVersion};
+use crate::config::InstallNamePolicy;
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
@@ -11,6 +12,25 @@ pub struct RemoteFlutterSdk {
     pub sha: String,
     pub short: String,
     pub long: String,
+
+    /// Given if this SDK is listed by the release manifest rather than by `git ls-remote`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub release: Option<ReleaseDetails>,
+}
+
+/// What the release manifest tells about a remote SDK besides its commit.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ReleaseDetails {
+    /// The channel where the release is published, such as `stable` and `beta`.
+    pub channel: String,
+
+    /// The version of the bundled Dart SDK, such as `3.4.3`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dart_sdk_version: Option<String>,
+
+    /// When the release is published in RFC 3339, such as `2024-06-06T18:40:14.437459Z`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub release_date: Option<String>,
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Clone)]
@@ -44,12 +64,46 @@ impl RemoteFlutterSdk {
                     sha: String::from(sha),
                     short: String::from(short),
                     long: String::from(long),
+                    release: None,
                 })
             }
             None => return None,
         }
     }
 
+    /// Whether this SDK is a pre-release which is published only to the beta channel.
+    /// Told by the channel in the release manifest if known, or by the tag otherwise.
+    pub fn is_prerelease(&self) -> bool {
+        match (&self.kind, &self.release) {
+            (GitRefsKind::Tag(_), Some(release)) if !release.channel.is_empty() => {
+                release.channel != "stable"
+            }
+            (GitRefsKind::Tag(version), _) => version.is_prerelease(),
+            (GitRefsKind::Head(_), _) => false,
+        }
+    }
+
+    /// Whether this SDK is tagged with the canonical name of its version, such as `1.17.5`
+    /// rather than `v1.17.5`. Always `true` for a branch.
+    pub fn is_canonical(&self) -> bool {
+        match &self.kind {
+            GitRefsKind::Tag(version) => self.short == version.canonical_name(),
+            GitRefsKind::Head(_) => true,
+        }
+    }
+
+    /// The name of the directory where this SDK is installed under the given `policy`.
+    pub fn install_name(&self, policy: InstallNamePolicy) -> String {
+        match (&self.kind, policy) {
+            (GitRefsKind::Head(channel), _) => channel.clone(),
+            (GitRefsKind::Tag(_), InstallNamePolicy::AsIs) => self.short.clone(),
+            (GitRefsKind::Tag(_), InstallNamePolicy::StripV) => {
+                self.short.trim_start_matches('v').to_string()
+            }
+            (GitRefsKind::Tag(version), InstallNamePolicy::Canonical) => version.canonical_name(),
+        }
+    }
+
     fn tag_to_version(tag: &str) -> Option<FlutterVersion> {
         FlutterVersion::parse(&tag)
     }
@@ -0,0 +1,278 @@
+use super::{
+    model::{local_flutter_sdk::LocalFlutterSdk, remote_flutter_sdk::RemoteFlutterSdk},
+    release_manifest::{
+        storage_download_options, FlutterRelease, DEFAULT_STORAGE_BASE_URL,
+        RELEASE_MANIFEST_REPOSITORY,
+    },
+    results::InstallFailureReason,
+    version_prefix_match::matches_prefix,
+};
+use crate::{
+    context::FenvContext,
+    external::downloader::{DownloadOptions, Downloader},
+    spawn_and_wait,
+    util::{checksum::sha256_of_file, network_fs::move_dir, path_like::PathLike},
+};
+use anyhow::{bail, Context};
+use log::debug;
+use std::process::Command;
+
+/// Finds the release archive of the latest version matching `prefix` for the platform of
+/// `context`. The pre-releases are excluded unless `include_beta` is set.
+///
+/// The channels never match because their archives are not checkouts of the branches.
+pub fn find_latest_release(
+    context: &impl FenvContext,
+    downloader: &impl Downloader,
+    storage_base_url: Option<&str>,
+    prefix: &str,
+    include_beta: bool,
+) -> anyhow::Result<Option<(RemoteFlutterSdk, FlutterRelease)>> {
+    let Some(manifest) =
+        RELEASE_MANIFEST_REPOSITORY.fetch_manifest(context, downloader, storage_base_url)?
+    else {
+        return anyhow::Ok(None);
+    };
+
+    let arch = context.architecture();
+    let mut releases: Vec<(RemoteFlutterSdk, FlutterRelease)> = manifest
+        .releases
+        .into_iter()
+        .filter(|release| release.is_built_for(arch))
+        .filter_map(|release| Some((release.to_remote_sdk()?, release)))
+        .filter(|(sdk, _)| include_beta || !sdk.is_prerelease())
+        .collect();
+    releases.sort_by(|(a, _), (b, _)| a.kind.cmp(&b.kind));
+    releases.dedup_by(|(a, _), (b, _)| a.kind == b.kind);
+    let sdks: Vec<RemoteFlutterSdk> = releases.iter().map(|(sdk, _)| sdk.clone()).collect();
+    let Some(latest) = matches_prefix(&sdks, prefix).pop() else {
+        return anyhow::Ok(None);
+    };
+    anyhow::Ok(
+        releases
+            .into_iter()
+            .find(|(sdk, _)| sdk.kind == latest.kind),
+    )
+}
+
+/// Downloads the archive of `release` into `{fenv_cache}/downloads`, verifies its checksum, and
+/// extracts it into `{fenv_root}/versions/{install_name}`.
+///
+/// The archive is downloaded into `{archive}.part`, which is kept there when the connection is
+/// lost so that the next attempt resumes it, and is renamed to the archive only after its
+/// checksum is verified. The verified archive is kept with its checksum in `{archive}.sha256`
+/// for `--offline`, and a mismatched download is removed.
+pub fn install_release(
+    context: &impl FenvContext,
+    downloader: &impl Downloader,
+    storage_base_url: Option<&str>,
+    release: &FlutterRelease,
+    install_name: &str,
+) -> anyhow::Result<PathLike> {
+    install_into(context, install_name, |staging| {
+        let storage_base_url = storage_base_url.unwrap_or(DEFAULT_STORAGE_BASE_URL);
+        let url = format!(
+            "{storage_base_url}/flutter_infra_release/releases/{}",
+            release.archive
+        );
+        let options = storage_download_options(context, Some(storage_base_url))?;
+        // Not retried by `InstallFailureReason::Network` because it never succeeds.
+        options.verify_host(&url)?;
+        let file_name = release.archive.rsplit('/').next().unwrap_or_default();
+        let downloads = downloads_directory(context);
+        downloads
+            .create_dir_all()
+            .with_context(|| format!("Failed to create `{downloads}`"))?;
+        let archive = downloads.join(file_name);
+        // The archive verified by the previous installation is not downloaded again.
+        if !(archive.is_file() && verify_checksum(&archive, &release.sha256).is_ok()) {
+            let partial = partial_download_of(&archive);
+            let options = DownloadOptions {
+                resumes: true,
+                ..options
+            };
+            debug!("install_release(): Downloading `{url}` into `{partial}`");
+            downloader
+                .download(&url, &partial.to_string(), &options)
+                .map_err(|e| InstallFailureReason::Network.wrap(e))?;
+            if let Err(e) = verify_checksum(&partial, &release.sha256) {
+                // Another attempt must download it from the start.
+                if let Err(e) = partial.remove_file() {
+                    debug!("install_release(): Failed to remove `{partial}`: {e}");
+                }
+                return Err(e);
+            }
+            std::fs::rename(&partial, &archive)
+                .with_context(|| format!("Failed to rename `{partial}` to `{archive}`"))?;
+            let checksum_file = checksum_file_of(&archive);
+            checksum_file
+                .writeln(&release.sha256)
+                .with_context(|| format!("Failed to write `{checksum_file}`"))?;
+        }
+        extract(&archive, staging)
+    })
+}
+
+/// The file which a release archive is downloaded into until its checksum is verified.
+///
+/// `{archive}.part`.
+fn partial_download_of(archive: &PathLike) -> PathLike {
+    PathLike::from(format!("{archive}.part").as_str())
+}
+
+/// The file which records the SHA-256 of a verified release archive, such as the output of
+/// `sha256sum`.
+///
+/// `{archive}.sha256`.
+fn checksum_file_of(archive: &PathLike) -> PathLike {
+    PathLike::from(format!("{archive}.sha256").as_str())
+}
+
+/// The directory in `{fenv_cache}` where the release archives are downloaded.
+const DOWNLOADS_DIRECTORY_NAME: &str = "downloads";
+
+/// The directory where the release archives are downloaded.
+///
+/// `{fenv_cache}/downloads`.
+pub fn downloads_directory(context: &impl FenvContext) -> PathLike {
+    context.fenv_cache().join(DOWNLOADS_DIRECTORY_NAME)
+}
+
+/// A release archive in `{fenv_cache}/downloads` with its SHA-256 in `{archive}.sha256`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedArchive {
+    pub sdk: LocalFlutterSdk,
+    pub path: PathLike,
+    pub sha256: String,
+}
+
+/// Finds the release archive of the latest version matching `prefix` in
+/// `{fenv_cache}/downloads`, such as `flutter_linux_3.22.2-stable.tar.xz`, to install it without
+/// the network.
+///
+/// The archives without `{archive}.sha256` are skipped, and so are the partial downloads in
+/// `{archive}.part`.
+pub fn find_cached_archive(context: &impl FenvContext, prefix: &str) -> Option<CachedArchive> {
+    let downloads = downloads_directory(context);
+    let mut archives: Vec<CachedArchive> = downloads
+        .read_dir()
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_str()?.to_string();
+            let sdk = LocalFlutterSdk::parse(version_of_archive(&file_name)?).ok()?;
+            if !matches!(sdk, LocalFlutterSdk::Version { .. }) {
+                return None;
+            }
+            let path = downloads.join(&file_name);
+            let checksum = checksum_file_of(&path).read_to_string().ok()?;
+            let sha256 = checksum.split_whitespace().next()?.to_string();
+            Some(CachedArchive { sdk, path, sha256 })
+        })
+        .collect();
+    archives.sort_by(|a, b| a.sdk.cmp(&b.sdk));
+    let sdks: Vec<LocalFlutterSdk> = archives.iter().map(|archive| archive.sdk.clone()).collect();
+    let latest = matches_prefix(&sdks, prefix).pop()?;
+    archives.into_iter().find(|archive| archive.sdk == latest)
+}
+
+/// The version of the release archive named `file_name`, such as `3.22.2` of
+/// `flutter_linux_3.22.2-stable.tar.xz` and `flutter_macos_arm64_3.22.2-stable.zip`.
+fn version_of_archive(file_name: &str) -> Option<&str> {
+    let stem = file_name
+        .strip_suffix(".tar.xz")
+        .or_else(|| file_name.strip_suffix(".zip"))?;
+    let (_, version_and_channel) = stem.strip_prefix("flutter_")?.rsplit_once('_')?;
+    let (version, _) = version_and_channel.rsplit_once('-')?;
+    Some(version)
+}
+
+/// Extracts the release archive `archive` downloaded beforehand, such as
+/// `flutter_linux_3.22.2-stable.tar.xz`, into `{fenv_root}/versions/{install_name}`, after
+/// verifying it against `sha256` if given.
+pub fn install_archive(
+    context: &impl FenvContext,
+    archive: &PathLike,
+    sha256: Option<&str>,
+    install_name: &str,
+) -> anyhow::Result<PathLike> {
+    if !archive.is_file() {
+        bail!("`{archive}` is not found")
+    }
+    if let Some(sha256) = sha256 {
+        verify_checksum(archive, sha256)?;
+    }
+    install_into(context, install_name, |staging| extract(archive, staging))
+}
+
+/// Runs `prepare` to extract an archive into the staging directory, and moves the `flutter`
+/// directory of the archive to `{fenv_root}/versions/{install_name}`.
+fn install_into(
+    context: &impl FenvContext,
+    install_name: &str,
+    prepare: impl FnOnce(&PathLike) -> anyhow::Result<()>,
+) -> anyhow::Result<PathLike> {
+    let destination = context.fenv_sdk_root(install_name);
+    let staging = context
+        .fenv_versions()
+        .join(format!(".archive_{install_name}"));
+    if staging.exists() {
+        staging
+            .remove_dir_all()
+            .with_context(|| format!("Failed to remove `{staging}`"))?;
+    }
+    staging
+        .create_dir_all()
+        .with_context(|| format!("Failed to create `{staging}`"))?;
+    let result = prepare(&staging).and_then(|_| {
+        // Every archive has the Flutter SDK in its `flutter` directory.
+        let extracted = staging.join("flutter");
+        if !extracted.is_dir() {
+            return Err(InstallFailureReason::ArchiveCorrupt.wrap(anyhow::anyhow!(
+                "The archive does not have the `flutter` directory"
+            )));
+        }
+        move_dir(&extracted, &destination)
+    });
+    if let Err(e) = staging.remove_dir_all() {
+        debug!("install_into(): Failed to remove `{staging}`: {e}");
+    }
+    result.map(|_| destination)
+}
+
+/// Extracts `archive` into `directory` by `unzip` if it is a `.zip`, or by `tar` otherwise.
+fn extract(archive: &PathLike, directory: &PathLike) -> anyhow::Result<()> {
+    let mut command = if archive.to_string().ends_with(".zip") {
+        let mut command = Command::new("unzip");
+        command
+            .arg("-q")
+            .arg(archive.path())
+            .arg("-d")
+            .arg(directory.path());
+        command
+    } else {
+        let mut command = Command::new("tar");
+        command
+            .arg("-xf")
+            .arg(archive.path())
+            .arg("-C")
+            .arg(directory.path());
+        command
+    };
+    run_extract(&mut command, archive).map_err(|e| InstallFailureReason::ArchiveCorrupt.wrap(e))
+}
+
+fn run_extract(command: &mut Command, archive: &PathLike) -> anyhow::Result<()> {
+    spawn_and_wait!(command, "extract", "Failed to extract `{archive}`");
+    anyhow::Ok(())
+}
+
+fn verify_checksum(archive: &PathLike, expected: &str) -> anyhow::Result<()> {
+    let actual = sha256_of_file(archive)?;
+    if !actual.eq_ignore_ascii_case(expected) {
+        return Err(InstallFailureReason::Checksum.wrap(anyhow::anyhow!(
+            "The checksum of `{archive}` does not match: expected `{expected}`, but `{actual}`"
+        )));
+    }
+    anyhow::Ok(())
+}
@@ -0,0 +1,315 @@
+use super::{
+    model::remote_flutter_sdk::{GitRefsKind, ReleaseDetails, RemoteFlutterSdk},
+    results::InstallFailureReason,
+};
+use crate::{
+    config::FenvConfig,
+    context::FenvContext,
+    external::downloader::{host_of, DownloadOptions, Downloader},
+    util::platform::{Architecture, OperatingSystem},
+};
+use anyhow::{bail, Context};
+use log::debug;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Where the release archives of Flutter are published, unless `storage-base-url` of
+/// `.fenv.toml` is given.
+pub const DEFAULT_STORAGE_BASE_URL: &str = "https://storage.googleapis.com";
+
+/// The options to download from the storage of the release archives, which allow only
+/// `storage.googleapis.com`, the host of `storage_base_url`, and `download.allowedHosts` of
+/// `{fenv_root}/config.json`, so that a misconfigured mirror cannot redirect to anywhere.
+pub fn storage_download_options(
+    context: &impl FenvContext,
+    storage_base_url: Option<&str>,
+) -> anyhow::Result<DownloadOptions> {
+    let mut allowed_hosts: Vec<String> = [Some(DEFAULT_STORAGE_BASE_URL), storage_base_url]
+        .into_iter()
+        .flatten()
+        .filter_map(host_of)
+        .collect();
+    allowed_hosts.extend(FenvConfig::load(context)?.download.allowed_hosts);
+    anyhow::Ok(DownloadOptions {
+        allowed_hosts: Some(allowed_hosts),
+        ..DownloadOptions::resolve(context)?
+    })
+}
+
+/// The list of the release archives, such as
+/// `{storage_base_url}/flutter_infra_release/releases/releases_linux.json`.
+#[derive(Debug, Deserialize)]
+pub struct ReleaseManifest {
+    /// The commit of the latest release of each channel.
+    #[serde(default)]
+    pub current_release: BTreeMap<String, String>,
+
+    pub releases: Vec<FlutterRelease>,
+}
+
+/// A release archive of Flutter.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct FlutterRelease {
+    /// The commit of the release.
+    pub hash: String,
+
+    /// The channel where the release is published, such as `stable` and `beta`.
+    #[serde(default)]
+    pub channel: String,
+
+    /// The version such as `3.22.2` and `v1.12.13+hotfix.9`.
+    pub version: String,
+
+    /// The version of the bundled Dart SDK, which is omitted by the old releases.
+    #[serde(default)]
+    pub dart_sdk_version: Option<String>,
+
+    /// When the release is published in RFC 3339.
+    #[serde(default)]
+    pub release_date: Option<String>,
+
+    /// The path of the archive relative to `{storage_base_url}/flutter_infra_release/releases`.
+    pub archive: String,
+
+    /// The SHA-256 of the archive.
+    pub sha256: String,
+
+    /// The CPU architecture of the archive such as `x64` and `arm64`, which is omitted by the
+    /// old releases built for `x64` only.
+    #[serde(default)]
+    pub dart_sdk_arch: Option<String>,
+}
+
+impl FlutterRelease {
+    /// The remote Flutter SDK tagged with the version of this release.
+    pub fn to_remote_sdk(&self) -> Option<RemoteFlutterSdk> {
+        let sdk = RemoteFlutterSdk::parse(&format!("{}\trefs/tags/{}", self.hash, self.version))?;
+        Some(RemoteFlutterSdk {
+            release: Some(self.details()),
+            ..sdk
+        })
+    }
+
+    pub fn is_built_for(&self, arch: Architecture) -> bool {
+        let expected = match arch {
+            Architecture::X86_64 => "x64",
+            Architecture::Aarch64 => "arm64",
+            Architecture::Unknown => return false,
+        };
+        self.dart_sdk_arch.as_deref().unwrap_or("x64") == expected
+    }
+
+    fn details(&self) -> ReleaseDetails {
+        ReleaseDetails {
+            channel: self.channel.clone(),
+            dart_sdk_version: self.dart_sdk_version.clone(),
+            release_date: self.release_date.clone(),
+        }
+    }
+}
+
+/// Lists the Flutter SDKs from the release manifest published with the release archives, which
+/// has the Dart SDK versions and the release dates unlike `git ls-remote`.
+pub struct ReleaseManifestRepository;
+
+pub const RELEASE_MANIFEST_REPOSITORY: ReleaseManifestRepository = ReleaseManifestRepository;
+
+impl ReleaseManifestRepository {
+    /// Fetches the release manifest for the operating system of `context`, or returns `None` if
+    /// no archive is published for it.
+    pub fn fetch_manifest(
+        &self,
+        context: &impl FenvContext,
+        downloader: &impl Downloader,
+        storage_base_url: Option<&str>,
+    ) -> anyhow::Result<Option<ReleaseManifest>> {
+        let os_name = match context.operating_system() {
+            OperatingSystem::Linux => "linux",
+            OperatingSystem::MacOs => "macos",
+            OperatingSystem::Windows => "windows",
+            OperatingSystem::Unknown => return anyhow::Ok(None),
+        };
+        let storage_base_url = storage_base_url.unwrap_or(DEFAULT_STORAGE_BASE_URL);
+        let url =
+            format!("{storage_base_url}/flutter_infra_release/releases/releases_{os_name}.json");
+        let options = storage_download_options(context, Some(storage_base_url))?;
+        options.verify_host(&url)?;
+        let body = downloader
+            .fetch(&url, &options)
+            .map_err(|e| InstallFailureReason::Network.wrap(e))?;
+        let manifest: ReleaseManifest = serde_json::from_str(&body)
+            .with_context(|| format!("Failed to parse the release archives: `{url}`"))?;
+        anyhow::Ok(Some(manifest))
+    }
+
+    /// Lists the released versions in ascending order, followed by the channels at their latest
+    /// releases.
+    ///
+    /// If `archives_only` is set, the versions without the archive for the architecture of
+    /// `context` are excluded, because they cannot be installed without `git`.
+    pub fn fetch_available_sdk_list(
+        &self,
+        context: &impl FenvContext,
+        downloader: &impl Downloader,
+        storage_base_url: Option<&str>,
+        archives_only: bool,
+    ) -> anyhow::Result<Vec<RemoteFlutterSdk>> {
+        let Some(manifest) = self.fetch_manifest(context, downloader, storage_base_url)? else {
+            bail!("No release archive is published for this operating system")
+        };
+        let arch = context.architecture();
+        let mut sdks: Vec<RemoteFlutterSdk> = manifest
+            .releases
+            .iter()
+            .filter(|release| !archives_only || release.is_built_for(arch))
+            .filter_map(|release| release.to_remote_sdk())
+            .collect();
+        // Same as `git ls-remote`, the canonical tag is kept for each version, preferring the
+        // stable release to the beta one of the same version.
+        sdks.sort_by(|a, b| {
+            a.kind
+                .cmp(&b.kind)
+                .then_with(|| b.is_canonical().cmp(&a.is_canonical()))
+                .then_with(|| a.is_prerelease().cmp(&b.is_prerelease()))
+        });
+        sdks.dedup_by(|a, b| a.kind == b.kind);
+        for (channel, hash) in &manifest.current_release {
+            let release = manifest
+                .releases
+                .iter()
+                .find(|release| &release.hash == hash && &release.channel == channel);
+            sdks.push(RemoteFlutterSdk {
+                kind: GitRefsKind::Head(channel.clone()),
+                sha: hash.clone(),
+                short: channel.clone(),
+                long: format!("refs/heads/{channel}"),
+                release: release.map(|release| release.details()),
+            });
+        }
+        debug!(
+            "fetch_available_sdk_list(): {} SDKs in the release manifest",
+            sdks.len()
+        );
+        anyhow::Ok(sdks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{storage_download_options, RELEASE_MANIFEST_REPOSITORY};
+    use crate::{
+        context::FenvContext,
+        external::downloader::MockDownloader,
+        sdk_service::model::remote_flutter_sdk::{GitRefsKind, ReleaseDetails},
+        service::macros::test_with_context,
+    };
+
+    #[test]
+    fn test_storage_download_options_allow_mirror_and_configured_hosts() {
+        test_with_context(|context, _| {
+            // setup
+            context
+                .fenv_config_file()
+                .writeln(r#"{"download": {"allowedHosts": ["cdn.example.com"]}}"#)
+                .unwrap();
+
+            // execution
+            let options =
+                storage_download_options(context, Some("https://flutter-storage.example.com:8443"))
+                    .unwrap();
+
+            // validation
+            assert_eq!(
+                options.allowed_hosts.unwrap(),
+                [
+                    "storage.googleapis.com",
+                    "flutter-storage.example.com",
+                    "cdn.example.com"
+                ]
+            );
+        })
+    }
+
+    #[test]
+    fn test_fetch_available_sdk_list() {
+        test_with_context(|context, _| {
+            // setup
+            let manifest = serde_json::json!({
+                "current_release": {
+                    "beta": "f4c7dd9f3e52f10a4bd1e7c0e0d0c66acb9de0a3",
+                    "stable": "761747bfc538b5af34aa0d3fac380f1bc331ec49",
+                },
+                "releases": [
+                    {
+                        "hash": "f4c7dd9f3e52f10a4bd1e7c0e0d0c66acb9de0a3",
+                        "channel": "beta",
+                        "version": "3.23.0-0.1.pre",
+                        "dart_sdk_version": "3.5.0 (build 3.5.0-180.3.beta)",
+                        "release_date": "2024-06-07T17:53:09.153371Z",
+                        "archive": "beta/linux/flutter_linux_3.23.0-0.1.pre-beta.tar.xz",
+                        "sha256": "0000",
+                    },
+                    {
+                        "hash": "761747bfc538b5af34aa0d3fac380f1bc331ec49",
+                        "channel": "beta",
+                        "version": "3.22.2",
+                        "archive": "beta/linux/flutter_linux_3.22.2-beta.tar.xz",
+                        "sha256": "0000",
+                    },
+                    {
+                        "hash": "761747bfc538b5af34aa0d3fac380f1bc331ec49",
+                        "channel": "stable",
+                        "version": "3.22.2",
+                        "dart_sdk_version": "3.4.3",
+                        "release_date": "2024-06-06T18:40:14.437459Z",
+                        "archive": "stable/linux/flutter_linux_3.22.2-stable.tar.xz",
+                        "sha256": "0000",
+                    },
+                    {
+                        "hash": "fd9d2d5d2e5dc3a7e4e5a5d2e5dc3a7e4e5a5d2e",
+                        "channel": "stable",
+                        "version": "v1.12.13+hotfix.9",
+                        "archive": "stable/linux/flutter_linux_v1.12.13+hotfix.9-stable.tar.xz",
+                        "sha256": "0000",
+                    },
+                ]
+            })
+            .to_string();
+            let mut downloader = MockDownloader::new();
+            downloader
+                .expect_fetch()
+                .returning(move |_, _| anyhow::Ok(manifest.clone()));
+
+            // execution
+            let sdks = RELEASE_MANIFEST_REPOSITORY
+                .fetch_available_sdk_list(context, &downloader, None, false)
+                .unwrap();
+
+            // validation
+            let names: Vec<&str> = sdks.iter().map(|sdk| &sdk.short[..]).collect();
+            assert_eq!(
+                names,
+                [
+                    "v1.12.13+hotfix.9",
+                    "3.22.2",
+                    "3.23.0-0.1.pre",
+                    "beta",
+                    "stable"
+                ]
+            );
+            let prereleases: Vec<bool> = sdks.iter().map(|sdk| sdk.is_prerelease()).collect();
+            assert_eq!(prereleases, [false, false, true, false, false]);
+            let stable = sdks.last().unwrap();
+            assert_eq!(stable.kind, GitRefsKind::Head(String::from("stable")));
+            assert_eq!(stable.sha, "761747bfc538b5af34aa0d3fac380f1bc331ec49");
+            assert_eq!(
+                stable.release,
+                Some(ReleaseDetails {
+                    channel: String::from("stable"),
+                    dart_sdk_version: Some(String::from("3.4.3")),
+                    release_date: Some(String::from("2024-06-06T18:40:14.437459Z")),
+                })
+            );
+        })
+    }
+}
@@ -1,10 +1,17 @@
-use super::model::{
-    flutter_sdk::FlutterSdk,
-    remote_flutter_sdk::{GitRefsKind, RemoteFlutterSdk},
+use super::{
+    model::{
+        flutter_sdk::FlutterSdk,
+        remote_flutter_sdk::{GitRefsKind, RemoteFlutterSdk},
+    },
+    results::InstallFailureReason,
 };
-use crate::{context::FenvContext, external::git_command::GitCommand, util::path_like::PathLike};
-use log::debug;
-use std::collections::HashSet;
+use crate::{
+    context::FenvContext,
+    external::git_command::{GitCommand, GitRemote},
+    util::path_like::PathLike,
+};
+use anyhow::Context;
+use log::{debug, info, warn};
 
 pub struct RemoteSdkRepository;
 
@@ -14,66 +21,118 @@ impl RemoteSdkRepository {
     pub fn fetch_available_sdk_list(
         &self,
         git_command: &impl GitCommand,
+        remote: &GitRemote,
     ) -> anyhow::Result<Vec<RemoteFlutterSdk>> {
-        let mut sdks = list_remote_sdks_by_tags(git_command)?;
-        sdks.extend(list_remote_sdks_by_branches(git_command)?);
+        let mut sdks = list_remote_sdks_by_tags(git_command, remote)?;
+        sdks.extend(list_remote_sdks_by_branches(git_command, remote)?);
         Ok(sdks)
     }
 
+    /// Lists the branches only, such as `stable` and `master`.
+    pub fn fetch_available_channel_list(
+        &self,
+        git_command: &impl GitCommand,
+        remote: &GitRemote,
+    ) -> anyhow::Result<Vec<RemoteFlutterSdk>> {
+        list_remote_sdks_by_branches(git_command, remote)
+    }
+
+    /// Installs the given `sdk` into `{fenv_root}/versions/{install_name}`.
+    ///
+    /// If `resumes` is set, the clone interrupted in the destination is resumed. When it cannot
+    /// be resumed, the destination is removed and cloned again from scratch.
     pub fn install_sdk(
         &self,
         context: &impl FenvContext,
         git_command: &impl GitCommand,
         sdk: &RemoteFlutterSdk,
+        install_name: &str,
+        remote: &GitRemote,
+        resumes: bool,
     ) -> anyhow::Result<PathLike> {
-        match &sdk.kind {
-            GitRefsKind::Tag(_) => {
-                let destination = context.fenv_sdk_root(&sdk.display_name());
-                git_command
-                    .clone_flutter_sdk_by_version(&sdk.display_name(), &destination.to_string())?;
-                anyhow::Ok(destination)
-            }
-            GitRefsKind::Head(channel) => {
-                let destination = context.fenv_sdk_root(channel);
-                git_command.clone_flutter_sdk_by_channel(channel, &destination.to_string())?;
-                anyhow::Ok(destination)
+        let destination = match &sdk.kind {
+            GitRefsKind::Tag(_) => context.fenv_sdk_root(install_name),
+            GitRefsKind::Head(channel) => context.fenv_sdk_root(channel),
+        };
+        if resumes {
+            info!("install_sdk(): Resuming the interrupted clone in `{destination}`");
+            match resume(git_command, sdk, &destination, remote) {
+                Ok(()) => return anyhow::Ok(destination),
+                Err(e) => {
+                    warn!("install_sdk(): Failed to resume, cloning again: {e}");
+                    destination
+                        .remove_dir_all()
+                        .with_context(|| format!("Failed to remove `{destination}`"))?;
+                }
             }
         }
+        match &sdk.kind {
+            GitRefsKind::Tag(_) => git_command.clone_flutter_sdk_by_version(
+                &sdk.display_name(),
+                &destination.to_string(),
+                remote,
+            )?,
+            GitRefsKind::Head(channel) => git_command.clone_flutter_sdk_by_channel(
+                channel,
+                &destination.to_string(),
+                remote,
+            )?,
+        }
+        anyhow::Ok(destination)
+    }
+}
+
+fn resume(
+    git_command: &impl GitCommand,
+    sdk: &RemoteFlutterSdk,
+    destination: &PathLike,
+    remote: &GitRemote,
+) -> anyhow::Result<()> {
+    match &sdk.kind {
+        GitRefsKind::Tag(_) => git_command.resume_flutter_sdk_by_version(
+            &sdk.display_name(),
+            &destination.to_string(),
+            remote,
+        ),
+        GitRefsKind::Head(channel) => {
+            git_command.resume_flutter_sdk_by_channel(channel, &destination.to_string(), remote)
+        }
     }
 }
 
 fn list_remote_sdks_by_tags(
     git_command: &impl GitCommand,
+    remote: &GitRemote,
 ) -> anyhow::Result<Vec<RemoteFlutterSdk>> {
-    let git_output = git_command.list_remote_sdks_by_tags()?;
+    let git_output = git_command
+        .list_remote_sdks_by_tags(remote)
+        .map_err(|e| InstallFailureReason::Network.wrap(e))?;
     debug!("list_remote_sdks_by_tags(): stdout:\n{git_output}");
 
     let mut lines = git_output.split("\n");
-    // Holds kind keys for eliminating duplications
-    let mut registered_kind_keys: HashSet<String> = HashSet::new();
     let mut git_refs = lines
         .by_ref()
         .map(|line| RemoteFlutterSdk::parse(line))
         .flatten()
-        // Remove duplications
-        .filter(|sdk| {
-            let key = sdk.kind.key();
-            if registered_kind_keys.contains(&key) {
-                false
-            } else {
-                registered_kind_keys.insert(key);
-                true
-            }
-        })
         .collect::<Vec<RemoteFlutterSdk>>();
-    git_refs.sort_by(|a, b| a.kind.cmp(&b.kind));
+    // Remove duplications such as `v1.17.5` of `1.17.5`, keeping the canonical tag so that
+    // the same release always resolves to the same tag.
+    git_refs.sort_by(|a, b| {
+        a.kind
+            .cmp(&b.kind)
+            .then_with(|| b.is_canonical().cmp(&a.is_canonical()))
+    });
+    git_refs.dedup_by(|a, b| a.kind.key() == b.kind.key());
     Ok(git_refs)
 }
 
 fn list_remote_sdks_by_branches(
     git_command: &impl GitCommand,
+    remote: &GitRemote,
 ) -> anyhow::Result<Vec<RemoteFlutterSdk>> {
-    let git_output = git_command.list_remote_sdks_by_branches()?;
+    let git_output = git_command
+        .list_remote_sdks_by_branches(remote)
+        .map_err(|e| InstallFailureReason::Network.wrap(e))?;
     debug!("list_remote_sdks_by_branches(): stdout:\n{git_output}");
 
     let mut lines = git_output.split("\n");
@@ -90,11 +149,15 @@ impl GitRefsKind {
     fn key(&self) -> String {
         match self {
             GitRefsKind::Tag(version) => format!(
-                "{major}.{minor}.{patch}.{hotfix}",
+                "{major}.{minor}.{patch}.{hotfix}{pre}",
                 major = version.major,
                 minor = version.minor,
                 patch = version.patch,
                 hotfix = version.hotfix,
+                pre = version
+                    .pre
+                    .map(|(pre_major, pre_minor)| format!("-{pre_major}.{pre_minor}"))
+                    .unwrap_or_default(),
             ),
             GitRefsKind::Head(branch) => String::from(branch),
         }
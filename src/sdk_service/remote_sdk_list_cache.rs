@@ -1,27 +1,34 @@
 use crate::{
-    context::FenvContext, sdk_service::model::remote_flutter_sdk::RemoteFlutterSdk,
-    util::chrono_wrapper::Clock,
+    context::FenvContext, external::git_command::FLUTTER_GIT_URL,
+    sdk_service::model::remote_flutter_sdk::RemoteFlutterSdk, util::chrono_wrapper::Clock,
 };
 use anyhow::Context;
 use chrono::{DateTime, Duration};
 use serde::{Deserialize, Serialize};
 
-const CACHE_FILE_NAME: &'static str = ".remote_list";
+/// The file name of the cached list in `{fenv_cache}`.
+pub const CACHE_FILE_NAME: &'static str = ".remote_list";
 
 /// Cache expiration in seconds.
 ///
 /// For now, 5 minutes.
 const CACHE_EXPIRATION: i64 = 5 * 60;
 
+/// Caches the refs in the remote repository only. Whether each of them is installed is never
+/// cached, and is read from `{fenv_versions}` whenever it is listed.
 pub struct RemoteSdkListCache;
 
 pub const REMOTE_SDK_LIST_CACHE: RemoteSdkListCache = RemoteSdkListCache;
 
 impl RemoteSdkListCache {
+    /// Loads the cached list of the remote flutter SDKs in the repository at `git_url`.
+    ///
+    /// Returns `None` if the list is expired or cached for another repository.
     pub fn load_list(
         &self,
         context: &impl FenvContext,
         clock: &impl Clock,
+        git_url: &str,
     ) -> Option<Vec<RemoteFlutterSdk>> {
         let content = context
             .fenv_cache()
@@ -32,16 +39,48 @@ impl RemoteSdkListCache {
         if is_cache_expired(&cache, clock) {
             return None;
         }
+        if cache.git_url.as_deref().unwrap_or(FLUTTER_GIT_URL) != git_url {
+            return None;
+        }
         Some(cache.list)
     }
 
-    /// Stores the given `list` of the remote flutter SDKs to `cache_file`.
+    /// Loads the cached list of the remote flutter SDKs in the repository at `git_url` even if it
+    /// is expired, with how long ago it was cached, for the answers without the network.
+    ///
+    /// Returns `None` if the list is not cached or cached for another repository.
+    pub fn load_stale_list(
+        &self,
+        context: &impl FenvContext,
+        clock: &impl Clock,
+        git_url: &str,
+    ) -> Option<(Vec<RemoteFlutterSdk>, std::time::Duration)> {
+        let content = context
+            .fenv_cache()
+            .join(CACHE_FILE_NAME)
+            .read_to_string()
+            .ok()?;
+        let cache = serde_json::from_str::<RemoteSdkListCacheContent>(&content).ok()?;
+        if cache.git_url.as_deref().unwrap_or(FLUTTER_GIT_URL) != git_url {
+            return None;
+        }
+        let expires_at = DateTime::parse_from_rfc3339(&cache.expires_at).ok()?;
+        let cached_at = expires_at - Duration::seconds(CACHE_EXPIRATION);
+        let age = (clock.utc_now() - cached_at.with_timezone(&chrono::Utc))
+            .to_std()
+            .unwrap_or_default();
+        Some((cache.list, age))
+    }
+
+    /// Stores the given `list` of the remote flutter SDKs in the repository at `git_url`
+    /// to `cache_file`.
     ///
     /// The cached list will be expired in 5 minutes.
     pub fn store_list(
         &self,
         context: &impl FenvContext,
         clock: &impl Clock,
+        git_url: &str,
         list: &[RemoteFlutterSdk],
     ) -> anyhow::Result<()> {
         let cache_file = context.fenv_cache().join(CACHE_FILE_NAME);
@@ -55,6 +94,9 @@ impl RemoteSdkListCache {
 
         let cache = RemoteSdkListCacheContent {
             expires_at: (clock.utc_now() + Duration::seconds(CACHE_EXPIRATION)).to_rfc3339(),
+            git_url: Some(git_url)
+                .filter(|git_url| *git_url != FLUTTER_GIT_URL)
+                .map(String::from),
             list: list.to_vec(),
         };
         cache_file
@@ -67,6 +109,9 @@ impl RemoteSdkListCache {
 #[derive(Debug, Serialize, Deserialize)]
 struct RemoteSdkListCacheContent {
     expires_at: String,
+    /// The repository where the list comes from. `None` for the upstream repository.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    git_url: Option<String>,
     list: Vec<RemoteFlutterSdk>,
 }
 
@@ -123,6 +168,7 @@ mod tests {
                 sha: sha.to_string(),
                 short: short.to_string(),
                 long: long.to_string(),
+                release: None,
             }
         }
 
@@ -361,7 +407,9 @@ mod tests {
             cache_file.write(BAKED_SAMPLE_JSON).unwrap();
 
             // execution
-            let actual = REMOTE_SDK_LIST_CACHE.load_list(context, &clock).unwrap();
+            let actual = REMOTE_SDK_LIST_CACHE
+                .load_list(context, &clock, FLUTTER_GIT_URL)
+                .unwrap();
 
             // validation
             assert_eq!(bake_sample(), actual)
@@ -377,7 +425,52 @@ mod tests {
             cache_file.write(BAKED_SAMPLE_JSON).unwrap();
 
             // execution && validation
-            assert!(REMOTE_SDK_LIST_CACHE.load_list(context, &clock).is_none());
+            assert!(REMOTE_SDK_LIST_CACHE
+                .load_list(context, &clock, FLUTTER_GIT_URL)
+                .is_none());
+        });
+    }
+
+    #[test]
+    fn test_load_stale_list_returns_expired_list_with_age() {
+        test_with_context(|context, _| {
+            // setup
+            let clock = FakeClock::from("2020-01-03T00:00:00+00:00");
+            let cache_file = context.fenv_cache().join(".remote_list");
+            cache_file.write(BAKED_SAMPLE_JSON).unwrap();
+
+            // execution
+            let (list, age) = REMOTE_SDK_LIST_CACHE
+                .load_stale_list(context, &clock, FLUTTER_GIT_URL)
+                .unwrap();
+
+            // validation
+            assert_eq!(list, bake_sample());
+            assert_eq!(age, std::time::Duration::from_secs(2 * 86400));
+            assert!(REMOTE_SDK_LIST_CACHE
+                .load_stale_list(context, &clock, "https://git.example.com/flutter.git")
+                .is_none());
+        });
+    }
+
+    #[test]
+    fn test_lookup_cached_list_returns_none_when_cached_for_another_repository() {
+        test_with_context(|context, _| {
+            // setup
+            let clock = FakeClock::from("2020-01-01T00:00:00+00:00");
+            let fork = "https://git.example.com/mobile/flutter.git";
+            REMOTE_SDK_LIST_CACHE
+                .store_list(context, &clock, fork, &bake_sample())
+                .unwrap();
+
+            // execution && validation
+            assert!(REMOTE_SDK_LIST_CACHE
+                .load_list(context, &clock, FLUTTER_GIT_URL)
+                .is_none());
+            assert_eq!(
+                REMOTE_SDK_LIST_CACHE.load_list(context, &clock, fork),
+                Some(bake_sample())
+            );
         });
     }
 
@@ -385,7 +478,9 @@ mod tests {
     fn test_lookup_cached_list_returns_none_when_no_file_exists() {
         test_with_context(|context, _| {
             let clock = FakeClock::new();
-            assert!(REMOTE_SDK_LIST_CACHE.load_list(context, &clock).is_none())
+            assert!(REMOTE_SDK_LIST_CACHE
+                .load_list(context, &clock, FLUTTER_GIT_URL)
+                .is_none())
         });
     }
 
@@ -398,7 +493,9 @@ mod tests {
             cache_file.write(r#"{"not_valid": "format"}"#).unwrap();
 
             // execution & validation
-            assert!(REMOTE_SDK_LIST_CACHE.load_list(context, &clock).is_none())
+            assert!(REMOTE_SDK_LIST_CACHE
+                .load_list(context, &clock, FLUTTER_GIT_URL)
+                .is_none())
         });
     }
 
@@ -412,7 +509,7 @@ mod tests {
 
             // execution
             REMOTE_SDK_LIST_CACHE
-                .store_list(context, &clock, &list)
+                .store_list(context, &clock, FLUTTER_GIT_URL, &list)
                 .unwrap();
 
             // validation
@@ -430,7 +527,8 @@ mod tests {
             cache_dir.create_file().unwrap();
 
             // execution
-            let actual = REMOTE_SDK_LIST_CACHE.store_list(context, &clock, &vec![]);
+            let actual =
+                REMOTE_SDK_LIST_CACHE.store_list(context, &clock, FLUTTER_GIT_URL, &vec![]);
 
             // validation
             assert!(actual.is_err());
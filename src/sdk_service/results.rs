@@ -63,3 +63,174 @@ pub struct InstalledSdkSummary {
     pub latest_local_sdk: LocalFlutterSdk,
     pub path_to_sdk_root: PathLike,
 }
+
+/// Returned when the selected Flutter SDK is installed but broken, such as an interrupted
+/// installation or a missing `bin/flutter`. `fenv` exits with [`BrokenInstallation::EXIT_CODE`].
+#[derive(Debug)]
+pub struct BrokenInstallation {
+    pub name: String,
+    pub path_to_sdk_root: PathLike,
+}
+
+impl BrokenInstallation {
+    pub const EXIT_CODE: i32 = 3;
+}
+
+impl std::fmt::Display for BrokenInstallation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The Flutter SDK in `{path}` is broken: do `fenv uninstall {name} && fenv install {name}`",
+            path = self.path_to_sdk_root,
+            name = self.name,
+        )
+    }
+}
+
+impl std::error::Error for BrokenInstallation {}
+
+/// Why installing a Flutter SDK failed, so that CI can retry only the transient failures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallFailureReason {
+    /// The remote repository or the release archive could not be fetched.
+    Network,
+    /// The downloaded release archive does not match its checksum.
+    Checksum,
+    /// No space is left on the device.
+    DiskFull,
+    /// No tag, branch, commit, or release archive matches the given version.
+    GitRefMissing,
+    /// A file or a directory is not permitted to be written.
+    Permissions,
+    /// The release archive cannot be extracted or does not have the Flutter SDK.
+    ArchiveCorrupt,
+}
+
+impl InstallFailureReason {
+    /// The name reported as `reason` by `--error-format json`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Network => "network",
+            Self::Checksum => "checksum",
+            Self::DiskFull => "disk-full",
+            Self::GitRefMissing => "git-ref-missing",
+            Self::Permissions => "permissions",
+            Self::ArchiveCorrupt => "archive-corrupt",
+        }
+    }
+
+    /// The exit code of `fenv`, which is distinct per reason.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Network => 10,
+            Self::Checksum => 11,
+            Self::DiskFull => 12,
+            Self::GitRefMissing => 13,
+            Self::Permissions => 14,
+            Self::ArchiveCorrupt => 15,
+        }
+    }
+
+    /// Whether retrying the same installation may succeed: a broken download may not happen
+    /// again, but the others need a fix.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::Network | Self::Checksum)
+    }
+
+    /// Wraps `error` as a failure of this reason, keeping its message and causes.
+    pub fn wrap(self, error: anyhow::Error) -> anyhow::Error {
+        anyhow::Error::new(InstallFailure {
+            reason: self,
+            error,
+        })
+    }
+}
+
+/// An error classified by [`InstallFailureReason`], which stays in the chain of the contexts
+/// added afterwards. `fenv` exits with [`InstallFailureReason::exit_code`].
+#[derive(Debug)]
+pub struct InstallFailure {
+    pub reason: InstallFailureReason,
+    error: anyhow::Error,
+}
+
+impl InstallFailure {
+    /// The reason of the innermost failure in the chain of `error`.
+    pub fn reason_of(error: &anyhow::Error) -> Option<InstallFailureReason> {
+        error
+            .chain()
+            .filter_map(|cause| cause.downcast_ref::<InstallFailure>())
+            .last()
+            .map(|failure| failure.reason)
+    }
+
+    /// Classifies `error` by the I/O error in its chain, such as no space left on the device,
+    /// unless it is classified already.
+    pub fn classify(error: anyhow::Error) -> anyhow::Error {
+        if Self::reason_of(&error).is_some() {
+            return error;
+        }
+        let reason = error
+            .chain()
+            .filter_map(|cause| cause.downcast_ref::<std::io::Error>())
+            .find_map(|e| match e.kind() {
+                std::io::ErrorKind::StorageFull => Some(InstallFailureReason::DiskFull),
+                std::io::ErrorKind::PermissionDenied | std::io::ErrorKind::ReadOnlyFilesystem => {
+                    Some(InstallFailureReason::Permissions)
+                }
+                _ => None,
+            });
+        match reason {
+            Some(reason) => reason.wrap(error),
+            None => error,
+        }
+    }
+}
+
+impl std::fmt::Display for InstallFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl std::error::Error for InstallFailure {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.error.source()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InstallFailure, InstallFailureReason};
+    use anyhow::Context;
+
+    #[test]
+    fn test_classify_keeps_messages() {
+        let error = Err::<(), _>(std::io::Error::from(std::io::ErrorKind::StorageFull))
+            .context("Failed to create `3.22.2`")
+            .unwrap_err();
+        let error = InstallFailure::classify(error).context("Failed to install `3.22`");
+        assert_eq!(
+            InstallFailure::reason_of(&error),
+            Some(InstallFailureReason::DiskFull)
+        );
+        let messages: Vec<String> = error.chain().map(|cause| cause.to_string()).collect();
+        assert_eq!(
+            messages,
+            [
+                "Failed to install `3.22`",
+                "Failed to create `3.22.2`",
+                &std::io::Error::from(std::io::ErrorKind::StorageFull).to_string(),
+            ]
+        );
+
+        // The reason attached beforehand is preserved.
+        let error = InstallFailureReason::Network.wrap(anyhow::anyhow!("Failed to fetch"));
+        let error = InstallFailure::classify(error);
+        assert_eq!(
+            InstallFailure::reason_of(&error),
+            Some(InstallFailureReason::Network)
+        );
+        assert!(!InstallFailure::classify(anyhow::anyhow!("Unknown")).is::<InstallFailure>());
+    }
+}
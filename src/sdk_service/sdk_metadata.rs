@@ -0,0 +1,151 @@
+use super::model::{flutter_sdk::FlutterSdk, local_flutter_sdk::LocalFlutterSdk};
+use crate::util::{path_like::PathLike, size::disk_usage};
+use chrono::{DateTime, Utc};
+use log::debug;
+use serde::Deserialize;
+
+/// The metadata of an installed Flutter SDK, collected by inspecting its directory.
+#[derive(Debug, Clone)]
+pub struct SdkMetadata {
+    pub name: String,
+    pub path: PathLike,
+    /// The commit SHA that the SDK is checked out at, if its repository is readable.
+    pub commit_sha: Option<String>,
+    /// The channel of the SDK: its name for a channel installation, or the one recorded by
+    /// `flutter` in `bin/cache/flutter.version.json` for a version.
+    pub channel: Option<String>,
+    /// The version in `bin/cache/dart-sdk/version`, which exists once `flutter` has run.
+    pub dart_sdk_version: Option<String>,
+    /// The total size of the files in the SDK in bytes.
+    pub disk_usage: u64,
+    /// When the SDK directory was created, or last modified if the creation time is unknown.
+    pub installed_at: Option<DateTime<Utc>>,
+}
+
+/// A definition of the part of `bin/cache/flutter.version.json` read by `fenv`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FlutterVersionJson {
+    channel: Option<String>,
+    framework_version: Option<String>,
+}
+
+/// Collects the metadata of `sdk` installed in `path_to_sdk_root`, and `commit_sha` read from
+/// its repository.
+pub fn collect(
+    sdk: &LocalFlutterSdk,
+    path_to_sdk_root: &PathLike,
+    commit_sha: Option<String>,
+) -> SdkMetadata {
+    let channel = match sdk {
+        LocalFlutterSdk::Channel(channel) => Some(channel.channel_name().to_string()),
+        LocalFlutterSdk::Version { .. } | LocalFlutterSdk::Commit(_) => {
+            read_channel(path_to_sdk_root)
+        }
+    };
+    SdkMetadata {
+        name: sdk.display_name(),
+        path: path_to_sdk_root.clone(),
+        commit_sha,
+        channel,
+        dart_sdk_version: read_dart_sdk_version(path_to_sdk_root),
+        disk_usage: disk_usage(path_to_sdk_root),
+        installed_at: read_installed_at(path_to_sdk_root),
+    }
+}
+
+fn read_channel(path_to_sdk_root: &PathLike) -> Option<String> {
+    read_flutter_version_json(path_to_sdk_root)?
+        .channel
+        .filter(|channel| !channel.is_empty())
+}
+
+/// The version of Flutter which the SDK at `path_to_sdk_root` is at, such as `3.22.2` of
+/// the `stable` channel, recorded in `bin/cache/flutter.version.json` or the legacy `version`
+/// file. `None` until `flutter` has run once.
+pub fn read_framework_version(path_to_sdk_root: &PathLike) -> Option<String> {
+    let framework_version = read_flutter_version_json(path_to_sdk_root)
+        .and_then(|json| json.framework_version)
+        .or_else(|| path_to_sdk_root.join("version").read_to_string().ok())?;
+    Some(framework_version.trim().to_string()).filter(|version| !version.is_empty())
+}
+
+fn read_flutter_version_json(path_to_sdk_root: &PathLike) -> Option<FlutterVersionJson> {
+    let path = path_to_sdk_root
+        .join("bin")
+        .join("cache")
+        .join("flutter.version.json");
+    let content = path.read_to_string().ok()?;
+    match serde_json::from_str::<FlutterVersionJson>(&content) {
+        Ok(json) => Some(json),
+        Err(e) => {
+            debug!("read_flutter_version_json(): Failed to parse `{path}`: {e}");
+            None
+        }
+    }
+}
+
+/// The version of the Dart SDK bundled with the SDK at `path_to_sdk_root`.
+/// `None` until the Dart SDK is downloaded by the first run of `flutter`.
+pub fn read_dart_sdk_version(path_to_sdk_root: &PathLike) -> Option<String> {
+    let path = path_to_sdk_root
+        .join("bin")
+        .join("cache")
+        .join("dart-sdk")
+        .join("version");
+    let version = path.read_to_string().ok()?;
+    Some(version.trim().to_string()).filter(|version| !version.is_empty())
+}
+
+/// When the SDK at `path_to_sdk_root` was installed.
+///
+/// The creation time of the directory is used if the file system supports it,
+/// otherwise its last modification time.
+pub fn read_installed_at(path_to_sdk_root: &PathLike) -> Option<DateTime<Utc>> {
+    let metadata = path_to_sdk_root.path().metadata().ok()?;
+    let time = metadata.created().or_else(|_| metadata.modified()).ok()?;
+    Some(DateTime::<Utc>::from(time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect, read_framework_version};
+    use crate::{
+        context::FenvContext, sdk_service::model::local_flutter_sdk::LocalFlutterSdk,
+        service::macros::test_with_context,
+    };
+
+    #[test]
+    fn test_collect() {
+        test_with_context(|context, _| {
+            // setup
+            let sdk_root = context.fenv_sdk_root("3.22.2");
+            let cache = sdk_root.join("bin").join("cache");
+            cache
+                .join("dart-sdk")
+                .join("version")
+                .writeln("3.4.3")
+                .unwrap();
+            cache
+                .join("flutter.version.json")
+                .write(r#"{"frameworkVersion": "3.22.2", "channel": "stable"}"#)
+                .unwrap();
+
+            // execution
+            let metadata = collect(
+                &LocalFlutterSdk::parse("3.22.2").unwrap(),
+                &sdk_root,
+                Some(String::from("761747bfc538b5af34aa0d3fac380f1bc331ec49")),
+            );
+
+            // validation
+            assert_eq!(metadata.name, "3.22.2");
+            assert_eq!(metadata.path, sdk_root);
+            assert_eq!(metadata.channel.as_deref(), Some("stable"));
+            assert_eq!(metadata.dart_sdk_version.as_deref(), Some("3.4.3"));
+            assert_eq!(metadata.disk_usage, 6 + 51);
+            assert!(metadata.installed_at.is_some());
+            assert_eq!(read_framework_version(&sdk_root).as_deref(), Some("3.22.2"));
+        })
+    }
+}
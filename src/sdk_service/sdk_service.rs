@@ -1,35 +1,100 @@
 use super::{
+    alias,
+    install_lock::InstallLocks,
     local_repository::{LocalSdkRepository, LOCAL_SDK_REPOSITORY},
-    model::{local_flutter_sdk::LocalFlutterSdk, remote_flutter_sdk::RemoteFlutterSdk},
+    model::{
+        flutter_channel::FlutterChannel,
+        flutter_version::FlutterVersion,
+        local_flutter_sdk::{is_commit_sha, is_valid_namespace, LocalFlutterSdk, SHORT_SHA_LENGTH},
+        remote_flutter_sdk::{GitRefsKind, RemoteFlutterSdk},
+    },
+    release_archive,
+    release_manifest::{FlutterRelease, ReleaseManifestRepository, RELEASE_MANIFEST_REPOSITORY},
     remote_repository::{RemoteSdkRepository, REMOTE_SDK_REPOSITORY},
     remote_sdk_list_cache::{RemoteSdkListCache, REMOTE_SDK_LIST_CACHE},
-    results::{InstalledSdkSummary, LookupResult, UninstalledSdkSummary, VersionFileReadResult},
+    results::{
+        InstallFailureReason, InstalledSdkSummary, LookupResult, UninstalledSdkSummary,
+        VersionFileReadResult,
+    },
+    sdk_metadata::{self, SdkMetadata},
     version_prefix_match::matches_prefix,
 };
 use crate::{
+    config::{FenvConfig, FenvProjectConfig},
     context::FenvContext,
     external::{
-        flutter_command::{FlutterCommand, FlutterCommandImpl},
-        git_command::{GitCommand, GitCommandImpl},
+        dart_command::{DartCommand, DartCommandImpl},
+        downloader::{Downloader, DownloaderImpl, OfflineDownloader},
+        flutter_command::{FlutterCommand, FlutterCommandImpl, PrecachePlatform},
+        git_command::{
+            is_git_available, GitCommand, GitCommandImpl, GitRemote, GitTlsOptions, FLUTTER_GIT_URL,
+        },
+        proxy::ProxyOptions,
     },
+    metrics::{self, Metric},
     sdk_service::model::flutter_sdk::FlutterSdk,
     unwrap_or_return,
     util::{
         chrono_wrapper::{Clock, SystemClock},
+        offline::ensure_online,
         path_like::PathLike,
     },
 };
 use anyhow::{bail, Context};
 use log::{debug, info, warn};
+use std::{sync::Mutex, time::Instant};
+
+/// How `SdkService` installs a Flutter SDK and sets it up.
+#[derive(Debug, Clone, Default)]
+pub struct InstallOptions {
+    pub should_doctor: bool,
+    pub should_precache: bool,
+    /// If empty, `flutter precache` downloads the artifacts of `precachePlatforms` of
+    /// `{fenv_root}/config.json`.
+    pub precache_platforms: Vec<PrecachePlatform>,
+    /// The Android SDK licenses are accepted also if `acceptAndroidLicenses` of
+    /// `{fenv_root}/config.json` is `true`.
+    pub accepts_android_licenses: bool,
+    pub fails_on_installed: bool,
+    pub include_beta: bool,
+}
 
-pub trait SdkService {
+/// Shared by the threads installing the Flutter SDKs in parallel.
+pub trait SdkService: Sync {
+    /// Installs the latest available Flutter SDK matching `prefix`.
     fn install_sdk(
         &self,
         context: &impl FenvContext,
         prefix: &str,
-        should_doctor: bool,
-        should_precache: bool,
-        fails_on_installed: bool,
+        options: &InstallOptions,
+        progress: &mut dyn std::io::Write,
+    ) -> anyhow::Result<()>;
+
+    /// Installs the Flutter SDK in the release archive `archive` downloaded beforehand as
+    /// `name`, without accessing the network except by `flutter` itself.
+    /// If `sha256` is given, the archive is verified against it before it is extracted.
+    /// It always fails if `name` is installed, and ignores `include_beta` of `options`.
+    fn install_sdk_from_archive(
+        &self,
+        context: &impl FenvContext,
+        archive: &PathLike,
+        sha256: Option<&str>,
+        name: &str,
+        options: &InstallOptions,
+        progress: &mut dyn std::io::Write,
+    ) -> anyhow::Result<()>;
+
+    /// Installs the latest version tagged in the repository at `git_url` matching `prefix` as
+    /// `{namespace}-{version}`, such as `fork-3.19.6`, so that it never collides with the
+    /// upstream one.
+    fn install_sdk_from_git(
+        &self,
+        context: &impl FenvContext,
+        git_url: &str,
+        namespace: &str,
+        prefix: &str,
+        options: &InstallOptions,
+        progress: &mut dyn std::io::Write,
     ) -> anyhow::Result<()>;
 
     fn get_installed_sdk_list(
@@ -37,9 +102,12 @@ pub trait SdkService {
         context: &impl FenvContext,
     ) -> anyhow::Result<Vec<LocalFlutterSdk>>;
 
+    /// Lists the available Flutter SDKs. The pre-releases published only to the beta channel,
+    /// such as `3.23.0-0.1.pre`, are excluded unless `include_beta` is set.
     fn get_available_remote_sdk_list(
         &self,
         context: &impl FenvContext,
+        include_beta: bool,
     ) -> anyhow::Result<Vec<RemoteFlutterSdk>>;
 
     fn find_nearest_version_file(
@@ -56,12 +124,25 @@ pub trait SdkService {
         prefix: &str,
     ) -> LookupResult<LocalFlutterSdk>;
 
+    /// Finds the latest available Flutter SDK which matches the given `prefix`.
+    /// The beta pre-releases are excluded unless `include_beta` is set or `prefix` itself is
+    /// a pre-release such as `3.23.0-0.1.pre`.
     fn find_latest_remote(
         &self,
         context: &impl FenvContext,
         prefix: &str,
+        include_beta: bool,
     ) -> LookupResult<RemoteFlutterSdk>;
 
+    /// Same as `find_latest_remote`, but from the cached list even if it is expired, without
+    /// accessing the network. Also returns how long ago the list was cached.
+    fn find_latest_stale_remote(
+        &self,
+        context: &impl FenvContext,
+        prefix: &str,
+        include_beta: bool,
+    ) -> LookupResult<(RemoteFlutterSdk, std::time::Duration)>;
+
     fn read_nearest_version_file(
         &self,
         context: &impl FenvContext,
@@ -74,6 +155,9 @@ pub trait SdkService {
         start_dir: &PathLike,
     ) -> VersionFileReadResult;
 
+    /// Reads the version prefix stored in the given version file without resolving it.
+    fn read_version_prefix(&self, path_to_version_file: &PathLike) -> anyhow::Result<String>;
+
     fn write_local_version(
         &self,
         destination_dir: &PathLike,
@@ -90,55 +174,211 @@ pub trait SdkService {
 
     fn uninstall(&self, context: &impl FenvContext, sdk: &LocalFlutterSdk) -> anyhow::Result<()>;
 
+    /// Removes `{fenv_root}/versions/{name}` even if `name` is not a valid Flutter SDK name.
+    fn uninstall_by_name(&self, context: &impl FenvContext, name: &str) -> anyhow::Result<()>;
+
+    /// Lists the names of the installations which are incomplete or do not have `bin/flutter`.
+    fn find_broken_installations(&self, context: &impl FenvContext) -> anyhow::Result<Vec<String>>;
+
+    /// Whether the installation named `name` is incomplete or does not have `bin/flutter`.
+    fn is_broken_installation(&self, context: &impl FenvContext, name: &str) -> bool;
+
+    /// Removes the installation named `name` if it is still broken after waiting for another
+    /// `fenv` installing it, and returns whether it was removed.
+    fn uninstall_broken_installation(
+        &self,
+        context: &impl FenvContext,
+        name: &str,
+    ) -> anyhow::Result<bool>;
+
+    /// Lists the problems of the installed `sdk`: the missing files, the remaining installing
+    /// marker, and the git HEAD which is not at the commit of the tag or the branch of `sdk`.
+    /// Returns an empty list if `sdk` is intact.
+    fn verify_sdk(&self, context: &impl FenvContext, sdk: &LocalFlutterSdk) -> Vec<String>;
+
+    /// Renames the installed `sdk` to `new_name` and leaves a symbolic link at the old name.
+    fn rename_sdk(
+        &self,
+        context: &impl FenvContext,
+        sdk: &LocalFlutterSdk,
+        new_name: &str,
+    ) -> anyhow::Result<()>;
+
+    /// Creates a symbolic link named `link_name` which points to the installed `sdk`.
+    fn link_sdk(
+        &self,
+        context: &impl FenvContext,
+        sdk: &LocalFlutterSdk,
+        link_name: &str,
+    ) -> anyhow::Result<()>;
+
     fn ensure_sdk_is_available(
         &self,
         version_file_read_result: &VersionFileReadResult,
     ) -> anyhow::Result<InstalledSdkSummary>;
+
+    /// Reads the commit SHA that the given installed SDK is checked out at.
+    fn read_sdk_commit_sha(&self, path_to_sdk_root: &PathLike) -> anyhow::Result<String>;
+
+    /// Runs `dart pub get` on `working_dir` with the `dart` of the given installed SDK.
+    fn pub_get(&self, path_to_sdk_root: &PathLike, working_dir: &PathLike) -> anyhow::Result<()>;
+
+    /// Runs `flutter create` with `args` to create a Flutter project in `project_dir` with the
+    /// `flutter` of the given installed SDK.
+    fn flutter_create(
+        &self,
+        path_to_sdk_root: &PathLike,
+        project_dir: &PathLike,
+        args: &[String],
+        progress: &mut dyn std::io::Write,
+    ) -> anyhow::Result<()>;
+
+    /// Collects the metadata of the given installed SDK by inspecting its directory.
+    fn collect_sdk_metadata(
+        &self,
+        context: &impl FenvContext,
+        sdk: &LocalFlutterSdk,
+    ) -> SdkMetadata;
+
+    /// Fast-forwards the installed `channel` to its upstream branch, followed by
+    /// `flutter precache` if `should_precache` is set and the channel is advanced.
+    /// Returns the commit SHAs before and after the upgrade.
+    fn upgrade_channel(
+        &self,
+        context: &impl FenvContext,
+        channel: &FlutterChannel,
+        should_precache: bool,
+        progress: &mut dyn std::io::Write,
+    ) -> anyhow::Result<(String, String)>;
 }
 
-struct SdkServiceInner<G: GitCommand, C: Clock, F: FlutterCommand> {
+struct SdkServiceInner<G, C, F, D, U>
+where
+    G: GitCommand,
+    C: Clock,
+    F: FlutterCommand,
+    D: DartCommand,
+    U: Downloader,
+{
     git_command: G,
     flutter_command: F,
+    dart_command: D,
+    downloader: U,
     clock: C,
     local_sdk_repository: LocalSdkRepository,
     remote_sdk_repository: RemoteSdkRepository,
+    release_manifest_repository: ReleaseManifestRepository,
     remote_sdk_list_cache: RemoteSdkListCache,
+    remote_sdk_list_lock: Mutex<()>,
+    install_locks: InstallLocks,
 }
 
-pub struct RealSdkService<G: GitCommand, C: Clock, F: FlutterCommand> {
-    inner: SdkServiceInner<G, C, F>,
+pub struct RealSdkService<G, C, F, D, U = DownloaderImpl>
+where
+    G: GitCommand,
+    C: Clock,
+    F: FlutterCommand,
+    D: DartCommand,
+    U: Downloader,
+{
+    inner: SdkServiceInner<G, C, F, D, U>,
 }
 
-impl RealSdkService<GitCommandImpl, SystemClock, FlutterCommandImpl> {
+impl RealSdkService<GitCommandImpl, SystemClock, FlutterCommandImpl, DartCommandImpl> {
     pub fn new() -> Self {
         Self {
             inner: SdkServiceInner {
                 git_command: GitCommandImpl::new(),
                 flutter_command: FlutterCommandImpl::new(),
+                dart_command: DartCommandImpl::new(),
+                downloader: DownloaderImpl::new(),
                 clock: SystemClock,
                 local_sdk_repository: LOCAL_SDK_REPOSITORY,
                 remote_sdk_repository: REMOTE_SDK_REPOSITORY,
+                release_manifest_repository: RELEASE_MANIFEST_REPOSITORY,
                 remote_sdk_list_cache: REMOTE_SDK_LIST_CACHE,
+                remote_sdk_list_lock: Mutex::new(()),
+                install_locks: InstallLocks::default(),
             },
         }
     }
 }
 
-impl<G, C, F> RealSdkService<G, C, F>
+impl<G, C, F> RealSdkService<G, C, F, DartCommandImpl, OfflineDownloader>
 where
     G: GitCommand,
     C: Clock,
     F: FlutterCommand,
 {
+    /// Nothing is downloaded unless `with_downloader` gives the backend, so the remote SDKs are
+    /// listed by `git_command` only.
     pub fn from(git_command: G, clock: C, flutter_command: F) -> Self {
         Self {
             inner: SdkServiceInner {
                 git_command,
                 flutter_command,
+                dart_command: DartCommandImpl::new(),
+                downloader: OfflineDownloader,
                 clock,
                 local_sdk_repository: LOCAL_SDK_REPOSITORY,
                 remote_sdk_repository: REMOTE_SDK_REPOSITORY,
+                release_manifest_repository: RELEASE_MANIFEST_REPOSITORY,
                 remote_sdk_list_cache: REMOTE_SDK_LIST_CACHE,
+                remote_sdk_list_lock: Mutex::new(()),
+                install_locks: InstallLocks::default(),
+            },
+        }
+    }
+}
+
+impl<G, C, F, D, U> RealSdkService<G, C, F, D, U>
+where
+    G: GitCommand,
+    C: Clock,
+    F: FlutterCommand,
+    D: DartCommand,
+    U: Downloader,
+{
+    /// Replaces the `dart` CLI, which is `DartCommandImpl` by default.
+    pub fn with_dart_command<D2: DartCommand>(
+        self,
+        dart_command: D2,
+    ) -> RealSdkService<G, C, F, D2, U> {
+        let inner = self.inner;
+        RealSdkService {
+            inner: SdkServiceInner {
+                git_command: inner.git_command,
+                flutter_command: inner.flutter_command,
+                dart_command,
+                downloader: inner.downloader,
+                clock: inner.clock,
+                local_sdk_repository: inner.local_sdk_repository,
+                remote_sdk_repository: inner.remote_sdk_repository,
+                release_manifest_repository: inner.release_manifest_repository,
+                remote_sdk_list_cache: inner.remote_sdk_list_cache,
+                remote_sdk_list_lock: inner.remote_sdk_list_lock,
+                install_locks: inner.install_locks,
+            },
+        }
+    }
+
+    /// Replaces the backend which downloads the release archives, which is `DownloaderImpl` by
+    /// default.
+    pub fn with_downloader<U2: Downloader>(self, downloader: U2) -> RealSdkService<G, C, F, D, U2> {
+        let inner = self.inner;
+        RealSdkService {
+            inner: SdkServiceInner {
+                git_command: inner.git_command,
+                flutter_command: inner.flutter_command,
+                dart_command: inner.dart_command,
+                downloader,
+                clock: inner.clock,
+                local_sdk_repository: inner.local_sdk_repository,
+                remote_sdk_repository: inner.remote_sdk_repository,
+                release_manifest_repository: inner.release_manifest_repository,
+                remote_sdk_list_cache: inner.remote_sdk_list_cache,
+                remote_sdk_list_lock: inner.remote_sdk_list_lock,
+                install_locks: inner.install_locks,
             },
         }
     }
@@ -174,7 +414,7 @@ where
             }
             LookupResult::None => {
                 // the version file is found, but any matching sdk is not installed.
-                match self.find_latest_remote(context, &version_prefix) {
+                match self.find_latest_remote(context, &version_prefix, false) {
                     LookupResult::Found(remote_sdk) => {
                         VersionFileReadResult::FoundButNotInstalled(UninstalledSdkSummary {
                             stored_version_prefix: version_prefix,
@@ -205,11 +445,13 @@ where
     }
 }
 
-impl<'a, G, C, F> RealSdkService<G, C, F>
+impl<'a, G, C, F, D, U> RealSdkService<G, C, F, D, U>
 where
     G: GitCommand,
     C: Clock,
     F: FlutterCommand,
+    D: DartCommand,
+    U: Downloader,
 {
     fn local(&'a self) -> &'a LocalSdkRepository {
         &self.inner.local_sdk_repository
@@ -219,6 +461,10 @@ where
         &self.inner.remote_sdk_repository
     }
 
+    fn release_manifest(&'a self) -> &'a ReleaseManifestRepository {
+        &self.inner.release_manifest_repository
+    }
+
     fn remote_list_cache(&'a self) -> &'a RemoteSdkListCache {
         &self.inner.remote_sdk_list_cache
     }
@@ -231,31 +477,279 @@ where
         &self.inner.flutter_command
     }
 
+    fn dart_command(&'a self) -> &'a D {
+        &self.inner.dart_command
+    }
+
+    fn downloader(&'a self) -> &'a U {
+        &self.inner.downloader
+    }
+
     fn clock(&'a self) -> &'a C {
         &self.inner.clock
     }
+
+    /// Runs `flutter doctor` and `flutter precache`, whose failure fails the installation, and
+    /// then accepts the Android SDK licenses and activates the pub packages on a best effort.
+    fn set_up_sdk(
+        &self,
+        sdk_dir: &PathLike,
+        config: &FenvConfig,
+        storage_base_url: Option<&str>,
+        options: &InstallOptions,
+        progress: &mut dyn std::io::Write,
+    ) -> anyhow::Result<()> {
+        if options.should_doctor {
+            self.flutter_command()
+                .doctor(&sdk_dir.to_string(), storage_base_url, progress)?;
+        }
+        if options.should_precache {
+            let precache_platforms = match &options.precache_platforms[..] {
+                [] => &config.precache_platforms[..],
+                platforms => platforms,
+            };
+            self.flutter_command().precache(
+                &sdk_dir.to_string(),
+                precache_platforms,
+                storage_base_url,
+                progress,
+            )?;
+        }
+        // The SDK itself is usable even if the following steps fail, so it is not uninstalled.
+        if options.accepts_android_licenses || config.accept_android_licenses {
+            if let Err(e) = self.flutter_command().accept_android_licenses(
+                &sdk_dir.to_string(),
+                storage_base_url,
+                progress,
+            ) {
+                warn!("set_up_sdk(): Failed to accept the Android SDK licenses: {e:?}");
+                writeln!(
+                    progress,
+                    "fenv: warning: Failed to accept the Android SDK licenses: {e}"
+                )?;
+            }
+        }
+        for package in &config.pub_global_activate {
+            let package: Vec<String> = package.split_whitespace().map(String::from).collect();
+            if package.is_empty() {
+                continue;
+            }
+            if let Err(e) = self
+                .dart_command()
+                .pub_global_activate(&sdk_dir.to_string(), &package)
+            {
+                warn!(
+                    "set_up_sdk(): Failed to activate `{}`: {e:?}",
+                    package.join(" ")
+                );
+                writeln!(
+                    progress,
+                    "fenv: warning: Failed to activate `{}`: {e}",
+                    package.join(" ")
+                )?;
+            }
+        }
+        anyhow::Ok(())
+    }
+
+    /// Installs `install_name` by `clone`, which is given whether to resume the interrupted
+    /// clone, and `set_up`, removing the incomplete installation if either fails.
+    fn clone_and_set_up(
+        &self,
+        context: &impl FenvContext,
+        install_name: &str,
+        fails_on_installed: bool,
+        clone: impl FnOnce(bool) -> anyhow::Result<PathLike>,
+        set_up: impl FnOnce(&PathLike) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        let _guard = self.inner.install_locks.lock(install_name);
+        let _lock = self.local().lock_installation(context, install_name)?;
+        if self.local().is_installed(context, install_name) {
+            if fails_on_installed {
+                bail!("`{install_name}` is already installed")
+            }
+            info!("`{install_name}` is already installed");
+            return anyhow::Ok(());
+        }
+        self.local()
+            .ensure_no_conflicting_entry(context, install_name)?;
+        let resumes = self.local().has_interrupted_clone(context, install_name);
+        if !resumes {
+            self.local()
+                .remove_installation_garbages(context, install_name)?;
+        }
+        self.local()
+            .create_installing_marker(context, install_name)?;
+        if let Err(e) = clone(resumes).and_then(|sdk_dir| set_up(&sdk_dir)) {
+            self.local()
+                .remove_installation_garbages(context, install_name)?;
+            return Err(e);
+        }
+        if let Err(e) = self.local().remove_installing_marker(context, install_name) {
+            info!("clone_and_set_up(): Failed to remove the installing marker: `{e}`");
+        }
+        anyhow::Ok(())
+    }
+
+    /// Installs the commit `sha` of the repository as its short SHA, which is given as a full
+    /// SHA to `fenv install` or a version file to bisect the framework.
+    fn install_commit(
+        &self,
+        context: &impl FenvContext,
+        sha: &str,
+        options: &InstallOptions,
+        progress: &mut dyn std::io::Write,
+    ) -> anyhow::Result<()> {
+        ensure_online(context, &format!("Installing the commit `{sha}`"))?;
+        if !is_git_available(context) {
+            bail!("`git` is not found on `PATH`: install it to install the commit `{sha}`")
+        }
+        let config = FenvConfig::load(context)?;
+        let project_config = FenvProjectConfig::load(context)?;
+        let storage_base_url = project_config.flutter.storage_base_url.as_deref();
+        let remote = GitRemote::resolve(context)?;
+        let install_name = &sha[..SHORT_SHA_LENGTH];
+        self.clone_and_set_up(
+            context,
+            install_name,
+            options.fails_on_installed,
+            |resumes| {
+                let destination = context.fenv_sdk_root(install_name);
+                // The commit is fetched from scratch, because it may be on any branch.
+                if resumes {
+                    destination
+                        .remove_dir_all()
+                        .with_context(|| format!("Failed to remove `{destination}`"))?;
+                }
+                self.git_command().clone_flutter_sdk_by_commit(
+                    sha,
+                    &destination.to_string(),
+                    &remote,
+                )?;
+                anyhow::Ok(destination)
+            },
+            |sdk_dir| self.set_up_sdk(sdk_dir, &config, storage_base_url, options, progress),
+        )
+    }
+
+    /// Lists the available Flutter SDKs from the release manifest, or by `git ls-remote` if the
+    /// manifest is unavailable or `git-url` of `.fenv.toml` is given.
+    ///
+    /// The channels are listed by `git ls-remote` if `git` is available, because the manifest
+    /// does not have `master` and points to the latest releases rather than the branches.
+    fn fetch_available_sdk_list(
+        &self,
+        context: &impl FenvContext,
+        remote: &GitRemote,
+    ) -> anyhow::Result<Vec<RemoteFlutterSdk>> {
+        let has_git = is_git_available(context);
+        if remote.url == FLUTTER_GIT_URL {
+            let project_config = FenvProjectConfig::load(context)?;
+            match self.release_manifest().fetch_available_sdk_list(
+                context,
+                self.downloader(),
+                project_config.flutter.storage_base_url.as_deref(),
+                !has_git,
+            ) {
+                Ok(mut sdks) if has_git => {
+                    sdks.retain(|sdk| matches!(sdk.kind, GitRefsKind::Tag(_)));
+                    sdks.extend(
+                        self.remote()
+                            .fetch_available_channel_list(self.git_command(), remote)?,
+                    );
+                    return anyhow::Ok(sdks);
+                }
+                Ok(sdks) => return anyhow::Ok(sdks),
+                Err(e) => debug!("Failed to fetch the release manifest, listing by `git`: {e}"),
+            }
+        }
+        if !has_git {
+            bail!("`git` is not found on `PATH`: install it to list the available Flutter SDKs")
+        }
+        self.remote()
+            .fetch_available_sdk_list(self.git_command(), remote)
+    }
+
+    /// Installs the latest version matching `prefix` from the release archives cached in
+    /// `{fenv_cache}/downloads` for `--offline`, without `flutter precache`, after verifying
+    /// their checksums.
+    fn install_cached_archive(
+        &self,
+        context: &impl FenvContext,
+        prefix: &str,
+        options: &InstallOptions,
+        progress: &mut dyn std::io::Write,
+    ) -> anyhow::Result<()> {
+        let prefix = &alias::resolve(context, prefix);
+        let Some(archive) = release_archive::find_cached_archive(context, prefix) else {
+            bail!(
+                "No release archive matching `{prefix}` is cached in `{}`, and `--offline` \
+                forbids downloading one: install it without `--offline`, or put its release \
+                archive there with its SHA-256 in `<archive>.sha256`",
+                release_archive::downloads_directory(context)
+            )
+        };
+        info!(
+            "install_cached_archive(): Installing `{}` from `{}`",
+            archive.sdk, archive.path
+        );
+        self.install_sdk_from_archive(
+            context,
+            &archive.path,
+            Some(&archive.sha256),
+            &archive.sdk.to_string(),
+            &InstallOptions {
+                should_precache: false,
+                ..options.clone()
+            },
+            progress,
+        )
+    }
+
+    /// Finds the release archive of the latest version matching `prefix`, which is installed
+    /// instead of cloning the repository if `git` is unavailable.
+    fn find_latest_release(
+        &self,
+        context: &impl FenvContext,
+        prefix: &str,
+        include_beta: bool,
+    ) -> anyhow::Result<Option<(RemoteFlutterSdk, FlutterRelease)>> {
+        let prefix = &alias::resolve(context, prefix);
+        let include_beta = include_beta
+            || FlutterVersion::parse(prefix).is_some_and(|version| version.is_prerelease());
+        let project_config = FenvProjectConfig::load(context)?;
+        release_archive::find_latest_release(
+            context,
+            self.downloader(),
+            project_config.flutter.storage_base_url.as_deref(),
+            prefix,
+            include_beta,
+        )
+    }
 }
 
-impl<G, C, F> SdkService for RealSdkService<G, C, F>
+impl<G, C, F, D, U> SdkService for RealSdkService<G, C, F, D, U>
 where
     G: GitCommand,
     C: Clock,
     F: FlutterCommand,
+    D: DartCommand,
+    U: Downloader,
 {
     fn install_sdk(
         &self,
         context: &impl FenvContext,
         prefix: &str,
-        should_doctor: bool,
-        should_precache: bool,
-        fails_on_installed: bool,
+        options: &InstallOptions,
+        progress: &mut dyn std::io::Write,
     ) -> anyhow::Result<()> {
         self.local().ensure_versions_exists(context)?;
 
         let local_latest_sdk_result = self.find_latest_local(context, prefix);
         match local_latest_sdk_result {
             LookupResult::Found(sdk) => {
-                if fails_on_installed {
+                metrics::emit(context, &[Metric::count("install.cache.hit")]);
+                if options.fails_on_installed {
                     anyhow::bail!("`{}` is already installed", sdk.display_name())
                 } else {
                     info!("`{}` is already installed", sdk.display_name());
@@ -265,20 +759,65 @@ where
             LookupResult::Err(e) => return Err(e),
             LookupResult::None => {}
         }
-
-        let remote_latest_sdk: RemoteFlutterSdk = match self.find_latest_remote(context, prefix) {
-            LookupResult::Found(remote_latest_sdk) => remote_latest_sdk,
-            LookupResult::Err(e) => return Result::Err(e),
-            LookupResult::None => {
-                return Result::Err(anyhow::anyhow!(
-                    "Not found any matched flutter sdk version: `{prefix}`"
-                ))
+        if prefix.len() == 40 && is_commit_sha(prefix) {
+            return self.install_commit(context, prefix, options, progress);
+        }
+        if context.is_offline() {
+            return self.install_cached_archive(context, prefix, options, progress);
+        }
+        let started_at = Instant::now();
+
+        let config = FenvConfig::load(context)?;
+        let project_config = FenvProjectConfig::load(context)?;
+        let storage_base_url = project_config.flutter.storage_base_url.as_deref();
+        let has_git = is_git_available(context);
+        let (remote_latest_sdk, release) = if has_git {
+            match self.find_latest_remote(context, prefix, options.include_beta) {
+                LookupResult::Found(remote_latest_sdk) => (remote_latest_sdk, None),
+                LookupResult::Err(e) => return Result::Err(e),
+                LookupResult::None => {
+                    return Result::Err(InstallFailureReason::GitRefMissing.wrap(anyhow::anyhow!(
+                        "Not found any matched flutter sdk version: `{prefix}`"
+                    )))
+                }
+            }
+        } else {
+            match self.find_latest_release(context, prefix, options.include_beta)? {
+                Some((remote_latest_sdk, release)) => (remote_latest_sdk, Some(release)),
+                None => {
+                    return Err(InstallFailureReason::GitRefMissing.wrap(anyhow::anyhow!(
+                        "Not found any release archive matching `{prefix}`: \
+                        install `git` to install the channels and the versions without archives"
+                    )))
+                }
             }
         };
-        let version_or_channel = &remote_latest_sdk.display_name()[..];
+        let install_name = remote_latest_sdk.install_name(config.install_name_policy);
+        let version_or_channel = &install_name[..];
+
+        // Another prefix installed in parallel may have been resolved to the same SDK.
+        let _guard = self.inner.install_locks.lock(version_or_channel);
+        let _lock = self
+            .local()
+            .lock_installation(context, version_or_channel)?;
+        if self.local().is_installed(context, version_or_channel) {
+            if options.fails_on_installed {
+                bail!("`{version_or_channel}` is already installed")
+            }
+            info!("`{version_or_channel}` is already installed");
+            return anyhow::Ok(());
+        }
 
         self.local()
-            .remove_installation_garbages(context, version_or_channel)?;
+            .ensure_no_conflicting_entry(context, version_or_channel)?;
+        let resumes = has_git
+            && self
+                .local()
+                .has_interrupted_clone(context, version_or_channel);
+        if !resumes {
+            self.local()
+                .remove_installation_garbages(context, version_or_channel)?;
+        }
         self.local()
             .create_installing_marker(context, version_or_channel)?;
 
@@ -295,18 +834,33 @@ where
             };
         }
 
-        let sdk_dir = early_returns_on_err!(self.remote().install_sdk(
-            context,
-            self.git_command(),
-            &remote_latest_sdk
-        ));
+        let sdk_dir = if let Some(release) = &release {
+            early_returns_on_err!(release_archive::install_release(
+                context,
+                self.downloader(),
+                storage_base_url,
+                release,
+                version_or_channel
+            ))
+        } else {
+            let remote = early_returns_on_err!(GitRemote::resolve(context));
+            early_returns_on_err!(self.remote().install_sdk(
+                context,
+                self.git_command(),
+                &remote_latest_sdk,
+                version_or_channel,
+                &remote,
+                resumes
+            ))
+        };
 
-        if should_doctor {
-            early_returns_on_err!(self.flutter_command().doctor(&sdk_dir.to_string(),));
-        }
-        if should_precache {
-            early_returns_on_err!(self.flutter_command().precache(&sdk_dir.to_string(),));
-        }
+        early_returns_on_err!(self.set_up_sdk(
+            &sdk_dir,
+            &config,
+            storage_base_url,
+            options,
+            progress
+        ));
 
         if let Err(e) = self
             .local()
@@ -314,9 +868,126 @@ where
         {
             info!("install_sdk(): Failed to remove the installing marker: `{e}`");
         }
+
+        // Keeps the git tag name available for the scripts which template the paths with it.
+        let legacy_name = remote_latest_sdk.display_name();
+        if legacy_name != version_or_channel {
+            if let Err(e) =
+                self.local()
+                    .create_compatibility_symlink(context, &legacy_name, version_or_channel)
+            {
+                warn!("install_sdk(): {e}");
+            }
+        }
+        metrics::emit(
+            context,
+            &[
+                Metric::count("install.cache.miss"),
+                Metric::timing("install.duration", started_at.elapsed()),
+            ],
+        );
         anyhow::Ok(())
     }
 
+    fn install_sdk_from_archive(
+        &self,
+        context: &impl FenvContext,
+        archive: &PathLike,
+        sha256: Option<&str>,
+        name: &str,
+        options: &InstallOptions,
+        progress: &mut dyn std::io::Write,
+    ) -> anyhow::Result<()> {
+        LocalFlutterSdk::parse(name)
+            .with_context(|| format!("`{name}` is neither a version nor a channel"))?;
+        self.local().ensure_versions_exists(context)?;
+        let config = FenvConfig::load(context)?;
+        let project_config = FenvProjectConfig::load(context)?;
+        let storage_base_url = project_config.flutter.storage_base_url.as_deref();
+
+        let _guard = self.inner.install_locks.lock(name);
+        let _lock = self.local().lock_installation(context, name)?;
+        if self.local().is_installed(context, name) {
+            bail!("`{name}` is already installed")
+        }
+        self.local().ensure_no_conflicting_entry(context, name)?;
+        self.local().remove_installation_garbages(context, name)?;
+        self.local().create_installing_marker(context, name)?;
+        let result =
+            release_archive::install_archive(context, archive, sha256, name).and_then(|sdk_dir| {
+                self.set_up_sdk(&sdk_dir, &config, storage_base_url, options, progress)
+            });
+        if let Err(e) = result {
+            self.local().remove_installation_garbages(context, name)?;
+            return Err(e);
+        }
+        if let Err(e) = self.local().remove_installing_marker(context, name) {
+            info!("install_sdk_from_archive(): Failed to remove the installing marker: `{e}`");
+        }
+        anyhow::Ok(())
+    }
+
+    fn install_sdk_from_git(
+        &self,
+        context: &impl FenvContext,
+        git_url: &str,
+        namespace: &str,
+        prefix: &str,
+        options: &InstallOptions,
+        progress: &mut dyn std::io::Write,
+    ) -> anyhow::Result<()> {
+        if !is_valid_namespace(namespace) {
+            bail!("Invalid namespace: `{namespace}`: use lowercase letters, digits, and `_`")
+        }
+        ensure_online(context, &format!("Installing from `{git_url}`"))?;
+        if !is_git_available(context) {
+            bail!("`git` is not found on `PATH`: install it to install from `{git_url}`")
+        }
+        self.local().ensure_versions_exists(context)?;
+        let config = FenvConfig::load(context)?;
+        let project_config = FenvProjectConfig::load(context)?;
+        let storage_base_url = project_config.flutter.storage_base_url.as_deref();
+        let remote = GitRemote {
+            url: git_url.to_string(),
+            ..GitRemote::resolve(context)?
+        };
+
+        // Only the tags are installed, because the branches of a fork are not the channels.
+        let tags: Vec<RemoteFlutterSdk> = self
+            .remote()
+            .fetch_available_sdk_list(self.git_command(), &remote)?
+            .into_iter()
+            .filter(|sdk| matches!(sdk.kind, GitRefsKind::Tag(_)))
+            .filter(|sdk| options.include_beta || !sdk.is_prerelease())
+            .collect();
+        let Some(remote_latest_sdk) = matches_prefix(&tags, prefix).pop() else {
+            return Err(InstallFailureReason::GitRefMissing.wrap(anyhow::anyhow!(
+                "Not found any matched tag in `{git_url}`: `{prefix}`"
+            )));
+        };
+        let install_name = format!(
+            "{namespace}-{}",
+            remote_latest_sdk.install_name(config.install_name_policy)
+        );
+
+        self.clone_and_set_up(
+            context,
+            &install_name,
+            options.fails_on_installed,
+            |resumes| {
+                self.remote().install_sdk(
+                    context,
+                    self.git_command(),
+                    &remote_latest_sdk,
+                    &install_name,
+                    &remote,
+                    resumes,
+                )
+            },
+            |sdk_dir| self.set_up_sdk(sdk_dir, &config, storage_base_url, options, progress),
+        )
+    }
+
     fn get_installed_sdk_list(
         &self,
         context: &impl FenvContext,
@@ -328,23 +999,54 @@ where
     fn get_available_remote_sdk_list(
         &self,
         context: &impl FenvContext,
+        include_beta: bool,
     ) -> anyhow::Result<Vec<RemoteFlutterSdk>> {
-        if let Some(sdks) = self.remote_list_cache().load_list(context, self.clock()) {
+        // The parallel installs fetch the list once, and read it from the cache afterwards.
+        let _lock = self
+            .inner
+            .remote_sdk_list_lock
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let remote = GitRemote::resolve(context)?;
+        let sdks = if context.is_offline() {
+            let Some((sdks, age)) =
+                self.remote_list_cache()
+                    .load_stale_list(context, self.clock(), &remote.url)
+            else {
+                bail!(
+                    "No list of the available Flutter SDKs is cached, and `--offline` forbids \
+                    fetching it: run `fenv list-remote` without `--offline` once"
+                )
+            };
+            debug!("sdk list from cache of {age:?} ago for `--offline`");
+            sdks
+        } else if let Some(sdks) =
+            self.remote_list_cache()
+                .load_list(context, self.clock(), &remote.url)
+        {
             debug!("sdk list from cache");
-            return anyhow::Ok(sdks);
-        }
-
-        let result = self.remote().fetch_available_sdk_list(self.git_command());
-        if let Ok(sdks) = &result {
+            metrics::emit(context, &[Metric::count("remote_list.cache.hit")]);
+            sdks
+        } else {
+            metrics::emit(context, &[Metric::count("remote_list.cache.miss")]);
+            let sdks = self.fetch_available_sdk_list(context, &remote)?;
             debug!("sdk list from remote");
-            if let Err(e) = self
-                .remote_list_cache()
-                .store_list(context, self.clock(), sdks)
+            if let Err(e) =
+                self.remote_list_cache()
+                    .store_list(context, self.clock(), &remote.url, &sdks)
             {
                 warn!("{e}");
             }
+            sdks
+        };
+        if include_beta {
+            return anyhow::Ok(sdks);
         }
-        result
+        anyhow::Ok(
+            sdks.into_iter()
+                .filter(|sdk| !sdk.is_prerelease())
+                .collect(),
+        )
     }
 
     fn find_nearest_version_file(
@@ -370,13 +1072,50 @@ where
         &self,
         context: &impl FenvContext,
         prefix: &str,
+        include_beta: bool,
     ) -> LookupResult<RemoteFlutterSdk> {
+        // `--offline` answers from the cached list, which is fetched by `git` or not.
+        if !is_git_available(context) && !context.is_offline() {
+            return match self.find_latest_release(context, prefix, include_beta) {
+                Ok(found) => found.map(|(sdk, _)| sdk).into(),
+                Err(e) => LookupResult::Err(e),
+            };
+        }
+        let prefix = &alias::resolve(context, prefix);
+        let include_beta = include_beta
+            || FlutterVersion::parse(prefix).is_some_and(|version| version.is_prerelease());
         let sdks: Vec<RemoteFlutterSdk> =
-            unwrap_or_return!(self.get_available_remote_sdk_list(context));
+            unwrap_or_return!(self.get_available_remote_sdk_list(context, include_beta));
         let filtered_sdks = matches_prefix(&sdks, prefix);
         filtered_sdks.last().map(|sdk| sdk.to_owned()).into()
     }
 
+    fn find_latest_stale_remote(
+        &self,
+        context: &impl FenvContext,
+        prefix: &str,
+        include_beta: bool,
+    ) -> LookupResult<(RemoteFlutterSdk, std::time::Duration)> {
+        let prefix = &alias::resolve(context, prefix);
+        let include_beta = include_beta
+            || FlutterVersion::parse(prefix).is_some_and(|version| version.is_prerelease());
+        let remote = unwrap_or_return!(GitRemote::resolve(context));
+        let Some((sdks, age)) =
+            self.remote_list_cache()
+                .load_stale_list(context, self.clock(), &remote.url)
+        else {
+            return LookupResult::None;
+        };
+        let sdks: Vec<RemoteFlutterSdk> = sdks
+            .into_iter()
+            .filter(|sdk| include_beta || !sdk.is_prerelease())
+            .collect();
+        matches_prefix(&sdks, prefix)
+            .last()
+            .map(|sdk| (sdk.to_owned(), age))
+            .into()
+    }
+
     fn find_nearest_local_version_file(&self, start_dir: &PathLike) -> LookupResult<PathLike> {
         self.local()
             .find_nearest_local_version_file(start_dir)
@@ -394,6 +1133,10 @@ where
         )
     }
 
+    fn read_version_prefix(&self, path_to_version_file: &PathLike) -> anyhow::Result<String> {
+        self.local().read_version_file(path_to_version_file)
+    }
+
     fn write_local_version(
         &self,
         destination_dir: &PathLike,
@@ -433,7 +1176,106 @@ where
         let sdk_location = context.fenv_sdk_root(&sdk.display_name());
         sdk_location
             .remove_dir_all()
-            .with_context(|| anyhow::anyhow!("Failed to remove sdk: `{sdk}`"))
+            .with_context(|| anyhow::anyhow!("Failed to remove sdk: `{sdk}`"))?;
+        self.local()
+            .remove_compatibility_symlinks(context, &sdk.display_name())
+    }
+
+    fn uninstall_by_name(&self, context: &impl FenvContext, name: &str) -> anyhow::Result<()> {
+        self.local().remove_sdk(context, name)
+    }
+
+    fn find_broken_installations(&self, context: &impl FenvContext) -> anyhow::Result<Vec<String>> {
+        self.local().find_broken_installations(context)
+    }
+
+    fn is_broken_installation(&self, context: &impl FenvContext, name: &str) -> bool {
+        self.local().is_broken_installation(context, name)
+    }
+
+    fn uninstall_broken_installation(
+        &self,
+        context: &impl FenvContext,
+        name: &str,
+    ) -> anyhow::Result<bool> {
+        let _guard = self.inner.install_locks.lock(name);
+        let _lock = self.local().lock_installation(context, name)?;
+        if !self.local().is_broken_installation(context, name) {
+            return anyhow::Ok(false);
+        }
+        self.local().remove_sdk(context, name)?;
+        anyhow::Ok(true)
+    }
+
+    fn verify_sdk(&self, context: &impl FenvContext, sdk: &LocalFlutterSdk) -> Vec<String> {
+        let name = sdk.display_name();
+        let mut problems = self.local().find_missing_files(context, &name);
+        let sdk_root = context.fenv_sdk_root(&name);
+        // Only the SDKs installed by `git clone` can be checked for their commits.
+        if !sdk_root.join(".git").exists() || !is_git_available(context) {
+            return problems;
+        }
+        let sdk_root = sdk_root.to_string();
+        let head = match self.git_command().rev_parse_head(&sdk_root) {
+            Ok(head) => head,
+            Err(e) => {
+                problems.push(format!("Could not read the git HEAD: {e}"));
+                return problems;
+            }
+        };
+        // The tag may be named with or without the leading `v` of the installation name.
+        let candidates = match sdk {
+            LocalFlutterSdk::Version { .. } => {
+                let name = sdk.tag_name();
+                let alternative = match name.strip_prefix('v') {
+                    Some(stripped) => stripped.to_string(),
+                    None => format!("v{name}"),
+                };
+                vec![
+                    format!("refs/tags/{name}"),
+                    format!("refs/tags/{alternative}"),
+                ]
+            }
+            LocalFlutterSdk::Channel(_) | LocalFlutterSdk::Commit(_) => vec![sdk.refs_name()],
+        };
+        let expected = candidates
+            .iter()
+            .find_map(|rev| self.git_command().rev_parse_commit(&sdk_root, rev).ok());
+        match expected {
+            Some(expected) if expected == head => {}
+            Some(expected) => problems.push(format!(
+                "The git HEAD `{head}` is not at `{}` (`{expected}`)",
+                candidates[0]
+            )),
+            None => problems.push(format!("Could not find `{}`", candidates[0])),
+        }
+        problems
+    }
+
+    fn rename_sdk(
+        &self,
+        context: &impl FenvContext,
+        sdk: &LocalFlutterSdk,
+        new_name: &str,
+    ) -> anyhow::Result<()> {
+        let old_name = sdk.display_name();
+        self.local().rename_sdk(context, &old_name, new_name)?;
+        self.local()
+            .create_compatibility_symlink(context, &old_name, new_name)
+    }
+
+    fn link_sdk(
+        &self,
+        context: &impl FenvContext,
+        sdk: &LocalFlutterSdk,
+        link_name: &str,
+    ) -> anyhow::Result<()> {
+        let link = context.fenv_sdk_root(link_name);
+        if link.path().symlink_metadata().is_ok() {
+            bail!("`{link}` already exists")
+        }
+        self.local()
+            .create_compatibility_symlink(context, link_name, &sdk.display_name())
     }
 
     fn ensure_sdk_is_available(
@@ -472,14 +1314,135 @@ where
             }
         }
     }
+
+    fn read_sdk_commit_sha(&self, path_to_sdk_root: &PathLike) -> anyhow::Result<String> {
+        self.git_command()
+            .rev_parse_head(&path_to_sdk_root.to_string())
+    }
+
+    fn pub_get(&self, path_to_sdk_root: &PathLike, working_dir: &PathLike) -> anyhow::Result<()> {
+        self.dart_command()
+            .pub_get(&path_to_sdk_root.to_string(), &working_dir.to_string())
+    }
+
+    fn flutter_create(
+        &self,
+        path_to_sdk_root: &PathLike,
+        project_dir: &PathLike,
+        args: &[String],
+        progress: &mut dyn std::io::Write,
+    ) -> anyhow::Result<()> {
+        self.flutter_command().create(
+            &path_to_sdk_root.to_string(),
+            &project_dir.to_string(),
+            args,
+            progress,
+        )
+    }
+
+    fn collect_sdk_metadata(
+        &self,
+        context: &impl FenvContext,
+        sdk: &LocalFlutterSdk,
+    ) -> SdkMetadata {
+        let path_to_sdk_root = context.fenv_sdk_root(&sdk.display_name());
+        let commit_sha = match self.read_sdk_commit_sha(&path_to_sdk_root) {
+            Ok(commit_sha) => Some(commit_sha),
+            Err(e) => {
+                debug!("collect_sdk_metadata(): {e}");
+                None
+            }
+        };
+        sdk_metadata::collect(sdk, &path_to_sdk_root, commit_sha)
+    }
+
+    fn upgrade_channel(
+        &self,
+        context: &impl FenvContext,
+        channel: &FlutterChannel,
+        should_precache: bool,
+        progress: &mut dyn std::io::Write,
+    ) -> anyhow::Result<(String, String)> {
+        let name = channel.channel_name();
+        ensure_online(context, &format!("Upgrading `{name}`"))?;
+        if self.is_broken_installation(context, name) {
+            bail!("`{name}` is broken: do `fenv uninstall {name} && fenv install {name}`")
+        }
+        if !is_git_available(context) {
+            bail!("`git` is not found on `PATH`: install it to upgrade `{name}`")
+        }
+        let sdk_root = context.fenv_sdk_root(name).to_string();
+        let tls = GitTlsOptions::resolve(context)?;
+        let proxy = ProxyOptions::resolve(context)?;
+        let old_sha = self.git_command().rev_parse_head(&sdk_root)?;
+        self.git_command().pull_ff_only(&sdk_root, &tls, &proxy)?;
+        let new_sha = self.git_command().rev_parse_head(&sdk_root)?;
+        if should_precache && old_sha != new_sha {
+            let config = FenvConfig::load(context)?;
+            let project_config = FenvProjectConfig::load(context)?;
+            self.flutter_command().precache(
+                &sdk_root,
+                &config.precache_platforms,
+                project_config.flutter.storage_base_url.as_deref(),
+                progress,
+            )?;
+        }
+        anyhow::Ok((old_sha, new_sha))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::process::Command;
 
-    use super::{RealSdkService, SdkService};
-    use crate::{context::FenvContext, service::macros::test_with_context};
+    use super::{InstallOptions, RealSdkService, SdkService};
+    use crate::{
+        context::FenvContext,
+        define_mock_flutter_command,
+        external::git_command::MockGitCommand,
+        sdk_service::results::{InstallFailure, InstallFailureReason},
+        service::macros::test_with_context,
+        util::{chrono_wrapper::SystemClock, path_like::PathLike},
+    };
+
+    #[cfg(target_os = "linux")]
+    use crate::external::downloader::MockDownloader;
+    #[cfg(target_os = "linux")]
+    use crate::util::checksum::sha256_of_file;
+
+    define_mock_flutter_command!();
+
+    /// Mocks `git` which lists `3.7.12` only.
+    fn mock_git_command_listing_3_7_12() -> MockGitCommand {
+        let mut git_command = MockGitCommand::new();
+        git_command
+            .expect_list_remote_sdks_by_tags()
+            .returning(|_| {
+                anyhow::Ok(String::from(
+                    "fd9d2d5d2e5dc3a7e4e5a5d2e5dc3a7e4e5a5d2e\trefs/tags/3.7.12",
+                ))
+            });
+        git_command
+            .expect_list_remote_sdks_by_branches()
+            .returning(|_| anyhow::Ok(String::new()));
+        git_command
+    }
+
+    /// Leaves `3.7.12` as if `git clone` was interrupted.
+    fn prepare_interrupted_clone(context: &impl FenvContext) -> PathLike {
+        context
+            .fenv_versions()
+            .join(".install_3.7.12")
+            .create_file()
+            .unwrap();
+        let git_dir = context.fenv_sdk_root("3.7.12").join(".git");
+        git_dir
+            .join("HEAD")
+            .writeln("ref: refs/heads/master")
+            .unwrap();
+        git_dir.join("objects").create_dir_all().unwrap();
+        context.fenv_sdk_root("3.7.12")
+    }
 
     #[test]
     pub fn test_install_specific_version_with_skipping_doctor_and_precache() {
@@ -494,7 +1457,15 @@ mod tests {
 
             // execution
             sdk_service
-                .install_sdk(context, "3.3", false, false, true)
+                .install_sdk(
+                    context,
+                    "3.3",
+                    &InstallOptions {
+                        fails_on_installed: true,
+                        ..Default::default()
+                    },
+                    &mut std::io::sink(),
+                )
                 .unwrap();
 
             // verification
@@ -534,7 +1505,15 @@ mod tests {
 
             // execution
             sdk_service
-                .install_sdk(context, "m", false, false, true)
+                .install_sdk(
+                    context,
+                    "m",
+                    &InstallOptions {
+                        fails_on_installed: true,
+                        ..Default::default()
+                    },
+                    &mut std::io::sink(),
+                )
                 .unwrap();
 
             // verification
@@ -564,7 +1543,15 @@ mod tests {
             let sdk_service = RealSdkService::new();
 
             // execution
-            let result = sdk_service.install_sdk(context, "3.3", false, false, true);
+            let result = sdk_service.install_sdk(
+                context,
+                "3.3",
+                &InstallOptions {
+                    fails_on_installed: true,
+                    ..Default::default()
+                },
+                &mut std::io::sink(),
+            );
 
             // verification
             assert!(result.is_err());
@@ -587,10 +1574,401 @@ mod tests {
             let sdk_service = RealSdkService::new();
 
             // execution
-            let result = sdk_service.install_sdk(context, "3.3", false, false, false);
+            let result = sdk_service.install_sdk(
+                context,
+                "3.3",
+                &InstallOptions {
+                    ..Default::default()
+                },
+                &mut std::io::sink(),
+            );
 
             // verification
             assert!(result.is_ok());
         });
     }
+
+    #[test]
+    pub fn test_install_sdk_resumes_interrupted_clone() {
+        test_with_context(|context, _| {
+            // setup
+            let sdk_root = prepare_interrupted_clone(context);
+            let mut git_command = mock_git_command_listing_3_7_12();
+            git_command
+                .expect_resume_flutter_sdk_by_version()
+                .withf(|version, _, _| version == "3.7.12")
+                .times(1)
+                .returning(|_, _, _| anyhow::Ok(()));
+            git_command.expect_clone_flutter_sdk_by_version().never();
+            let sdk_service =
+                RealSdkService::from(git_command, SystemClock::new(), MockFlutterCommand);
+
+            // execution
+            sdk_service
+                .install_sdk(
+                    context,
+                    "3.7.12",
+                    &InstallOptions {
+                        fails_on_installed: true,
+                        ..Default::default()
+                    },
+                    &mut std::io::sink(),
+                )
+                .unwrap();
+
+            // verification
+            assert!(sdk_root.join(".git").join("HEAD").is_file());
+            assert!(!context.fenv_versions().join(".install_3.7.12").exists());
+        });
+    }
+
+    #[test]
+    pub fn test_install_sdk_clones_again_if_interrupted_clone_is_unusable() {
+        test_with_context(|context, _| {
+            // setup
+            let sdk_root = prepare_interrupted_clone(context);
+            let mut git_command = mock_git_command_listing_3_7_12();
+            git_command
+                .expect_resume_flutter_sdk_by_version()
+                .times(1)
+                .returning(|_, _, _| anyhow::bail!("broken repository"));
+            git_command
+                .expect_clone_flutter_sdk_by_version()
+                .times(1)
+                .returning(|_, destination, _| {
+                    // The unusable repository must be removed before cloning.
+                    assert!(!PathLike::from(destination).exists());
+                    std::fs::create_dir(destination).map_err(|e| anyhow::anyhow!(e))
+                });
+            let sdk_service =
+                RealSdkService::from(git_command, SystemClock::new(), MockFlutterCommand);
+
+            // execution
+            sdk_service
+                .install_sdk(
+                    context,
+                    "3.7.12",
+                    &InstallOptions {
+                        fails_on_installed: true,
+                        ..Default::default()
+                    },
+                    &mut std::io::sink(),
+                )
+                .unwrap();
+
+            // verification
+            assert!(sdk_root.is_dir());
+            assert!(!sdk_root.join(".git").exists());
+        });
+    }
+
+    /// Mocks the downloader which serves the release archives of `3.22.2` in the archive of `tar`.
+    #[cfg(target_os = "linux")]
+    fn mock_downloader_serving_3_22_2(context: &impl FenvContext) -> MockDownloader {
+        let sources = context.home().join("sources");
+        sources
+            .join("flutter")
+            .join("bin")
+            .join("flutter")
+            .writeln("")
+            .unwrap();
+        let archive = context.home().join("flutter.tar");
+        let status = Command::new("tar")
+            .arg("-cf")
+            .arg(archive.path())
+            .arg("-C")
+            .arg(sources.path())
+            .arg("flutter")
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let sha256 = sha256_of_file(&archive).unwrap();
+        let releases = serde_json::json!({
+            "releases": [
+                {
+                    "hash": "f4c7dd9f3e52f10a4bd1e7c0e0d0c66acb9de0a3",
+                    "channel": "beta",
+                    "version": "3.23.0-0.1.pre",
+                    "archive": "beta/linux/flutter_linux_3.23.0-0.1.pre-beta.tar.xz",
+                    "sha256": sha256,
+                },
+                {
+                    "hash": "761747bfc538b5af34aa0d3fac380f1bc331ec49",
+                    "channel": "stable",
+                    "version": "3.22.2",
+                    "archive": "stable/linux/flutter_linux_3.22.2-stable.tar.xz",
+                    "sha256": sha256,
+                    "dart_sdk_arch": if cfg!(target_arch = "aarch64") { "arm64" } else { "x64" },
+                },
+                {
+                    "hash": "54e66469a933b60ddf175f858f82eaeb97e48c8d",
+                    "channel": "stable",
+                    "version": "3.19.6",
+                    "archive": "stable/linux/flutter_linux_3.19.6-stable.tar.xz",
+                    "sha256": sha256,
+                },
+            ]
+        })
+        .to_string();
+        let mut downloader = MockDownloader::new();
+        downloader
+            .expect_fetch()
+            .withf(|url, _| {
+                url == "https://storage.googleapis.com/flutter_infra_release/releases/releases_linux.json"
+            })
+            .returning(move |_, _| anyhow::Ok(releases.clone()));
+        downloader
+            .expect_download()
+            .returning(move |_, destination, options| {
+                // Same as `curl -C -`, the rest of the partial destination is appended.
+                assert!(options.resumes);
+                let content = std::fs::read(&archive).unwrap();
+                let offset = std::fs::metadata(destination).map_or(0, |metadata| metadata.len());
+                let mut file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(destination)
+                    .unwrap();
+                std::io::Write::write_all(&mut file, &content[offset as usize..]).unwrap();
+                anyhow::Ok(())
+            });
+        downloader
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_install_sdk_from_release_archive_without_git() {
+        test_with_context(|context, _| {
+            // setup
+            let context = context.clone().with_path(&[context.home().join("bin")]);
+            let mut git_command = MockGitCommand::new();
+            git_command.expect_clone_flutter_sdk_by_version().never();
+            let sdk_service =
+                RealSdkService::from(git_command, SystemClock::new(), MockFlutterCommand)
+                    .with_downloader(mock_downloader_serving_3_22_2(&context));
+
+            // execution
+            sdk_service
+                .install_sdk(
+                    &context,
+                    "3.22",
+                    &InstallOptions {
+                        fails_on_installed: true,
+                        ..Default::default()
+                    },
+                    &mut std::io::sink(),
+                )
+                .unwrap();
+            let error = sdk_service
+                .install_sdk(
+                    &context,
+                    "stable",
+                    &InstallOptions {
+                        fails_on_installed: true,
+                        ..Default::default()
+                    },
+                    &mut std::io::sink(),
+                )
+                .unwrap_err();
+            let listed = sdk_service
+                .get_available_remote_sdk_list(&context, false)
+                .unwrap();
+
+            // verification
+            let sdk_root = context.fenv_sdk_root("3.22.2");
+            assert!(sdk_root.join("bin").join("flutter").is_file());
+            assert!(!context.fenv_versions().join(".install_3.22.2").exists());
+            assert!(!context.fenv_versions().join(".archive_3.22.2").exists());
+            assert!(error
+                .to_string()
+                .starts_with("Not found any release archive matching `stable`"));
+            assert_eq!(
+                InstallFailure::reason_of(&error),
+                Some(InstallFailureReason::GitRefMissing)
+            );
+            let listed_names: Vec<&str> = listed.iter().map(|sdk| &sdk.short[..]).collect();
+            assert_eq!(listed_names, ["3.19.6", "3.22.2"]);
+        });
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_install_sdk_offline_from_cached_archive() {
+        test_with_context(|context, _| {
+            // setup
+            let context = &context.to_offline();
+            let mut downloader = mock_downloader_serving_3_22_2(context);
+            downloader.checkpoint();
+            downloader.expect_fetch().never();
+            downloader.expect_download().never();
+            let downloads = context.fenv_cache().join("downloads");
+            downloads.create_dir_all().unwrap();
+            let sha256 = sha256_of_file(&context.home().join("flutter.tar")).unwrap();
+            for file_name in [
+                "flutter_linux_3.19.6-stable.tar.xz",
+                "flutter_linux_3.22.2-stable.tar.xz",
+                "flutter_linux_3.24.0-stable.tar.xz",
+                "flutter_linux_3.24.1-stable.tar.xz.part",
+            ] {
+                std::fs::copy(
+                    context.home().join("flutter.tar"),
+                    downloads.join(file_name),
+                )
+                .unwrap();
+            }
+            // `3.24.0` is not verified, and `3.24.1` is being downloaded.
+            for file_name in [
+                "flutter_linux_3.19.6-stable.tar.xz.sha256",
+                "flutter_linux_3.22.2-stable.tar.xz.sha256",
+            ] {
+                downloads
+                    .join(file_name)
+                    .writeln(format!("{sha256}  flutter.tar"))
+                    .unwrap();
+            }
+            let sdk_service = RealSdkService::from(
+                MockGitCommand::new(),
+                SystemClock::new(),
+                MockFlutterCommand,
+            )
+            .with_downloader(downloader);
+            let install = |prefix: &str| {
+                sdk_service.install_sdk(
+                    context,
+                    prefix,
+                    &InstallOptions {
+                        should_precache: true,
+                        fails_on_installed: true,
+                        ..Default::default()
+                    },
+                    &mut std::io::sink(),
+                )
+            };
+
+            // execution
+            install("3").unwrap();
+            let error = install("3.10").unwrap_err();
+
+            // verification
+            assert!(context
+                .fenv_sdk_root("3.22.2")
+                .join("bin/flutter")
+                .is_file());
+            assert!(!context.fenv_sdk_root("3.19.6").exists());
+            assert!(!context.fenv_sdk_root("3.24.0").exists());
+            assert!(!context.fenv_sdk_root("3.24.1").exists());
+            assert!(error
+                .to_string()
+                .starts_with("No release archive matching `3.10` is cached"));
+        });
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_install_sdk_resumes_partial_download() {
+        test_with_context(|context, _| {
+            // setup
+            let context = context.clone().with_path(&[context.home().join("bin")]);
+            let downloader = mock_downloader_serving_3_22_2(&context);
+            let archive = std::fs::read(context.home().join("flutter.tar")).unwrap();
+            let downloads = context.fenv_cache().join("downloads");
+            let partial = downloads.join("flutter_linux_3.22.2-stable.tar.xz.part");
+            partial.parent().unwrap().create_dir_all().unwrap();
+            std::fs::write(&partial, &archive[..archive.len() / 2]).unwrap();
+            let sdk_service = RealSdkService::from(
+                MockGitCommand::new(),
+                SystemClock::new(),
+                MockFlutterCommand,
+            )
+            .with_downloader(downloader);
+
+            // execution
+            sdk_service
+                .install_sdk(
+                    &context,
+                    "3.22.2",
+                    &InstallOptions {
+                        fails_on_installed: true,
+                        ..Default::default()
+                    },
+                    &mut std::io::sink(),
+                )
+                .unwrap();
+
+            // verification
+            assert!(context
+                .fenv_sdk_root("3.22.2")
+                .join("bin")
+                .join("flutter")
+                .is_file());
+            assert!(!partial.exists());
+            let sha256 = sha256_of_file(&context.home().join("flutter.tar")).unwrap();
+            assert!(downloads
+                .join("flutter_linux_3.22.2-stable.tar.xz")
+                .is_file());
+            assert_eq!(
+                downloads
+                    .join("flutter_linux_3.22.2-stable.tar.xz.sha256")
+                    .read_to_string()
+                    .unwrap(),
+                format!("{sha256}\n")
+            );
+        });
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_install_sdk_fails_by_checksum_mismatch() {
+        test_with_context(|context, _| {
+            // setup
+            let context = context.clone().with_path(&[context.home().join("bin")]);
+            let releases = serde_json::json!({
+                "releases": [
+                    {
+                        "hash": "761747bfc538b5af34aa0d3fac380f1bc331ec49",
+                        "version": "3.22.2",
+                        "archive": "stable/linux/flutter_linux_3.22.2-stable.tar.xz",
+                        "sha256": "0".repeat(64),
+                        "dart_sdk_arch": if cfg!(target_arch = "aarch64") { "arm64" } else { "x64" },
+                    },
+                ]
+            })
+            .to_string();
+            let mut downloader = MockDownloader::new();
+            downloader
+                .expect_fetch()
+                .returning(move |_, _| anyhow::Ok(releases.clone()));
+            downloader.expect_download().returning(|_, destination, _| {
+                std::fs::write(destination, "broken").unwrap();
+                anyhow::Ok(())
+            });
+            let sdk_service = RealSdkService::from(
+                MockGitCommand::new(),
+                SystemClock::new(),
+                MockFlutterCommand,
+            )
+            .with_downloader(downloader);
+
+            // execution
+            let error = sdk_service
+                .install_sdk(
+                    &context,
+                    "3.22",
+                    &InstallOptions {
+                        fails_on_installed: true,
+                        ..Default::default()
+                    },
+                    &mut std::io::sink(),
+                )
+                .unwrap_err();
+
+            // verification
+            assert!(error.to_string().starts_with("The checksum of "));
+            assert_eq!(
+                InstallFailure::reason_of(&error),
+                Some(InstallFailureReason::Checksum)
+            );
+            assert!(!context.fenv_sdk_root("3.22.2").exists());
+        });
+    }
 }
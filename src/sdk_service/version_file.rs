@@ -0,0 +1,138 @@
+/// Parses the content of a version file such as `.flutter-version` into the version prefix.
+///
+/// A `#` starts a comment which lasts until the end of the line, so that a version file can
+/// carry the reason why the version is pinned:
+///
+/// ```text
+/// # Pinned for the release branch.
+/// 3.22.2 # Waiting for the fix of flutter/flutter#12345
+/// ```
+///
+/// Blank lines and comment-only lines are skipped, and the first remaining line is the
+/// version prefix. Returns an empty string if there is no such line.
+pub fn parse_version_prefix(content: &str) -> String {
+    content
+        .lines()
+        .map(strip_comment)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default()
+        .to_owned()
+}
+
+/// The name of the version file of asdf, which is read if no `.flutter-version` exists.
+pub const TOOL_VERSIONS_FILE_NAME: &str = ".tool-versions";
+
+/// Parses the content of an asdf `.tool-versions` file into the version prefix of `flutter`.
+///
+/// The first version of the `flutter` line is used, dropping the channel suffix which the asdf
+/// plugin appends, such as `-stable` of `3.22.2-stable`. Returns an empty string if there is no
+/// `flutter` line.
+pub fn parse_tool_versions(content: &str) -> String {
+    let version = content
+        .lines()
+        .map(strip_comment)
+        .find_map(|line| {
+            let mut tokens = line.split_whitespace();
+            match tokens.next() {
+                Some("flutter") => tokens.next(),
+                _ => None,
+            }
+        })
+        .unwrap_or_default();
+    ["-stable", "-beta", "-dev", "-master"]
+        .iter()
+        .find_map(|suffix| version.strip_suffix(suffix))
+        .unwrap_or(version)
+        .to_owned()
+}
+
+/// Replaces the version prefix in the content of a version file with `version_prefix`,
+/// preserving the comments and the other lines as they are.
+///
+/// If `content` has no version prefix, `version_prefix` is written in front of it.
+pub fn replace_version_prefix(content: &str, version_prefix: &str) -> String {
+    let mut lines: Vec<String> = content.lines().map(str::to_owned).collect();
+    match lines
+        .iter()
+        .position(|line| !strip_comment(line).is_empty())
+    {
+        Some(index) => {
+            let line = &lines[index];
+            let start = line.len() - line.trim_start().len();
+            let end = start + strip_comment(line).len();
+            lines[index] = format!("{}{version_prefix}{}", &line[..start], &line[end..]);
+        }
+        None => lines.insert(0, version_prefix.to_owned()),
+    }
+    lines.join("\n")
+}
+
+/// Strips the comment and the surrounding whitespaces from the given line.
+fn strip_comment(line: &str) -> &str {
+    match line.find('#') {
+        Some(index) => line[..index].trim(),
+        None => line.trim(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_tool_versions, parse_version_prefix, replace_version_prefix};
+
+    #[test]
+    fn test_parse_version_prefix() {
+        for (content, expected) in [
+            ("3.22.2\n", "3.22.2"),
+            ("  stable  \n", "stable"),
+            ("3.22.2 # pinned for release branch\n", "3.22.2"),
+            ("# pinned for release branch\n\n3.22.2\n", "3.22.2"),
+            ("3.22.2#no-space\n", "3.22.2"),
+            ("# only a comment\n", ""),
+            ("", ""),
+        ] {
+            assert_eq!(parse_version_prefix(content), expected, "{content:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_tool_versions() {
+        for (content, expected) in [
+            ("flutter 3.22.2-stable\n", "3.22.2"),
+            (
+                "nodejs 20.0.0\nflutter 3.10.0-1.4.pre-beta\n",
+                "3.10.0-1.4.pre",
+            ),
+            (
+                "# flutter 3.3.10\nflutter   3.7.12 3.3.10 # fallback\n",
+                "3.7.12",
+            ),
+            ("flutter-tools 1.0.0\n", ""),
+            ("nodejs 20.0.0\n", ""),
+        ] {
+            assert_eq!(parse_tool_versions(content), expected, "{content:?}");
+        }
+    }
+
+    #[test]
+    fn test_replace_version_prefix() {
+        for (content, expected) in [
+            ("3.22.2", "3.24.0"),
+            (
+                "3.22.2 # pinned for release branch",
+                "3.24.0 # pinned for release branch",
+            ),
+            (
+                "# pinned for release branch\n  3.22.2  # see #123",
+                "# pinned for release branch\n  3.24.0  # see #123",
+            ),
+            ("# only a comment", "3.24.0\n# only a comment"),
+            ("", "3.24.0"),
+        ] {
+            assert_eq!(
+                replace_version_prefix(content, "3.24.0"),
+                expected,
+                "{content:?}"
+            );
+        }
+    }
+}
@@ -1,8 +1,36 @@
-use super::model::flutter_sdk::FlutterSdk;
+use super::{
+    latest_keyword,
+    model::{
+        flutter_sdk::FlutterSdk,
+        flutter_version::FlutterVersion,
+        local_flutter_sdk::{is_commit_sha, split_namespace},
+    },
+    version_range::VersionRange,
+};
 use lazy_static::lazy_static;
 use regex::Regex;
 
 pub fn matches_prefix<T: FlutterSdk>(list: &[T], prefix: &str) -> Vec<T> {
+    if let Some(prefix) = latest_keyword::parse(prefix) {
+        let releases: Vec<T> = list
+            .iter()
+            .filter(|sdk| latest_keyword::is_release(&sdk.display_name()))
+            .cloned()
+            .collect();
+        return match prefix {
+            "" => releases,
+            prefix => matches_prefix(&releases, prefix),
+        };
+    }
+    if let Some(range) = VersionRange::parse(prefix) {
+        return list
+            .iter()
+            .filter(|sdk| {
+                FlutterVersion::parse(&sdk.display_name()).is_some_and(|v| range.matches(&v))
+            })
+            .cloned()
+            .collect();
+    }
     let fragments = VersionFragments::parse(prefix);
     list.to_vec()
         .into_iter()
@@ -11,8 +39,11 @@ pub fn matches_prefix<T: FlutterSdk>(list: &[T], prefix: &str) -> Vec<T> {
 }
 
 enum VersionFragments<'a> {
-    Version(Vec<&'a str>),
+    /// The fragments of a version, and its namespace such as `fork` of `fork-3.19`.
+    Version(Option<&'a str>, Vec<&'a str>),
     Channel(&'a str),
+    /// An abbreviated or full commit SHA, which matches the commits sharing the leading digits.
+    Commit(&'a str),
 }
 
 impl<'a> VersionFragments<'a> {
@@ -21,11 +52,18 @@ impl<'a> VersionFragments<'a> {
             static ref VERSION_PATTERN: Regex = Regex::new(r"^v?(\d.*)$").unwrap();
             static ref SPLITTER: Regex = Regex::new(r"((-|\+)hotfix)?\.").unwrap();
         }
+        if is_commit_sha(prefix) {
+            return Self::Commit(prefix);
+        }
+        let (namespace, prefix) = match split_namespace(prefix) {
+            Some((namespace, version)) => (Some(namespace), version),
+            None => (None, prefix),
+        };
         match VERSION_PATTERN.captures(prefix) {
             Some(captures) => {
                 let version = captures.get(1).unwrap().as_str();
                 let fragments: Vec<&str> = SPLITTER.split(version).collect();
-                Self::Version(fragments)
+                Self::Version(namespace, fragments)
             }
             None => Self::Channel(prefix),
         }
@@ -35,19 +73,26 @@ impl<'a> VersionFragments<'a> {
         let version_or_channel = sdk.display_name();
         let sdk_fragments = VersionFragments::parse(&version_or_channel);
         match self {
-            VersionFragments::Version(version_me) => match sdk_fragments {
-                VersionFragments::Version(version_you) => {
-                    if version_me.len() > version_you.len() {
+            VersionFragments::Version(namespace_me, version_me) => match sdk_fragments {
+                VersionFragments::Version(namespace_you, version_you) => {
+                    if *namespace_me != namespace_you || version_me.len() > version_you.len() {
                         false
                     } else {
                         version_you[..version_me.len()] == *version_me
                     }
                 }
-                VersionFragments::Channel(_) => false,
+                _ => false,
             },
             VersionFragments::Channel(channel_me) => match sdk_fragments {
-                VersionFragments::Version(_) => false,
                 VersionFragments::Channel(channel_you) => channel_you.starts_with(*channel_me),
+                _ => false,
+            },
+            // The full SHA in a version file selects the commit installed under its short SHA.
+            VersionFragments::Commit(sha_me) => match sdk_fragments {
+                VersionFragments::Commit(sha_you) => {
+                    sha_you.starts_with(*sha_me) || sha_me.starts_with(sha_you)
+                }
+                _ => false,
             },
         }
     }
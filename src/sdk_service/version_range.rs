@@ -0,0 +1,202 @@
+use super::model::flutter_version::FlutterVersion;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// A range of versions such as `^3.10`, `>=3.7 <3.16`, and `3.*`, which is given wherever a
+/// version prefix is accepted.
+///
+/// The space-separated comparators must be satisfied altogether. A partial version such as `3.7`
+/// stands for every version starting with it, so `<=3.7` includes `3.7.12` and `>3.7` starts
+/// from `3.8.0`. Only the releases match: neither the pre-releases nor the channels do.
+#[derive(Debug, PartialEq, Eq)]
+pub struct VersionRange {
+    comparators: Vec<(Operator, FlutterVersion)>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Operator {
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+    Equal,
+}
+
+impl VersionRange {
+    /// Parses `prefix` as a range. Returns `None` if `prefix` is not written in the range syntax,
+    /// such as a plain version prefix `3.7` or a channel.
+    pub fn parse(prefix: &str) -> Option<Self> {
+        let prefix = prefix.trim();
+        let is_range = prefix.starts_with(['^', '>', '<', '='])
+            || prefix.contains('*')
+            || prefix.ends_with(".x");
+        if !is_range {
+            return None;
+        }
+        let mut comparators = vec![];
+        for term in prefix.split_whitespace() {
+            comparators.extend(parse_term(term)?);
+        }
+        Some(Self { comparators })
+    }
+
+    pub fn matches(&self, version: &FlutterVersion) -> bool {
+        !version.is_prerelease()
+            && self
+                .comparators
+                .iter()
+                .all(|(operator, bound)| match operator {
+                    Operator::Greater => version > bound,
+                    Operator::GreaterOrEqual => version >= bound,
+                    Operator::Less => version < bound,
+                    Operator::LessOrEqual => version <= bound,
+                    Operator::Equal => version == bound,
+                })
+    }
+}
+
+/// Parses a comparator such as `^3.10`, `>=3.7`, and `3.*` into the bounds of the range.
+fn parse_term(term: &str) -> Option<Vec<(Operator, FlutterVersion)>> {
+    let (operator, version) = ["^", ">=", "<=", ">", "<", "="]
+        .iter()
+        .find_map(|operator| term.strip_prefix(operator).map(|rest| (*operator, rest)))
+        .unwrap_or(("=", term));
+    if version == "*" || version == "x" {
+        return (operator == "=").then_some(vec![]);
+    }
+    let partial = PartialVersion::parse(version)?;
+    let lowest = partial.lowest();
+    let comparators = match (operator, partial.next()) {
+        ("^", _) => {
+            let mut comparators = vec![(Operator::GreaterOrEqual, lowest.clone())];
+            comparators.extend(caret_upper_bound(&lowest).map(|upper| (Operator::Less, upper)));
+            comparators
+        }
+        (">=", _) => vec![(Operator::GreaterOrEqual, lowest)],
+        ("<", _) => vec![(Operator::Less, lowest)],
+        (">", Some(next)) => vec![(Operator::GreaterOrEqual, next)],
+        (">", None) => vec![(Operator::Greater, lowest)],
+        ("<=", Some(next)) => vec![(Operator::Less, next)],
+        ("<=", None) => vec![(Operator::LessOrEqual, lowest)],
+        (_, Some(next)) => vec![(Operator::GreaterOrEqual, lowest), (Operator::Less, next)],
+        (_, None) => vec![(Operator::Equal, lowest)],
+    };
+    Some(comparators)
+}
+
+/// The first version which `^{lowest}` excludes: the next major version, or the next minor
+/// version for `0.x`.
+fn caret_upper_bound(lowest: &FlutterVersion) -> Option<FlutterVersion> {
+    match lowest.major {
+        0 => Some(FlutterVersion::new(0, lowest.minor.checked_add(1)?, 0, 0)),
+        major => Some(FlutterVersion::new(major.checked_add(1)?, 0, 0, 0)),
+    }
+}
+
+/// A version whose trailing fragments may be omitted or be a wildcard, such as `3`, `3.7`,
+/// `3.7.*`, and `3.7.12+hotfix.1`.
+enum PartialVersion {
+    Major(u8),
+    Minor(u8, u8),
+    Full(FlutterVersion),
+}
+
+impl PartialVersion {
+    fn parse(version: &str) -> Option<Self> {
+        lazy_static! {
+            static ref PATTERN: Regex =
+                Regex::new(r"^v?(\d+)(?:\.(\d+))?(?:\.[*x])?(?:\.[*x])?$").unwrap();
+        }
+        if let Some(version) = FlutterVersion::parse(version) {
+            return (!version.is_prerelease()).then_some(Self::Full(version));
+        }
+        let captures = PATTERN.captures(version)?;
+        let major = captures.get(1)?.as_str().parse().ok()?;
+        match captures.get(2) {
+            Some(minor) => Some(Self::Minor(major, minor.as_str().parse().ok()?)),
+            None => Some(Self::Major(major)),
+        }
+    }
+
+    /// The lowest version which starts with this.
+    fn lowest(&self) -> FlutterVersion {
+        match self {
+            Self::Major(major) => FlutterVersion::new(*major, 0, 0, 0),
+            Self::Minor(major, minor) => FlutterVersion::new(*major, *minor, 0, 0),
+            Self::Full(version) => version.clone(),
+        }
+    }
+
+    /// The first version which does not start with this, or `None` if this is a full version.
+    fn next(&self) -> Option<FlutterVersion> {
+        match self {
+            Self::Major(major) => Some(FlutterVersion::new(major.checked_add(1)?, 0, 0, 0)),
+            Self::Minor(major, minor) => {
+                Some(FlutterVersion::new(*major, minor.checked_add(1)?, 0, 0))
+            }
+            Self::Full(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VersionRange;
+    use crate::sdk_service::model::flutter_version::FlutterVersion;
+
+    fn matches(range: &str, version: &str) -> bool {
+        VersionRange::parse(range)
+            .unwrap()
+            .matches(&FlutterVersion::parse(version).unwrap())
+    }
+
+    #[test]
+    fn test_parse() {
+        assert!(VersionRange::parse("^3.10").is_some());
+        assert!(VersionRange::parse(">=3.7 <3.16").is_some());
+        assert!(VersionRange::parse("3.*").is_some());
+        assert!(VersionRange::parse("3.x").is_some());
+        assert!(VersionRange::parse("*").is_some());
+        assert!(VersionRange::parse("3.7").is_none());
+        assert!(VersionRange::parse("stable").is_none());
+        assert!(VersionRange::parse("latest:3").is_none());
+        assert!(VersionRange::parse("^stable").is_none());
+        assert!(VersionRange::parse(">=3.7 <beta").is_none());
+    }
+
+    #[test]
+    fn test_caret() {
+        assert!(matches("^3.10", "3.10.0"));
+        assert!(matches("^3.10", "3.22.2"));
+        assert!(!matches("^3.10", "3.7.12"));
+        assert!(!matches("^3.10", "4.0.0"));
+        assert!(matches("^0.11", "0.11.13"));
+        assert!(!matches("^0.11", "0.12.0"));
+        assert!(!matches("^3.10", "3.23.0-0.1.pre"));
+    }
+
+    #[test]
+    fn test_comparators() {
+        assert!(matches(">=3.7 <3.16", "3.7.0"));
+        assert!(matches(">=3.7 <3.16", "3.13.9"));
+        assert!(!matches(">=3.7 <3.16", "3.16.0"));
+        assert!(matches("<=3.7", "3.7.12"));
+        assert!(!matches("<=3.7", "3.8.0"));
+        assert!(!matches(">3.7", "3.7.12"));
+        assert!(matches(">3.7", "3.8.0"));
+        assert!(matches(">3.7.12", "3.7.12+hotfix.1"));
+        assert!(matches("<=1.17.5", "v1.17.5"));
+        assert!(matches("=1.17.5", "v1.17.5"));
+        assert!(!matches("=1.17.5", "1.17.5+hotfix.1"));
+    }
+
+    #[test]
+    fn test_wildcards() {
+        assert!(matches("3.*", "3.0.0"));
+        assert!(matches("3.x", "3.22.2"));
+        assert!(!matches("3.*", "2.10.5"));
+        assert!(matches("3.7.*", "3.7.12"));
+        assert!(!matches("3.7.*", "3.8.0"));
+        assert!(matches("*", "1.0.0"));
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{
+    args::FenvAliasArgs,
+    context::FenvContext,
+    sdk_service::{alias, sdk_service::SdkService},
+    service::service::Service,
+    util::io::ConsoleOutput,
+};
+use anyhow::bail;
+
+/// Shows, sets, and deletes the aliases in `{fenv_root}/aliases`.
+pub struct FenvAliasService {
+    pub args: FenvAliasArgs,
+}
+
+impl FenvAliasService {
+    pub fn new(args: FenvAliasArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvAliasService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        _: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        match (&self.args.name, &self.args.prefix) {
+            (Some(name), _) if self.args.delete => alias::remove(context, name),
+            (Some(name), Some(prefix)) => alias::write(context, name, prefix),
+            (Some(name), None) => match alias::read(context, name) {
+                Some(target) => {
+                    writeln!(output.stdout(), "{target}")?;
+                    anyhow::Ok(())
+                }
+                None => bail!("No such alias: `{name}`"),
+            },
+            (None, _) => {
+                for (name, target) in alias::list(context)? {
+                    writeln!(output.stdout(), "{name} -> {target}")?;
+                }
+                anyhow::Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::FenvContext, sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context, try_run,
+    };
+
+    #[test]
+    fn test_alias_is_resolved_by_version_file() {
+        test_with_context(|context, output| {
+            // setup
+            for version in ["3.19.6", "3.22.2"] {
+                context
+                    .fenv_sdk_root(version)
+                    .join("bin")
+                    .join("flutter")
+                    .writeln("")
+                    .unwrap();
+            }
+            let sdk_service = RealSdkService::new();
+            try_run(
+                &["fenv", "alias", "lts", "3.19"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("lts")
+                .unwrap();
+
+            // execution
+            try_run(&["fenv", "alias"], context, &sdk_service, output).unwrap();
+            try_run(&["fenv", "version-name"], context, &sdk_service, output).unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "lts -> 3.19\n3.19.6\n");
+        })
+    }
+
+    #[test]
+    fn test_alias_fails_with_channel_name() {
+        test_with_context(|context, output| {
+            // execution
+            let result = try_run(
+                &["fenv", "alias", "stable", "3.19.6"],
+                context,
+                &RealSdkService::new(),
+                output,
+            );
+
+            // validation
+            assert!(result.is_err());
+            assert!(!context.fenv_aliases().join("stable").exists());
+        })
+    }
+}
@@ -0,0 +1 @@
+pub mod alias_service;
@@ -0,0 +1,97 @@
+use crate::{
+    args::FenvArgsPrependArgs, config::FenvConfig, context::FenvContext,
+    sdk_service::sdk_service::SdkService, service::service::Service, util::io::ConsoleOutput,
+};
+
+/// Prints the arguments configured by `argsPrepend` of `{fenv_root}/config.json`
+/// for the given tool, one argument per line.
+///
+/// This is a plumbing command for the shims.
+pub struct FenvArgsPrependService {
+    pub args: FenvArgsPrependArgs,
+}
+
+impl FenvArgsPrependService {
+    pub fn new(args: FenvArgsPrependArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvArgsPrependService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        _: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let config = FenvConfig::load(context)?;
+        for arg in config.args_prepend_of(&self.args.tool) {
+            writeln!(output.stdout(), "{arg}")?;
+        }
+        anyhow::Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::FenvContext, sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context, try_run,
+    };
+    use indoc::indoc;
+
+    #[test]
+    fn test_print_args_prepend_of_configured_tool() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_config_file()
+                .write(indoc! {r#"
+                    {
+                      "argsPrepend": {
+                        "flutter": ["--suppress-analytics", "--no-version-check"]
+                      }
+                    }
+                "#})
+                .unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "args-prepend", "flutter"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                "--suppress-analytics\n--no-version-check\n"
+            );
+            assert!(output.stderr_to_string().is_empty());
+        })
+    }
+
+    #[test]
+    fn test_print_nothing_if_tool_is_not_configured() {
+        test_with_context(|context, output| {
+            // execution
+            try_run(
+                &["fenv", "args-prepend", "dart"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert!(output.stdout_to_string().is_empty());
+            assert!(output.stderr_to_string().is_empty());
+        })
+    }
+}
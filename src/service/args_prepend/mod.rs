@@ -0,0 +1 @@
+pub mod args_prepend_service;
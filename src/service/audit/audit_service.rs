@@ -0,0 +1,183 @@
+use crate::{
+    args::{FenvAuditShowArgs, FenvAuditSubcommands},
+    audit::{audit_log_file, read_records},
+    context::FenvContext,
+    sdk_service::sdk_service::SdkService,
+    service::service::Service,
+    util::io::ConsoleOutput,
+};
+
+/// Shows the records of `{fenv_root}/audit.log`.
+pub struct FenvAuditService {
+    pub args: FenvAuditSubcommands,
+}
+
+impl FenvAuditService {
+    pub fn new(args: FenvAuditSubcommands) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvAuditService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        _: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        match &self.args {
+            FenvAuditSubcommands::Show(args) => show(context, args, output),
+        }
+    }
+}
+
+fn show<OUT: std::io::Write, ERR: std::io::Write>(
+    context: &impl FenvContext,
+    args: &FenvAuditShowArgs,
+    output: &mut dyn ConsoleOutput<OUT, ERR>,
+) -> anyhow::Result<()> {
+    let records = read_records(context)?;
+    if records.is_empty() {
+        output.info(&format!(
+            "No command is recorded in `{}`",
+            audit_log_file(context)
+        ))?;
+        return anyhow::Ok(());
+    }
+    let skipped = records.len() - args.limit.unwrap_or(records.len()).min(records.len());
+    for record in &records[skipped..] {
+        if args.json {
+            writeln!(output.stdout(), "{}", serde_json::to_string(record)?)?;
+            continue;
+        }
+        writeln!(
+            output.stdout(),
+            "{}  {}  {:<5}  {}",
+            record.timestamp,
+            record.user,
+            record.result,
+            record.command
+        )?;
+        if let Some(error) = &record.error {
+            writeln!(output.stdout(), "    {error}")?;
+        }
+    }
+    anyhow::Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        audit::{read_records, AuditRecord},
+        context::FenvContext,
+        sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context,
+        try_run,
+        util::io::ConsoleOutput,
+    };
+
+    #[test]
+    fn test_audit_show_lists_mutating_commands() {
+        test_with_context(|context, output| {
+            // setup
+            let sdk_service = RealSdkService::new();
+            try_run(&["fenv", "versions"], context, &sdk_service, output).unwrap();
+            try_run(
+                &["fenv", "uninstall", "--broken"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+            try_run(
+                &["fenv", "install", "--archive", "/no/such.tar.xz", "3.22.2"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap_err();
+            output.stdout().clear();
+
+            // execution
+            try_run(&["fenv", "audit", "show"], context, &sdk_service, output).unwrap();
+            let text = output.stdout_to_string();
+            output.stdout().clear();
+            try_run(
+                &["fenv", "audit", "show", "-n", "1", "--json"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+            let json = output.stdout_to_string();
+
+            // validation
+            let records = read_records(context).unwrap();
+            assert_eq!(records.len(), 2);
+            assert_eq!(records[0].command, "fenv uninstall --broken");
+            assert_eq!(records[0].version, None);
+            assert_eq!(records[0].user, "unknown");
+            assert_eq!(records[0].result, "ok");
+            assert_eq!(
+                records[1].command,
+                "fenv install --archive /no/such.tar.xz 3.22.2"
+            );
+            assert_eq!(records[1].version.as_deref(), Some("3.22.2"));
+            assert_eq!(records[1].result, "error");
+            assert!(records[1].error.is_some());
+            let lines: Vec<&str> = text.lines().collect();
+            assert_eq!(lines.len(), 3);
+            assert!(lines[0].ends_with("  unknown  ok     fenv uninstall --broken"));
+            assert!(lines[1]
+                .ends_with("  unknown  error  fenv install --archive /no/such.tar.xz 3.22.2"));
+            assert!(lines[2].starts_with("    "));
+            let shown: AuditRecord = serde_json::from_str(json.trim_end()).unwrap();
+            assert_eq!(shown, records[1]);
+        })
+    }
+
+    #[test]
+    fn test_audit_records_setup_and_new() {
+        test_with_context(|context, output| {
+            // setup
+            let sdk_service = RealSdkService::new();
+            context.fenv_root().create_dir_all().unwrap();
+            context
+                .fenv_dir()
+                .join("app")
+                .join("main.dart")
+                .writeln("")
+                .unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "setup", "no_project"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap_err();
+            try_run(
+                &["fenv", "new", "app", "--version", "3.22"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap_err();
+
+            // validation
+            let records = read_records(context).unwrap();
+            assert_eq!(records.len(), 2);
+            assert_eq!(records[0].command, "fenv setup no_project");
+            assert_eq!(records[0].version, None);
+            assert_eq!(records[0].result, "error");
+            assert_eq!(records[1].command, "fenv new app --version 3.22");
+            assert_eq!(records[1].version.as_deref(), Some("3.22"));
+            assert_eq!(records[1].result, "error");
+        })
+    }
+}
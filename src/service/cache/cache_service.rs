@@ -0,0 +1,268 @@
+use crate::{
+    args::{FenvCacheClearArgs, FenvCacheSubcommands},
+    context::FenvContext,
+    sdk_service::{remote_sdk_list_cache::CACHE_FILE_NAME, sdk_service::SdkService},
+    service::service::Service,
+    util::{
+        io::ConsoleOutput,
+        path_like::PathLike,
+        size::{disk_usage, human_duration, human_size},
+    },
+};
+use anyhow::Context;
+use std::time::SystemTime;
+
+/// Inspects and cleans the caches in `{fenv_cache}`.
+pub struct FenvCacheService {
+    pub args: FenvCacheSubcommands,
+}
+
+impl FenvCacheService {
+    pub fn new(args: FenvCacheSubcommands) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvCacheService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        _: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        match &self.args {
+            FenvCacheSubcommands::Info => show_info(context, output),
+            FenvCacheSubcommands::Clear(args) => clear(context, args, output),
+        }
+    }
+}
+
+/// An entry in `{fenv_cache}`.
+struct CacheEntry {
+    name: String,
+    path: PathLike,
+    size: u64,
+    modified: Option<SystemTime>,
+}
+
+/// Lists the entries in `{fenv_cache}` sorted by their names.
+fn list_entries(context: &impl FenvContext) -> anyhow::Result<Vec<CacheEntry>> {
+    let fenv_cache = context.fenv_cache();
+    if !fenv_cache.is_dir() {
+        return anyhow::Ok(vec![]);
+    }
+    let entries = fenv_cache
+        .read_dir()
+        .with_context(|| format!("Failed to read `{fenv_cache}`"))?;
+    let mut entries: Vec<CacheEntry> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            let path = PathLike::from(entry.path().as_path());
+            let metadata = path.path().symlink_metadata().ok()?;
+            Some(CacheEntry {
+                name,
+                size: disk_usage(&path),
+                modified: metadata.modified().ok(),
+                path,
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    anyhow::Ok(entries)
+}
+
+fn show_info<OUT: std::io::Write, ERR: std::io::Write>(
+    context: &impl FenvContext,
+    output: &mut dyn ConsoleOutput<OUT, ERR>,
+) -> anyhow::Result<()> {
+    let fenv_cache = context.fenv_cache();
+    let entries = list_entries(context)?;
+    if entries.is_empty() {
+        writeln!(output.stdout(), "`{fenv_cache}` is empty")?;
+        return anyhow::Ok(());
+    }
+    let width = entries
+        .iter()
+        .map(|entry| entry.name.len())
+        .max()
+        .unwrap_or(0);
+    writeln!(output.stdout(), "{fenv_cache}")?;
+    for entry in &entries {
+        let age = entry
+            .modified
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .map(|age| format!("{} ago", human_duration(age)))
+            .unwrap_or_else(|| String::from("-"));
+        writeln!(
+            output.stdout(),
+            "  {name:<width$}  {size:>10}  {age}",
+            name = entry.name,
+            size = human_size(entry.size),
+        )?;
+    }
+    let total: u64 = entries.iter().map(|entry| entry.size).sum();
+    writeln!(output.stdout(), "Total: {}", human_size(total))?;
+    anyhow::Ok(())
+}
+
+fn clear<OUT: std::io::Write, ERR: std::io::Write>(
+    context: &impl FenvContext,
+    args: &FenvCacheClearArgs,
+    output: &mut dyn ConsoleOutput<OUT, ERR>,
+) -> anyhow::Result<()> {
+    let entries: Vec<CacheEntry> = list_entries(context)?
+        .into_iter()
+        .filter(|entry| !args.remote_list_only || entry.name == CACHE_FILE_NAME)
+        .collect();
+    if entries.is_empty() {
+        writeln!(output.stdout(), "Nothing to clear")?;
+        return anyhow::Ok(());
+    }
+    for entry in &entries {
+        let path = &entry.path;
+        let is_dir = path
+            .path()
+            .symlink_metadata()
+            .is_ok_and(|metadata| metadata.is_dir());
+        if is_dir {
+            path.remove_dir_all()
+        } else {
+            path.remove_file()
+        }
+        .with_context(|| format!("Failed to remove `{path}`"))?;
+        writeln!(
+            output.stdout(),
+            "Removed `{path}` ({})",
+            human_size(entry.size)
+        )?;
+    }
+    anyhow::Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::FenvContext,
+        sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context,
+        try_run,
+        util::size::{human_duration, human_size},
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn test_cache_info() {
+        test_with_context(|context, output| {
+            // setup
+            let fenv_cache = context.fenv_cache();
+            fenv_cache.join(".remote_list").writeln("[]").unwrap();
+            fenv_cache
+                .join("deprecated_usages")
+                .writeln("fenv local --symlink")
+                .unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "cache", "info"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            let stdout = output.stdout_to_string();
+            let lines: Vec<&str> = stdout.lines().collect();
+            assert_eq!(lines[0], fenv_cache.to_string());
+            assert!(lines[1].starts_with("  .remote_list              3 B  "));
+            assert!(lines[1].ends_with(" ago"));
+            assert!(lines[2].starts_with("  deprecated_usages        21 B  "));
+            assert_eq!(lines[3], "Total: 24 B");
+        })
+    }
+
+    #[test]
+    fn test_cache_info_without_cache() {
+        test_with_context(|context, output| {
+            // execution
+            try_run(
+                &["fenv", "cache", "info"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                format!("`{}` is empty\n", context.fenv_cache())
+            );
+        })
+    }
+
+    #[test]
+    fn test_cache_clear_remote_list_only() {
+        test_with_context(|context, output| {
+            // setup
+            let fenv_cache = context.fenv_cache();
+            fenv_cache.join(".remote_list").writeln("[]").unwrap();
+            fenv_cache.join("deprecated_usages").writeln("").unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "cache", "clear", "--remote-list-only"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert!(!fenv_cache.join(".remote_list").exists());
+            assert!(fenv_cache.join("deprecated_usages").exists());
+            assert_eq!(
+                output.stdout_to_string(),
+                format!("Removed `{fenv_cache}/.remote_list` (3 B)\n")
+            );
+        })
+    }
+
+    #[test]
+    fn test_cache_clear() {
+        test_with_context(|context, output| {
+            // setup
+            let fenv_cache = context.fenv_cache();
+            fenv_cache.join(".remote_list").writeln("[]").unwrap();
+            fenv_cache.join("a").join("b").writeln("").unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "cache", "clear"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert!(fenv_cache.is_dir());
+            assert_eq!(fenv_cache.read_dir().unwrap().count(), 0);
+        })
+    }
+
+    #[test]
+    fn test_human_readable_units() {
+        assert_eq!(human_size(1023), "1023 B");
+        assert_eq!(human_size(1536), "1.5 KiB");
+        assert_eq!(human_size(5 * 1024 * 1024), "5.0 MiB");
+        assert_eq!(human_duration(Duration::from_secs(59)), "59s");
+        assert_eq!(human_duration(Duration::from_secs(3 * 60 + 1)), "3m");
+        assert_eq!(human_duration(Duration::from_secs(2 * 86400)), "2d");
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{
+    args::FenvCheckFlutterArgsArgs,
+    context::FenvContext,
+    sdk_service::{model::local_flutter_sdk::LocalFlutterSdk, sdk_service::SdkService},
+    service::{service::Service, version_name::version_name_service::resolve_selected_sdk},
+    util::io::{BufferedOutput, ConsoleOutput},
+};
+use anyhow::bail;
+
+/// Fails if the given arguments of `flutter` are not allowed with the selected Flutter SDK.
+///
+/// | Selected SDK | `upgrade` | `downgrade` | `channel`                                   |
+/// | ------------ | --------- | ----------- | ------------------------------------------- |
+/// | A version    | blocked   | blocked     | blocked unless `FENV_ALLOW_FLUTTER_CHANNEL` |
+/// | A channel    | allowed   | allowed     | blocked unless `FENV_ALLOW_FLUTTER_CHANNEL` |
+///
+/// This is a plumbing command for the `flutter` shim.
+pub struct FenvCheckFlutterArgsService {
+    pub args: FenvCheckFlutterArgsArgs,
+}
+
+impl FenvCheckFlutterArgsService {
+    pub fn new(args: FenvCheckFlutterArgsArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvCheckFlutterArgsService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        _: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let Some(command) = self.args.args.iter().find(|arg| !arg.starts_with('-')) else {
+            return anyhow::Ok(());
+        };
+        // Leaves the failure of the resolution and its warnings to the shim, which reports them
+        // in detail.
+        let Ok(selected) = resolve_selected_sdk(
+            context,
+            sdk_service,
+            &mut BufferedOutput::new(),
+            &context.fenv_dir(),
+        ) else {
+            return anyhow::Ok(());
+        };
+        if is_blocked(&selected.sdk, command, context.allows_flutter_channel()) {
+            if command == "channel" {
+                bail!(
+                    "`flutter channel` is not allowed. use `fenv install/uninstall` instead, \
+                    or set `FENV_ALLOW_FLUTTER_CHANNEL=1` to allow it"
+                )
+            }
+            bail!("`flutter {command}` is not allowed. use `fenv install/uninstall` instead")
+        }
+        anyhow::Ok(())
+    }
+}
+
+fn is_blocked(sdk: &LocalFlutterSdk, command: &str, allows_flutter_channel: bool) -> bool {
+    match command {
+        "channel" => !allows_flutter_channel,
+        "upgrade" | "downgrade" => !matches!(sdk, LocalFlutterSdk::Channel(_)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_blocked;
+    use crate::{
+        context::FenvContext, sdk_service::model::local_flutter_sdk::LocalFlutterSdk,
+        sdk_service::sdk_service::RealSdkService, service::macros::test_with_context, try_run,
+    };
+
+    #[test]
+    fn test_is_blocked() {
+        let version = LocalFlutterSdk::parse("3.7.12").unwrap();
+        let channel = LocalFlutterSdk::parse("stable").unwrap();
+        for (sdk, command, allows_flutter_channel, expected) in [
+            (&version, "upgrade", false, true),
+            (&version, "downgrade", true, true),
+            (&version, "channel", false, true),
+            (&version, "channel", true, false),
+            (&version, "pub", false, false),
+            (&channel, "upgrade", false, false),
+            (&channel, "downgrade", false, false),
+            (&channel, "channel", false, true),
+            (&channel, "channel", true, false),
+        ] {
+            assert_eq!(
+                is_blocked(sdk, command, allows_flutter_channel),
+                expected,
+                "`flutter {command}` with `{sdk}` (allows channel: {allows_flutter_channel})"
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_flutter_args() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("3.7.12")
+                .create_dir_all()
+                .unwrap();
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("3.7.12")
+                .unwrap();
+            let sdk_service = RealSdkService::new();
+
+            // execution
+            let allowed = try_run(
+                &[
+                    "fenv",
+                    "check-flutter-args",
+                    "--",
+                    "--verbose",
+                    "pub",
+                    "get",
+                ],
+                context,
+                &sdk_service,
+                output,
+            );
+            let blocked = try_run(
+                &["fenv", "__check-flutter-args", "--", "--verbose", "upgrade"],
+                context,
+                &sdk_service,
+                output,
+            );
+
+            // validation
+            assert!(allowed.is_ok());
+            assert_eq!(
+                blocked.unwrap_err().to_string(),
+                "`flutter upgrade` is not allowed. use `fenv install/uninstall` instead"
+            );
+        })
+    }
+
+    #[test]
+    fn test_check_flutter_args_selects_fenv_version() {
+        test_with_context(|context, output| {
+            // setup
+            for version_or_channel in ["3.7.12", "stable"] {
+                context
+                    .fenv_versions()
+                    .join(version_or_channel)
+                    .create_dir_all()
+                    .unwrap();
+            }
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("3.7.12")
+                .unwrap();
+            let context = context.clone().with_shell_version("stable");
+
+            // execution
+            let result = try_run(
+                &["fenv", "check-flutter-args", "--", "upgrade"],
+                &context,
+                &RealSdkService::new(),
+                output,
+            );
+
+            // validation
+            assert!(result.is_ok());
+        })
+    }
+}
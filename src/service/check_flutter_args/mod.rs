@@ -0,0 +1 @@
+pub mod check_flutter_args_service;
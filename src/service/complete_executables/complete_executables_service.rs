@@ -0,0 +1,135 @@
+use crate::{
+    context::FenvContext,
+    invoke_command,
+    sdk_service::sdk_service::SdkService,
+    service::service::Service,
+    util::{io::ConsoleOutput, path_like::PathLike},
+};
+use is_executable::is_executable;
+use std::collections::BTreeSet;
+
+/// Prints the candidates of the wrapped executable of `fenv exec --` for the shell completion
+/// scripts.
+///
+/// The candidates are the executables in `bin` of the currently selected Flutter SDK and
+/// `$PUB_CACHE/bin`. Prints nothing from the SDK if no Flutter SDK is selected.
+#[derive(Default)]
+pub struct FenvCompleteExecutablesService;
+
+impl FenvCompleteExecutablesService {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvCompleteExecutablesService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let mut names = BTreeSet::new();
+        if let Ok(version_or_channel) =
+            invoke_command!(context, sdk_service, output, "version-name")
+        {
+            let prefix =
+                invoke_command!(context, sdk_service, output, "prefix", &version_or_channel)?;
+            names.extend(list_executables(
+                &PathLike::from(prefix.as_str()).join("bin"),
+            ));
+        }
+        names.extend(list_executables(&context.pub_cache().join("bin")));
+        for name in names {
+            writeln!(output.stdout(), "{name}")?;
+        }
+        anyhow::Ok(())
+    }
+}
+
+fn list_executables(bin_dir: &PathLike) -> Vec<String> {
+    let Ok(entries) = bin_dir.read_dir() else {
+        return vec![];
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file() && is_executable(entry.path()))
+        .filter_map(|entry| entry.file_name().to_str().map(|name| name.to_owned()))
+        .collect()
+}
+
+#[cfg(unix)]
+#[cfg(test)]
+mod tests_unix {
+    use crate::{
+        context::FenvContext, sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context, try_run, util::path_like::PathLike,
+    };
+    use indoc::indoc;
+    use std::os::unix::prelude::PermissionsExt;
+
+    fn create_file(path: &PathLike, mode: u32) {
+        path.writeln("").unwrap();
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    #[test]
+    fn test_complete_executables_of_selected_sdk_and_pub_cache() {
+        test_with_context(|context, output| {
+            // setup
+            let sdk_bin = context.fenv_versions().join("3.7.12").join("bin");
+            create_file(&sdk_bin.join("flutter"), 0o755);
+            create_file(&sdk_bin.join("dart"), 0o755);
+            create_file(&sdk_bin.join("README"), 0o644);
+            create_file(&context.pub_cache().join("bin").join("melos"), 0o755);
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("3.7")
+                .unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "__complete-executables"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                indoc! {"
+                    dart
+                    flutter
+                    melos
+                "}
+            );
+        })
+    }
+
+    #[test]
+    fn test_complete_executables_without_selected_sdk() {
+        test_with_context(|context, output| {
+            // setup
+            create_file(&context.pub_cache().join("bin").join("melos"), 0o755);
+
+            // execution
+            try_run(
+                &["fenv", "complete-executables"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "melos\n");
+        })
+    }
+}
@@ -0,0 +1 @@
+pub mod complete_executables_service;
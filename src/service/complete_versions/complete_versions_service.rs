@@ -0,0 +1,121 @@
+use crate::{
+    args::FenvCompleteVersionsArgs,
+    context::FenvContext,
+    sdk_service::{model::flutter_sdk::FlutterSdk, sdk_service::SdkService},
+    service::service::Service,
+    util::io::ConsoleOutput,
+};
+
+/// Prints the candidates of the version arguments for the shell completion scripts.
+///
+/// `global`, `local`, and `uninstall` complete the installed Flutter SDKs while `install` and
+/// `latest --remote` complete the available ones.
+pub struct FenvCompleteVersionsService {
+    pub args: FenvCompleteVersionsArgs,
+}
+
+impl FenvCompleteVersionsService {
+    pub fn new(args: FenvCompleteVersionsArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvCompleteVersionsService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let names: Vec<String> = if self.args.remote {
+            sdk_service
+                .get_available_remote_sdk_list(context, false)?
+                .iter()
+                .map(|sdk| sdk.display_name())
+                .collect()
+        } else {
+            sdk_service
+                .get_installed_sdk_list(context)?
+                .iter()
+                .map(|sdk| sdk.display_name())
+                .collect()
+        };
+        for name in names {
+            writeln!(output.stdout(), "{name}")?;
+        }
+        anyhow::Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::FenvContext, define_mock_valid_git_command,
+        external::flutter_command::FlutterCommandImpl, sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context, try_run, util::chrono_wrapper::SystemClock,
+    };
+    use indoc::indoc;
+
+    define_mock_valid_git_command!();
+
+    #[test]
+    fn test_complete_installed_versions() {
+        test_with_context(|context, output| {
+            // setup
+            for name in ["3.7.12", "stable", "v1.7.8+hotfix.4"] {
+                context.fenv_versions().join(name).create_dir_all().unwrap();
+            }
+
+            // execution
+            try_run(
+                &["fenv", "__complete-versions"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                indoc! {"
+                    v1.7.8+hotfix.4
+                    3.7.12
+                    stable
+                "}
+            );
+        })
+    }
+
+    #[test]
+    fn test_complete_remote_versions() {
+        test_with_context(|context, output| {
+            // setup
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(
+                &["fenv", "__complete-versions", "--remote"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            let expected = read_resource_file(
+                "resources/test/install_service/install-list-result-with-bare.txt",
+            )
+            .unwrap();
+            assert_eq!(output.stdout_to_string(), expected);
+        })
+    }
+}
@@ -0,0 +1 @@
+pub mod complete_versions_service;
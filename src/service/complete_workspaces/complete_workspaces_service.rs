@@ -0,0 +1,139 @@
+use crate::{
+    context::FenvContext,
+    sdk_service::sdk_service::SdkService,
+    service::service::Service,
+    util::{io::ConsoleOutput, path_like::PathLike},
+};
+
+/// How deep the directories under the current directory are searched, so that the completion
+/// stays quick in a large repository.
+const MAX_DEPTH: usize = 3;
+
+/// The directories which never contain a workspace of its own, besides the hidden ones.
+const IGNORED_DIRS: [&str; 2] = ["build", "node_modules"];
+
+/// Prints the candidates of the workspace of `fenv workspace` and `fenv setup` for the shell
+/// completion scripts.
+///
+/// The candidates are the directories containing `pubspec.yaml` up to `MAX_DEPTH` levels under
+/// the current directory, relative to it, such as `.` and `packages/app`. The hidden
+/// directories such as `.dart_tool` and the build outputs are skipped.
+#[derive(Default)]
+pub struct FenvCompleteWorkspacesService;
+
+impl FenvCompleteWorkspacesService {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvCompleteWorkspacesService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        _: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let mut workspaces = vec![];
+        find_workspaces(&context.fenv_dir(), "", 0, &mut workspaces);
+        workspaces.sort();
+        for workspace in workspaces {
+            writeln!(output.stdout(), "{workspace}")?;
+        }
+        anyhow::Ok(())
+    }
+}
+
+/// Collects `dir` into `workspaces` as `relative_path` if it contains `pubspec.yaml`, and then
+/// its subdirectories unless `depth` reaches `MAX_DEPTH`.
+fn find_workspaces(
+    dir: &PathLike,
+    relative_path: &str,
+    depth: usize,
+    workspaces: &mut Vec<String>,
+) {
+    if dir.join("pubspec.yaml").is_file() {
+        workspaces.push(match relative_path {
+            "" => String::from("."),
+            path => path.to_string(),
+        });
+    }
+    if depth >= MAX_DEPTH {
+        return;
+    }
+    let Ok(entries) = dir.read_dir() else {
+        return;
+    };
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let Some(name) = entry.file_name().to_str().map(|name| name.to_owned()) else {
+            continue;
+        };
+        if name.starts_with('.') || IGNORED_DIRS.contains(&name.as_str()) {
+            continue;
+        }
+        if !entry.path().is_dir() {
+            continue;
+        }
+        let relative_path = match relative_path {
+            "" => name.clone(),
+            parent => format!("{parent}/{name}"),
+        };
+        find_workspaces(&dir.join(&name), &relative_path, depth + 1, workspaces);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::FenvContext, sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context, try_run,
+    };
+    use indoc::indoc;
+
+    #[test]
+    fn test_complete_workspaces_containing_pubspec_yaml() {
+        test_with_context(|context, output| {
+            // setup
+            let root = context.fenv_dir();
+            for workspace in [
+                "",
+                "app",
+                "packages/core",
+                "packages/core/example",
+                "packages/core/example/too_deep",
+                ".dart_tool/hidden",
+                "app/build/ignored",
+            ] {
+                root.join(workspace)
+                    .join("pubspec.yaml")
+                    .writeln("name: app")
+                    .unwrap();
+            }
+            root.join("docs").join("README.md").writeln("").unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "__complete-workspaces"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                indoc! {"
+                    .
+                    app
+                    packages/core
+                    packages/core/example
+                "}
+            );
+        })
+    }
+}
@@ -0,0 +1 @@
+pub mod complete_workspaces_service;
@@ -3,9 +3,13 @@ use crate::{
     sdk_service::sdk_service::SdkService, service::service::Service, util::io::ConsoleOutput,
 };
 use anyhow::anyhow;
-use clap::ValueEnum;
+use clap::{Command, ValueEnum};
 use clap_complete::{generate, Shell};
 
+/// The line which `clap_complete` emits at the end to register the bash completion.
+/// `test_clap_bash_completions_end_with_registration` fails if `clap_complete` changes it.
+const CLAP_BASH_REGISTRATION: &str = "complete -F _fenv -o nosort -o bashdefault -o default fenv\n";
+
 pub struct FenvCompletionsService {
     pub args: FenvCompletionsArgs,
 }
@@ -17,11 +21,47 @@ impl FenvCompletionsService {
 
     pub fn completions_commands(shell: &Shell) -> String {
         let mut buffer: Vec<u8> = Vec::new();
-        generate(shell.to_owned(), &mut build_command(), "fenv", &mut buffer);
-        return String::from_utf8_lossy(&buffer).to_string();
+        generate(
+            shell.to_owned(),
+            &mut completion_command(),
+            "fenv",
+            &mut buffer,
+        );
+        let mut completions = String::from_utf8_lossy(&buffer).to_string();
+        if shell == &Shell::Bash {
+            // `bash/versions.txt` registers the completion again, guarding `-o nosort` which
+            // makes `complete` fail on bash older than 4.4.
+            if let Some(function) = completions.strip_suffix(CLAP_BASH_REGISTRATION) {
+                completions = String::from(function);
+            }
+        }
+        let versions_completion = match shell {
+            Shell::Bash => include_str!("bash/versions.txt"),
+            Shell::Zsh => include_str!("zsh/versions.txt"),
+            Shell::Fish => include_str!("fish/versions.txt"),
+            _ => "",
+        };
+        return completions + versions_completion;
     }
 }
 
+/// The command line of `fenv` without the plumbing commands named after `__`, such as
+/// `__resolve-version`, which are never completed. The bash completion of `clap_complete` joins
+/// the path of the subcommands by `__`, and cannot find them.
+fn completion_command() -> Command {
+    let command = build_command();
+    Command::new("fenv")
+        .version(env!("CARGO_PKG_VERSION"))
+        .disable_help_subcommand(command.is_disable_help_subcommand_set())
+        .args(command.get_arguments().cloned())
+        .subcommands(
+            command
+                .get_subcommands()
+                .filter(|subcommand| !subcommand.get_name().starts_with("__"))
+                .cloned(),
+        )
+}
+
 impl<OUT, ERR> Service<OUT, ERR> for FenvCompletionsService
 where
     OUT: std::io::Write,
@@ -42,3 +82,55 @@ where
         .map_err(|e| anyhow!(e))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{completion_command, FenvCompletionsService, CLAP_BASH_REGISTRATION};
+    use clap_complete::{generate, Shell};
+
+    #[test]
+    fn test_clap_bash_completions_end_with_registration() {
+        // execution
+        let mut buffer: Vec<u8> = Vec::new();
+        generate(Shell::Bash, &mut completion_command(), "fenv", &mut buffer);
+
+        // validation
+        let completions = String::from_utf8(buffer).unwrap();
+        assert!(completions.ends_with(CLAP_BASH_REGISTRATION));
+        assert_eq!(completions.matches("complete -F _fenv ").count(), 1);
+    }
+
+    #[test]
+    fn test_completions_exclude_plumbing_commands() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+            // execution
+            let completions = FenvCompletionsService::completions_commands(&shell);
+
+            // validation
+            assert!(!completions.contains("resolve-version"), "{shell}");
+        }
+    }
+
+    #[test]
+    fn test_bash_completions_guard_nosort_for_old_bash() {
+        // execution
+        let completions = FenvCompletionsService::completions_commands(&Shell::Bash);
+
+        // validation
+        let registrations: Vec<&str> = completions
+            .lines()
+            .filter(|line| line.trim_start().starts_with("complete "))
+            .map(|line| line.trim())
+            .collect();
+        assert_eq!(
+            registrations,
+            [
+                "complete -F _fenv_with_versions -o nosort -o bashdefault -o default fenv",
+                "complete -F _fenv_with_versions -o bashdefault -o default fenv",
+            ]
+        );
+        assert!(completions.contains(
+            "if ((BASH_VERSINFO[0] > 4 || BASH_VERSINFO[0] == 4 && BASH_VERSINFO[1] >= 4)); then"
+        ));
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{
+    args::FenvContextArgs, context::FenvContext, sdk_service::sdk_service::SdkService,
+    service::service::Service, util::io::ConsoleOutput,
+};
+
+/// Prints the resolved [`FenvContext`] to show which directories and environment variables
+/// `fenv` picked up, without running with `--debug`.
+pub struct FenvContextService {
+    pub args: FenvContextArgs,
+}
+
+impl FenvContextService {
+    pub fn new(args: FenvContextArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvContextService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        _: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let entries = collect_entries(context);
+        if self.args.json {
+            let object: serde_json::Map<String, serde_json::Value> = entries
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), serde_json::Value::String(value)))
+                .collect();
+            writeln!(
+                output.stdout(),
+                "{}",
+                serde_json::to_string_pretty(&object)?
+            )?;
+        } else {
+            let width = entries.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+            for (key, value) in entries {
+                writeln!(output.stdout(), "{key:width$} {value}")?;
+            }
+        }
+        anyhow::Ok(())
+    }
+}
+
+/// Lists the context as `(key, value)` pairs in the order to be printed.
+fn collect_entries(context: &impl FenvContext) -> Vec<(&'static str, String)> {
+    vec![
+        ("fenv_root", context.fenv_root().to_string()),
+        ("fenv_dir", context.fenv_dir().to_string()),
+        ("home", context.home().to_string()),
+        ("shell", context.default_shell()),
+        ("pub_cache", context.pub_cache().to_string()),
+        ("os", std::env::consts::OS.to_string()),
+        ("arch", std::env::consts::ARCH.to_string()),
+        (
+            "temp_dir",
+            std::env::temp_dir().to_string_lossy().to_string(),
+        ),
+        (
+            "suppresses_deprecations",
+            context.suppresses_deprecations().to_string(),
+        ),
+        (
+            "allows_flutter_channel",
+            context.allows_flutter_channel().to_string(),
+        ),
+        (
+            "ca_bundle",
+            context
+                .ca_bundle()
+                .map(|ca_bundle| ca_bundle.to_string())
+                .unwrap_or_default(),
+        ),
+        ("skips_tls_verify", context.skips_tls_verify().to_string()),
+        ("shell_version", context.shell_version().unwrap_or_default()),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::FenvContext, sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context, try_run,
+    };
+
+    #[test]
+    fn test_context_as_human_text() {
+        test_with_context(|context, output| {
+            // execution
+            try_run(
+                &["fenv", "context"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            let stdout = output.stdout_to_string();
+            let lines: Vec<&str> = stdout.lines().collect();
+            assert_eq!(lines.len(), 13);
+            assert_eq!(
+                lines[0],
+                format!("fenv_root               {}", context.fenv_root())
+            );
+            assert_eq!(
+                lines[4],
+                format!("pub_cache               {}", context.pub_cache())
+            );
+            assert_eq!(lines[8], "suppresses_deprecations false");
+        })
+    }
+
+    #[test]
+    fn test_context_as_json() {
+        test_with_context(|context, output| {
+            // execution
+            try_run(
+                &["fenv", "context", "--json"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            let json: serde_json::Value = serde_json::from_str(&output.stdout_to_string()).unwrap();
+            assert_eq!(json["fenv_dir"], context.fenv_dir().to_string());
+            assert_eq!(json["shell"], context.default_shell());
+            assert_eq!(json["os"], std::env::consts::OS);
+            assert_eq!(json["arch"], std::env::consts::ARCH);
+        })
+    }
+}
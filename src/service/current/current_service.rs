@@ -0,0 +1,204 @@
+use crate::{
+    args::FenvCurrentArgs,
+    context::FenvContext,
+    sdk_service::{
+        model::flutter_sdk::FlutterSdk, results::LookupResult, sdk_service::SdkService,
+        version_file::TOOL_VERSIONS_FILE_NAME,
+    },
+    service::{service::Service, start_dir::StartDir},
+    util::{io::ConsoleOutput, path_like::PathLike},
+};
+use anyhow::{bail, Context};
+
+/// Prints the selected Flutter SDK version with the kind of its source, such as
+/// `3.19.6 (set by `/repo/.flutter-version`, local)`.
+///
+/// The sources are listed in the order of precedence: `shell` (`$FENV_VERSION`), `local`
+/// (`.flutter-version`), `asdf` (`.tool-versions`), and `global` (`{fenv_root}/version`).
+/// Never accesses the remote repository.
+pub struct FenvCurrentService {
+    pub args: FenvCurrentArgs,
+}
+
+impl FenvCurrentService {
+    pub fn new(args: FenvCurrentArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvCurrentService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let start_dir = self.args.start_dir.resolve_start_dir(context);
+        let (prefix, origin, source) = match context.shell_version() {
+            Some(prefix) => (prefix, String::from("$FENV_VERSION"), "shell"),
+            None => {
+                let path_to_version_file =
+                    match sdk_service.find_nearest_version_file(context, &start_dir) {
+                        LookupResult::Found(path) => path,
+                        LookupResult::Err(err) => return Err(err),
+                        LookupResult::None => bail!("Could not find a version file"),
+                    };
+                let prefix = sdk_service
+                    .read_version_prefix(&path_to_version_file)
+                    .with_context(|| {
+                        format!("Could not read the version file (set by `{path_to_version_file}`)")
+                    })?;
+                let source = source_of(context, &path_to_version_file);
+                (prefix, path_to_version_file.to_string(), source)
+            }
+        };
+        let installed = match sdk_service.find_latest_local(context, &prefix) {
+            LookupResult::Found(sdk) => Some(sdk.display_name()),
+            LookupResult::Err(err) => return Err(err),
+            LookupResult::None => None,
+        };
+
+        if self.args.json {
+            let path = installed
+                .as_ref()
+                .map(|name| context.fenv_sdk_root(name).to_string());
+            let object = serde_json::json!({
+                "version": installed.as_deref().unwrap_or(&prefix),
+                "prefix": prefix,
+                "source": source,
+                "origin": origin,
+                "installed": installed.is_some(),
+                "path": path,
+            });
+            writeln!(
+                output.stdout(),
+                "{}",
+                serde_json::to_string_pretty(&object)?
+            )?;
+        } else if let Some(version) = installed {
+            writeln!(output.stdout(), "{version} (set by `{origin}`, {source})")?;
+        } else {
+            writeln!(
+                output.stdout(),
+                "{prefix} (set by `{origin}`, {source}, not installed)"
+            )?;
+        }
+        anyhow::Ok(())
+    }
+}
+
+/// The kind of the given version file: `global`, `asdf`, or `local`.
+fn source_of(context: &impl FenvContext, path_to_version_file: &PathLike) -> &'static str {
+    if *path_to_version_file == context.fenv_global_version_file() {
+        "global"
+    } else if path_to_version_file.path().file_name()
+        == Some(std::ffi::OsStr::new(TOOL_VERSIONS_FILE_NAME))
+    {
+        "asdf"
+    } else {
+        "local"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::FenvContext, sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context, try_run,
+    };
+
+    #[test]
+    fn test_current_of_local_version() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("3.19.6")
+                .create_dir_all()
+                .unwrap();
+            let version_file = context.fenv_dir().join(".flutter-version");
+            version_file.writeln("3.19").unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "current"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                format!("3.19.6 (set by `{version_file}`, local)\n")
+            );
+        })
+    }
+
+    #[test]
+    fn test_current_of_uninstalled_global_version() {
+        test_with_context(|context, output| {
+            // setup
+            context.fenv_global_version_file().writeln("3.22").unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "current"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                format!(
+                    "3.22 (set by `{}`, global, not installed)\n",
+                    context.fenv_global_version_file()
+                )
+            );
+        })
+    }
+
+    #[test]
+    fn test_current_of_shell_version_as_json() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("stable")
+                .create_dir_all()
+                .unwrap();
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("3.19")
+                .unwrap();
+            let context = &context.clone().with_shell_version("s");
+
+            // execution
+            try_run(
+                &["fenv", "current", "--json"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            let json: serde_json::Value = serde_json::from_str(&output.stdout_to_string()).unwrap();
+            assert_eq!(json["version"], "stable");
+            assert_eq!(json["prefix"], "s");
+            assert_eq!(json["source"], "shell");
+            assert_eq!(json["origin"], "$FENV_VERSION");
+            assert_eq!(json["installed"], true);
+            assert_eq!(json["path"], context.fenv_sdk_root("stable").to_string());
+        })
+    }
+}
@@ -0,0 +1,185 @@
+use super::doctor_service::Finding;
+use crate::{context::FenvContext, util::path_like::PathLike};
+use is_executable::is_executable;
+use std::collections::HashSet;
+
+/// The locations where the system-wide Flutter SDKs are commonly installed.
+const SYSTEM_FLUTTER_LOCATIONS: [&str; 4] = [
+    "/snap/bin/flutter",
+    "/usr/bin/flutter",
+    "/usr/local/bin/flutter",
+    "/opt/flutter/bin/flutter",
+];
+
+/// Finds the other Flutter version managers and the system-wide Flutter SDKs which may be
+/// used instead of the Flutter SDK selected by `fenv`.
+pub fn find_conflicting_managers(context: &impl FenvContext) -> Vec<Finding> {
+    find_conflicts(context, &SYSTEM_FLUTTER_LOCATIONS)
+}
+
+fn find_conflicts(context: &impl FenvContext, system_locations: &[&str]) -> Vec<Finding> {
+    let mut findings = vec![];
+    let mut reported: HashSet<String> = HashSet::new();
+
+    // `flutter` on `$PATH` which precedes the shims wins over `fenv`. Skipped if the shims are
+    // not on `$PATH` at all, which `check_shims` reports instead.
+    let shims = context.fenv_shims();
+    let path = context.path();
+    let path = if path.contains(&shims) { path } else { vec![] };
+    for dir in path {
+        if dir == shims {
+            break;
+        }
+        let flutter = dir.join("flutter");
+        if is_executable(&flutter) && reported.insert(flutter.to_string()) {
+            findings.push(Finding::new(
+                format!(
+                    "`flutter` of {} precedes the shims of fenv on PATH: `{flutter}`",
+                    manager_of(&flutter.to_string())
+                ),
+                format!("Move `{shims}` before `{dir}` on PATH, or uninstall it"),
+            ));
+        }
+    }
+
+    for location in system_locations {
+        let flutter = PathLike::from(*location);
+        if is_executable(&flutter) && reported.insert(flutter.to_string()) {
+            let manager = manager_of(location);
+            let resolution = if manager == "snap" {
+                String::from("Uninstall it by `sudo snap remove flutter`")
+            } else {
+                format!("Uninstall it, or make sure `{shims}` precedes `{flutter}` on PATH")
+            };
+            findings.push(Finding::new(
+                format!("Flutter of {manager} is installed: `{flutter}`"),
+                resolution,
+            ));
+        }
+    }
+
+    let home = context.home();
+    for fvm_dir in [home.join("fvm"), home.join(".fvm")] {
+        if fvm_dir.is_dir() {
+            findings.push(Finding::new(
+                format!("FVM is installed: `{fvm_dir}`"),
+                String::from(
+                    "Uninstall FVM, or do not run `fvm use` in the projects managed by fenv",
+                ),
+            ));
+        }
+    }
+
+    let asdf_plugin = home.join(".asdf").join("plugins").join("flutter");
+    if asdf_plugin.is_dir() {
+        findings.push(Finding::new(
+            format!("The flutter plugin of asdf is installed: `{asdf_plugin}`"),
+            String::from(
+                "Remove `flutter` from `.tool-versions` and run `asdf plugin remove flutter`",
+            ),
+        ));
+    }
+    findings
+}
+
+/// Guesses who installed the given `flutter` executable from its path.
+fn manager_of(path: &str) -> &'static str {
+    if path.starts_with("/snap/") {
+        "snap"
+    } else if path.contains("/.asdf/") {
+        "asdf"
+    } else if path.contains("/fvm/") || path.contains("/.fvm/") {
+        "FVM"
+    } else {
+        "the system"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_conflicts, manager_of};
+    use crate::{
+        context::FenvContext, service::macros::test_with_context, util::path_like::PathLike,
+    };
+    use std::os::unix::prelude::PermissionsExt;
+
+    fn create_executable(path: &PathLike) {
+        path.writeln("").unwrap();
+        let mut permissions = path.path().metadata().unwrap().permissions();
+        permissions.set_mode(0o755);
+        std::fs::set_permissions(path, permissions).unwrap();
+    }
+
+    #[test]
+    fn test_manager_of() {
+        assert_eq!(manager_of("/snap/bin/flutter"), "snap");
+        assert_eq!(manager_of("/home/user/.asdf/shims/flutter"), "asdf");
+        assert_eq!(manager_of("/home/user/fvm/default/bin/flutter"), "FVM");
+        assert_eq!(manager_of("/usr/local/bin/flutter"), "the system");
+    }
+
+    #[test]
+    fn test_find_no_conflict() {
+        test_with_context(|context, _| {
+            // setup
+            create_executable(&context.fenv_shims().join("flutter"));
+            let context = context.clone().with_path(&[context.fenv_shims()]);
+
+            // execution & validation
+            assert!(find_conflicts(&context, &[]).is_empty());
+        })
+    }
+
+    #[test]
+    fn test_find_no_preceding_flutter_if_shims_are_not_on_path() {
+        test_with_context(|context, _| {
+            // setup
+            let bin = context.home().join("bin");
+            create_executable(&bin.join("flutter"));
+            let context = context.clone().with_path(&[bin]);
+
+            // execution & validation
+            assert!(find_conflicts(&context, &[]).is_empty());
+        })
+    }
+
+    #[test]
+    fn test_find_conflicts() {
+        test_with_context(|context, _| {
+            // setup
+            let home = context.home();
+            let fvm_bin = home.join("fvm").join("default").join("bin");
+            let system_flutter = home.join("usr").join("bin").join("flutter");
+            create_executable(&fvm_bin.join("flutter"));
+            create_executable(&system_flutter);
+            home.join(".asdf/plugins/flutter").create_dir_all().unwrap();
+            let context = context.clone().with_path(&[
+                fvm_bin.clone(),
+                context.fenv_shims(),
+                system_flutter.parent().unwrap(),
+            ]);
+            let system_location = system_flutter.to_string();
+
+            // execution
+            let findings = find_conflicts(&context, &[&system_location]);
+
+            // validation
+            let problems: Vec<&str> = findings.iter().map(|f| f.problem.as_str()).collect();
+            assert_eq!(
+                problems,
+                [
+                    format!(
+                        "`flutter` of FVM precedes the shims of fenv on PATH: `{}`",
+                        fvm_bin.join("flutter")
+                    ),
+                    format!("Flutter of the system is installed: `{system_flutter}`"),
+                    format!("FVM is installed: `{}`", home.join("fvm")),
+                    format!(
+                        "The flutter plugin of asdf is installed: `{}`",
+                        home.join(".asdf/plugins/flutter")
+                    ),
+                ]
+            );
+        })
+    }
+}
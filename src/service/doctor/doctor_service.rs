@@ -0,0 +1,117 @@
+use super::{
+    conflicting_managers::find_conflicting_managers, installation::check_installation,
+    workspaces::check_workspaces,
+};
+use crate::{
+    context::FenvContext,
+    sdk_service::sdk_service::SdkService,
+    service::service::Service,
+    util::io::{ConsoleOutput, SilentFailure},
+};
+
+/// A problem found by `fenv doctor` and how to resolve it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub problem: String,
+    pub resolution: String,
+}
+
+impl Finding {
+    pub fn new(problem: String, resolution: String) -> Self {
+        Self {
+            problem,
+            resolution,
+        }
+    }
+}
+
+/// Diagnoses the environment where `fenv` runs and reports the problems with their
+/// resolutions. Fails with the exit code 1 if any problem is found.
+#[derive(Default)]
+pub struct FenvDoctorService;
+
+impl FenvDoctorService {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvDoctorService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let mut findings = check_installation(context, sdk_service);
+        findings.extend(find_conflicting_managers(context));
+        findings.extend(check_workspaces(context, sdk_service));
+        if findings.is_empty() {
+            writeln!(output.stdout(), "No problem found")?;
+            return anyhow::Ok(());
+        }
+        for finding in &findings {
+            writeln!(output.stdout(), "[!] {}", finding.problem)?;
+            writeln!(output.stdout(), "    {}", finding.resolution)?;
+        }
+        writeln!(output.stdout(), "{} problem(s) found", findings.len())?;
+        Err(SilentFailure(1).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::FenvContext, sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context, try_run, util::io::SilentFailure,
+    };
+    use indoc::{formatdoc, indoc};
+
+    #[test]
+    fn test_doctor_reports_conflicting_managers() {
+        test_with_context(|context, output| {
+            // setup
+            context.home().join(".fvm").create_dir_all().unwrap();
+
+            // execution
+            let err =
+                try_run(&["fenv", "doctor"], context, &RealSdkService::new(), output).unwrap_err();
+
+            // validation
+            assert_eq!(err.downcast_ref::<SilentFailure>().unwrap().0, 1);
+            // The system-wide Flutter SDKs of the machine running this test may be reported too.
+            assert!(output.stdout_to_string().contains(&formatdoc! {"
+                [!] FVM is installed: `{fvm}`
+                    Uninstall FVM, or do not run `fvm use` in the projects managed by fenv
+                ",
+                fvm = context.home().join(".fvm"),
+            }));
+        })
+    }
+
+    #[test]
+    fn test_doctor_reports_deprecated_usages() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_cache()
+                .join("deprecated_usages")
+                .writeln("fenv local --symlink")
+                .unwrap();
+
+            // execution
+            let result = try_run(&["fenv", "doctor"], context, &RealSdkService::new(), output);
+
+            // validation
+            assert!(result.is_err());
+            assert!(output.stdout_to_string().contains(indoc! {"
+                [!] `fenv local --symlink` is used, which is deprecated
+                    For IDE support, use `fenv workspace` instead.
+                "}));
+        })
+    }
+}
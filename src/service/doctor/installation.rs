@@ -0,0 +1,311 @@
+use super::doctor_service::Finding;
+use crate::{
+    context::FenvContext,
+    deprecation::{recorded_usages, DEPRECATIONS},
+    sdk_service::{
+        model::local_flutter_sdk::LocalFlutterSdk, results::LookupResult, sdk_service::SdkService,
+    },
+    service::rehash::rehash_service::SHIMS,
+};
+use is_executable::is_executable;
+
+/// The prefix of the markers which `fenv install` leaves in `{fenv_versions}` while installing.
+const INSTALLING_MARKER_PREFIX: &str = ".install_";
+
+/// Checks the health of the installation of `fenv` itself and the Flutter SDKs.
+pub fn check_installation(
+    context: &impl FenvContext,
+    sdk_service: &impl SdkService,
+) -> Vec<Finding> {
+    let mut findings = vec![];
+    findings.extend(check_root_layout(context));
+    findings.extend(check_shims(context));
+    findings.extend(check_git(context));
+    findings.extend(check_selected_version(context, sdk_service));
+    findings.extend(check_installed_sdks(context, sdk_service));
+    findings.extend(check_deprecated_usages(context));
+    findings
+}
+
+fn check_root_layout(context: &impl FenvContext) -> Vec<Finding> {
+    let fenv_root = context.fenv_root();
+    if !fenv_root.is_dir() {
+        return vec![Finding::new(
+            format!("`$FENV_ROOT` does not exist: `{fenv_root}`"),
+            String::from("Install fenv again, or set `$FENV_ROOT` to the right directory"),
+        )];
+    }
+    [context.fenv_versions(), context.fenv_shims()]
+        .into_iter()
+        .filter(|dir| dir.exists() && !dir.is_dir())
+        .map(|dir| {
+            Finding::new(
+                format!("Not a directory: `{dir}`"),
+                format!("Remove `{dir}`, which fenv uses as a directory"),
+            )
+        })
+        .collect()
+}
+
+fn check_shims(context: &impl FenvContext) -> Vec<Finding> {
+    let shims = context.fenv_shims();
+    let mut findings = vec![];
+    if !SHIMS
+        .iter()
+        .all(|(name, _)| is_executable(shims.join(name)))
+    {
+        findings.push(Finding::new(
+            format!("The shims are not placed in `{shims}`"),
+            String::from("Run `fenv rehash`"),
+        ));
+    }
+    if !context.path().contains(&shims) {
+        findings.push(Finding::new(
+            format!("`{shims}` is not on PATH"),
+            String::from("Follow the instructions of `fenv init` and restart your shell"),
+        ));
+    }
+    findings
+}
+
+fn check_git(context: &impl FenvContext) -> Vec<Finding> {
+    if context
+        .path()
+        .iter()
+        .any(|dir| is_executable(dir.join("git")))
+    {
+        return vec![];
+    }
+    vec![Finding::new(
+        String::from("`git` is not found on PATH"),
+        String::from("Install git, which fenv uses to install the Flutter SDKs"),
+    )]
+}
+
+fn check_selected_version(
+    context: &impl FenvContext,
+    sdk_service: &impl SdkService,
+) -> Vec<Finding> {
+    let (version_prefix, origin) = match context.shell_version() {
+        Some(version_prefix) => (version_prefix, String::from("`$FENV_VERSION`")),
+        None => match sdk_service.find_nearest_version_file(context, &context.fenv_dir()) {
+            LookupResult::Found(path) => match sdk_service.read_version_prefix(&path) {
+                Ok(version_prefix) => (version_prefix, format!("`{path}`")),
+                Err(err) => {
+                    return vec![Finding::new(
+                        format!("Could not read the version file `{path}`: {err}"),
+                        format!("Fix `{path}`, or run `fenv local <VERSION>` to rewrite it"),
+                    )]
+                }
+            },
+            LookupResult::Err(err) => {
+                return vec![Finding::new(
+                    format!("Could not find a version file: {err}"),
+                    String::from("Run `fenv global <VERSION>`"),
+                )]
+            }
+            LookupResult::None => {
+                return vec![Finding::new(
+                    String::from("Neither a local version file nor the global version file exists"),
+                    String::from("Run `fenv global <VERSION>`"),
+                )]
+            }
+        },
+    };
+    match sdk_service.find_latest_local(context, &version_prefix) {
+        LookupResult::Found(_) => vec![],
+        LookupResult::Err(err) => vec![Finding::new(
+            format!("Could not resolve `{version_prefix}` (set by {origin}): {err}"),
+            String::from("Run `fenv versions` to check the installed Flutter SDKs"),
+        )],
+        LookupResult::None => vec![Finding::new(
+            format!("`{version_prefix}` (set by {origin}) is not installed"),
+            format!("Run `fenv install {version_prefix}`"),
+        )],
+    }
+}
+
+fn check_installed_sdks(context: &impl FenvContext, sdk_service: &impl SdkService) -> Vec<Finding> {
+    let mut findings: Vec<Finding> = match sdk_service.find_broken_installations(context) {
+        // The markers whose installation directory has gone are reported below.
+        Ok(names) => names
+            .into_iter()
+            .filter(|name| context.fenv_sdk_root(name).exists())
+            .map(|name| {
+                Finding::new(
+                    format!(
+                        "The installation of `{name}` is incomplete or does not have `bin/flutter`"
+                    ),
+                    format!("Run `fenv uninstall --broken && fenv install {name}`"),
+                )
+            })
+            .collect(),
+        Err(err) => vec![Finding::new(
+            format!("Could not check the installed Flutter SDKs: {err}"),
+            format!("Make sure `{}` is readable", context.fenv_versions()),
+        )],
+    };
+
+    let versions = context.fenv_versions();
+    let Ok(children) = versions.read_dir() else {
+        return findings;
+    };
+    let mut names: Vec<String> = children
+        .flatten()
+        .filter_map(|child| child.file_name().to_str().map(|s| s.to_owned()))
+        .collect();
+    names.sort();
+    // A stray file is not listed as installed, but blocks installing the version of its name.
+    findings.extend(
+        names
+            .iter()
+            .filter(|name| !versions.join(name).is_dir() && LocalFlutterSdk::parse(name).is_ok())
+            .map(|name| {
+                let path = versions.join(name);
+                Finding::new(
+                    format!("`{path}` is not a directory, which blocks installing `{name}`"),
+                    format!("Remove it by `rm {path}`"),
+                )
+            }),
+    );
+    findings.extend(
+        names
+            .iter()
+            .filter(|name| {
+                name.strip_prefix(INSTALLING_MARKER_PREFIX)
+                    .is_some_and(|sdk_name| !versions.join(sdk_name).exists())
+            })
+            .map(|marker| {
+                Finding::new(
+                    format!("An installing marker is left: `{}`", versions.join(marker)),
+                    String::from("Run `fenv uninstall --broken`"),
+                )
+            }),
+    );
+    findings
+}
+
+fn check_deprecated_usages(context: &impl FenvContext) -> Vec<Finding> {
+    recorded_usages(context)
+        .into_iter()
+        .filter_map(|usage| {
+            DEPRECATIONS
+                .iter()
+                .find(|deprecation| deprecation.usage() == usage)
+        })
+        .map(|deprecation| {
+            Finding::new(
+                format!("`{}` is used, which is deprecated", deprecation.usage()),
+                String::from(deprecation.advice),
+            )
+        })
+        .collect()
+}
+
+#[cfg(unix)]
+#[cfg(test)]
+mod tests_unix {
+    use super::{check_git, check_installed_sdks, check_selected_version, check_shims};
+    use crate::{
+        context::FenvContext, sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context,
+    };
+    use std::os::unix::prelude::PermissionsExt;
+
+    fn problems_of(findings: Vec<super::Finding>) -> Vec<String> {
+        findings
+            .into_iter()
+            .map(|finding| finding.problem)
+            .collect()
+    }
+
+    #[test]
+    fn test_check_shims() {
+        test_with_context(|context, _| {
+            // setup
+            let context = context.clone().with_path(&[]);
+
+            // execution & validation
+            assert_eq!(
+                problems_of(check_shims(&context)),
+                [
+                    format!("The shims are not placed in `{}`", context.fenv_shims()),
+                    format!("`{}` is not on PATH", context.fenv_shims()),
+                ]
+            );
+        })
+    }
+
+    #[test]
+    fn test_check_git() {
+        test_with_context(|context, _| {
+            // setup
+            let bin = context.home().join("bin");
+            let git = bin.join("git");
+            git.writeln("").unwrap();
+            std::fs::set_permissions(&git, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+            // execution & validation
+            assert_eq!(
+                problems_of(check_git(&context.clone().with_path(&[]))),
+                ["`git` is not found on PATH"]
+            );
+            assert!(check_git(&context.clone().with_path(&[bin])).is_empty());
+        })
+    }
+
+    #[test]
+    fn test_check_selected_version() {
+        test_with_context(|context, _| {
+            // setup
+            let sdk_service = RealSdkService::new();
+            context.fenv_global_version_file().writeln("3.7").unwrap();
+
+            // execution & validation
+            assert_eq!(
+                problems_of(check_selected_version(context, &sdk_service)),
+                [format!(
+                    "`3.7` (set by `{}`) is not installed",
+                    context.fenv_global_version_file()
+                )]
+            );
+            context
+                .fenv_versions()
+                .join("3.7.12")
+                .create_dir_all()
+                .unwrap();
+            assert!(check_selected_version(context, &sdk_service).is_empty());
+        })
+    }
+
+    #[test]
+    fn test_check_installed_sdks() {
+        test_with_context(|context, _| {
+            // setup
+            let versions = context.fenv_versions();
+            versions.join("3.7.12/bin/flutter").writeln("").unwrap();
+            versions.join("3.10.0").create_dir_all().unwrap();
+            versions.join(".install_3.13.0").writeln("").unwrap();
+            versions.join("3.19.0").writeln("").unwrap();
+            versions.join("notes.txt").writeln("").unwrap();
+
+            // execution & validation
+            assert_eq!(
+                problems_of(check_installed_sdks(context, &RealSdkService::new())),
+                [
+                    String::from(
+                        "The installation of `3.10.0` is incomplete or does not have `bin/flutter`"
+                    ),
+                    format!(
+                        "`{}` is not a directory, which blocks installing `3.19.0`",
+                        versions.join("3.19.0")
+                    ),
+                    format!(
+                        "An installing marker is left: `{}`",
+                        versions.join(".install_3.13.0")
+                    ),
+                ]
+            );
+        })
+    }
+}
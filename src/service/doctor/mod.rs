@@ -0,0 +1,4 @@
+pub mod conflicting_managers;
+pub mod doctor_service;
+pub mod installation;
+pub mod workspaces;
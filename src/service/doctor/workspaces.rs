@@ -0,0 +1,114 @@
+use super::doctor_service::Finding;
+use crate::{
+    context::FenvContext, sdk_service::sdk_service::SdkService,
+    service::workspace::workspace_service::find_stale_files, workspaces::recorded_workspaces,
+};
+use log::debug;
+
+/// Checks that the IDE files generated in the workspaces recorded by `fenv workspace` refer to
+/// the Flutter SDK which the version file of each workspace selects now.
+///
+/// The workspaces whose Flutter SDK cannot be resolved are skipped, because running
+/// `fenv workspace` there fails anyway.
+pub fn check_workspaces(context: &impl FenvContext, sdk_service: &impl SdkService) -> Vec<Finding> {
+    let mut findings = vec![];
+    for workspace in recorded_workspaces(context) {
+        let read_result = sdk_service.read_nearest_version_file(context, &workspace);
+        let summary = match sdk_service.ensure_sdk_is_available(&read_result) {
+            Ok(summary) => summary,
+            Err(e) => {
+                debug!("check_workspaces(): Skipped `{workspace}`: {e}");
+                continue;
+            }
+        };
+        let stale_files = find_stale_files(&workspace, &summary.path_to_sdk_root, &context.home());
+        findings.extend(stale_files.into_iter().map(|file| {
+            Finding::new(
+                format!(
+                    "`{file}` does not refer to `{}` (set by `{}`)",
+                    summary.latest_local_sdk, summary.path_to_version_file
+                ),
+                format!("Run `fenv workspace --force {workspace}`"),
+            )
+        }));
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_workspaces;
+    use crate::{
+        args::FenvWorkspaceArgs,
+        context::FenvContext,
+        sdk_service::sdk_service::RealSdkService,
+        service::{
+            macros::test_with_context, service::Service,
+            workspace::workspace_service::FenvWorkspaceService,
+        },
+        util::path_like::PathLike,
+    };
+
+    fn prepare_dart_sdk_lib(sdk_root: &PathLike) {
+        sdk_root
+            .join("bin")
+            .join("cache")
+            .join("dart-sdk")
+            .join("lib")
+            .join("core")
+            .create_dir_all()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_check_workspaces_reports_files_of_previous_version() {
+        test_with_context(|context, output| {
+            // setup
+            prepare_dart_sdk_lib(&context.fenv_sdk_root("3.19.6"));
+            prepare_dart_sdk_lib(&context.fenv_sdk_root("3.22.2"));
+            let workspace = context.fenv_dir().join("app");
+            workspace.join("pubspec.yaml").writeln("name: app").unwrap();
+            workspace.join(".flutter-version").writeln("3.19").unwrap();
+            let sdk_service = RealSdkService::new();
+            FenvWorkspaceService::new(FenvWorkspaceArgs {
+                workspace: workspace.to_string(),
+                prefix: None,
+                should_pub_get: false,
+                force: false,
+                emit_path_file: None,
+                clean: false,
+            })
+            .execute(context, &sdk_service, output)
+            .unwrap();
+            assert!(check_workspaces(context, &sdk_service).is_empty());
+
+            // execution
+            workspace.join(".flutter-version").writeln("3.22").unwrap();
+            let findings = check_workspaces(context, &sdk_service);
+
+            // validation
+            let workspace = PathLike::from(&std::fs::canonicalize(&workspace).unwrap());
+            let problems: Vec<String> = findings
+                .iter()
+                .map(|finding| finding.problem.clone())
+                .collect();
+            assert_eq!(
+                problems,
+                [
+                    format!(
+                        "`{workspace}/.dart_tool/package_config.json` does not refer to `3.22.2` \
+                        (set by `{workspace}/.flutter-version`)"
+                    ),
+                    format!(
+                        "`{workspace}/.idea/libraries/Dart_SDK.xml` does not refer to `3.22.2` \
+                        (set by `{workspace}/.flutter-version`)"
+                    ),
+                ]
+            );
+            assert_eq!(
+                findings[0].resolution,
+                format!("Run `fenv workspace --force {workspace}`")
+            );
+        })
+    }
+}
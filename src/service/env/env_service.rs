@@ -0,0 +1,264 @@
+use crate::{
+    args::FenvEnvArgs,
+    context::FenvContext,
+    sdk_service::sdk_service::SdkService,
+    service::{
+        init::init_service::detect_shell,
+        service::Service,
+        shell::shell_service::{quote_fish, quote_posix},
+        start_dir::StartDir,
+        version_name::version_name_service::resolve_selected_sdk,
+    },
+    util::{io::ConsoleOutput, path_like::PathLike},
+};
+use anyhow::Context;
+
+/// Prints the environment variables of the Flutter SDK selected by `$FENV_VERSION` or the
+/// nearest version file, so that the scripts such as Makefiles and CI steps can use the Flutter
+/// SDK without the shims.
+///
+/// `FLUTTER_ROOT`, `DART_SDK`, and `PUB_CACHE` are exported, and the `bin` directories of the
+/// Flutter SDK, the Dart SDK, and `PUB_CACHE` are prepended to `PATH`.
+pub struct FenvEnvService {
+    pub args: FenvEnvArgs,
+}
+
+impl FenvEnvService {
+    pub fn new(args: FenvEnvArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvEnvService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let start_dir = self.args.start_dir.resolve_start_dir(context);
+        let selected = resolve_selected_sdk(context, sdk_service, output, &start_dir)?;
+
+        let sdk_root = &context.fenv_sdk_root(&selected.sdk.to_string());
+        let dart_sdk = sdk_root.join("bin").join("cache").join("dart-sdk");
+        let pub_cache = context.pub_cache();
+        let variables = [
+            ("FLUTTER_ROOT", sdk_root.to_string()),
+            ("DART_SDK", dart_sdk.to_string()),
+            ("PUB_CACHE", pub_cache.to_string()),
+        ];
+        let path: Vec<PathLike> = vec![
+            sdk_root.join("bin"),
+            dart_sdk.join("bin"),
+            pub_cache.join("bin"),
+        ];
+
+        if self.args.json {
+            let mut object: serde_json::Map<String, serde_json::Value> = variables
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), serde_json::Value::String(value)))
+                .collect();
+            object.insert(
+                String::from("PATH"),
+                path.iter()
+                    .map(|dir| serde_json::Value::String(dir.to_string()))
+                    .collect(),
+            );
+            writeln!(
+                output.stdout(),
+                "{}",
+                serde_json::to_string_pretty(&object)?
+            )?;
+            return anyhow::Ok(());
+        }
+
+        let shell = match &self.args.shell {
+            Some(shell) => String::from(shell),
+            None => detect_shell(context).context("Failed to detect the current shell")?,
+        };
+        if shell == "fish" {
+            for (key, value) in &variables {
+                writeln!(output.stdout(), "set -gx {key} {}", quote_fish(value))?;
+            }
+            let dirs: Vec<String> = path
+                .iter()
+                .map(|dir| quote_fish(&dir.to_string()))
+                .collect();
+            writeln!(output.stdout(), "set -gx PATH {} $PATH", dirs.join(" "))?;
+        } else {
+            for (key, value) in &variables {
+                writeln!(output.stdout(), "export {key}={}", quote_posix(value))?;
+            }
+            let dirs: Vec<String> = path.iter().map(|dir| dir.to_string()).collect();
+            writeln!(
+                output.stdout(),
+                "export PATH={}:\"$PATH\"",
+                quote_posix(&dirs.join(":"))
+            )?;
+        }
+        anyhow::Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::FenvContext, sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context, try_run,
+    };
+    use indoc::formatdoc;
+
+    fn install_sdk(context: &impl FenvContext) {
+        context
+            .fenv_sdk_root("3.22.2")
+            .join("bin")
+            .join("flutter")
+            .writeln("")
+            .unwrap();
+        context
+            .fenv_dir()
+            .join(".flutter-version")
+            .writeln("3.22")
+            .unwrap();
+    }
+
+    #[test]
+    fn test_env_for_bash() {
+        test_with_context(|context, output| {
+            // setup
+            install_sdk(context);
+
+            // execution
+            try_run(
+                &["fenv", "env", "--shell", "bash"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            let sdk_root = context.fenv_sdk_root("3.22.2");
+            let pub_cache = context.pub_cache();
+            assert_eq!(
+                output.stdout_to_string(),
+                formatdoc! {"
+                    export FLUTTER_ROOT='{sdk_root}'
+                    export DART_SDK='{sdk_root}/bin/cache/dart-sdk'
+                    export PUB_CACHE='{pub_cache}'
+                    export PATH='{sdk_root}/bin:{sdk_root}/bin/cache/dart-sdk/bin:{pub_cache}/bin':\"$PATH\"
+                "}
+            );
+        })
+    }
+
+    #[test]
+    fn test_env_selects_fenv_version() {
+        test_with_context(|context, output| {
+            // setup
+            install_sdk(context);
+            context
+                .fenv_sdk_root("3.19.6")
+                .join("bin")
+                .join("flutter")
+                .writeln("")
+                .unwrap();
+            let context = context.clone().with_shell_version("3.19");
+
+            // execution
+            try_run(
+                &["fenv", "env", "--json"],
+                &context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            let env: serde_json::Value = serde_json::from_str(&output.stdout_to_string()).unwrap();
+            assert_eq!(
+                env["FLUTTER_ROOT"],
+                context.fenv_sdk_root("3.19.6").to_string()
+            );
+        })
+    }
+
+    #[test]
+    fn test_env_for_fish() {
+        test_with_context(|context, output| {
+            // setup
+            install_sdk(context);
+
+            // execution
+            try_run(
+                &["fenv", "env", "--shell", "fish"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            let sdk_root = context.fenv_sdk_root("3.22.2");
+            let pub_cache = context.pub_cache();
+            let stdout = output.stdout_to_string();
+            assert!(stdout.starts_with(&format!("set -gx FLUTTER_ROOT '{sdk_root}'\n")));
+            assert!(stdout.ends_with(&format!(
+                "set -gx PATH '{sdk_root}/bin' '{sdk_root}/bin/cache/dart-sdk/bin' \
+                '{pub_cache}/bin' $PATH\n"
+            )));
+        })
+    }
+
+    #[test]
+    fn test_env_as_json() {
+        test_with_context(|context, output| {
+            // setup
+            install_sdk(context);
+
+            // execution
+            try_run(
+                &["fenv", "env", "--json"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            let sdk_root = context.fenv_sdk_root("3.22.2");
+            let json: serde_json::Value = serde_json::from_str(&output.stdout_to_string()).unwrap();
+            assert_eq!(json["FLUTTER_ROOT"], sdk_root.to_string());
+            assert_eq!(json["PUB_CACHE"], context.pub_cache().to_string());
+            assert_eq!(json["PATH"][0], sdk_root.join("bin").to_string());
+        })
+    }
+
+    #[test]
+    fn test_env_fails_if_selected_sdk_is_not_installed() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("3.22")
+                .unwrap();
+
+            // execution
+            let result = try_run(
+                &["fenv", "env", "--shell", "bash"],
+                context,
+                &RealSdkService::new(),
+                output,
+            );
+
+            // validation
+            assert!(result.is_err());
+            assert!(output.stdout_to_string().is_empty());
+        })
+    }
+}
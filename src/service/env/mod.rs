@@ -0,0 +1 @@
+pub mod env_service;
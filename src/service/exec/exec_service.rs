@@ -0,0 +1,466 @@
+use crate::{
+    args::{FenvCheckFlutterArgsArgs, FenvExecArgs},
+    config::{FenvConfig, FenvProjectConfig},
+    context::FenvContext,
+    sdk_service::{
+        model::local_flutter_sdk::LocalFlutterSdk, results::BrokenInstallation,
+        sdk_service::SdkService,
+    },
+    service::{
+        check_flutter_args::check_flutter_args_service::FenvCheckFlutterArgsService,
+        rehash::rehash_service::heal_shims, service::Service, start_dir::StartDir,
+        version_name::version_name_service::resolve_selected_sdk,
+    },
+    util::{
+        io::{ConsoleOutput, SilentFailure},
+        path_like::PathLike,
+    },
+};
+use anyhow::{bail, Context};
+use log::debug;
+use std::{path::Path, process::Command};
+
+/// Runs the given command with the Flutter SDK selected by `$FENV_VERSION` or the nearest
+/// version file, without the shims.
+///
+/// `{sdk_root}/bin` and `{sdk_root}/bin/cache/dart-sdk/bin` are prepended to `PATH`, and the
+/// arguments configured by `argsPrepend` of `config.json` are prepended as the shims do.
+/// The command inherits the standard I/O, and its exit code becomes the exit code of `fenv`.
+/// `flutter` is guarded as the shims do, see [`resolve_sdk_root_to_run`].
+///
+/// `[env]` of the nearest `.fenv.toml` from the start directory is set to the command, and
+/// `--env` takes precedence over it.
+///
+/// `--cwd`, `--env`, and `--clean-env` let automation run the command in a hermetic environment
+/// without wrapper scripts.
+pub struct FenvExecService {
+    pub args: FenvExecArgs,
+}
+
+impl FenvExecService {
+    pub fn new(args: FenvExecArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvExecService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let cwd = match &self.args.cwd {
+            Some(cwd) => context.fenv_dir().join(cwd),
+            None => context.fenv_dir(),
+        };
+        if !cwd.is_dir() {
+            bail!("Not a directory: `{cwd}`")
+        }
+        let envs = parse_envs(&self.args.envs)?;
+        let start_dir = match self.args.start_dir.start_dir() {
+            Some(_) => self.args.start_dir.resolve_start_dir(context),
+            None => cwd.clone(),
+        };
+        let (program, args) = self
+            .args
+            .command
+            .split_first()
+            .expect("clap requires at least one argument");
+        let sdk_root =
+            resolve_sdk_root_to_run(context, sdk_service, output, &start_dir, program, args)?;
+        heal_shims(context, &sdk_root);
+
+        let config = FenvConfig::load(context)?;
+        let project_config = FenvProjectConfig::load_from(context, &start_dir)?;
+        let path = path_with_sdk(context, &sdk_root)?;
+        debug!("exec: program={program}: args={args:?}: PATH={path:?}: cwd={cwd}");
+        let mut command = Command::new(program);
+        command.args(config.args_prepend_of(program)).args(args);
+        if self.args.clean_env {
+            command.env_clear().env("HOME", context.home().path());
+        }
+        let status = command
+            .envs(&project_config.env)
+            .env("PATH", path)
+            .envs(envs)
+            .current_dir(&cwd)
+            .status()
+            .with_context(|| format!("Failed to execute `{program}`"))?;
+        if !status.success() {
+            return Err(SilentFailure(status.code().unwrap_or(1)).into());
+        }
+        anyhow::Ok(())
+    }
+}
+
+/// Resolves the root of the Flutter SDK selected from `start_dir` to run `program` with `args`,
+/// and guards it as the shims do: fails if the Flutter SDK is broken, and for `flutter`, blocks
+/// the arguments as `fenv check-flutter-args` does and fails if the Flutter SDK of a version is
+/// polluted by `flutter upgrade` or `flutter channel`.
+///
+/// The failures of the guards of `flutter` are written to stderr and exit with 2 as the
+/// `flutter` shim script does.
+pub(crate) fn resolve_sdk_root_to_run<OUT: std::io::Write, ERR: std::io::Write>(
+    context: &impl FenvContext,
+    sdk_service: &impl SdkService,
+    output: &mut dyn ConsoleOutput<OUT, ERR>,
+    start_dir: &PathLike,
+    program: &str,
+    args: &[String],
+) -> anyhow::Result<PathLike> {
+    let selected = resolve_selected_sdk(context, sdk_service, output, start_dir)?;
+    let version_or_channel = selected.sdk.to_string();
+    let sdk_root = context.fenv_sdk_root(&version_or_channel);
+    if sdk_service.is_broken_installation(context, &version_or_channel) {
+        return Err(BrokenInstallation {
+            name: version_or_channel,
+            path_to_sdk_root: sdk_root,
+        }
+        .into());
+    }
+    let is_flutter = Path::new(program)
+        .file_name()
+        .is_some_and(|name| name == "flutter");
+    if is_flutter {
+        if let Err(e) = check_flutter(context, sdk_service, output, &selected.sdk, &sdk_root, args)
+        {
+            writeln!(output.stderr(), "fenv: {e}")?;
+            return Err(SilentFailure(2).into());
+        }
+    }
+    anyhow::Ok(sdk_root)
+}
+
+/// Blocks the arguments of `flutter` as `fenv check-flutter-args` does, and fails if the Flutter
+/// SDK of a version is polluted by `flutter upgrade` or `flutter channel`.
+fn check_flutter<OUT: std::io::Write, ERR: std::io::Write>(
+    context: &impl FenvContext,
+    sdk_service: &impl SdkService,
+    output: &mut dyn ConsoleOutput<OUT, ERR>,
+    sdk: &LocalFlutterSdk,
+    sdk_root: &PathLike,
+    args: &[String],
+) -> anyhow::Result<()> {
+    FenvCheckFlutterArgsService::new(FenvCheckFlutterArgsArgs {
+        args: args.to_vec(),
+    })
+    .execute(context, sdk_service, output)?;
+    if !matches!(sdk, LocalFlutterSdk::Version { .. }) {
+        return anyhow::Ok(());
+    }
+    let is_polluted = sdk_root
+        .join("version")
+        .read_to_string()
+        .map_or(true, |version| version.trim() != sdk.to_string());
+    if is_polluted {
+        bail!(
+            "Flutter SDK in `{sdk_root}` is polluted: do `fenv uninstall {sdk} && fenv install {sdk}`"
+        )
+    }
+    anyhow::Ok(())
+}
+
+/// Parses the values of `--env` given as `KEY=VALUE`.
+fn parse_envs(envs: &[String]) -> anyhow::Result<Vec<(&str, &str)>> {
+    envs.iter()
+        .map(|env| match env.split_once('=') {
+            Some((key, value)) if !key.is_empty() => anyhow::Ok((key, value)),
+            _ => bail!("Invalid `--env`: `{env}`. Specify it as `KEY=VALUE`"),
+        })
+        .collect()
+}
+
+/// `PATH` where the executables of the Flutter SDK at `sdk_root` precede the others.
+///
+/// `{fenv_shims}` is removed so that a shim never runs itself through `fenv exec`.
+pub(crate) fn path_with_sdk(
+    context: &impl FenvContext,
+    sdk_root: &PathLike,
+) -> anyhow::Result<std::ffi::OsString> {
+    let sdk_bin = sdk_root.join("bin");
+    let dart_sdk_bin = sdk_bin.join("cache").join("dart-sdk").join("bin");
+    let dirs = [sdk_bin, dart_sdk_bin]
+        .into_iter()
+        .chain(
+            context
+                .path()
+                .into_iter()
+                .filter(|dir| *dir != context.fenv_shims()),
+        )
+        .map(|dir| dir.path().to_path_buf());
+    std::env::join_paths(dirs).context("Failed to build `PATH` for the Flutter SDK")
+}
+
+#[cfg(unix)]
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::FenvContext, sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context, try_run, util::io::SilentFailure,
+        util::path_like::PathLike,
+    };
+    use std::os::unix::prelude::PermissionsExt;
+
+    /// Places a fake `flutter` which records its arguments into `{fenv_dir}/args.txt`
+    /// and exits with the code given by the last argument.
+    fn setup_fake_flutter(context: &impl FenvContext) -> PathLike {
+        let flutter = context.fenv_versions().join("3.7.12/bin/flutter");
+        flutter
+            .writeln("#!/bin/sh\necho \"$@\" > args.txt\nfor last; do :; done\nexit \"$last\"")
+            .unwrap();
+        let mut permissions = flutter.path().metadata().unwrap().permissions();
+        permissions.set_mode(0o755);
+        std::fs::set_permissions(&flutter, permissions).unwrap();
+        context
+            .fenv_sdk_root("3.7.12")
+            .join("version")
+            .writeln("3.7.12")
+            .unwrap();
+        context
+            .fenv_dir()
+            .join(".flutter-version")
+            .writeln("3.7.12")
+            .unwrap();
+        flutter
+    }
+
+    #[test]
+    fn test_exec_runs_command_with_selected_sdk() {
+        test_with_context(|context, output| {
+            // setup
+            setup_fake_flutter(context);
+            context
+                .fenv_config_file()
+                .writeln(r#"{"argsPrepend": {"flutter": ["--suppress-analytics"]}}"#)
+                .unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "exec", "--", "flutter", "--verbose", "0"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                context
+                    .fenv_dir()
+                    .join("args.txt")
+                    .read_to_string()
+                    .unwrap(),
+                "--suppress-analytics --verbose 0\n"
+            );
+        })
+    }
+
+    #[test]
+    fn test_exec_selects_fenv_version() {
+        test_with_context(|context, output| {
+            // setup
+            setup_fake_flutter(context);
+            let flutter = context.fenv_versions().join("3.10.6/bin/flutter");
+            flutter
+                .writeln("#!/bin/sh\necho 3.10.6 > args.txt")
+                .unwrap();
+            std::fs::set_permissions(&flutter, std::fs::Permissions::from_mode(0o755)).unwrap();
+            context
+                .fenv_sdk_root("3.10.6")
+                .join("version")
+                .writeln("3.10.6")
+                .unwrap();
+            let context = context.clone().with_shell_version("3.10");
+
+            // execution
+            try_run(
+                &["fenv", "exec", "--", "flutter"],
+                &context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                context
+                    .fenv_dir()
+                    .join("args.txt")
+                    .read_to_string()
+                    .unwrap(),
+                "3.10.6\n"
+            );
+        })
+    }
+
+    #[test]
+    fn test_exec_guards_flutter_as_shims_do() {
+        test_with_context(|context, output| {
+            // setup
+            setup_fake_flutter(context);
+            let sdk_service = RealSdkService::new();
+
+            // execution
+            let blocked = try_run(
+                &["fenv", "exec", "--", "flutter", "upgrade"],
+                context,
+                &sdk_service,
+                output,
+            );
+            context
+                .fenv_sdk_root("3.7.12")
+                .join("version")
+                .writeln("3.10.6")
+                .unwrap();
+            let polluted = try_run(
+                &["fenv", "exec", "--", "flutter", "0"],
+                context,
+                &sdk_service,
+                output,
+            );
+
+            // validation
+            for result in [blocked, polluted] {
+                assert!(matches!(
+                    result.unwrap_err().downcast_ref::<SilentFailure>(),
+                    Some(SilentFailure(2))
+                ));
+            }
+            let stderr = output.stderr_to_string();
+            assert!(stderr.contains("fenv: `flutter upgrade` is not allowed"));
+            assert!(stderr.contains("is polluted"));
+            assert!(!context.fenv_dir().join("args.txt").exists());
+        })
+    }
+
+    #[test]
+    fn test_exec_with_cwd_and_envs() {
+        test_with_context(|context, output| {
+            // setup
+            let printenv = context.fenv_versions().join("3.7.12/bin/printenv-all");
+            printenv
+                .writeln("#!/bin/sh\npwd > env.txt\nenv | sort >> env.txt")
+                .unwrap();
+            std::fs::set_permissions(&printenv, std::fs::Permissions::from_mode(0o755)).unwrap();
+            context
+                .fenv_versions()
+                .join("3.7.12/bin/flutter")
+                .writeln("")
+                .unwrap();
+            let app = context.fenv_dir().join("app");
+            app.join(".flutter-version").writeln("3.7.12").unwrap();
+            app.join(".fenv.toml")
+                .writeln("[env]\nPUB_HOSTED_URL = \"https://pub.example.com\"\nCI = \"false\"")
+                .unwrap();
+            let context = context
+                .clone()
+                .with_path(&[PathLike::from("/usr/bin"), PathLike::from("/bin")]);
+
+            // execution
+            try_run(
+                &[
+                    "fenv",
+                    "exec",
+                    "--cwd",
+                    "app",
+                    "--clean-env",
+                    "--env",
+                    "CI=true",
+                    "--env",
+                    "EMPTY=",
+                    "--",
+                    "printenv-all",
+                ],
+                &context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            let env_txt = app.join("env.txt").read_to_string().unwrap();
+            let lines: Vec<&str> = env_txt.lines().collect();
+            assert_eq!(
+                std::fs::canonicalize(lines[0]).unwrap(),
+                std::fs::canonicalize(&app).unwrap()
+            );
+            assert!(lines.contains(&"CI=true"));
+            assert!(lines.contains(&"PUB_HOSTED_URL=https://pub.example.com"));
+            assert!(lines.contains(&"EMPTY="));
+            assert!(lines.contains(&format!("HOME={}", context.home()).as_str()));
+            let path = lines
+                .iter()
+                .find_map(|line| line.strip_prefix("PATH="))
+                .unwrap();
+            assert!(path.starts_with(&context.fenv_sdk_root("3.7.12").join("bin").to_string()));
+            assert!(!lines.iter().any(|line| line.starts_with("CARGO")));
+        })
+    }
+
+    #[test]
+    fn test_exec_fails_with_invalid_env() {
+        test_with_context(|context, output| {
+            // setup
+            setup_fake_flutter(context);
+
+            // execution
+            let result = try_run(
+                &["fenv", "exec", "--env", "=1", "--", "flutter", "0"],
+                context,
+                &RealSdkService::new(),
+                output,
+            );
+
+            // validation
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                "Invalid `--env`: `=1`. Specify it as `KEY=VALUE`"
+            );
+            assert!(!context.fenv_dir().join("args.txt").exists());
+        })
+    }
+
+    #[test]
+    fn test_exec_fails_with_exit_code_of_command() {
+        test_with_context(|context, output| {
+            // setup
+            setup_fake_flutter(context);
+
+            // execution
+            let error = try_run(
+                &["fenv", "exec", "--", "flutter", "3"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap_err();
+
+            // validation
+            assert_eq!(error.downcast_ref::<SilentFailure>().unwrap().0, 3);
+        })
+    }
+
+    #[test]
+    fn test_exec_fails_if_no_version_file_exists() {
+        test_with_context(|context, output| {
+            // execution
+            let result = try_run(
+                &["fenv", "exec", "--", "flutter", "--version"],
+                context,
+                &RealSdkService::new(),
+                output,
+            );
+
+            // validation
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                "Could not find a version file"
+            );
+        })
+    }
+}
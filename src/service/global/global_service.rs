@@ -1,7 +1,11 @@
 use crate::{
     args::FenvGlobalArgs,
     context::FenvContext,
-    sdk_service::{results::LookupResult, sdk_service::SdkService},
+    sdk_service::{
+        latest_keyword::{self, LatestKeyword},
+        results::LookupResult,
+        sdk_service::SdkService,
+    },
     service::service::Service,
     util::io::ConsoleOutput,
 };
@@ -29,22 +33,32 @@ where
         output: &mut dyn ConsoleOutput<OUT, ERR>,
     ) -> anyhow::Result<()> {
         match &self.args.prefix {
-            Some(version_prefix) => set_global_version(context, sdk_service, version_prefix),
+            Some(version_prefix) => {
+                set_global_version(context, sdk_service, output, version_prefix)
+            }
             None => show_global_version(context, sdk_service, output),
         }
     }
 }
 
-fn set_global_version<'a>(
+fn set_global_version<'a, OUT, ERR>(
     context: &impl FenvContext,
     sdk_service: &impl SdkService,
+    output: &mut dyn ConsoleOutput<OUT, ERR>,
     prefix: &str,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<()>
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
     let local_sdk = match sdk_service.find_latest_local(context, prefix) {
         LookupResult::Found(sdk) => sdk,
         LookupResult::Err(err) => return Err(anyhow::anyhow!(err)),
         LookupResult::None => {
-            if sdk_service.find_latest_remote(context, prefix).is_found() {
+            if sdk_service
+                .find_latest_remote(context, prefix, false)
+                .is_found()
+            {
                 bail!("The specified version is not installed: do `fenv install {prefix} && fenv global {prefix}`")
             } else {
                 bail!("Not found any matched flutter sdk version: `{prefix}`")
@@ -52,6 +66,10 @@ fn set_global_version<'a>(
         }
     };
 
+    if latest_keyword::is_latest(prefix) {
+        output.warning(&latest_keyword::warning_of(prefix))?;
+        return sdk_service.write_global_version(context, &LatestKeyword(prefix.to_string()));
+    }
     sdk_service.write_global_version(context, &local_sdk)
 }
 
@@ -82,6 +100,33 @@ mod tests {
 
     define_mock_valid_git_command!();
 
+    #[test]
+    fn test_set_global_version_to_latest_keyword() {
+        test_with_context(|context, output| {
+            // setup
+            for version in ["3.19.6", "3.22.2", "3.23.0-0.1.pre", "stable"] {
+                context
+                    .fenv_versions()
+                    .join(version)
+                    .create_dir_all()
+                    .unwrap();
+            }
+            let sdk_service = RealSdkService::new();
+
+            // execution
+            try_run(&["fenv", "global", "latest"], context, &sdk_service, output).unwrap();
+            try_run(&["fenv", "global"], context, &sdk_service, output).unwrap();
+
+            // validation
+            assert_eq!(
+                context.fenv_global_version_file().read_to_string().unwrap(),
+                "latest\n"
+            );
+            assert_eq!(output.stdout_to_string(), "3.22.2\n");
+            assert!(output.stderr_to_string().contains("is not reproducible"));
+        })
+    }
+
     #[test]
     fn test_set_global_version_succeeds() {
         test_with_context(|context, output| {
@@ -0,0 +1,214 @@
+use crate::{
+    args::FenvHelpArgs, build_command, context::FenvContext, sdk_service::sdk_service::SdkService,
+    service::service::Service, util::io::ConsoleOutput,
+};
+use anyhow::{bail, Context};
+use log::debug;
+use std::{
+    io::{IsTerminal, Write},
+    process::{Command, Stdio},
+};
+
+/// A guide shown by `fenv help <TOPIC>`, which is written in Markdown.
+pub struct Topic {
+    pub name: &'static str,
+    pub summary: &'static str,
+    markdown: &'static str,
+}
+
+pub const TOPICS: [Topic; 3] = [
+    Topic {
+        name: "version-files",
+        summary: "How the version files and `$FENV_VERSION` select the Flutter SDK",
+        markdown: include_str!("topics/version-files.md"),
+    },
+    Topic {
+        name: "shims",
+        summary: "How the shims in `$FENV_ROOT/shims` run the selected Flutter SDK",
+        markdown: include_str!("topics/shims.md"),
+    },
+    Topic {
+        name: "ci",
+        summary: "How to install, cache, and retry the Flutter SDKs on CI",
+        markdown: include_str!("topics/ci.md"),
+    },
+];
+
+/// The section of `fenv --help` which lists the topics.
+pub fn guides_note() -> String {
+    let mut note = String::from("Guides:\n  fenv help <TOPIC>\n");
+    for topic in &TOPICS {
+        note.push_str(&format!("      {:15}{}\n", topic.name, topic.summary));
+    }
+    note
+}
+
+pub struct FenvHelpService {
+    pub args: FenvHelpArgs,
+}
+
+impl FenvHelpService {
+    pub fn new(args: FenvHelpArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvHelpService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        _: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let mut command = build_command();
+        let Some(name) = &self.args.topic else {
+            write!(output.stdout(), "{}", command.render_help())?;
+            return anyhow::Ok(());
+        };
+        if let Some(topic) = TOPICS.iter().find(|topic| topic.name == name) {
+            return page(context, &render(topic.markdown), output);
+        }
+        // Same as `fenv <COMMAND> --help`, whose usage starts with `fenv`.
+        command.build();
+        if let Some(subcommand) = command.find_subcommand_mut(name) {
+            write!(output.stdout(), "{}", subcommand.render_help())?;
+            return anyhow::Ok(());
+        }
+        let names: Vec<String> = TOPICS
+            .iter()
+            .map(|topic| format!("`{}`", topic.name))
+            .collect();
+        bail!(
+            "`{name}` is neither a command nor a topic: the topics are {}",
+            names.join(", ")
+        )
+    }
+}
+
+/// Renders `markdown` for the terminal: the headings are underlined, and the code blocks are
+/// indented instead of fenced.
+fn render(markdown: &str) -> String {
+    let mut rendered = String::new();
+    let mut in_code_block = false;
+    for line in markdown.lines() {
+        if line.starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            if !line.is_empty() {
+                rendered.push_str("    ");
+            }
+            rendered.push_str(line);
+        } else if let Some(heading) = line.strip_prefix("# ") {
+            rendered.push_str(&format!(
+                "{heading}\n{}",
+                "=".repeat(heading.chars().count())
+            ));
+        } else if let Some(heading) = line.strip_prefix("## ") {
+            rendered.push_str(&format!(
+                "{heading}\n{}",
+                "-".repeat(heading.chars().count())
+            ));
+        } else {
+            rendered.push_str(line);
+        }
+        rendered.push('\n');
+    }
+    rendered
+}
+
+/// Writes `text` through the pager of `context` if stdout is a terminal, or into `output`
+/// otherwise, including when the pager cannot run.
+fn page<OUT, ERR>(
+    context: &impl FenvContext,
+    text: &str,
+    output: &mut dyn ConsoleOutput<OUT, ERR>,
+) -> anyhow::Result<()>
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    if let Some(pager) = context.pager().filter(|_| std::io::stdout().is_terminal()) {
+        match run_pager(&pager, text) {
+            Ok(()) => return anyhow::Ok(()),
+            Err(e) => debug!("page(): {e}"),
+        }
+    }
+    write!(output.stdout(), "{text}")?;
+    anyhow::Ok(())
+}
+
+fn run_pager(pager: &str, text: &str) -> anyhow::Result<()> {
+    let mut child = Command::new("sh")
+        .args(["-c", pager])
+        .stdin(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to execute `{pager}`"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager may quit before reading everything.
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    let status = child
+        .wait()
+        .with_context(|| format!("Failed to wait for `{pager}`"))?;
+    // `sh` exits with 127 if the pager is not found, where nothing has been shown.
+    if status.code() == Some(127) {
+        bail!("`{pager}` is not found")
+    }
+    anyhow::Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        sdk_service::sdk_service::RealSdkService, service::macros::test_with_context, try_run,
+        util::io::ConsoleOutput,
+    };
+
+    #[test]
+    fn test_help_of_topic_and_command() {
+        test_with_context(|context, output| {
+            // execution
+            try_run(
+                &["fenv", "help", "version-files"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+            let topic = output.stdout_to_string();
+            output.stdout().clear();
+            try_run(
+                &["fenv", "help", "install"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+            let command = output.stdout_to_string();
+            let error = try_run(
+                &["fenv", "help", "unknown"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap_err();
+
+            // validation
+            assert!(topic.starts_with("Version files\n=============\n"));
+            assert!(topic.contains("\n    3.22      # 3.22.x\n"));
+            assert!(!topic.contains("```"));
+            assert!(command.contains("fenv install"));
+            assert_eq!(
+                error.to_string(),
+                "`unknown` is neither a command nor a topic: \
+                 the topics are `version-files`, `shims`, `ci`"
+            );
+        })
+    }
+}
@@ -0,0 +1 @@
+pub mod help_service;
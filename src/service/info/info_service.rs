@@ -0,0 +1,186 @@
+use crate::{
+    args::FenvInfoArgs,
+    context::FenvContext,
+    sdk_service::{results::LookupResult, sdk_metadata::SdkMetadata, sdk_service::SdkService},
+    service::service::Service,
+    util::{io::ConsoleOutput, size::human_size},
+};
+use anyhow::bail;
+use chrono::SecondsFormat;
+
+/// Shows the metadata of the latest installed Flutter SDK matching the given prefix, such as
+/// its commit, channel, Dart SDK version, disk usage, and install date.
+pub struct FenvInfoService {
+    pub args: FenvInfoArgs,
+}
+
+impl FenvInfoService {
+    pub fn new(args: FenvInfoArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvInfoService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let prefix = &self.args.prefix;
+        let sdk = match sdk_service.find_latest_local(context, prefix) {
+            LookupResult::Found(sdk) => sdk,
+            LookupResult::Err(err) => return Err(err),
+            LookupResult::None => {
+                bail!(
+                    "The specified version `{prefix}` is not installed: do `fenv install {prefix}`"
+                )
+            }
+        };
+        let metadata = sdk_service.collect_sdk_metadata(context, &sdk);
+        if self.args.json {
+            let object = serde_json::json!({
+                "name": metadata.name,
+                "path": metadata.path.to_string(),
+                "commit": metadata.commit_sha,
+                "channel": metadata.channel,
+                "dart_sdk_version": metadata.dart_sdk_version,
+                "disk_usage": metadata.disk_usage,
+                "installed_at": metadata
+                    .installed_at
+                    .map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true)),
+            });
+            writeln!(
+                output.stdout(),
+                "{}",
+                serde_json::to_string_pretty(&object)?
+            )?;
+        } else {
+            let entries = collect_entries(&metadata);
+            let width = entries.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+            for (key, value) in entries {
+                writeln!(output.stdout(), "{key:width$} {value}")?;
+            }
+        }
+        anyhow::Ok(())
+    }
+}
+
+/// Lists the metadata as `(key, value)` pairs in the order to be printed. The unknown values
+/// are printed as `-`.
+fn collect_entries(metadata: &SdkMetadata) -> Vec<(&'static str, String)> {
+    let or_dash = |value: Option<String>| value.unwrap_or_else(|| String::from("-"));
+    vec![
+        ("name", metadata.name.clone()),
+        ("path", metadata.path.to_string()),
+        ("commit", or_dash(metadata.commit_sha.clone())),
+        ("channel", or_dash(metadata.channel.clone())),
+        (
+            "dart_sdk_version",
+            or_dash(metadata.dart_sdk_version.clone()),
+        ),
+        ("disk_usage", human_size(metadata.disk_usage)),
+        (
+            "installed_at",
+            or_dash(
+                metadata
+                    .installed_at
+                    .map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true)),
+            ),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::FenvContext, define_mock_flutter_command, define_mock_valid_git_command,
+        sdk_service::sdk_service::RealSdkService, service::macros::test_with_context, try_run,
+        util::chrono_wrapper::SystemClock,
+    };
+
+    define_mock_valid_git_command!();
+    define_mock_flutter_command!();
+
+    #[test]
+    fn test_info() {
+        test_with_context(|context, output| {
+            // setup
+            let sdk_root = context.fenv_sdk_root("3.22.2");
+            sdk_root
+                .join("bin/cache/dart-sdk/version")
+                .writeln("3.4.3")
+                .unwrap();
+            context.fenv_sdk_root("3.19.6").create_dir_all().unwrap();
+            let sdk_service =
+                RealSdkService::from(MockValidGitCommand, SystemClock::new(), MockFlutterCommand);
+
+            // execution
+            try_run(&["fenv", "info", "3"], context, &sdk_service, output).unwrap();
+
+            // validation
+            let stdout = output.stdout_to_string();
+            let lines: Vec<&str> = stdout.lines().collect();
+            assert_eq!(lines[0], "name             3.22.2");
+            assert_eq!(lines[1], format!("path             {sdk_root}"));
+            assert_eq!(
+                lines[2],
+                "commit           135454af32477f815a7525073027a3ff9eff1bfd"
+            );
+            assert_eq!(lines[3], "channel          -");
+            assert_eq!(lines[4], "dart_sdk_version 3.4.3");
+            assert_eq!(lines[5], "disk_usage       6 B");
+            let installed_at = lines[6].strip_prefix("installed_at     ").unwrap();
+            assert!(chrono::DateTime::parse_from_rfc3339(installed_at).is_ok());
+        })
+    }
+
+    #[test]
+    fn test_info_of_channel_as_json() {
+        test_with_context(|context, output| {
+            // setup
+            context.fenv_sdk_root("stable").create_dir_all().unwrap();
+            let sdk_service =
+                RealSdkService::from(MockValidGitCommand, SystemClock::new(), MockFlutterCommand);
+
+            // execution
+            try_run(
+                &["fenv", "info", "stable", "--json"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            let json: serde_json::Value = serde_json::from_str(&output.stdout_to_string()).unwrap();
+            assert_eq!(json["name"], "stable");
+            assert_eq!(json["channel"], "stable");
+            assert_eq!(json["dart_sdk_version"], serde_json::Value::Null);
+            assert_eq!(json["disk_usage"], 0);
+        })
+    }
+
+    #[test]
+    fn test_info_fails_if_not_installed() {
+        test_with_context(|context, output| {
+            // execution
+            let result = try_run(
+                &["fenv", "info", "3.22"],
+                context,
+                &RealSdkService::new(),
+                output,
+            );
+
+            // validation
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                "The specified version `3.22` is not installed: do `fenv install 3.22`"
+            );
+        })
+    }
+}
@@ -54,15 +54,18 @@ impl FenvInitService {
         match &detected_shell[..] {
             "fish" => writedoc!(
                 stdout,
-                "{}",
+                "{}{}",
                 include_str!("fish/path_template.txt")
                     .replace("%FENV_ROOT%", &context.fenv_root().to_string()),
+                include_str!("fish/shell_function.txt"),
             ),
             _ => writedoc!(
                 stdout,
-                "{}",
+                "{}{}",
                 include_str!("common/path_template.txt")
                     .replace("%FENV_ROOT%", &context.fenv_root().to_string()),
+                // `fenv shell` must be evaluated by the current shell to set `$FENV_VERSION`.
+                include_str!("common/shell_function.txt").replace("%SHELL%", detected_shell),
             ),
         }
         .map_err(|e| anyhow!(e))
@@ -110,7 +113,7 @@ where
     }
 }
 
-fn detect_shell<'a>(context: &impl FenvContext) -> Result<String> {
+pub fn detect_shell<'a>(context: &impl FenvContext) -> Result<String> {
     // With `ps -o 'args='`,
     // captures the command line arguments which launched the shell.
     let ppid = getppid().as_raw();
@@ -347,6 +350,14 @@ mod tests {
                     while set fenv_index (contains -i -- "$FENV_ROOT/shims" $PATH)
                     set -eg PATH[$fenv_index]; end; set -e fenv_index
                     set -gx PATH "$FENV_ROOT/shims" $PATH
+                    function fenv
+                      if test "$argv[1]" = shell
+                        set -e argv[1]
+                        command fenv shell --shell fish $argv | source
+                      else
+                        command fenv $argv
+                      end
+                    end
                     %COMPLETIONS%"#,
             }
             .replace(
@@ -382,6 +393,14 @@ mod tests {
                 fi; done;
                 echo "${paths[*]}"')"
                 export PATH="$FENV_ROOT/shims:${PATH}"
+                fenv() {
+                  if [ "${1:-}" = shell ]; then
+                    shift
+                    eval "$(command fenv shell --shell bash "$@")"
+                  else
+                    command fenv "$@"
+                  fi
+                }
                 %COMPLETIONS%"#
             }
             .replace(
@@ -417,6 +436,14 @@ mod tests {
                 fi; done;
                 echo "${paths[*]}"')"
                 export PATH="$FENV_ROOT/shims:${PATH}"
+                fenv() {
+                  if [ "${1:-}" = shell ]; then
+                    shift
+                    eval "$(command fenv shell --shell zsh "$@")"
+                  else
+                    command fenv "$@"
+                  fi
+                }
                 if [[ -z "$(command -v compdef || true)" ]]; then
                   autoload -Uz compinit && compinit
                 fi
@@ -452,6 +479,14 @@ mod tests {
                 fi; done;
                 echo "${paths[*]}"')"
                 export PATH="$FENV_ROOT/shims:${PATH}"
+                fenv() {
+                  if [ "${1:-}" = shell ]; then
+                    shift
+                    eval "$(command fenv shell --shell ksh "$@")"
+                  else
+                    command fenv "$@"
+                  fi
+                }
                 "#
             }
         )
@@ -1,11 +1,31 @@
 use crate::{
     args::{self, FenvListRemoteArgs},
     context::FenvContext,
-    sdk_service::{results::VersionFileReadResult, sdk_service::SdkService},
-    service::{list_remote::list_remote_service::FenvListRemoteService, service::Service},
-    util::io::ConsoleOutput,
+    external::{flutter_command::PrecachePlatform, progress::interruption},
+    sdk_service::{
+        model::{flutter_sdk::FlutterSdk, local_flutter_sdk::split_namespace},
+        results::{InstallFailure, LookupResult, VersionFileReadResult},
+        sdk_service::{InstallOptions, SdkService},
+    },
+    service::{
+        install::prerequisites::ensure_prerequisites,
+        list_remote::list_remote_service::FenvListRemoteService, rehash::rehash_service::rehash,
+        service::Service, start_dir::StartDir,
+    },
+    util::{
+        io::{ConsoleOutput, MultiplexedOutput},
+        network_fs::is_network_filesystem,
+    },
 };
 use anyhow::bail;
+use std::{
+    collections::VecDeque,
+    io::Write,
+    sync::{
+        mpsc::{self, Sender},
+        Mutex,
+    },
+};
 
 pub struct FenvInstallService {
     pub args: args::FenvInstallArgs,
@@ -15,6 +35,164 @@ impl FenvInstallService {
     pub fn new(args: args::FenvInstallArgs) -> Self {
         Self { args }
     }
+
+    /// The options given by the arguments, where the platforms are given by `--precache-*`.
+    fn install_options(&self, fails_on_installed: bool) -> InstallOptions {
+        let precache_platforms = [
+            (self.args.precache_android, PrecachePlatform::Android),
+            (self.args.precache_ios, PrecachePlatform::Ios),
+            (self.args.precache_web, PrecachePlatform::Web),
+        ]
+        .into_iter()
+        .filter_map(|(is_given, platform)| is_given.then_some(platform))
+        .collect();
+        InstallOptions {
+            should_doctor: true,
+            should_precache: self.args.should_precache,
+            precache_platforms,
+            accepts_android_licenses: self.args.accept_android_licenses,
+            fails_on_installed,
+            include_beta: self.args.include_beta,
+        }
+    }
+
+    /// Installs `prefixes` by `--jobs` threads, relaying their progress prefixed with each
+    /// prefix. Unlike the sequential install, the rest of the prefixes are installed even if one
+    /// of them fails, and all the failures are reported at last. No more prefix is started once
+    /// `is_interrupted` returns `true`.
+    fn install_in_parallel<OUT: std::io::Write, ERR: std::io::Write>(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        prefixes: &[String],
+        is_interrupted: &(dyn Fn() -> bool + Sync),
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let (sender, receiver) = mpsc::channel();
+        let multiplexed = MultiplexedOutput::new(
+            ChannelWriter::new(Stream::Stdout, sender.clone()),
+            ChannelWriter::new(Stream::Stderr, sender),
+            *output.options(),
+        );
+        let queue: Mutex<VecDeque<_>> = Mutex::new(
+            prefixes
+                .iter()
+                .map(|prefix| (prefix, multiplexed.job(prefix)))
+                .collect(),
+        );
+        // The relay below ends when every job has finished and dropped its output.
+        drop(multiplexed);
+        let failures = Mutex::new(vec![]);
+        let options = self.install_options(self.args.fails_on_installed);
+        let jobs = prefixes.len().min(self.args.jobs as usize);
+        std::thread::scope(|scope| -> anyhow::Result<()> {
+            for _ in 0..jobs {
+                scope.spawn(|| loop {
+                    if is_interrupted() {
+                        // Drops the outputs of the rest, so that the relay below ends.
+                        queue.lock().unwrap_or_else(|e| e.into_inner()).clear();
+                        break;
+                    }
+                    let next = queue.lock().unwrap_or_else(|e| e.into_inner()).pop_front();
+                    let Some((prefix, mut job)) = next else {
+                        break;
+                    };
+                    let result = sdk_service.install_sdk(context, prefix, &options, job.stderr());
+                    if let Err(e) = result.map_err(InstallFailure::classify) {
+                        let _ = writeln!(job.stderr(), "Failed: {e}");
+                        failures
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .push((prefix, InstallFailure::reason_of(&e)));
+                    }
+                });
+            }
+            for (stream, bytes) in receiver {
+                match stream {
+                    Stream::Stdout => output.stdout().write_all(&bytes)?,
+                    Stream::Stderr => output.stderr().write_all(&bytes)?,
+                }
+            }
+            anyhow::Ok(())
+        })?;
+
+        if is_interrupted() {
+            bail!("`fenv install` is interrupted")
+        }
+        let failures = failures.into_inner().unwrap_or_else(|e| e.into_inner());
+        if !failures.is_empty() {
+            let names = prefixes
+                .iter()
+                .filter(|prefix| failures.iter().any(|(failed, _)| failed == prefix))
+                .map(|prefix| format!("`{prefix}`"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let error = anyhow::anyhow!("Failed to install {names}");
+            // Classified only if every failure has the same reason, so that CI retries the
+            // installation only when all of them are transient in the same way.
+            let (_, reason) = failures[0];
+            return match reason.filter(|_| failures.iter().all(|(_, other)| *other == reason)) {
+                Some(reason) => Err(reason.wrap(error)),
+                None => Err(error),
+            };
+        }
+        anyhow::Ok(())
+    }
+
+    /// Removes the installed Flutter SDK matching `prefix` if `--force` is given, and returns the
+    /// prefix to install: the name of the removed one, so that the same SDK is installed again.
+    fn remove_if_forced<OUT: std::io::Write, ERR: std::io::Write>(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        prefix: &str,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<String> {
+        if !self.args.force {
+            return anyhow::Ok(prefix.to_string());
+        }
+        match sdk_service.find_latest_local(context, prefix) {
+            LookupResult::Found(sdk) => {
+                let name = sdk.display_name();
+                sdk_service.uninstall_by_name(context, &name)?;
+                writeln!(output.stderr(), "Removed `{name}` to reinstall it")?;
+                anyhow::Ok(name)
+            }
+            LookupResult::None => anyhow::Ok(prefix.to_string()),
+            LookupResult::Err(e) => Err(e),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Sends the lines written by the parallel jobs to the thread relaying them into the output.
+struct ChannelWriter {
+    stream: Stream,
+    sender: Sender<(Stream, Vec<u8>)>,
+}
+
+impl ChannelWriter {
+    fn new(stream: Stream, sender: Sender<(Stream, Vec<u8>)>) -> Self {
+        Self { stream, sender }
+    }
+}
+
+impl Write for ChannelWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.sender
+            .send((self.stream, buf.to_vec()))
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::BrokenPipe, e))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
 impl<OUT, ERR> Service<OUT, ERR> for FenvInstallService
@@ -28,43 +206,150 @@ where
         sdk_service: &impl SdkService,
         output: &mut dyn ConsoleOutput<OUT, ERR>,
     ) -> anyhow::Result<()> {
+        let result = match &self.args.limit_rate {
+            Some(limit_rate) => {
+                self.install(&context.with_limit_rate(limit_rate), sdk_service, output)
+            }
+            None => self.install(context, sdk_service, output),
+        };
+        result.map_err(InstallFailure::classify)
+    }
+}
+
+impl FenvInstallService {
+    fn install<OUT: std::io::Write, ERR: std::io::Write>(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        if is_network_filesystem(&context.fenv_versions()) {
+            output.warning(&format!(
+                "`{}` is on a network file system. \
+                Installing may be slow, because the Flutter SDK is copied and synced file by file \
+                where renaming fails",
+                context.fenv_root()
+            ))?;
+        }
+
         if self.args.list {
             let list_remote_service = FenvListRemoteService::new(FenvListRemoteArgs {
                 bare: self.args.bare,
+                with_sha: false,
+                include_beta: self.args.include_beta,
+                json: false,
             });
             return list_remote_service.execute(context, sdk_service, output);
         }
 
+        if let Some(archive) = &self.args.archive {
+            let [name] = &self.args.prefixes[..] else {
+                bail!("Specify exactly one name to install the archive as, such as `3.22.2`")
+            };
+            let name = self.remove_if_forced(context, sdk_service, name, output)?;
+            sdk_service.install_sdk_from_archive(
+                context,
+                &context.fenv_dir().join(archive),
+                self.args.sha256.as_deref(),
+                &name,
+                &self.install_options(true),
+                output.stderr(),
+            )?;
+            return rehash(context);
+        }
+
+        if let Some(git_url) = &self.args.git_url {
+            ensure_prerequisites(context, self.args.should_precache)?;
+            let namespace = &self.args.namespace;
+            for prefix in &self.args.prefixes {
+                let prefix = self.remove_if_forced(
+                    context,
+                    sdk_service,
+                    &format!("{namespace}-{prefix}"),
+                    output,
+                )?;
+                let prefix = match split_namespace(&prefix) {
+                    Some((_, prefix)) => prefix,
+                    None => &prefix,
+                };
+                sdk_service.install_sdk_from_git(
+                    context,
+                    git_url,
+                    namespace,
+                    prefix,
+                    &self.install_options(self.args.fails_on_installed),
+                    output.stderr(),
+                )?;
+            }
+            return rehash(context);
+        }
+
         if !self.args.prefixes.is_empty() {
+            ensure_prerequisites(context, self.args.should_precache)?;
+            if self.args.jobs > 1 && self.args.prefixes.len() > 1 {
+                let mut prefixes = vec![];
+                for prefix in &self.args.prefixes {
+                    prefixes.push(self.remove_if_forced(context, sdk_service, prefix, output)?);
+                }
+                // Catches `SIGINT` and `SIGTERM` also between the installations.
+                let guard = interruption::Guard::install();
+                self.install_in_parallel(
+                    context,
+                    sdk_service,
+                    &prefixes,
+                    &|| guard.is_interrupted(),
+                    output,
+                )?;
+                return rehash(context);
+            }
             for prefix in &self.args.prefixes {
+                let prefix = self.remove_if_forced(context, sdk_service, prefix, output)?;
                 sdk_service.install_sdk(
                     context,
-                    prefix,
-                    true,
-                    self.args.should_precache,
-                    self.args.fails_on_installed,
+                    &prefix,
+                    &self.install_options(self.args.fails_on_installed),
+                    output.stderr(),
                 )?;
             }
-            return anyhow::Ok(());
+            return rehash(context);
         }
 
-        match sdk_service.read_nearest_local_version(context, &context.fenv_dir()) {
+        let start_dir = self.args.resolve_start_dir(context);
+        match sdk_service.read_nearest_local_version(context, &start_dir) {
             VersionFileReadResult::NotFoundVersionFile => {
                 bail!("Could not find any local version file. Specify a version to install.")
             }
-            VersionFileReadResult::FoundButNotInstalled(summary) => sdk_service.install_sdk(
-                context,
-                &summary.stored_version_prefix,
-                true,
-                self.args.should_precache,
-                true,
-            ),
-            VersionFileReadResult::FoundAndInstalled(summary) => {
-                writeln!(
+            VersionFileReadResult::FoundButNotInstalled(summary) => {
+                ensure_prerequisites(context, self.args.should_precache)?;
+                sdk_service.install_sdk(
+                    context,
+                    &summary.stored_version_prefix,
+                    &self.install_options(true),
+                    output.stderr(),
+                )?;
+                rehash(context)
+            }
+            VersionFileReadResult::FoundAndInstalled(summary) if self.args.force => {
+                ensure_prerequisites(context, self.args.should_precache)?;
+                let prefix = self.remove_if_forced(
+                    context,
+                    sdk_service,
+                    &summary.latest_local_sdk.display_name(),
+                    output,
+                )?;
+                sdk_service.install_sdk(
+                    context,
+                    &prefix,
+                    &self.install_options(true),
                     output.stderr(),
+                )?;
+                rehash(context)
+            }
+            VersionFileReadResult::FoundAndInstalled(summary) => {
+                output.warning(&format!(
                     "`{}` is already installed",
                     summary.latest_local_sdk
-                )?;
+                ))?;
                 Ok(())
             }
             VersionFileReadResult::Err {
@@ -77,17 +362,78 @@ where
 
 #[cfg(test)]
 mod tests {
-    use std::io::Write;
+    use std::{
+        io::Write,
+        sync::{Arc, Mutex},
+    };
 
+    use super::FenvInstallService;
     use crate::{
-        context::FenvContext, define_mock_flutter_command, define_mock_valid_git_command,
-        sdk_service::sdk_service::RealSdkService, service::macros::test_with_context, try_run,
-        util::chrono_wrapper::SystemClock, write_invalid_utf8,
+        args::FenvSubcommands,
+        context::FenvContext,
+        define_mock_flutter_command, define_mock_valid_git_command,
+        external::{
+            dart_command::MockDartCommand,
+            flutter_command::{FlutterCommand, PrecachePlatform},
+            git_command::MockGitCommand,
+            progress::interruption,
+        },
+        parse_command_line,
+        sdk_service::{
+            model::flutter_sdk::FlutterSdk,
+            results::{InstallFailure, InstallFailureReason, LookupResult},
+            sdk_service::{RealSdkService, SdkService},
+        },
+        service::macros::test_with_context,
+        try_run,
+        util::chrono_wrapper::SystemClock,
+        write_invalid_utf8,
     };
 
     define_mock_valid_git_command!();
     define_mock_flutter_command!();
 
+    /// Records the platforms given to `precache`, and the SDKs whose Android SDK licenses are
+    /// accepted.
+    struct RecordingFlutterCommand {
+        precached: Arc<Mutex<Vec<Vec<PrecachePlatform>>>>,
+        licenses_accepted: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl FlutterCommand for RecordingFlutterCommand {
+        fn doctor(&self, _: &str, _: Option<&str>, _: &mut dyn Write) -> anyhow::Result<()> {
+            anyhow::Ok(())
+        }
+
+        fn precache(
+            &self,
+            _: &str,
+            platforms: &[PrecachePlatform],
+            _: Option<&str>,
+            _: &mut dyn Write,
+        ) -> anyhow::Result<()> {
+            self.precached.lock().unwrap().push(platforms.to_vec());
+            anyhow::Ok(())
+        }
+
+        fn accept_android_licenses(
+            &self,
+            flutter_sdk_root: &str,
+            _: Option<&str>,
+            _: &mut dyn Write,
+        ) -> anyhow::Result<()> {
+            self.licenses_accepted
+                .lock()
+                .unwrap()
+                .push(flutter_sdk_root.to_string());
+            anyhow::Ok(())
+        }
+
+        fn create(&self, _: &str, _: &str, _: &[String], _: &mut dyn Write) -> anyhow::Result<()> {
+            anyhow::Ok(())
+        }
+    }
+
     #[test]
     pub fn test_install_channel_without_prefix_succeeds() {
         test_with_context(|context, output| {
@@ -108,7 +454,420 @@ mod tests {
 
             // validation
             assert_eq!(output.stdout_to_string(), "");
-            assert!(context.fenv_versions().join("stable").is_dir())
+            assert!(context.fenv_versions().join("stable").is_dir());
+            assert!(context.fenv_shims().join("flutter").is_file());
+            assert!(context.fenv_shims().join("dart").is_file());
+        })
+    }
+
+    #[test]
+    pub fn test_install_force_reinstalls_installed_sdk() {
+        test_with_context(|context, output| {
+            // setup
+            let sdk_root = context.fenv_sdk_root("3.7.12");
+            sdk_root.join("corrupted").writeln("").unwrap();
+            let sdk_service =
+                RealSdkService::from(MockValidGitCommand, SystemClock::new(), MockFlutterCommand);
+
+            // execution
+            try_run(
+                &["fenv", "install", "--force", "3"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert!(sdk_root.is_dir());
+            assert!(!sdk_root.join("corrupted").exists());
+            assert!(output
+                .stderr_to_string()
+                .contains("Removed `3.7.12` to reinstall it\n"));
+        })
+    }
+
+    #[test]
+    pub fn test_install_commit_as_short_sha() {
+        test_with_context(|context, output| {
+            // setup
+            let sha = "761747bfc538b5af34aa0d3fac380f1bc331ec49";
+            let sdk_service =
+                RealSdkService::from(MockValidGitCommand, SystemClock::new(), MockFlutterCommand);
+
+            // execution
+            try_run(&["fenv", "install", sha], context, &sdk_service, output).unwrap();
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln(sha)
+                .unwrap();
+            try_run(&["fenv", "install"], context, &sdk_service, output).unwrap();
+
+            // validation
+            assert!(context.fenv_sdk_root("761747bfc5").is_dir());
+            assert!(!context.fenv_sdk_root(sha).exists());
+            assert!(output
+                .stderr_to_string()
+                .contains("fenv: warning: `761747bfc5` is already installed\n"));
+        })
+    }
+
+    #[test]
+    pub fn test_install_from_git_url() {
+        test_with_context(|context, output| {
+            // setup
+            let fork_url = "https://git.example.com/mobile/flutter.git";
+            let mut git_command = MockGitCommand::new();
+            git_command
+                .expect_list_remote_sdks_by_tags()
+                .withf(move |remote| remote.url == fork_url)
+                .returning(|_| {
+                    anyhow::Ok(String::from(
+                        "fd9d2d5d2e5dc3a7e4e5a5d2e5dc3a7e4e5a5d2e\trefs/tags/3.19.6\n\
+                        e5dc3a7e4e5a5d2e5dc3a7e4e5a5d2efd9d2d5d2\trefs/tags/3.19.5",
+                    ))
+                });
+            git_command
+                .expect_list_remote_sdks_by_branches()
+                .returning(|_| anyhow::Ok(String::new()));
+            git_command
+                .expect_clone_flutter_sdk_by_version()
+                .withf(move |version, _, remote| version == "3.19.6" && remote.url == fork_url)
+                .times(1)
+                .returning(|_, destination, _| {
+                    std::fs::create_dir(destination).map_err(|e| anyhow::anyhow!(e))
+                });
+            let sdk_service =
+                RealSdkService::from(git_command, SystemClock::new(), MockFlutterCommand);
+
+            // execution
+            try_run(
+                &["fenv", "install", "--git-url", fork_url, "3.19"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert!(context.fenv_sdk_root("fork-3.19.6").is_dir());
+            assert!(!context.fenv_sdk_root("3.19.6").exists());
+            assert!(matches!(
+                sdk_service.find_latest_local(context, "3.19"),
+                LookupResult::None
+            ));
+            assert!(matches!(
+                sdk_service.find_latest_local(context, "fork-3.19"),
+                LookupResult::Found(sdk) if sdk.display_name() == "fork-3.19.6"
+            ));
+        })
+    }
+
+    #[test]
+    pub fn test_install_in_parallel() {
+        test_with_context(|context, output| {
+            // setup
+            let _serial = interruption::SERIAL
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            let sdk_service =
+                RealSdkService::from(MockValidGitCommand, SystemClock::new(), MockFlutterCommand);
+
+            // execution
+            try_run(
+                &[
+                    "fenv",
+                    "install",
+                    "--jobs",
+                    "3",
+                    "--ignore-installed",
+                    "3.7.12",
+                    "stable",
+                    "3.7",
+                ],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert!(context.fenv_versions().join("3.7.12").is_dir());
+            assert!(context.fenv_versions().join("stable").is_dir());
+            assert!(context.fenv_shims().join("flutter").is_file());
+        })
+    }
+
+    #[test]
+    pub fn test_install_in_parallel_reports_all_failures() {
+        test_with_context(|context, output| {
+            // setup
+            let _serial = interruption::SERIAL
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            let sdk_service =
+                RealSdkService::from(MockValidGitCommand, SystemClock::new(), MockFlutterCommand);
+
+            // execution
+            let error = try_run(
+                &["fenv", "install", "-j", "2", "unknown", "3.7.12", "0.0.0"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap_err();
+
+            // validation
+            assert_eq!(error.to_string(), "Failed to install `unknown`, `0.0.0`");
+            assert!(context.fenv_versions().join("3.7.12").is_dir());
+            let stderr = output.stderr_to_string();
+            assert!(stderr.contains(
+                "[unknown] Failed: Not found any matched flutter sdk version: `unknown`\n"
+            ));
+            assert!(stderr
+                .contains("[0.0.0] Failed: Not found any matched flutter sdk version: `0.0.0`\n"));
+        })
+    }
+
+    #[test]
+    pub fn test_install_in_parallel_stops_when_interrupted() {
+        test_with_context(|context, output| {
+            // setup
+            let sdk_service =
+                RealSdkService::from(MockValidGitCommand, SystemClock::new(), MockFlutterCommand);
+            let command_line =
+                parse_command_line(["fenv", "install", "-j", "2", "3.7.12", "stable"]);
+            let FenvSubcommands::Install(args) = command_line.args.command else {
+                unreachable!()
+            };
+
+            // execution
+            let error = FenvInstallService::new(args)
+                .install_in_parallel(
+                    context,
+                    &sdk_service,
+                    &["3.7.12".to_string(), "stable".to_string()],
+                    &|| true,
+                    output,
+                )
+                .unwrap_err();
+
+            // validation
+            assert_eq!(error.to_string(), "`fenv install` is interrupted");
+            assert!(!context.fenv_versions().join("3.7.12").exists());
+            assert!(!context.fenv_versions().join("stable").exists());
+        })
+    }
+
+    #[cfg(unix)]
+    #[test]
+    pub fn test_install_from_archive() {
+        test_with_context(|context, output| {
+            // setup
+            let sources = context.home().join("sources");
+            sources
+                .join("flutter")
+                .join("bin")
+                .join("flutter")
+                .writeln("#!/bin/sh")
+                .unwrap();
+            let status = std::process::Command::new("tar")
+                .arg("-cf")
+                .arg(context.fenv_dir().join("flutter.tar").path())
+                .arg("-C")
+                .arg(sources.path())
+                .arg("flutter")
+                .status()
+                .unwrap();
+            assert!(status.success());
+            let sdk_service =
+                RealSdkService::from(MockValidGitCommand, SystemClock::new(), MockFlutterCommand);
+
+            // execution
+            let mismatch_error = try_run(
+                &[
+                    "fenv",
+                    "install",
+                    "--archive",
+                    "flutter.tar",
+                    "--sha256",
+                    "0000",
+                    "3.22.2",
+                ],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap_err();
+            assert!(!context.fenv_sdk_root("3.22.2").exists());
+            try_run(
+                &["fenv", "install", "--archive", "flutter.tar", "3.22.2"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+            let error = try_run(
+                &[
+                    "fenv",
+                    "install",
+                    "--archive",
+                    "flutter.tar",
+                    "3.22.2",
+                    "stable",
+                ],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap_err();
+
+            // validation
+            let sdk_root = context.fenv_sdk_root("3.22.2");
+            assert!(sdk_root.join("bin").join("flutter").is_file());
+            assert!(!context.fenv_versions().join(".install_3.22.2").exists());
+            assert!(context.fenv_shims().join("flutter").is_file());
+            assert_eq!(
+                error.to_string(),
+                "Specify exactly one name to install the archive as, such as `3.22.2`"
+            );
+            assert!(mismatch_error.to_string().starts_with("The checksum of `"));
+            assert_eq!(
+                InstallFailure::reason_of(&mismatch_error),
+                Some(InstallFailureReason::Checksum)
+            );
+        })
+    }
+
+    #[test]
+    pub fn test_install_activates_configured_pub_packages() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_config_file()
+                .writeln(r#"{"pubGlobalActivate": ["melos ^3.0.0", "unknown_package", "very_good_cli"]}"#)
+                .unwrap();
+            let mut dart_command = MockDartCommand::new();
+            dart_command
+                .expect_pub_global_activate()
+                .withf(|_, package| package == ["melos", "^3.0.0"])
+                .times(1)
+                .returning(|_, _| anyhow::Ok(()));
+            dart_command
+                .expect_pub_global_activate()
+                .withf(|_, package| package == ["unknown_package"])
+                .times(1)
+                .returning(|_, _| anyhow::bail!("Could not find package `unknown_package`"));
+            dart_command
+                .expect_pub_global_activate()
+                .withf(|_, package| package == ["very_good_cli"])
+                .times(1)
+                .returning(|_, _| anyhow::Ok(()));
+            let sdk_service =
+                RealSdkService::from(MockValidGitCommand, SystemClock::new(), MockFlutterCommand)
+                    .with_dart_command(dart_command);
+
+            // execution
+            try_run(
+                &["fenv", "install", "3.7.12"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert!(context.fenv_versions().join("3.7.12").is_dir());
+            assert!(output.stderr_to_string().contains(
+                "fenv: warning: Failed to activate `unknown_package`: \
+                Could not find package `unknown_package`\n"
+            ));
+        })
+    }
+
+    #[test]
+    pub fn test_install_precaches_given_or_configured_platforms() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_config_file()
+                .writeln(r#"{"precachePlatforms": ["android"]}"#)
+                .unwrap();
+            let precached = Arc::new(Mutex::new(vec![]));
+            let flutter_command = RecordingFlutterCommand {
+                precached: precached.clone(),
+                licenses_accepted: Arc::new(Mutex::new(vec![])),
+            };
+            let sdk_service =
+                RealSdkService::from(MockValidGitCommand, SystemClock::new(), flutter_command);
+
+            // execution
+            try_run(
+                &[
+                    "fenv",
+                    "install",
+                    "3.7.12",
+                    "--precache-web",
+                    "--precache-ios",
+                ],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+            try_run(
+                &["fenv", "install", "stable"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                *precached.lock().unwrap(),
+                [
+                    vec![PrecachePlatform::Ios, PrecachePlatform::Web],
+                    vec![PrecachePlatform::Android]
+                ]
+            );
+        })
+    }
+
+    #[test]
+    pub fn test_install_accepts_android_licenses_if_requested() {
+        test_with_context(|context, output| {
+            // setup
+            let licenses_accepted = Arc::new(Mutex::new(vec![]));
+            let flutter_command = RecordingFlutterCommand {
+                precached: Arc::new(Mutex::new(vec![])),
+                licenses_accepted: licenses_accepted.clone(),
+            };
+            let sdk_service =
+                RealSdkService::from(MockValidGitCommand, SystemClock::new(), flutter_command);
+
+            // execution
+            try_run(
+                &["fenv", "install", "3.7.12"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+            try_run(
+                &["fenv", "install", "stable", "--accept-android-licenses"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                *licenses_accepted.lock().unwrap(),
+                [context.fenv_sdk_root("stable").to_string()]
+            );
         })
     }
 
@@ -158,7 +917,10 @@ mod tests {
 
             // validation
             assert_eq!(output.stdout_to_string(), "");
-            assert_eq!(output.stderr_to_string(), "`stable` is already installed\n");
+            assert_eq!(
+                output.stderr_to_string(),
+                "fenv: warning: `stable` is already installed\n"
+            );
             assert!(context.fenv_versions().join("stable").is_dir())
         })
     }
@@ -278,4 +1040,156 @@ mod tests {
             assert!(output.stderr_to_string().is_empty());
         })
     }
+
+    #[test]
+    fn test_install_warns_if_tls_verification_is_skipped() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("stable")
+                .create_dir_all()
+                .unwrap();
+            context
+                .fenv_config_file()
+                .writeln(r#"{"tls": {"insecureSkipVerify": true}}"#)
+                .unwrap();
+            let sdk_service =
+                RealSdkService::from(MockValidGitCommand, SystemClock::new(), MockFlutterCommand);
+
+            // execution
+            try_run(
+                &["fenv", "install", "stable", "--ignore-installed", "--quiet"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                output.stderr_to_string(),
+                "fenv: warning: TLS certificate verification is disabled. \
+                The Flutter SDK may be tampered with by anyone on the network\n"
+            );
+        })
+    }
+
+    #[test]
+    fn test_install_sdk_fails_if_file_occupies_install_destination() {
+        test_with_context(|context, output| {
+            // setup
+            // Prepare a stray file which has the same name as the version to install.
+            context.fenv_versions().join("3.7.12").write("").unwrap();
+            let sdk_service =
+                RealSdkService::from(MockValidGitCommand, SystemClock::new(), MockFlutterCommand);
+
+            // execution
+            let result = try_run(
+                &["fenv", "install", "3.7.12"],
+                context,
+                &sdk_service,
+                output,
+            );
+
+            // validation
+            assert!(result.is_err());
+            assert_eq!(
+                result.err().unwrap().to_string(),
+                format!(
+                    "`{path}` already exists but is not a directory: remove it by `rm {path}` and try again",
+                    path = context.fenv_versions().join("3.7.12")
+                )
+            );
+            assert!(context.fenv_versions().join("3.7.12").is_file());
+        })
+    }
+
+    #[test]
+    fn test_install_sdk_uses_canonical_name_if_configured() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_config_file()
+                .writeln(r#"{"installNamePolicy": "canonical"}"#)
+                .unwrap();
+            let sdk_service =
+                RealSdkService::from(MockValidGitCommand, SystemClock::new(), MockFlutterCommand);
+
+            // execution
+            try_run(&["fenv", "install", "1.7.8"], context, &sdk_service, output).unwrap();
+
+            // validation
+            let installed = context.fenv_versions().join("1.7.8+hotfix.4");
+            let compatibility_link = context.fenv_versions().join("v1.7.8+hotfix.4");
+            assert!(installed.is_dir());
+            assert!(compatibility_link
+                .path()
+                .symlink_metadata()
+                .unwrap()
+                .is_symlink());
+            assert_eq!(
+                std::fs::read_link(compatibility_link.path()).unwrap(),
+                std::path::PathBuf::from("1.7.8+hotfix.4")
+            );
+        })
+    }
+
+    #[test]
+    fn test_install_with_dir_reads_version_file_in_given_directory() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_dir()
+                .join("packages")
+                .join("app")
+                .join(".flutter-version")
+                .write("3.7.12")
+                .unwrap();
+            let sdk_service =
+                RealSdkService::from(MockValidGitCommand, SystemClock::new(), MockFlutterCommand);
+
+            // execution
+            try_run(
+                &["fenv", "install", "--dir", "packages/app"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert!(context.fenv_versions().join("3.7.12").is_dir())
+        })
+    }
+
+    #[test]
+    fn test_install_with_dir_fails_if_no_version_file_exists_in_given_directory() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_dir()
+                .join("packages")
+                .join("app")
+                .create_dir_all()
+                .unwrap();
+            let sdk_service =
+                RealSdkService::from(MockValidGitCommand, SystemClock::new(), MockFlutterCommand);
+
+            // execution
+            let result = try_run(
+                &["fenv", "install", "--dir", "packages/app"],
+                context,
+                &sdk_service,
+                output,
+            );
+
+            // validation
+            assert!(result.is_err());
+            assert_eq!(
+                result.err().unwrap().to_string(),
+                "Could not find any local version file. Specify a version to install."
+            )
+        })
+    }
 }
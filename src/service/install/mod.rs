@@ -1 +1,2 @@
 pub mod install_service;
+pub mod prerequisites;
@@ -0,0 +1,150 @@
+use crate::{
+    context::FenvContext, external::git_command::is_git_available, util::platform::OperatingSystem,
+};
+use anyhow::bail;
+use is_executable::is_executable;
+
+/// The tools which `fenv install` requires on `PATH`.
+///
+/// The Flutter SDKs are cloned by `git`, and the shims are `bash` scripts. Without `git`, the
+/// release archives are downloaded by `curl` and extracted by `tar` and `xz` on Linux, or by
+/// `unzip` on the others. With `--precache`, `flutter` downloads the Dart SDK by `curl` and
+/// extracts it by `unzip` on its first run.
+fn prerequisites(os: OperatingSystem, should_precache: bool, has_git: bool) -> Vec<&'static str> {
+    let mut tools = vec![];
+    if has_git {
+        tools.push("git");
+    } else {
+        tools.push("curl");
+        match os {
+            OperatingSystem::Linux => tools.extend(["tar", "xz"]),
+            _ => tools.push("unzip"),
+        }
+    }
+    if os != OperatingSystem::Windows {
+        tools.push("bash");
+        if should_precache {
+            tools.extend(["curl", "unzip"]);
+        }
+    }
+    let mut unique = vec![];
+    for tool in tools {
+        if !unique.contains(&tool) {
+            unique.push(tool);
+        }
+    }
+    unique
+}
+
+/// Triggers a failure listing all the missing prerequisites at once, with the commands to
+/// install them.
+///
+/// Does nothing if `PATH` is empty, where the tools cannot be looked up.
+pub fn ensure_prerequisites(
+    context: &impl FenvContext,
+    should_precache: bool,
+) -> anyhow::Result<()> {
+    let path = context.path();
+    if path.is_empty() {
+        return anyhow::Ok(());
+    }
+    let os = OperatingSystem::current();
+    let missing: Vec<&str> = prerequisites(os, should_precache, is_git_available(context))
+        .into_iter()
+        .filter(|tool| !path.iter().any(|dir| is_executable(dir.join(tool))))
+        .collect();
+    if missing.is_empty() {
+        return anyhow::Ok(());
+    }
+    let names = missing
+        .iter()
+        .map(|tool| format!("`{tool}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    bail!(
+        "Missing prerequisites: {names} not found on PATH. Install them by:\n{}",
+        install_instructions(os, &missing.join(" "))
+    )
+}
+
+fn install_instructions(os: OperatingSystem, packages: &str) -> String {
+    let instructions: Vec<(&str, String)> = match os {
+        OperatingSystem::MacOs => vec![("Homebrew", format!("brew install {packages}"))],
+        OperatingSystem::Windows => vec![("winget", String::from("winget install Git.Git"))],
+        OperatingSystem::Linux | OperatingSystem::Unknown => vec![
+            ("Debian/Ubuntu", format!("sudo apt-get install {packages}")),
+            ("Fedora/RHEL", format!("sudo dnf install {packages}")),
+            ("Arch Linux", format!("sudo pacman -S {packages}")),
+            ("Alpine", format!("sudo apk add {packages}")),
+        ],
+    };
+    let width = instructions
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0)
+        + 1;
+    instructions
+        .iter()
+        .map(|(name, command)| format!("  {:width$} {command}", format!("{name}:")))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(unix)]
+#[cfg(test)]
+mod tests_unix {
+    use super::ensure_prerequisites;
+    use crate::{context::FenvContext, service::macros::test_with_context};
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn test_ensure_prerequisites() {
+        test_with_context(|context, _| {
+            // setup
+            let bin = context.home().join("bin");
+            for tool in ["git", "bash"] {
+                let path = bin.join(tool);
+                path.writeln("").unwrap();
+                std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+            }
+            let context = context.clone().with_path(&[bin]);
+
+            // execution & validation
+            assert!(ensure_prerequisites(&context, false).is_ok());
+            let message = ensure_prerequisites(&context, true)
+                .unwrap_err()
+                .to_string();
+            assert!(message.starts_with(
+                "Missing prerequisites: `curl`, `unzip` not found on PATH. Install them by:\n"
+            ));
+            #[cfg(target_os = "linux")]
+            assert!(message.contains("\n  Debian/Ubuntu: sudo apt-get install curl unzip\n"));
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_ensure_prerequisites_without_git() {
+        test_with_context(|context, _| {
+            // setup
+            let bin = context.home().join("bin");
+            for tool in ["bash", "curl"] {
+                let path = bin.join(tool);
+                path.writeln("").unwrap();
+                std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+            }
+            let context = context.clone().with_path(&[bin]);
+
+            // execution
+            let message = ensure_prerequisites(&context, true)
+                .unwrap_err()
+                .to_string();
+
+            // validation
+            assert!(message.starts_with(
+                "Missing prerequisites: `tar`, `xz`, `unzip` not found on PATH. Install them by:\n"
+            ));
+        })
+    }
+}
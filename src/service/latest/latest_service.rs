@@ -1,11 +1,14 @@
 use crate::{
     args::FenvLatestArgs,
     context::FenvContext,
-    sdk_service::{model::flutter_sdk::FlutterSdk, sdk_service::SdkService},
+    sdk_service::{model::flutter_sdk::FlutterSdk, results::LookupResult, sdk_service::SdkService},
     service::service::Service,
-    util::io::ConsoleOutput,
+    util::{
+        io::{ConsoleOutput, SilentFailure},
+        size::human_duration,
+    },
 };
-use std::result::Result::Ok;
+use std::{result::Result::Ok, time::Duration};
 
 pub struct FenvLatestService {
     pub args: FenvLatestArgs,
@@ -28,47 +31,157 @@ where
         sdk_service: &impl SdkService,
         output: &mut dyn ConsoleOutput<OUT, ERR>,
     ) -> anyhow::Result<()> {
-        #[allow(deprecated)]
-        let from_remote = self.args.from_remote || self.args.known;
-        let prefix = &self.args.prefix;
-
-        macro_rules! sdk_to_display_name {
-            ($sdk: expr) => {
-                match $sdk {
-                    crate::sdk_service::results::LookupResult::Found(sdk) => {
-                        std::result::Result::Ok(sdk.display_name())
-                    }
-                    crate::sdk_service::results::LookupResult::None => std::result::Result::Err(
-                        anyhow::anyhow!("Not found any matched flutter sdk version: `{prefix}`"),
-                    ),
-                    crate::sdk_service::results::LookupResult::Err(e) => {
-                        std::result::Result::Err(anyhow::anyhow!(e))
+        if self.args.json {
+            return self.print_json(context, sdk_service, output);
+        }
+        let mut staleness = None;
+        for prefix in &self.args.prefixes {
+            match self
+                .resolve(context, sdk_service, prefix, &mut staleness)
+                .and_then(|version_or_channel| {
+                    version_or_channel.ok_or_else(|| {
+                        anyhow::anyhow!("Not found any matched flutter sdk version: `{prefix}`")
+                    })
+                }) {
+                Ok(version_or_channel) => writeln!(output.stdout(), "{version_or_channel}")?,
+                Err(_) if output.options().quiet => {
+                    if self.args.exit_code {
+                        return Err(SilentFailure(1).into());
                     }
                 }
-            };
+                Err(e) => return Err(e),
+            }
         }
+        note_staleness(staleness, output)
+    }
+}
 
-        let version_or_channel: anyhow::Result<String> = if from_remote {
-            sdk_to_display_name!(sdk_service.find_latest_remote(context, prefix))
-        } else {
-            sdk_to_display_name!(sdk_service.find_latest_local(context, prefix))
+impl FenvLatestService {
+    /// The display name of the latest Flutter SDK matching `prefix`, or `None` if not found.
+    ///
+    /// With `--allow-stale`, the prefix is resolved from the cached list if the remote repository
+    /// is unreachable, and so are the following ones without retrying. `staleness` records the
+    /// age of the cached list and why the remote repository was unreachable.
+    fn resolve(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        prefix: &str,
+        staleness: &mut Option<Staleness>,
+    ) -> anyhow::Result<Option<String>> {
+        #[allow(deprecated)]
+        let from_remote = self.args.from_remote || self.args.known;
+        if !from_remote {
+            return display_name_of(sdk_service.find_latest_local(context, prefix));
+        }
+        let include_beta = self.args.include_beta;
+        if staleness.is_some() {
+            return match sdk_service.find_latest_stale_remote(context, prefix, include_beta) {
+                LookupResult::Found((sdk, _)) => Ok(Some(sdk.display_name())),
+                LookupResult::None => Ok(None),
+                LookupResult::Err(e) => Err(e),
+            };
+        }
+        if context.is_offline() {
+            // Fails with the explanation if nothing is cached.
+            let LookupResult::Found((sdk, age)) =
+                sdk_service.find_latest_stale_remote(context, prefix, include_beta)
+            else {
+                return display_name_of(sdk_service.find_latest_remote(
+                    context,
+                    prefix,
+                    include_beta,
+                ));
+            };
+            *staleness = Some(Staleness {
+                age,
+                reason: String::from("`--offline` forbids accessing the remote repository"),
+            });
+            return Ok(Some(sdk.display_name()));
+        }
+        let error = match sdk_service.find_latest_remote(context, prefix, include_beta) {
+            LookupResult::Err(e) if self.args.allow_stale => e,
+            remote => return display_name_of(remote),
         };
-        if version_or_channel.is_err() && self.args.quiet {
-            Ok(())
-        } else if let Ok(version_or_channel) = version_or_channel {
-            writeln!(output.stdout(), "{version_or_channel}")?;
-            Ok(())
-        } else {
-            version_or_channel.map(|_| ())
+        match sdk_service.find_latest_stale_remote(context, prefix, include_beta) {
+            LookupResult::Found((sdk, age)) => {
+                *staleness = Some(Staleness {
+                    age,
+                    reason: format!("the remote repository is unreachable: {error}"),
+                });
+                Ok(Some(sdk.display_name()))
+            }
+            _ => Err(error),
+        }
+    }
+
+    /// Prints a JSON object from each prefix to its latest version, or `null` if not found.
+    fn print_json<OUT: std::io::Write, ERR: std::io::Write>(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let mut versions = serde_json::Map::new();
+        let mut staleness = None;
+        for prefix in &self.args.prefixes {
+            let version_or_channel = self.resolve(context, sdk_service, prefix, &mut staleness)?;
+            versions.insert(prefix.clone(), serde_json::json!(version_or_channel));
+        }
+        writeln!(
+            output.stdout(),
+            "{}",
+            serde_json::to_string_pretty(&versions)?
+        )?;
+        note_staleness(staleness, output)?;
+        if self.args.exit_code && versions.values().any(|version| version.is_null()) {
+            return Err(SilentFailure(1).into());
         }
+        Ok(())
+    }
+}
+
+/// The cached list which answered instead of the unreachable remote repository, or for
+/// `--offline`.
+struct Staleness {
+    age: Duration,
+    reason: String,
+}
+
+fn note_staleness<OUT: std::io::Write, ERR: std::io::Write>(
+    staleness: Option<Staleness>,
+    output: &mut dyn ConsoleOutput<OUT, ERR>,
+) -> anyhow::Result<()> {
+    if let Some(Staleness { age, reason }) = staleness {
+        output.warning(&format!(
+            "Answered from the list of the available versions cached {} ago, because {reason}",
+            human_duration(age)
+        ))?;
     }
+    Ok(())
 }
 
+fn display_name_of(sdk: LookupResult<impl FlutterSdk>) -> anyhow::Result<Option<String>> {
+    match sdk {
+        LookupResult::Found(sdk) => Ok(Some(sdk.display_name())),
+        LookupResult::None => Ok(None),
+        LookupResult::Err(e) => Err(e),
+    }
+}
 #[cfg(test)]
 #[allow(deprecated)]
 mod tests {
     use super::*;
-    use crate::{sdk_service::sdk_service::RealSdkService, service::macros::test_with_context};
+    use crate::{
+        define_mock_valid_git_command,
+        external::{flutter_command::FlutterCommandImpl, git_command::MockGitCommand},
+        sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context,
+        try_run,
+        util::chrono_wrapper::SystemClock,
+    };
+
+    define_mock_valid_git_command!();
 
     fn setup_installed_versions<'a>(context: &impl FenvContext) {
         let versions = context.fenv_versions();
@@ -101,8 +214,11 @@ mod tests {
             let args = FenvLatestArgs {
                 from_remote: false,
                 known: false,
-                quiet: false,
-                prefix: "v1".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["v1".to_string()],
             };
             let service = FenvLatestService::new(args);
 
@@ -123,8 +239,11 @@ mod tests {
             let args = FenvLatestArgs {
                 from_remote: false,
                 known: false,
-                quiet: false,
-                prefix: "1".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["1".to_string()],
             };
             let service = FenvLatestService::new(args);
 
@@ -145,8 +264,11 @@ mod tests {
             let args = FenvLatestArgs {
                 from_remote: false,
                 known: false,
-                quiet: false,
-                prefix: "1.1".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["1.1".to_string()],
             };
             let service = FenvLatestService::new(args);
 
@@ -167,8 +289,11 @@ mod tests {
             let args = FenvLatestArgs {
                 from_remote: false,
                 known: false,
-                quiet: false,
-                prefix: "v1.4".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["v1.4".to_string()],
             };
             let service = FenvLatestService::new(args);
 
@@ -189,8 +314,11 @@ mod tests {
             let args = FenvLatestArgs {
                 from_remote: false,
                 known: false,
-                quiet: false,
-                prefix: "1.4".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["1.4".to_string()],
             };
             let service = FenvLatestService::new(args);
 
@@ -211,8 +339,11 @@ mod tests {
             let args = FenvLatestArgs {
                 from_remote: false,
                 known: false,
-                quiet: false,
-                prefix: "1.4.5".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["1.4.5".to_string()],
             };
             let service = FenvLatestService::new(args);
 
@@ -233,8 +364,11 @@ mod tests {
             let args = FenvLatestArgs {
                 from_remote: false,
                 known: false,
-                quiet: false,
-                prefix: "3".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["3".to_string()],
             };
             let service = FenvLatestService::new(args);
 
@@ -255,8 +389,11 @@ mod tests {
             let args = FenvLatestArgs {
                 from_remote: false,
                 known: false,
-                quiet: false,
-                prefix: "3.1".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["3.1".to_string()],
             };
             let service = FenvLatestService::new(args);
 
@@ -277,8 +414,11 @@ mod tests {
             let args = FenvLatestArgs {
                 from_remote: false,
                 known: false,
-                quiet: false,
-                prefix: "3.10".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["3.10".to_string()],
             };
             let service = FenvLatestService::new(args);
 
@@ -299,8 +439,11 @@ mod tests {
             let args = FenvLatestArgs {
                 from_remote: false,
                 known: false,
-                quiet: false,
-                prefix: "3.10.9".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["3.10.9".to_string()],
             };
             let service = FenvLatestService::new(args);
 
@@ -321,8 +464,11 @@ mod tests {
             let args = FenvLatestArgs {
                 from_remote: false,
                 known: false,
-                quiet: false,
-                prefix: "stable".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["stable".to_string()],
             };
             let service = FenvLatestService::new(args);
 
@@ -343,8 +489,11 @@ mod tests {
             let args = FenvLatestArgs {
                 from_remote: false,
                 known: false,
-                quiet: false,
-                prefix: "m".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["m".to_string()],
             };
             let service = FenvLatestService::new(args);
 
@@ -365,8 +514,11 @@ mod tests {
             let args = FenvLatestArgs {
                 from_remote: false,
                 known: false,
-                quiet: false,
-                prefix: "unknown".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["unknown".to_string()],
             };
             let service = FenvLatestService::new(args);
 
@@ -390,10 +542,14 @@ mod tests {
             let args = FenvLatestArgs {
                 from_remote: false,
                 known: false,
-                quiet: true,
-                prefix: "1.2.3.4".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["1.2.3.4".to_string()],
             };
             let service = FenvLatestService::new(args);
+            output.options_mut().quiet = true;
 
             // execution
             service
@@ -405,14 +561,38 @@ mod tests {
         });
     }
 
+    #[test]
+    pub fn test_latest_find_unknown_fails_silently_when_quiet_and_exit_code_are_enabled() {
+        test_with_context(|context, output| {
+            setup_installed_versions(context);
+
+            // execution
+            let error = try_run(
+                &["fenv", "latest", "--quiet", "--exit-code", "1.2.3.4"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap_err();
+
+            // validation
+            assert!(error.is::<SilentFailure>());
+            assert_eq!("", output.stdout_to_string());
+            assert_eq!("", output.stderr_to_string());
+        });
+    }
+
     #[test]
     pub fn test_latest_remote_find_v1() {
         test_with_context(|context, output| {
             let args = FenvLatestArgs {
                 from_remote: true,
                 known: false,
-                quiet: false,
-                prefix: "v1".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["v1".to_string()],
             };
             let service = FenvLatestService::new(args);
 
@@ -432,8 +612,11 @@ mod tests {
             let args = FenvLatestArgs {
                 from_remote: true,
                 known: false,
-                quiet: false,
-                prefix: "1".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["1".to_string()],
             };
             let service = FenvLatestService::new(args);
 
@@ -453,8 +636,11 @@ mod tests {
             let args = FenvLatestArgs {
                 from_remote: true,
                 known: false,
-                quiet: false,
-                prefix: "1.1".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["1.1".to_string()],
             };
             let service = FenvLatestService::new(args);
 
@@ -475,8 +661,11 @@ mod tests {
             let args = FenvLatestArgs {
                 from_remote: true,
                 known: false,
-                quiet: false,
-                prefix: "v1.4".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["v1.4".to_string()],
             };
             let service = FenvLatestService::new(args);
 
@@ -497,8 +686,11 @@ mod tests {
             let args = FenvLatestArgs {
                 from_remote: true,
                 known: false,
-                quiet: false,
-                prefix: "1.4".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["1.4".to_string()],
             };
             let service = FenvLatestService::new(args);
 
@@ -519,8 +711,11 @@ mod tests {
             let args = FenvLatestArgs {
                 from_remote: true,
                 known: false,
-                quiet: false,
-                prefix: "1.4.5".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["1.4.5".to_string()],
             };
             let service = FenvLatestService::new(args);
 
@@ -540,8 +735,11 @@ mod tests {
             let args = FenvLatestArgs {
                 from_remote: true,
                 known: false,
-                quiet: false,
-                prefix: "stable".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["stable".to_string()],
             };
             let service = FenvLatestService::new(args);
 
@@ -561,8 +759,11 @@ mod tests {
             let args = FenvLatestArgs {
                 from_remote: true,
                 known: false,
-                quiet: false,
-                prefix: "m".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["m".to_string()],
             };
             let service = FenvLatestService::new(args);
 
@@ -582,8 +783,11 @@ mod tests {
             let args = FenvLatestArgs {
                 from_remote: true,
                 known: false,
-                quiet: false,
-                prefix: "unknown".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["unknown".to_string()],
             };
             let service = FenvLatestService::new(args);
 
@@ -606,10 +810,14 @@ mod tests {
             let args = FenvLatestArgs {
                 from_remote: true,
                 known: false,
-                quiet: true,
-                prefix: "1.2.3.4".to_string(),
+                exit_code: false,
+                include_beta: false,
+                allow_stale: false,
+                json: false,
+                prefixes: vec!["1.2.3.4".to_string()],
             };
             let service = FenvLatestService::new(args);
+            output.options_mut().quiet = true;
 
             // execution
             service
@@ -620,4 +828,275 @@ mod tests {
             assert_eq!("", output.stdout_to_string())
         });
     }
+
+    #[test]
+    pub fn test_latest_remote_find_3_with_or_without_beta() {
+        test_with_context(|context, output| {
+            // setup
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(
+                &["fenv", "latest", "--remote", "3"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+            try_run(
+                &["fenv", "latest", "--remote", "--include-beta", "3"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+            let result = try_run(
+                &["fenv", "latest", "--remote", "3.10"],
+                context,
+                &sdk_service,
+                output,
+            );
+
+            // validation
+            assert_eq!("3.7.12\n3.10.0-11.0.pre\n", output.stdout_to_string());
+            assert_eq!(
+                "Not found any matched flutter sdk version: `3.10`",
+                result.unwrap_err().to_string()
+            );
+        });
+    }
+
+    #[test]
+    pub fn test_latest_resolves_dual_form_tags_identically() {
+        test_with_context(|context, output| {
+            // setup
+            let versions = context.fenv_versions();
+            versions.join("v1.17.5").create_dir_all().unwrap();
+            versions.join("1.17.5").create_dir_all().unwrap();
+            let mut git_command = crate::external::git_command::MockGitCommand::new();
+            git_command
+                .expect_list_remote_sdks_by_tags()
+                .returning(|_| {
+                    anyhow::Ok(String::from(
+                        "1111111111111111111111111111111111111111\trefs/tags/1.17.5\n\
+                        1111111111111111111111111111111111111111\trefs/tags/v1.17.5\n\
+                        2222222222222222222222222222222222222222\trefs/tags/v1.4.19",
+                    ))
+                });
+            git_command
+                .expect_list_remote_sdks_by_branches()
+                .returning(|_| anyhow::Ok(String::new()));
+            let sdk_service =
+                RealSdkService::from(git_command, SystemClock::new(), FlutterCommandImpl::new());
+
+            // execution
+            for args in [
+                &["fenv", "latest", "1"][..],
+                &["fenv", "latest", "v1.17"],
+                &["fenv", "latest", "--remote", "1"],
+                &["fenv", "latest", "--remote", "v1.17"],
+            ] {
+                try_run(args, context, &sdk_service, output).unwrap();
+            }
+
+            // validation
+            assert_eq!(
+                "1.17.5\n1.17.5\n1.17.5\n1.17.5\n",
+                output.stdout_to_string()
+            );
+        });
+    }
+
+    #[test]
+    pub fn test_latest_find_multiple_prefixes() {
+        test_with_context(|context, output| {
+            // setup
+            setup_installed_versions(context);
+
+            // execution
+            try_run(
+                &["fenv", "latest", "3.1", "stable", "v1.4"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                "3.1.10\nstable\nv1.4.9-hotfix.1\n",
+                output.stdout_to_string()
+            );
+        });
+    }
+
+    #[test]
+    pub fn test_latest_find_multiple_prefixes_as_json() {
+        test_with_context(|context, output| {
+            // setup
+            setup_installed_versions(context);
+
+            // execution
+            let result = try_run(
+                &["fenv", "latest", "--json", "--exit-code", "3.10", "2", "m"],
+                context,
+                &RealSdkService::new(),
+                output,
+            );
+
+            // validation
+            assert!(result.is_err());
+            let json: serde_json::Value = serde_json::from_str(&output.stdout_to_string()).unwrap();
+            assert_eq!(
+                json,
+                serde_json::json!({"3.10": "3.10.10", "2": null, "m": "master"})
+            );
+        });
+    }
+
+    #[test]
+    pub fn test_latest_find_version_ranges() {
+        test_with_context(|context, output| {
+            // setup
+            setup_installed_versions(context);
+
+            // execution
+            try_run(
+                &[
+                    "fenv",
+                    "latest",
+                    "^3.1",
+                    ">=3.1 <3.10",
+                    "3.1.*",
+                    "<=1.20",
+                    "^1.4.5",
+                ],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                "3.10.10\n3.1.10\n3.1.10\n1.20.4\n1.22.6\n",
+                output.stdout_to_string()
+            );
+        });
+    }
+
+    #[test]
+    pub fn test_latest_remote_allow_stale_answers_from_expired_cache() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_cache()
+                .join(".remote_list")
+                .write(
+                    r#"{
+                      "expires_at": "2020-01-01T00:05:00+00:00",
+                      "list": [
+                        {
+                          "kind": {"Tag": {"major": 3, "minor": 19, "patch": 6, "hotfix": 0}},
+                          "sha": "54e66469a933b60ddf175f858f82eaeb97e48c8d",
+                          "short": "3.19.6",
+                          "long": "refs/tags/3.19.6"
+                        },
+                        {
+                          "kind": {"Head": "stable"},
+                          "sha": "761747bfc538b5af34aa0d3fac380f1bc331ec49",
+                          "short": "stable",
+                          "long": "refs/heads/stable"
+                        }
+                      ]
+                    }"#,
+                )
+                .unwrap();
+            let mut git_command = MockGitCommand::new();
+            git_command
+                .expect_list_remote_sdks_by_tags()
+                .times(1)
+                .returning(|_| anyhow::bail!("Could not resolve host: github.com"));
+            let sdk_service =
+                RealSdkService::from(git_command, SystemClock::new(), FlutterCommandImpl::new());
+
+            // execution
+            try_run(
+                &["fenv", "latest", "--remote", "--allow-stale", "3", "s"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "3.19.6\nstable\n");
+            let stderr = output.stderr_to_string();
+            assert!(stderr.starts_with(
+                "fenv: warning: Answered from the list of the available versions cached "
+            ));
+            assert!(stderr.ends_with(
+                "d ago, because the remote repository is unreachable: \
+                Could not resolve host: github.com\n"
+            ));
+        });
+    }
+
+    #[test]
+    pub fn test_latest_remote_offline_answers_from_cache_without_network() {
+        test_with_context(|context, output| {
+            // setup
+            let sdk_service = RealSdkService::from(
+                MockGitCommand::new(),
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+            let error = try_run(
+                &["fenv", "--offline", "latest", "--remote", "3"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap_err();
+            context
+                .fenv_cache()
+                .join(".remote_list")
+                .write(
+                    r#"{
+                      "expires_at": "2020-01-01T00:05:00+00:00",
+                      "list": [
+                        {
+                          "kind": {"Tag": {"major": 3, "minor": 19, "patch": 6, "hotfix": 0}},
+                          "sha": "54e66469a933b60ddf175f858f82eaeb97e48c8d",
+                          "short": "3.19.6",
+                          "long": "refs/tags/3.19.6"
+                        }
+                      ]
+                    }"#,
+                )
+                .unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "--offline", "latest", "--remote", "3"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert!(error
+                .to_string()
+                .starts_with("No list of the available Flutter SDKs is cached"));
+            assert_eq!(output.stdout_to_string(), "3.19.6\n");
+            assert!(output
+                .stderr_to_string()
+                .ends_with("d ago, because `--offline` forbids accessing the remote repository\n"));
+        });
+    }
 }
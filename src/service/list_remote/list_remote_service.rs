@@ -3,14 +3,17 @@ use crate::{
     context::FenvContext,
     sdk_service::{
         model::{
-            flutter_sdk::FlutterSdk, local_flutter_sdk::LocalFlutterSdk,
-            remote_flutter_sdk::RemoteFlutterSdk,
+            flutter_sdk::FlutterSdk,
+            local_flutter_sdk::LocalFlutterSdk,
+            remote_flutter_sdk::{GitRefsKind, RemoteFlutterSdk},
         },
+        results::{LookupResult, VersionFileReadResult},
         sdk_service::SdkService,
     },
     service::service::Service,
     util::io::ConsoleOutput,
 };
+use serde::Serialize;
 use std::collections::HashSet;
 
 pub struct FenvListRemoteService {
@@ -34,7 +37,15 @@ where
         sdk_service: &impl SdkService,
         output: &mut dyn ConsoleOutput<OUT, ERR>,
     ) -> anyhow::Result<()> {
-        execute_list_remote_command(context, output.stdout(), sdk_service, self.args.bare)
+        execute_list_remote_command(
+            context,
+            output.stdout(),
+            sdk_service,
+            self.args.bare,
+            self.args.with_sha,
+            self.args.include_beta,
+            self.args.json,
+        )
     }
 }
 
@@ -43,46 +54,173 @@ fn execute_list_remote_command(
     stdout: &mut impl std::io::Write,
     sdk_service: &impl SdkService,
     bare: bool,
+    with_sha: bool,
+    include_beta: bool,
+    json: bool,
 ) -> anyhow::Result<()> {
-    let remote_sdks = sdk_service.get_available_remote_sdk_list(context)?;
+    let remote_sdks = sdk_service.get_available_remote_sdk_list(context, include_beta)?;
+    // The installed SDKs are never cached so that the markers follow `fenv install` and
+    // `fenv uninstall` immediately even while the remote list is cached.
     let installed_sdks = sdk_service.get_installed_sdk_list(context)?;
-    display_remote_sdks(stdout, &remote_sdks, &installed_sdks, bare)
+    if json {
+        return display_remote_sdks_in_json(stdout, &remote_sdks, &installed_sdks);
+    }
+    let selected_refs_name = if bare {
+        None
+    } else {
+        find_selected_refs_name(context, sdk_service)
+    };
+    display_remote_sdks(
+        stdout,
+        &remote_sdks,
+        &installed_sdks,
+        selected_refs_name.as_deref(),
+        bare,
+        with_sha,
+    )
 }
 
+/// Finds the refs name of the SDK which `$FENV_VERSION` or the nearest version file from the
+/// current directory resolves to, regardless of whether the version file's one is installed.
+fn find_selected_refs_name(
+    context: &impl FenvContext,
+    sdk_service: &impl SdkService,
+) -> Option<String> {
+    if let Some(prefix) = context.shell_version() {
+        return match sdk_service.find_latest_local(context, &prefix) {
+            LookupResult::Found(sdk) => Some(sdk.refs_name()),
+            LookupResult::None | LookupResult::Err(_) => None,
+        };
+    }
+    match sdk_service.read_nearest_version_file(context, &context.fenv_dir()) {
+        VersionFileReadResult::FoundAndInstalled(summary) => {
+            Some(summary.latest_local_sdk.refs_name())
+        }
+        VersionFileReadResult::FoundButNotInstalled(summary) => {
+            summary.latest_remote_sdk.map(|sdk| sdk.long)
+        }
+        VersionFileReadResult::NotFoundVersionFile | VersionFileReadResult::Err { .. } => None,
+    }
+}
+
+/// Displays the given `remote_sdks`.
+///
+/// Unless `bare` is set, the selected SDK is marked with `>` and the other installed SDKs are
+/// marked with `*`. If `bare` and `with_sha` are set, the full commit SHA follows each name
+/// after a tab.
 fn display_remote_sdks(
     stdout: &mut impl std::io::Write,
     remote_sdks: &[RemoteFlutterSdk],
     installed_sdks: &[LocalFlutterSdk],
+    selected_refs_name: Option<&str>,
     bare: bool,
+    with_sha: bool,
 ) -> anyhow::Result<()> {
     let installed_sdks_set: HashSet<String> =
         installed_sdks.iter().map(|sdk| sdk.refs_name()).collect();
 
     for sdk in remote_sdks {
-        if bare {
+        if bare && with_sha {
+            writeln!(stdout, "{}\t{}", sdk.display_name(), sdk.sha)?;
+        } else if bare {
             writeln!(stdout, "{}", sdk.display_name())?;
         } else {
-            let is_installed = installed_sdks_set.contains(&sdk.long);
-            if is_installed {
-                writeln!(stdout, "* {:18} [{}]", sdk.display_name(), &sdk.sha[..7])?;
+            let marker = if selected_refs_name == Some(&sdk.long[..]) {
+                '>'
+            } else if installed_sdks_set.contains(&sdk.long) {
+                '*'
             } else {
-                writeln!(stdout, "  {:18} [{}]", sdk.display_name(), &sdk.sha[..7])?;
+                ' '
+            };
+            write!(
+                stdout,
+                "{marker} {:18} [{}]",
+                sdk.display_name(),
+                &sdk.sha[..7]
+            )?;
+            // Only the SDKs listed by the release manifest have the details.
+            if let Some(release) = &sdk.release {
+                if let Some(dart_sdk_version) = &release.dart_sdk_version {
+                    write!(stdout, " Dart {dart_sdk_version}")?;
+                }
+                if let Some(release_date) = &release.release_date {
+                    write!(
+                        stdout,
+                        " ({})",
+                        release_date.get(..10).unwrap_or(release_date)
+                    )?;
+                }
             }
+            writeln!(stdout)?;
         }
     }
     Ok(())
 }
 
+/// An entry of `--json` output.
+#[derive(Serialize)]
+struct RemoteSdkEntry<'a> {
+    name: String,
+    sha: &'a str,
+    kind: &'static str,
+    #[serde(rename = "ref")]
+    refs_name: &'a str,
+    installed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    channel: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dart_sdk_version: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    release_date: Option<&'a str>,
+}
+
+/// Displays the given `remote_sdks` as a JSON array.
+fn display_remote_sdks_in_json(
+    stdout: &mut impl std::io::Write,
+    remote_sdks: &[RemoteFlutterSdk],
+    installed_sdks: &[LocalFlutterSdk],
+) -> anyhow::Result<()> {
+    let installed_sdks_set: HashSet<String> =
+        installed_sdks.iter().map(|sdk| sdk.refs_name()).collect();
+    let entries: Vec<RemoteSdkEntry> = remote_sdks
+        .iter()
+        .map(|sdk| RemoteSdkEntry {
+            name: sdk.display_name(),
+            sha: &sdk.sha,
+            kind: match sdk.kind {
+                GitRefsKind::Tag(_) => "tag",
+                GitRefsKind::Head(_) => "branch",
+            },
+            refs_name: &sdk.long,
+            installed: installed_sdks_set.contains(&sdk.long),
+            channel: sdk.release.as_ref().map(|release| &release.channel[..]),
+            dart_sdk_version: sdk
+                .release
+                .as_ref()
+                .and_then(|release| release.dart_sdk_version.as_deref()),
+            release_date: sdk
+                .release
+                .as_ref()
+                .and_then(|release| release.release_date.as_deref()),
+        })
+        .collect();
+    writeln!(stdout, "{}", serde_json::to_string_pretty(&entries)?)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
         define_mock_valid_git_command,
-        external::{flutter_command::FlutterCommandImpl, git_command::MockGitCommand},
+        external::{
+            downloader::MockDownloader, flutter_command::FlutterCommandImpl,
+            git_command::MockGitCommand,
+        },
         sdk_service::sdk_service::RealSdkService,
         service::macros::test_with_context,
         try_run,
-        util::chrono_wrapper::SystemClock,
+        util::{chrono_wrapper::SystemClock, io::BufferedOutput},
     };
 
     define_mock_valid_git_command!();
@@ -184,4 +322,412 @@ mod tests {
             assert_eq!(output.stdout_to_string(), expected);
         });
     }
+
+    #[test]
+    fn text_list_remote_sdks_marks_selected_sdk() {
+        test_with_context(|context, output| {
+            // setup
+            // Make `stable` installed.
+            context
+                .fenv_versions()
+                .join("stable")
+                .create_dir_all()
+                .unwrap();
+            // Select `3.7.x`, which is not installed.
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("3.7")
+                .unwrap();
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(&["fenv", "list-remote"], context, &sdk_service, output).unwrap();
+
+            // validation
+            let expected = read_resource_file(
+                "resources/test/install_service/install-list-result-without-bare.txt",
+            )
+            .unwrap()
+            .replace("  3.7.12             [", "> 3.7.12             [");
+            assert_eq!(output.stdout_to_string(), expected);
+        });
+    }
+
+    #[test]
+    fn text_list_remote_sdks_marks_selected_sdk_prior_to_installed_one() {
+        test_with_context(|context, output| {
+            // setup
+            // Make `stable` installed and selected.
+            context
+                .fenv_versions()
+                .join("stable")
+                .create_dir_all()
+                .unwrap();
+            context.fenv_global_version_file().writeln("s").unwrap();
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(&["fenv", "list-remote"], context, &sdk_service, output).unwrap();
+
+            // validation
+            let expected = read_resource_file(
+                "resources/test/install_service/install-list-result-without-bare.txt",
+            )
+            .unwrap()
+            .replace("* stable", "> stable");
+            assert_eq!(output.stdout_to_string(), expected);
+        });
+    }
+
+    #[test]
+    fn text_list_remote_sdks_reflects_uninstall_with_cached_list() {
+        test_with_context(|context, output| {
+            // setup
+            // Cache the list, and make `3.7.12` and `stable` installed.
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+            try_run(
+                &["fenv", "list-remote", "--bare"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+            output.stdout().clear();
+            for version in ["3.7.12", "stable"] {
+                context
+                    .fenv_versions()
+                    .join(version)
+                    .create_dir_all()
+                    .unwrap();
+            }
+            // Fails if the cached list is not used.
+            let sdk_service = RealSdkService::from(
+                MockGitCommand::new(),
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+            try_run(&["fenv", "list-remote"], context, &sdk_service, output).unwrap();
+            assert!(output.stdout_to_string().contains("* 3.7.12             ["));
+            output.stdout().clear();
+
+            // execution
+            try_run(
+                &["fenv", "uninstall", "3.7.12"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+            output.stdout().clear();
+            try_run(&["fenv", "list-remote"], context, &sdk_service, output).unwrap();
+
+            // validation
+            let expected = read_resource_file(
+                "resources/test/install_service/install-list-result-without-bare.txt",
+            )
+            .unwrap();
+            assert_eq!(output.stdout_to_string(), expected);
+        });
+    }
+
+    #[test]
+    fn text_list_remote_sdks_does_not_mark_incomplete_installation() {
+        test_with_context(|context, output| {
+            // setup
+            for version in ["3.7.12", "stable"] {
+                context
+                    .fenv_versions()
+                    .join(version)
+                    .create_dir_all()
+                    .unwrap();
+            }
+            context
+                .fenv_versions()
+                .join(".install_3.7.12")
+                .writeln("")
+                .unwrap();
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(&["fenv", "list-remote"], context, &sdk_service, output).unwrap();
+
+            // validation
+            let expected = read_resource_file(
+                "resources/test/install_service/install-list-result-without-bare.txt",
+            )
+            .unwrap();
+            assert_eq!(output.stdout_to_string(), expected);
+        });
+    }
+
+    #[test]
+    fn test_list_remote_sdks_with_bare_and_with_sha_options() {
+        test_with_context(|context, output| {
+            // setup
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(
+                &["fenv", "list-remote", "--bare", "--with-sha"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            let stdout = output.stdout_to_string();
+            let expected = read_resource_file(
+                "resources/test/install_service/install-list-result-with-bare.txt",
+            )
+            .unwrap();
+            let names: Vec<&str> = stdout
+                .lines()
+                .map(|line| line.split_once('\t').unwrap().0)
+                .collect();
+            assert_eq!(names, expected.lines().collect::<Vec<&str>>());
+            assert!(stdout
+                .lines()
+                .any(|line| line == "3.7.12\t4d9e56e694b656610ab87fcf2efbcd226e0ed8cf"));
+            assert!(stdout
+                .lines()
+                .any(|line| line == "stable\t4d9e56e694b656610ab87fcf2efbcd226e0ed8cf"));
+        });
+    }
+
+    #[test]
+    fn test_list_remote_sdks_warns_if_tls_verification_is_skipped() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_config_file()
+                .writeln(r#"{"tls": {"insecureSkipVerify": true}}"#)
+                .unwrap();
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(
+                &["fenv", "list-remote", "--bare", "--quiet"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                output.stderr_to_string(),
+                "fenv: warning: TLS certificate verification is disabled. \
+                The Flutter SDK may be tampered with by anyone on the network\n"
+            );
+        });
+    }
+
+    #[test]
+    fn test_list_remote_sdks_in_json() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("3.7.12")
+                .create_dir_all()
+                .unwrap();
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(
+                &["fenv", "list-remote", "--json"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            let json: serde_json::Value = serde_json::from_str(&output.stdout_to_string()).unwrap();
+            let entries = json.as_array().unwrap();
+            let expected = read_resource_file(
+                "resources/test/install_service/install-list-result-with-bare.txt",
+            )
+            .unwrap();
+            assert_eq!(entries.len(), expected.lines().count());
+            let entry_of = |name: &str| {
+                entries
+                    .iter()
+                    .find(|entry| entry["name"] == name)
+                    .unwrap()
+                    .clone()
+            };
+            let entry = entry_of("3.7.12");
+            assert_eq!(entry["kind"], "tag");
+            assert_eq!(entry["ref"], "refs/tags/3.7.12");
+            assert_eq!(entry["installed"], true);
+            assert_eq!(entry["sha"].as_str().unwrap().len(), 40);
+            let entry = entry_of("stable");
+            assert_eq!(entry["kind"], "branch");
+            assert_eq!(entry["ref"], "refs/heads/stable");
+            assert_eq!(entry["installed"], false);
+        });
+    }
+
+    #[test]
+    fn test_list_remote_sdks_with_include_beta_option() {
+        test_with_context(|context, output| {
+            // setup
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(
+                &["fenv", "list-remote", "--bare", "--include-beta"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            let stdout = output.stdout_to_string();
+            let lines: Vec<&str> = stdout.lines().map(|line| line.trim()).collect();
+            let position_of = |name: &str| lines.iter().position(|line| *line == name).unwrap();
+            assert!(position_of("3.7.12") < position_of("3.10.0-1.0.pre"));
+            assert!(position_of("3.10.0-1.0.pre") < position_of("3.10.0-11.0.pre"));
+
+            // without `--include-beta`
+            let mut output = BufferedOutput::new();
+            try_run(
+                &["fenv", "list-remote", "--bare"],
+                context,
+                &sdk_service,
+                &mut output,
+            )
+            .unwrap();
+            let stdout = output.stdout_to_string();
+            assert!(stdout.contains("3.7.12"));
+            assert!(!stdout.contains(".pre"));
+        });
+    }
+
+    #[test]
+    fn test_list_remote_sdks_from_release_manifest() {
+        test_with_context(|context, output| {
+            // setup
+            let manifest = serde_json::json!({
+                "current_release": {"stable": "761747bfc538b5af34aa0d3fac380f1bc331ec49"},
+                "releases": [
+                    {
+                        "hash": "761747bfc538b5af34aa0d3fac380f1bc331ec49",
+                        "channel": "stable",
+                        "version": "3.22.2",
+                        "dart_sdk_version": "3.4.3",
+                        "release_date": "2024-06-06T18:40:14.437459Z",
+                        "archive": "stable/linux/flutter_linux_3.22.2-stable.tar.xz",
+                        "sha256": "0000",
+                    },
+                ]
+            })
+            .to_string();
+            let mut downloader = MockDownloader::new();
+            downloader
+                .expect_fetch()
+                .returning(move |_, _| anyhow::Ok(manifest.clone()));
+            let mut git_command = MockGitCommand::new();
+            git_command.expect_list_remote_sdks_by_tags().never();
+            git_command
+                .expect_list_remote_sdks_by_branches()
+                .times(1)
+                .returning(|_| {
+                    anyhow::Ok(String::from(
+                        "4d9e56e694b656610ab87fcf2efbcd226e0ed8cf\trefs/heads/master",
+                    ))
+                });
+            let sdk_service =
+                RealSdkService::from(git_command, SystemClock::new(), FlutterCommandImpl::new())
+                    .with_downloader(downloader);
+
+            // execution
+            try_run(&["fenv", "list-remote"], context, &sdk_service, output).unwrap();
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                "  3.22.2             [761747b] Dart 3.4.3 (2024-06-06)\n\
+                 \x20 master             [4d9e56e]\n"
+            );
+        });
+    }
+
+    #[test]
+    fn test_list_remote_sdks_of_fork_configured_by_project() {
+        test_with_context(|context, output| {
+            // setup
+            let fork = "https://git.example.com/mobile/flutter.git";
+            context
+                .fenv_dir()
+                .join(".fenv.toml")
+                .writeln(format!("[flutter]\ngit-url = \"{fork}\""))
+                .unwrap();
+            let mut git_command = MockGitCommand::new();
+            git_command
+                .expect_list_remote_sdks_by_tags()
+                .withf(move |remote| remote.url == fork)
+                .times(1)
+                .returning(|_| {
+                    anyhow::Ok(String::from(
+                        "fd9d2d5d2e5dc3a7e4e5a5d2e5dc3a7e4e5a5d2e\trefs/tags/3.7.12",
+                    ))
+                });
+            git_command
+                .expect_list_remote_sdks_by_branches()
+                .withf(move |remote| remote.url == fork)
+                .times(1)
+                .returning(|_| anyhow::Ok(String::new()));
+            let sdk_service =
+                RealSdkService::from(git_command, SystemClock::new(), FlutterCommandImpl::new());
+
+            // execution
+            try_run(
+                &["fenv", "list-remote", "--bare"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "3.7.12\n");
+        });
+    }
 }
@@ -1,9 +1,13 @@
 use crate::{
     args::FenvLocalArgs,
     context::FenvContext,
-    sdk_service::{results::LookupResult, sdk_service::SdkService},
-    service::service::Service,
-    util::io::ConsoleOutput,
+    sdk_service::{
+        latest_keyword::{self, LatestKeyword},
+        results::LookupResult,
+        sdk_service::SdkService,
+    },
+    service::{service::Service, start_dir::StartDir},
+    util::{io::ConsoleOutput, path_like::PathLike},
 };
 use anyhow::bail;
 use std::io::Write;
@@ -29,17 +33,10 @@ where
         sdk_service: &impl SdkService,
         output: &mut dyn ConsoleOutput<OUT, ERR>,
     ) -> anyhow::Result<()> {
+        let start_dir = self.args.start_dir.resolve_start_dir(context);
         match &self.args.prefix {
-            Some(prefix) => set_local_version(context, sdk_service, prefix),
-            None => {
-                if self.args.symlink {
-                    writeln!(
-                        output.stderr(),
-                        "fenv: info: `--symlink` option is deprecated. For IDE support, use `fenv workspace` instead."
-                    )?;
-                }
-                show_local_version(context, sdk_service, output)
-            }
+            Some(prefix) => set_local_version(context, sdk_service, output, &start_dir, prefix),
+            None => show_local_version(context, sdk_service, output, &start_dir),
         }
     }
 }
@@ -48,23 +45,29 @@ fn show_local_version<OUT: Write, ERR: Write>(
     context: &impl FenvContext,
     sdk_service: &impl SdkService,
     output: &mut dyn ConsoleOutput<OUT, ERR>,
+    start_dir: &PathLike,
 ) -> anyhow::Result<()> {
-    let result = sdk_service.read_nearest_local_version(context, &context.fenv_dir());
+    let result = sdk_service.read_nearest_local_version(context, start_dir);
     let summary = sdk_service.ensure_sdk_is_available(&result)?;
     writeln!(output.stdout(), "{}", summary.store_version_prefix)?;
     anyhow::Ok(())
 }
 
-fn set_local_version(
+fn set_local_version<OUT: Write, ERR: Write>(
     context: &impl FenvContext,
     sdk_service: &impl SdkService,
+    output: &mut dyn ConsoleOutput<OUT, ERR>,
+    start_dir: &PathLike,
     prefix: &str,
 ) -> anyhow::Result<()> {
     let sdk = match sdk_service.find_latest_local(context, prefix) {
         LookupResult::Found(sdk) => sdk,
         LookupResult::Err(err) => return Err(anyhow::anyhow!(err)),
         LookupResult::None => {
-            if sdk_service.find_latest_remote(context, prefix).is_found() {
+            if sdk_service
+                .find_latest_remote(context, prefix, false)
+                .is_found()
+            {
                 bail!("The specified version is not installed: do `fenv install {prefix} && fenv local {prefix}`")
             } else {
                 bail!("Not found any matched flutter sdk version: `{prefix}`")
@@ -73,13 +76,17 @@ fn set_local_version(
     };
 
     // write a local version file.
-    sdk_service.write_local_version(&context.fenv_dir(), &sdk)
+    if latest_keyword::is_latest(prefix) {
+        output.warning(&latest_keyword::warning_of(prefix))?;
+        return sdk_service.write_local_version(start_dir, &LatestKeyword(prefix.to_string()));
+    }
+    sdk_service.write_local_version(start_dir, &sdk)
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        context::FenvContext, define_mock_valid_git_command,
+        context::FenvContext, define_mock_valid_git_command, deprecation::recorded_usages,
         external::flutter_command::FlutterCommandImpl, sdk_service::sdk_service::RealSdkService,
         service::macros::test_with_context, try_run, util::chrono_wrapper::SystemClock,
         write_invalid_utf8,
@@ -232,7 +239,88 @@ mod tests {
             assert_eq!(output.stdout_to_string(), "1.0.0\n");
             assert_eq!(
                 output.stderr_to_string(),
-                "fenv: info: `--symlink` option is deprecated. For IDE support, use `fenv workspace` instead.\n"
+                "fenv: warning: `fenv local --symlink` is deprecated. For IDE support, use `fenv workspace` instead.\n"
+            );
+            assert_eq!(recorded_usages(context), ["fenv local --symlink"]);
+        })
+    }
+
+    #[test]
+    pub fn test_deprecation_notice_of_symlink_option_is_suppressed_by_quiet() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("1.0.0")
+                .unwrap();
+            context
+                .fenv_versions()
+                .join("1.0.0")
+                .create_dir_all()
+                .unwrap();
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(
+                &["fenv", "--quiet", "local", "--symlink"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+            try_run(
+                &["fenv", "local", "--symlink", "-q"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "1.0.0\n1.0.0\n");
+            assert!(output.stderr_to_string().is_empty());
+        })
+    }
+
+    #[test]
+    pub fn test_deprecation_notice_of_symlink_option_in_json_format() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("1.0.0")
+                .unwrap();
+            context
+                .fenv_versions()
+                .join("1.0.0")
+                .create_dir_all()
+                .unwrap();
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(
+                &["fenv", "local", "--symlink", "--error-format", "json"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "1.0.0\n");
+            assert_eq!(
+                output.stderr_to_string(),
+                "{\"level\":\"warning\",\"message\":\"`fenv local --symlink` is deprecated. For IDE support, use `fenv workspace` instead.\"}\n"
             );
         })
     }
@@ -331,6 +419,92 @@ mod tests {
         })
     }
 
+    #[test]
+    pub fn test_set_and_show_local_version_preserve_comments() {
+        test_with_context(|context, output| {
+            // setup
+            for version in ["1.0.0", "2.0.0"] {
+                context
+                    .fenv_versions()
+                    .join(version)
+                    .create_dir_all()
+                    .unwrap();
+            }
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("# pinned for release branch\n1.0.0 # see the changelog")
+                .unwrap();
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(&["fenv", "local"], context, &sdk_service, output).unwrap();
+            try_run(&["fenv", "local", "2.0.0"], context, &sdk_service, output).unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "1.0.0\n");
+            assert_eq!(
+                context
+                    .fenv_dir()
+                    .join(".flutter-version")
+                    .read_to_string()
+                    .unwrap(),
+                "# pinned for release branch\n2.0.0 # see the changelog\n"
+            );
+        })
+    }
+
+    #[test]
+    pub fn test_set_and_show_local_version_in_given_dir() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("1.0.0")
+                .create_dir_all()
+                .unwrap();
+            context.fenv_dir().join("app").create_dir_all().unwrap();
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(
+                &["fenv", "local", "--dir", "app", "1.0.0"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+            try_run(
+                &["fenv", "local", "--dir", "app"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "1.0.0\n");
+            assert_eq!(
+                context
+                    .fenv_dir()
+                    .join("app")
+                    .join(".flutter-version")
+                    .read_to_string()
+                    .unwrap(),
+                "1.0.0\n"
+            );
+            assert!(!context.fenv_dir().join(".flutter-version").exists());
+        })
+    }
+
     #[test]
     pub fn test_set_local_version_fails_if_specified_version_is_not_installed() {
         test_with_context(|context, output| {
@@ -472,4 +646,47 @@ mod tests {
             )
         })
     }
+
+    #[test]
+    pub fn test_set_local_version_to_latest_keyword() {
+        test_with_context(|context, output| {
+            // setup
+            for version in ["3.19.6", "3.22.2", "3.23.0-0.1.pre", "stable"] {
+                context
+                    .fenv_versions()
+                    .join(version)
+                    .create_dir_all()
+                    .unwrap();
+            }
+            let sdk_service = RealSdkService::new();
+
+            // execution
+            try_run(
+                &["fenv", "local", "latest:3.19"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+            try_run(&["fenv", "version-name"], context, &sdk_service, output).unwrap();
+            context
+                .fenv_versions()
+                .join("3.19.7")
+                .create_dir_all()
+                .unwrap();
+            try_run(&["fenv", "version-name"], context, &sdk_service, output).unwrap();
+
+            // validation
+            assert_eq!(
+                context
+                    .fenv_dir()
+                    .join(".flutter-version")
+                    .read_to_string()
+                    .unwrap(),
+                "latest:3.19\n"
+            );
+            assert_eq!(output.stdout_to_string(), "3.19.6\n3.19.7\n");
+            assert!(output.stderr_to_string().contains("is not reproducible"));
+        })
+    }
 }
@@ -0,0 +1,411 @@
+use crate::{
+    args::FenvMigrateFvmArgs,
+    context::FenvContext,
+    sdk_service::{local_repository::LOCAL_SDK_REPOSITORY, sdk_service::SdkService},
+    service::{rehash::rehash_service::rehash, service::Service},
+    util::{io::ConsoleOutput, path_like::PathLike},
+};
+use anyhow::{bail, Context};
+use log::debug;
+use serde::Deserialize;
+
+/// Migrates a project managed by FVM to `fenv`.
+///
+/// The Flutter version pinned by `.fvmrc` (FVM 3) or `.fvm/fvm_config.json` (FVM 2) is written
+/// into `.flutter-version`. With `--copy-sdks`, the Flutter SDKs in `{fvm_cache}/versions` are
+/// copied into `{fenv_versions}`, where `{fvm_cache}` is `--fvm-cache`, `$FVM_CACHE_PATH` or
+/// `$HOME/fvm` in this order. The files of FVM are left as they are.
+pub struct FenvMigrateFvmService {
+    pub args: FenvMigrateFvmArgs,
+}
+
+impl FenvMigrateFvmService {
+    pub fn new(args: FenvMigrateFvmArgs) -> Self {
+        Self { args }
+    }
+}
+
+/// A definition of format of `.fvmrc` file of FVM 3.
+#[derive(Debug, Deserialize)]
+struct Fvmrc {
+    flutter: String,
+}
+
+/// A definition of format of `.fvm/fvm_config.json` file of FVM 2.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FvmConfigJson {
+    flutter_sdk_version: String,
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvMigrateFvmService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        _: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let project = match &self.args.project {
+            Some(project) => context.fenv_dir().join(project),
+            None => context.fenv_dir(),
+        };
+        let pinned = read_pinned_version(&project)?;
+        if pinned.is_none() && !self.args.copy_sdks {
+            bail!("Neither `.fvmrc` nor `.fvm/fvm_config.json` exists in `{project}`")
+        }
+        if let Some((version, fvm_config)) = pinned {
+            write_version_file(output, &project, &version, &fvm_config)?;
+        }
+        if self.args.copy_sdks {
+            let fvm_cache = match &self.args.fvm_cache {
+                Some(fvm_cache) => context.fenv_dir().join(fvm_cache),
+                None => context
+                    .fvm_cache_path()
+                    .unwrap_or_else(|| context.home().join("fvm")),
+            };
+            copy_sdks(context, output, &fvm_cache)?;
+        }
+        anyhow::Ok(())
+    }
+}
+
+/// Reads the Flutter version pinned by FVM in `project` with the path of the config file.
+fn read_pinned_version(project: &PathLike) -> anyhow::Result<Option<(String, PathLike)>> {
+    let fvmrc = project.join(".fvmrc");
+    let fvm_config_json = project.join(".fvm").join("fvm_config.json");
+    let version = if fvmrc.is_file() {
+        let content = fvmrc
+            .read_to_string()
+            .with_context(|| format!("Failed to read `{fvmrc}`"))?;
+        let config: Fvmrc =
+            serde_json::from_str(&content).with_context(|| format!("Failed to parse `{fvmrc}`"))?;
+        (config.flutter, fvmrc)
+    } else if fvm_config_json.is_file() {
+        let content = fvm_config_json
+            .read_to_string()
+            .with_context(|| format!("Failed to read `{fvm_config_json}`"))?;
+        let config: FvmConfigJson = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse `{fvm_config_json}`"))?;
+        (config.flutter_sdk_version, fvm_config_json)
+    } else {
+        return anyhow::Ok(None);
+    };
+    anyhow::Ok(Some(version))
+}
+
+/// Writes `{project}/.flutter-version` unless it already exists.
+fn write_version_file<OUT: std::io::Write, ERR: std::io::Write>(
+    output: &mut dyn ConsoleOutput<OUT, ERR>,
+    project: &PathLike,
+    version: &str,
+    fvm_config: &PathLike,
+) -> anyhow::Result<()> {
+    // FVM 3 pins a version of a specific channel such as `3.19.0@beta`.
+    let version = version.split('@').next().unwrap_or_default().trim();
+    if version.is_empty() || version.starts_with("custom_") {
+        bail!("`{version}` (set by `{fvm_config}`) cannot be migrated to fenv")
+    }
+    let version_file = project.join(".flutter-version");
+    if version_file.exists() {
+        output.warning(&format!(
+            "`{version_file}` already exists. Skipped migrating `{version}` (set by `{fvm_config}`)"
+        ))?;
+        return anyhow::Ok(());
+    }
+    version_file
+        .writeln(version)
+        .with_context(|| format!("Failed to write `{version_file}`"))?;
+    writeln!(
+        output.stdout(),
+        "`{version_file}` is generated: `{version}` (set by `{fvm_config}`)"
+    )?;
+    anyhow::Ok(())
+}
+
+/// Copies the Flutter SDKs in `{fvm_cache}/versions` into `{fenv_versions}` unless they are
+/// already installed.
+///
+/// Each copy is guarded by the installing marker same as `fenv install`, so that an interrupted
+/// copy is not regarded as installed, and is copied again by the next migration.
+fn copy_sdks<OUT: std::io::Write, ERR: std::io::Write>(
+    context: &impl FenvContext,
+    output: &mut dyn ConsoleOutput<OUT, ERR>,
+    fvm_cache: &PathLike,
+) -> anyhow::Result<()> {
+    let fvm_versions = fvm_cache.join("versions");
+    let entries = fvm_versions
+        .read_dir()
+        .with_context(|| format!("Failed to read the FVM cache: `{fvm_versions}`"))?;
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.path().join("bin").join("flutter").is_file())
+        .filter_map(|entry| entry.file_name().to_str().map(String::from))
+        .collect();
+    names.sort();
+
+    let local = LOCAL_SDK_REPOSITORY;
+    local.ensure_versions_exists(context)?;
+    let fenv_versions = context.fenv_versions();
+    for name in &names {
+        let source = fvm_versions.join(name);
+        let destination = fenv_versions.join(name);
+        local.remove_installation_garbages(context, name)?;
+        if destination.exists() {
+            writeln!(output.stdout(), "`{name}` is already installed. Skipped")?;
+            continue;
+        }
+        debug!("copy_sdks(): Copying `{source}` to `{destination}`");
+        local.create_installing_marker(context, name)?;
+        if let Err(e) = copy_dir_all(&source, &destination) {
+            if let Err(e) = local.remove_sdk(context, name) {
+                debug!("copy_sdks(): Failed to remove `{destination}`: {e}");
+            }
+            return Err(e);
+        }
+        local.remove_installing_marker(context, name)?;
+        writeln!(output.stdout(), "`{source}` is copied to `{destination}`")?;
+    }
+    if !names.is_empty() {
+        rehash(context)?;
+    }
+    anyhow::Ok(())
+}
+
+/// Copies the directory `source` into `destination` recursively, preserving the symbolic links.
+fn copy_dir_all(source: &PathLike, destination: &PathLike) -> anyhow::Result<()> {
+    destination
+        .create_dir_all()
+        .with_context(|| format!("Failed to create `{destination}`"))?;
+    let entries = source
+        .read_dir()
+        .with_context(|| format!("Failed to read `{source}`"))?;
+    for entry in entries {
+        let entry = entry.with_context(|| format!("Failed to read `{source}`"))?;
+        let from = PathLike::from(entry.path().as_path());
+        let to = destination.join(entry.file_name());
+        let file_type = entry
+            .file_type()
+            .with_context(|| format!("Failed to read `{from}`"))?;
+        if file_type.is_symlink() {
+            copy_symlink(&from, &to)?;
+        } else if file_type.is_dir() {
+            copy_dir_all(&from, &to)?;
+        } else {
+            std::fs::copy(&from, &to)
+                .with_context(|| format!("Failed to copy `{from}` to `{to}`"))?;
+        }
+    }
+    anyhow::Ok(())
+}
+
+fn copy_symlink(from: &PathLike, to: &PathLike) -> anyhow::Result<()> {
+    #[cfg(unix)]
+    {
+        let target = std::fs::read_link(from)
+            .with_context(|| format!("Failed to read the link `{from}`"))?;
+        std::os::unix::fs::symlink(target, to)
+            .with_context(|| format!("Failed to create the link `{to}`"))?;
+    }
+    #[cfg(not(unix))]
+    std::fs::copy(from, to).with_context(|| format!("Failed to copy `{from}` to `{to}`"))?;
+    anyhow::Ok(())
+}
+
+#[cfg(unix)]
+#[cfg(test)]
+mod tests_unix {
+    use crate::{
+        context::FenvContext, sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context, try_run,
+    };
+
+    #[test]
+    fn test_migrate_fvmrc() {
+        test_with_context(|context, output| {
+            // setup
+            let project = context.fenv_dir();
+            project
+                .join(".fvmrc")
+                .writeln(r#"{"flutter": "3.19.0@beta", "flavors": {}}"#)
+                .unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "migrate", "fvm"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                project.join(".flutter-version").read_to_string().unwrap(),
+                "3.19.0\n"
+            );
+            assert_eq!(
+                output.stdout_to_string(),
+                format!(
+                    "`{project}/.flutter-version` is generated: `3.19.0` (set by `{project}/.fvmrc`)\n"
+                )
+            );
+        })
+    }
+
+    #[test]
+    fn test_migrate_fvm_config_json_and_copy_sdks() {
+        test_with_context(|context, output| {
+            // setup
+            let project = context.fenv_dir().join("app");
+            project
+                .join(".fvm")
+                .join("fvm_config.json")
+                .writeln(r#"{"flutterSdkVersion": "3.7.12", "flavors": {}}"#)
+                .unwrap();
+            let fvm_versions = context.home().join("fvm").join("versions");
+            fvm_versions
+                .join("3.7.12/bin/flutter")
+                .writeln("#!/bin/sh")
+                .unwrap();
+            std::os::unix::fs::symlink("flutter", fvm_versions.join("3.7.12/bin/flutter.sh"))
+                .unwrap();
+            fvm_versions.join("stable/bin/flutter").writeln("").unwrap();
+            context
+                .fenv_versions()
+                .join("stable")
+                .create_dir_all()
+                .unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "migrate", "fvm", "app", "--copy-sdks"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                project.join(".flutter-version").read_to_string().unwrap(),
+                "3.7.12\n"
+            );
+            let copied = context.fenv_versions().join("3.7.12");
+            assert_eq!(
+                copied.join("bin/flutter").read_to_string().unwrap(),
+                "#!/bin/sh\n"
+            );
+            assert_eq!(
+                std::fs::read_link(copied.join("bin/flutter.sh")).unwrap(),
+                std::path::Path::new("flutter")
+            );
+            assert!(context.fenv_shims().join("flutter").is_file());
+            assert!(output
+                .stdout_to_string()
+                .contains("`stable` is already installed. Skipped\n"));
+        })
+    }
+
+    #[test]
+    fn test_migrate_copies_sdks_from_fvm_cache_path_again_if_interrupted() {
+        test_with_context(|context, output| {
+            // setup
+            let fvm_cache = context.home().join("fvm_cache");
+            fvm_cache
+                .join("versions/3.7.12/bin/flutter")
+                .writeln("#!/bin/sh")
+                .unwrap();
+            // The previous migration was interrupted while copying.
+            context
+                .fenv_versions()
+                .join("3.7.12/bin")
+                .create_dir_all()
+                .unwrap();
+            context
+                .fenv_versions()
+                .join(".install_3.7.12")
+                .create_file()
+                .unwrap();
+            let context = context.clone().with_fvm_cache_path(&fvm_cache);
+
+            // execution
+            try_run(
+                &["fenv", "migrate", "fvm", "--copy-sdks"],
+                &context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            let copied = context.fenv_versions().join("3.7.12");
+            assert_eq!(
+                copied.join("bin/flutter").read_to_string().unwrap(),
+                "#!/bin/sh\n"
+            );
+            assert!(!context.fenv_versions().join(".install_3.7.12").exists());
+            assert_eq!(
+                output.stdout_to_string(),
+                format!(
+                    "`{}` is copied to `{copied}`\n",
+                    fvm_cache.join("versions/3.7.12")
+                )
+            );
+        })
+    }
+
+    #[test]
+    fn test_migrate_keeps_existing_version_file() {
+        test_with_context(|context, output| {
+            // setup
+            let project = context.fenv_dir();
+            project
+                .join(".fvmrc")
+                .writeln(r#"{"flutter": "3.19.0"}"#)
+                .unwrap();
+            project.join(".flutter-version").writeln("3.7").unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "migrate", "fvm"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                project.join(".flutter-version").read_to_string().unwrap(),
+                "3.7\n"
+            );
+            assert!(output.stderr_to_string().contains("already exists"));
+        })
+    }
+
+    #[test]
+    fn test_migrate_fails_if_no_fvm_config_exists() {
+        test_with_context(|context, output| {
+            // execution
+            let result = try_run(
+                &["fenv", "migrate", "fvm"],
+                context,
+                &RealSdkService::new(),
+                output,
+            );
+
+            // validation
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                format!(
+                    "Neither `.fvmrc` nor `.fvm/fvm_config.json` exists in `{}`",
+                    context.fenv_dir()
+                )
+            );
+        })
+    }
+}
@@ -0,0 +1 @@
+pub mod migrate_fvm_service;
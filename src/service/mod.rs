@@ -1,14 +1,45 @@
+pub mod alias;
+pub mod args_prepend;
+pub mod audit;
+pub mod cache;
+pub mod check_flutter_args;
+pub mod complete_executables;
+pub mod complete_versions;
+pub mod complete_workspaces;
 pub mod completions;
+pub mod context;
+pub mod current;
+pub mod doctor;
+pub mod env;
+pub mod exec;
 pub mod global;
+pub mod help;
+pub mod info;
 pub mod init;
 pub mod install;
 pub mod latest;
 pub mod list_remote;
 pub mod local;
+pub mod migrate_fvm;
+pub mod new;
+pub mod normalize_names;
+pub mod pin;
 pub mod prefix;
+pub mod print_cache_key;
+pub mod project_env;
+pub mod prune;
+pub mod ready;
+pub mod rehash;
+pub mod resolve_version;
 pub mod root;
+pub mod self_update;
 pub mod service;
+pub mod setup;
+pub mod shell;
+pub mod start_dir;
 pub mod uninstall;
+pub mod upgrade;
+pub mod verify;
 pub mod version;
 pub mod version_file;
 pub mod version_name;
@@ -17,8 +48,6 @@ pub mod which;
 pub mod workspace;
 
 pub mod macros {
-    use crate::{context::RealFenvContext, util::io::BufferedOutput};
-
     #[macro_export(local_inner_macros)]
     macro_rules! spawn_and_wait {
         ($expr: expr, $fn_name: expr, $($arg:tt)+) => {{
@@ -81,6 +110,7 @@ pub mod macros {
                     &self,
                     _channel: &str,
                     destination: &str,
+                    _remote: &crate::external::git_command::GitRemote,
                 ) -> anyhow::Result<()> {
                     std::fs::create_dir(destination).map_err(|e| anyhow::anyhow!(e))
                 }
@@ -89,19 +119,78 @@ pub mod macros {
                     &self,
                     _version: &str,
                     destination: &str,
+                    _remote: &crate::external::git_command::GitRemote,
+                ) -> anyhow::Result<()> {
+                    std::fs::create_dir(destination).map_err(|e| anyhow::anyhow!(e))
+                }
+
+                fn clone_flutter_sdk_by_commit(
+                    &self,
+                    _sha: &str,
+                    destination: &str,
+                    _remote: &crate::external::git_command::GitRemote,
                 ) -> anyhow::Result<()> {
                     std::fs::create_dir(destination).map_err(|e| anyhow::anyhow!(e))
                 }
 
-                fn list_remote_sdks_by_tags(&self) -> anyhow::Result<String> {
+                fn resume_flutter_sdk_by_channel(
+                    &self,
+                    _channel: &str,
+                    _destination: &str,
+                    _remote: &crate::external::git_command::GitRemote,
+                ) -> anyhow::Result<()> {
+                    std::result::Result::Ok(())
+                }
+
+                fn resume_flutter_sdk_by_version(
+                    &self,
+                    _version: &str,
+                    _destination: &str,
+                    _remote: &crate::external::git_command::GitRemote,
+                ) -> anyhow::Result<()> {
+                    std::result::Result::Ok(())
+                }
+
+                fn list_remote_sdks_by_tags(
+                    &self,
+                    _remote: &crate::external::git_command::GitRemote,
+                ) -> anyhow::Result<String> {
                     read_resource_file("resources/test/install_service/git_lf-remote_tags.txt")
                         .map_err(|e| anyhow::anyhow!(e))
                 }
 
-                fn list_remote_sdks_by_branches(&self) -> anyhow::Result<String> {
+                fn list_remote_sdks_by_branches(
+                    &self,
+                    _remote: &crate::external::git_command::GitRemote,
+                ) -> anyhow::Result<String> {
                     read_resource_file("resources/test/install_service/git_lf-remote_heads.txt")
                         .map_err(|e| anyhow::anyhow!(e))
                 }
+
+                fn rev_parse_head(&self, _working_dir: &str) -> anyhow::Result<String> {
+                    std::result::Result::Ok(std::string::String::from(
+                        "135454af32477f815a7525073027a3ff9eff1bfd",
+                    ))
+                }
+
+                fn rev_parse_commit(
+                    &self,
+                    _working_dir: &str,
+                    _rev: &str,
+                ) -> anyhow::Result<String> {
+                    std::result::Result::Ok(std::string::String::from(
+                        "135454af32477f815a7525073027a3ff9eff1bfd",
+                    ))
+                }
+
+                fn pull_ff_only(
+                    &self,
+                    _working_dir: &str,
+                    _tls: &crate::external::git_command::GitTlsOptions,
+                    _proxy: &crate::external::proxy::ProxyOptions,
+                ) -> anyhow::Result<()> {
+                    std::result::Result::Ok(())
+                }
             }
 
             fn read_resource_file(relative_path: &str) -> std::io::Result<String> {
@@ -118,35 +207,54 @@ pub mod macros {
             struct MockFlutterCommand;
 
             impl crate::external::flutter_command::FlutterCommand for MockFlutterCommand {
-                fn doctor(&self, _: &str) -> anyhow::Result<()> {
+                fn doctor(
+                    &self,
+                    _: &str,
+                    _: Option<&str>,
+                    _: &mut dyn std::io::Write,
+                ) -> anyhow::Result<()> {
                     std::result::Result::Ok(())
                 }
 
-                fn precache(&self, _: &str) -> anyhow::Result<()> {
+                fn precache(
+                    &self,
+                    _: &str,
+                    _: &[crate::external::flutter_command::PrecachePlatform],
+                    _: Option<&str>,
+                    _: &mut dyn std::io::Write,
+                ) -> anyhow::Result<()> {
                     std::result::Result::Ok(())
                 }
+
+                fn accept_android_licenses(
+                    &self,
+                    _: &str,
+                    _: Option<&str>,
+                    _: &mut dyn std::io::Write,
+                ) -> anyhow::Result<()> {
+                    std::result::Result::Ok(())
+                }
+
+                /// Creates the minimum `pubspec.yaml` only.
+                fn create(
+                    &self,
+                    _: &str,
+                    project_dir: &str,
+                    _: &[String],
+                    _: &mut dyn std::io::Write,
+                ) -> anyhow::Result<()> {
+                    let project_dir = crate::util::path_like::PathLike::from(project_dir);
+                    project_dir
+                        .join("pubspec.yaml")
+                        .writeln("name: app")
+                        .map_err(|e| anyhow::anyhow!(e))
+                }
             }
         };
     }
 
-    pub fn test_with_context<F>(lambda: F)
-    where
-        F: FnOnce(&RealFenvContext, &mut BufferedOutput),
-    {
-        let home = tempfile::tempdir().unwrap();
-        let fenv_root = home.path().join(".fenv");
-        let fenv_dir = tempfile::tempdir().unwrap();
-        let pub_cache = home.path().join(".pub-cache");
-        let context = RealFenvContext::new(
-            fenv_root.to_str().unwrap(),
-            fenv_dir.path().to_str().unwrap(),
-            home.path().to_str().unwrap(),
-            "/bin/bash",
-            pub_cache.to_str().unwrap(),
-        );
-        let mut output = BufferedOutput::new();
-        lambda(&context, &mut output);
-    }
+    #[cfg(test)]
+    pub use crate::test_util::test_with_context;
 
     #[macro_export(local_inner_macros)]
     macro_rules! stdout_to_string {
@@ -166,7 +274,8 @@ pub mod macros {
     #[macro_export(local_inner_macros)]
     macro_rules! invoke_command {
         ($context: ident, $sdk_service: ident, $output: ident, $($arg:tt)+) => {{
-            let mut buffered_output = crate::util::io::BufferedOutput::new();
+            let mut buffered_output =
+                crate::util::io::BufferedOutput::with_options(*($output).options());
             let result = crate::try_run(
                 &["fenv", $($arg)+],
                 $context,
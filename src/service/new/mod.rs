@@ -0,0 +1 @@
+pub mod new_service;
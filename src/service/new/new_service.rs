@@ -0,0 +1,251 @@
+use crate::{
+    args::{FenvNewArgs, FenvWorkspaceArgs},
+    context::FenvContext,
+    sdk_service::{
+        model::flutter_sdk::FlutterSdk,
+        sdk_service::{InstallOptions, SdkService},
+    },
+    service::{
+        service::Service, setup::setup_service::find_installed_sdk,
+        workspace::workspace_service::FenvWorkspaceService,
+    },
+    util::{io::ConsoleOutput, path_like::PathLike},
+};
+use anyhow::{bail, Context};
+
+/// Creates a new Flutter project with the given Flutter SDK version: writes `.flutter-version`
+/// into the new directory, installs the Flutter SDK if missing, runs its `flutter create`, and
+/// generates the workspace files for IDEs.
+pub struct FenvNewService {
+    pub args: FenvNewArgs,
+}
+
+impl FenvNewService {
+    pub fn new(args: FenvNewArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvNewService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let project_path = context.fenv_dir().join(&self.args.dir);
+        if !is_empty_or_missing(&project_path)? {
+            bail!("`{project_path}` already exists and is not empty")
+        }
+
+        let prefix = &self.args.prefix;
+        let sdk = match find_installed_sdk(context, sdk_service, prefix)? {
+            Some(sdk) => sdk,
+            None => {
+                sdk_service.install_sdk(
+                    context,
+                    prefix,
+                    &InstallOptions {
+                        should_doctor: true,
+                        should_precache: self.args.should_precache,
+                        ..Default::default()
+                    },
+                    output.stderr(),
+                )?;
+                match find_installed_sdk(context, sdk_service, prefix)? {
+                    Some(sdk) => sdk,
+                    None => bail!("Failed to install Flutter `{prefix}`"),
+                }
+            }
+        };
+
+        project_path
+            .create_dir_all()
+            .with_context(|| format!("Failed to create `{project_path}`"))?;
+        sdk_service.write_local_version(&project_path, &sdk)?;
+        writeln!(
+            output.stdout(),
+            "`{}` is written with `{}`",
+            project_path.join(".flutter-version"),
+            sdk.display_name()
+        )?;
+        if !self.args.should_create {
+            return anyhow::Ok(());
+        }
+
+        let sdk_root = context.fenv_sdk_root(&sdk.display_name());
+        sdk_service.flutter_create(
+            &sdk_root,
+            &project_path,
+            &self.args.create_args,
+            output.stderr(),
+        )?;
+        FenvWorkspaceService::new(FenvWorkspaceArgs {
+            workspace: project_path.to_string(),
+            prefix: Some(sdk.display_name()),
+            should_pub_get: self.args.should_pub_get,
+            force: false,
+            emit_path_file: None,
+            clean: false,
+        })
+        .execute(context, sdk_service, output)
+    }
+}
+
+/// Whether `path` does not exist or is an empty directory, so that `flutter create` never
+/// overwrites the files of an existing project.
+fn is_empty_or_missing(path: &PathLike) -> anyhow::Result<bool> {
+    if !path.exists() {
+        return anyhow::Ok(true);
+    }
+    if !path.is_dir() {
+        return anyhow::Ok(false);
+    }
+    let mut entries = path
+        .read_dir()
+        .with_context(|| format!("Failed to read `{path}`"))?;
+    anyhow::Ok(entries.next().is_none())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::FenvContext,
+        define_mock_flutter_command,
+        external::git_command::MockGitCommand,
+        sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context,
+        try_run,
+        util::{chrono_wrapper::SystemClock, path_like::PathLike},
+    };
+    use indoc::formatdoc;
+
+    define_mock_flutter_command!();
+
+    fn prepare_dart_sdk_lib(sdk_root: &str) {
+        let dart_sdk_lib = PathLike::from(sdk_root)
+            .join("bin")
+            .join("cache")
+            .join("dart-sdk")
+            .join("lib");
+        dart_sdk_lib.join("core").create_dir_all().unwrap();
+    }
+
+    #[test]
+    fn test_new_with_installed_version() {
+        test_with_context(|context, output| {
+            // setup
+            prepare_dart_sdk_lib(&context.fenv_sdk_root("3.22.2").to_string());
+            let sdk_service = RealSdkService::from(
+                MockGitCommand::new(),
+                SystemClock::new(),
+                MockFlutterCommand,
+            );
+
+            // execution
+            try_run(
+                &["fenv", "new", "--version", "3.22", "app"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            let project = context.fenv_dir().join("app");
+            assert_eq!(
+                project.join(".flutter-version").read_to_string().unwrap(),
+                "3.22.2\n"
+            );
+            assert!(project.join("pubspec.yaml").is_file());
+            assert_eq!(
+                output.stdout_to_string(),
+                formatdoc! {"
+                    `{project}/.flutter-version` is written with `3.22.2`
+                    `{project}/.dart_tool/package_config.json` is generated
+                    `{project}/.idea/libraries/Dart_SDK.xml` is generated
+                    ",
+                    project = project,
+                }
+            );
+        })
+    }
+
+    #[test]
+    fn test_new_without_create_installs_missing_version() {
+        test_with_context(|context, output| {
+            // setup
+            let mut git_command = MockGitCommand::new();
+            git_command
+                .expect_list_remote_sdks_by_tags()
+                .returning(|_| {
+                    anyhow::Ok(String::from(
+                        "fd9d2d5d2e5dc3a7e4e5a5d2e5dc3a7e4e5a5d2e\trefs/tags/3.22.2",
+                    ))
+                });
+            git_command
+                .expect_list_remote_sdks_by_branches()
+                .returning(|_| anyhow::Ok(String::new()));
+            git_command
+                .expect_clone_flutter_sdk_by_version()
+                .times(1)
+                .returning(|_, destination, _| {
+                    prepare_dart_sdk_lib(destination);
+                    anyhow::Ok(())
+                });
+            let sdk_service =
+                RealSdkService::from(git_command, SystemClock::new(), MockFlutterCommand);
+
+            // execution
+            try_run(
+                &["fenv", "new", "--version", "3.22", "--no-create", "app"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            let project = context.fenv_dir().join("app");
+            assert!(context.fenv_sdk_root("3.22.2").is_dir());
+            assert_eq!(
+                project.join(".flutter-version").read_to_string().unwrap(),
+                "3.22.2\n"
+            );
+            assert!(!project.join("pubspec.yaml").exists());
+        })
+    }
+
+    #[test]
+    fn test_new_fails_if_directory_is_not_empty() {
+        test_with_context(|context, output| {
+            // setup
+            let project = context.fenv_dir().join("app");
+            project.join("main.dart").writeln("void main() {}").unwrap();
+            let sdk_service = RealSdkService::from(
+                MockGitCommand::new(),
+                SystemClock::new(),
+                MockFlutterCommand,
+            );
+
+            // execution
+            let result = try_run(
+                &["fenv", "new", "--version", "3.22", "app"],
+                context,
+                &sdk_service,
+                output,
+            );
+
+            // validation
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                format!("`{project}` already exists and is not empty")
+            );
+            assert!(!project.join(".flutter-version").exists());
+        })
+    }
+}
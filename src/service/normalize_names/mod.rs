@@ -0,0 +1 @@
+pub mod normalize_names_service;
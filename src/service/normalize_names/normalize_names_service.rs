@@ -0,0 +1,349 @@
+use crate::{
+    args::FenvNormalizeNamesArgs,
+    context::FenvContext,
+    sdk_service::{
+        model::local_flutter_sdk::LocalFlutterSdk, results::LookupResult, sdk_service::SdkService,
+    },
+    service::service::Service,
+    util::{io::ConsoleOutput, path_like::PathLike},
+};
+use log::debug;
+
+/// Renames the installed Flutter SDKs which are named after legacy git tags such as
+/// `v1.12.13+hotfix.8` to their canonical names, and rewrites the global version file and the
+/// nearest local version file if they refer to the legacy names.
+pub struct FenvNormalizeNamesService {
+    pub args: FenvNormalizeNamesArgs,
+}
+
+impl FenvNormalizeNamesService {
+    pub fn new(args: FenvNormalizeNamesArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvNormalizeNamesService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let mut has_legacy_named_sdks = false;
+        let mut normalized_sdks: Vec<(String, LocalFlutterSdk)> = vec![];
+        for sdk in sdk_service.get_installed_sdk_list(context)? {
+            let (legacy_name, canonical_name) = match &sdk {
+                LocalFlutterSdk::Version {
+                    version,
+                    display_name,
+                } => (display_name.clone(), version.canonical_name()),
+                LocalFlutterSdk::Channel(_) | LocalFlutterSdk::Commit(_) => continue,
+            };
+            // The namespaced ones are named after the tags of their repository on purpose.
+            if legacy_name == canonical_name || sdk.namespace().is_some() {
+                continue;
+            }
+            has_legacy_named_sdks = true;
+
+            let result = if self.args.link_only {
+                sdk_service.link_sdk(context, &sdk, &canonical_name)
+            } else {
+                sdk_service.rename_sdk(context, &sdk, &canonical_name)
+            };
+            if let Err(e) = result {
+                output.warning(&format!("Skipped `{legacy_name}`: {e}"))?;
+                continue;
+            }
+            if self.args.link_only {
+                writeln!(
+                    output.stdout(),
+                    "Linked `{canonical_name}` to `{legacy_name}`"
+                )?;
+            } else {
+                writeln!(
+                    output.stdout(),
+                    "Renamed `{legacy_name}` to `{canonical_name}`"
+                )?;
+            }
+            // Even if only linked, the version files are rewritten to the canonical name, because
+            // it is resolved by its version to the legacy-named installation.
+            normalized_sdks.push((legacy_name, LocalFlutterSdk::parse(&canonical_name)?));
+        }
+
+        if !has_legacy_named_sdks {
+            writeln!(
+                output.stdout(),
+                "Every installed Flutter SDK already has its canonical name"
+            )?;
+            return anyhow::Ok(());
+        }
+
+        let global_version_file = context.fenv_global_version_file();
+        if global_version_file.is_file() {
+            if let Some(sdk) =
+                find_normalized_sdk_of(sdk_service, &global_version_file, &normalized_sdks)
+            {
+                sdk_service.write_global_version(context, sdk)?;
+                writeln!(output.stdout(), "Updated `{global_version_file}`")?;
+            }
+        }
+
+        if let LookupResult::Found(local_version_file) =
+            sdk_service.find_nearest_local_version_file(&context.fenv_dir())
+        {
+            // An asdf `.tool-versions` is left as it is.
+            if !local_version_file.path().ends_with(".flutter-version") {
+                return anyhow::Ok(());
+            }
+            let sdk = find_normalized_sdk_of(sdk_service, &local_version_file, &normalized_sdks);
+            if let (Some(sdk), Some(dir)) = (sdk, local_version_file.parent()) {
+                sdk_service.write_local_version(&dir, sdk)?;
+                writeln!(output.stdout(), "Updated `{local_version_file}`")?;
+            }
+        }
+        anyhow::Ok(())
+    }
+}
+
+/// Returns the normalized sdk if the given version file refers to its legacy name exactly.
+fn find_normalized_sdk_of<'a>(
+    sdk_service: &impl SdkService,
+    path_to_version_file: &PathLike,
+    normalized_sdks: &'a [(String, LocalFlutterSdk)],
+) -> Option<&'a LocalFlutterSdk> {
+    let version_prefix = match sdk_service.read_version_prefix(path_to_version_file) {
+        Ok(version_prefix) => version_prefix,
+        Err(e) => {
+            debug!("Could not read `{path_to_version_file}`: {e}");
+            return None;
+        }
+    };
+    normalized_sdks
+        .iter()
+        .find(|(legacy_name, _)| legacy_name == &version_prefix)
+        .map(|(_, sdk)| sdk)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::FenvContext, sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context, try_run, util::io::BufferedOutput,
+    };
+    use indoc::formatdoc;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_normalize_names_renames_legacy_named_sdks() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("v1.7.8+hotfix.4")
+                .create_dir_all()
+                .unwrap();
+            context
+                .fenv_versions()
+                .join("stable")
+                .create_dir_all()
+                .unwrap();
+            context
+                .fenv_global_version_file()
+                .writeln("v1.7.8+hotfix.4")
+                .unwrap();
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("v1.7.8+hotfix.4")
+                .unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "normalize-names"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                formatdoc! {"
+                    Renamed `v1.7.8+hotfix.4` to `1.7.8+hotfix.4`
+                    Updated `{global}`
+                    Updated `{local}`
+                    ",
+                    global = context.fenv_global_version_file(),
+                    local = context.fenv_dir().join(".flutter-version"),
+                }
+            );
+            assert!(context.fenv_versions().join("1.7.8+hotfix.4").is_dir());
+            assert_eq!(
+                std::fs::read_link(context.fenv_versions().join("v1.7.8+hotfix.4")).unwrap(),
+                PathBuf::from("1.7.8+hotfix.4")
+            );
+            assert_eq!(
+                context.fenv_global_version_file().read_to_string().unwrap(),
+                "1.7.8+hotfix.4\n"
+            );
+            assert_eq!(
+                context
+                    .fenv_dir()
+                    .join(".flutter-version")
+                    .read_to_string()
+                    .unwrap(),
+                "1.7.8+hotfix.4\n"
+            );
+        })
+    }
+
+    #[test]
+    fn test_normalize_names_with_link_only_keeps_legacy_named_sdks() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("v1.4.5-hotfix.2")
+                .create_dir_all()
+                .unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "normalize-names", "--link-only"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                "Linked `1.4.5+hotfix.2` to `v1.4.5-hotfix.2`\n"
+            );
+            assert!(context.fenv_versions().join("v1.4.5-hotfix.2").is_dir());
+            assert_eq!(
+                std::fs::read_link(context.fenv_versions().join("1.4.5+hotfix.2")).unwrap(),
+                PathBuf::from("v1.4.5-hotfix.2")
+            );
+        })
+    }
+
+    #[test]
+    fn test_normalize_names_with_link_only_keeps_version_files_resolvable() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_sdk_root("v1.4.5-hotfix.2")
+                .join("bin")
+                .join("flutter")
+                .writeln("")
+                .unwrap();
+            context
+                .fenv_global_version_file()
+                .writeln("v1.4.5-hotfix.2")
+                .unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "normalize-names", "--link-only"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+            let mut prefix_output = BufferedOutput::new();
+            try_run(
+                &["fenv", "prefix"],
+                context,
+                &RealSdkService::new(),
+                &mut prefix_output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                context.fenv_global_version_file().read_to_string().unwrap(),
+                "1.4.5+hotfix.2\n"
+            );
+            // The canonical name is resolved to the legacy-named installation which it links to.
+            assert_eq!(
+                prefix_output.stdout_to_string(),
+                format!("{}\n", context.fenv_sdk_root("v1.4.5-hotfix.2"))
+            );
+            assert_eq!(
+                std::fs::canonicalize(context.fenv_sdk_root("1.4.5+hotfix.2").path()).unwrap(),
+                std::fs::canonicalize(context.fenv_sdk_root("v1.4.5-hotfix.2").path()).unwrap()
+            );
+        })
+    }
+
+    #[test]
+    fn test_normalize_names_does_nothing_if_every_sdk_has_canonical_name() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("3.7.12")
+                .create_dir_all()
+                .unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "normalize-names"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                "Every installed Flutter SDK already has its canonical name\n"
+            );
+        })
+    }
+
+    #[test]
+    fn test_normalize_names_skips_if_canonical_name_is_occupied() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("v1.7.8+hotfix.4")
+                .create_dir_all()
+                .unwrap();
+            context
+                .fenv_versions()
+                .join("1.7.8+hotfix.4")
+                .create_dir_all()
+                .unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "normalize-names"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                output.stderr_to_string(),
+                format!(
+                    "fenv: warning: Skipped `v1.7.8+hotfix.4`: `{}` already exists\n",
+                    context.fenv_versions().join("1.7.8+hotfix.4")
+                )
+            );
+            assert!(output.stdout_to_string().is_empty());
+            assert!(context.fenv_versions().join("v1.7.8+hotfix.4").is_dir());
+        })
+    }
+}
@@ -0,0 +1 @@
+pub mod pin_service;
@@ -0,0 +1,203 @@
+use crate::{
+    args::FenvPinArgs,
+    context::FenvContext,
+    sdk_service::{
+        model::{flutter_sdk::FlutterSdk, local_flutter_sdk::LocalFlutterSdk},
+        results::LookupResult,
+        sdk_metadata,
+        sdk_service::SdkService,
+        version_file::TOOL_VERSIONS_FILE_NAME,
+    },
+    service::{service::Service, start_dir::StartDir},
+    util::io::ConsoleOutput,
+};
+use anyhow::{bail, Context};
+
+/// Rewrites the nearest version file with the exact version which its prefix currently selects,
+/// such as `3.22.2` for `3`, so that the selected Flutter SDK no longer floats.
+///
+/// A channel is pinned to the version which its installation is at.
+pub struct FenvPinService {
+    pub args: FenvPinArgs,
+}
+
+impl FenvPinService {
+    pub fn new(args: FenvPinArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvPinService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let start_dir = self.args.start_dir.resolve_start_dir(context);
+        let path_to_version_file = match sdk_service.find_nearest_version_file(context, &start_dir)
+        {
+            LookupResult::Found(path) => path,
+            LookupResult::Err(err) => return Err(err),
+            LookupResult::None => bail!("Could not find a version file"),
+        };
+        let prefix = sdk_service
+            .read_version_prefix(&path_to_version_file)
+            .with_context(|| {
+                format!("Could not read the version file (set by `{path_to_version_file}`)")
+            })?;
+        let sdk = match sdk_service.find_latest_local(context, &prefix) {
+            LookupResult::Found(sdk) => sdk,
+            LookupResult::Err(err) => return Err(err),
+            LookupResult::None => bail!(
+                "The specified version `{prefix}` is not installed (set by `{path_to_version_file}`): do `fenv install`"
+            ),
+        };
+        let pinned = match &sdk {
+            LocalFlutterSdk::Version { .. } | LocalFlutterSdk::Commit(_) => sdk.clone(),
+            LocalFlutterSdk::Channel(_) => {
+                let sdk_root = context.fenv_sdk_root(&sdk.display_name());
+                match sdk_metadata::read_framework_version(&sdk_root) {
+                    Some(version) => LocalFlutterSdk::parse(&version)?,
+                    None => bail!(
+                        "Could not find the version which `{sdk}` is at: run `flutter --version` with it once"
+                    ),
+                }
+            }
+        };
+        if pinned.display_name() == prefix {
+            writeln!(
+                output.stdout(),
+                "`{prefix}` is already pinned (set by `{path_to_version_file}`)"
+            )?;
+            return anyhow::Ok(());
+        }
+        if path_to_version_file.path().file_name()
+            == Some(std::ffi::OsStr::new(TOOL_VERSIONS_FILE_NAME))
+        {
+            bail!(
+                "`{path_to_version_file}` is not rewritten by `fenv`: replace `{prefix}` with `{pinned}` by yourself"
+            )
+        }
+
+        if path_to_version_file == context.fenv_global_version_file() {
+            sdk_service.write_global_version(context, &pinned)?;
+        } else {
+            match path_to_version_file.parent() {
+                Some(dir) => sdk_service.write_local_version(&dir, &pinned)?,
+                None => bail!("Invalid version file: `{path_to_version_file}`"),
+            }
+        }
+        if !sdk_service
+            .find_latest_local(context, &pinned.display_name())
+            .is_found()
+        {
+            output.warning(&format!(
+                "`{pinned}` is not installed: do `fenv install {pinned}`"
+            ))?;
+        }
+        writeln!(
+            output.stdout(),
+            "`{prefix}` is pinned to `{pinned}` (set by `{path_to_version_file}`)"
+        )?;
+        anyhow::Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::FenvContext, sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context, try_run,
+    };
+
+    #[test]
+    fn test_pin_local_version_keeps_comment() {
+        test_with_context(|context, output| {
+            // setup
+            for version in ["3.19.6", "3.22.2"] {
+                context
+                    .fenv_versions()
+                    .join(version)
+                    .create_dir_all()
+                    .unwrap();
+            }
+            let version_file = context.fenv_dir().join(".flutter-version");
+            version_file.writeln("3 # the newest").unwrap();
+
+            // execution
+            try_run(&["fenv", "pin"], context, &RealSdkService::new(), output).unwrap();
+
+            // validation
+            assert_eq!(
+                version_file.read_to_string().unwrap(),
+                "3.22.2 # the newest\n"
+            );
+            assert_eq!(
+                output.stdout_to_string(),
+                format!("`3` is pinned to `3.22.2` (set by `{version_file}`)\n")
+            );
+        })
+    }
+
+    #[test]
+    fn test_pin_global_channel() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_sdk_root("stable")
+                .join("bin/cache/flutter.version.json")
+                .writeln(r#"{"frameworkVersion": "3.22.2", "channel": "stable"}"#)
+                .unwrap();
+            context
+                .fenv_global_version_file()
+                .writeln("stable")
+                .unwrap();
+
+            // execution
+            try_run(&["fenv", "pin"], context, &RealSdkService::new(), output).unwrap();
+
+            // validation
+            assert_eq!(
+                context.fenv_global_version_file().read_to_string().unwrap(),
+                "3.22.2\n"
+            );
+            assert!(output
+                .stderr_to_string()
+                .contains("`3.22.2` is not installed: do `fenv install 3.22.2`"));
+        })
+    }
+
+    #[test]
+    fn test_pin_fails_for_tool_versions() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("3.22.2")
+                .create_dir_all()
+                .unwrap();
+            let tool_versions = context.fenv_dir().join(".tool-versions");
+            tool_versions.writeln("flutter 3.22-stable").unwrap();
+
+            // execution
+            let result = try_run(&["fenv", "pin"], context, &RealSdkService::new(), output);
+
+            // validation
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                format!(
+                    "`{tool_versions}` is not rewritten by `fenv`: replace `3.22` with `3.22.2` by yourself"
+                )
+            );
+            assert_eq!(
+                tool_versions.read_to_string().unwrap(),
+                "flutter 3.22-stable\n"
+            );
+        })
+    }
+}
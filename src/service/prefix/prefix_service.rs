@@ -1,6 +1,10 @@
 use crate::{
-    args::FenvPrefixArgs, context::FenvContext, invoke_command,
-    sdk_service::sdk_service::SdkService, service::service::Service, util::io::ConsoleOutput,
+    args::FenvPrefixArgs,
+    context::FenvContext,
+    invoke_command,
+    sdk_service::{results::BrokenInstallation, sdk_service::SdkService},
+    service::{service::Service, start_dir::StartDir},
+    util::io::ConsoleOutput,
 };
 
 pub struct FenvPrefixService {
@@ -26,15 +30,22 @@ where
     ) -> anyhow::Result<()> {
         let version_prefix = match &self.args.prefix {
             Some(prefix) => prefix.to_owned(),
-            None => invoke_command!(context, sdk_service, output, "version-name")?,
+            None => {
+                let start_dir = self.args.start_dir.resolve_start_dir(context).to_string();
+                invoke_command!(context, sdk_service, output, "version-name", &start_dir)?
+            }
         };
         let version_or_channel =
             invoke_command!(context, sdk_service, output, "latest", &version_prefix)?;
-        writeln!(
-            output.stdout(),
-            "{}",
-            context.fenv_sdk_root(&version_or_channel).to_string()
-        )?;
+        let path_to_sdk_root = context.fenv_sdk_root(&version_or_channel);
+        if self.args.validate && sdk_service.is_broken_installation(context, &version_or_channel) {
+            return Err(BrokenInstallation {
+                name: version_or_channel,
+                path_to_sdk_root,
+            }
+            .into());
+        }
+        writeln!(output.stdout(), "{}", path_to_sdk_root.to_string())?;
         Ok(())
     }
 }
@@ -42,9 +53,13 @@ where
 #[cfg(test)]
 mod tests {
     use crate::{
-        context::FenvContext, define_mock_valid_git_command,
-        external::flutter_command::FlutterCommandImpl, sdk_service::sdk_service::RealSdkService,
-        service::macros::test_with_context, try_run, util::chrono_wrapper::SystemClock,
+        context::FenvContext,
+        define_mock_valid_git_command,
+        external::flutter_command::FlutterCommandImpl,
+        sdk_service::{results::BrokenInstallation, sdk_service::RealSdkService},
+        service::macros::test_with_context,
+        try_run,
+        util::chrono_wrapper::SystemClock,
     };
 
     define_mock_valid_git_command!();
@@ -128,6 +143,78 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_prefix_succeeds_without_prefix_if_shell_version_is_set() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("1.22.6")
+                .create_dir_all()
+                .unwrap();
+            context
+                .fenv_versions()
+                .join("stable")
+                .create_dir_all()
+                .unwrap();
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("1")
+                .unwrap();
+            let context = &context.clone().with_shell_version("stable");
+
+            // execution
+            try_run(&["fenv", "prefix"], context, &RealSdkService::new(), output).unwrap();
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                format!("{}\n", context.fenv_versions().join("stable"))
+            );
+        })
+    }
+
+    #[test]
+    fn test_prefix_succeeds_without_prefix_in_given_dir() {
+        test_with_context(|context, output| {
+            // setup
+            for version in ["1.22.6", "2.0.0"] {
+                context
+                    .fenv_versions()
+                    .join(version)
+                    .create_dir_all()
+                    .unwrap();
+            }
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("1")
+                .unwrap();
+            context
+                .fenv_dir()
+                .join("app")
+                .join(".flutter-version")
+                .writeln("2")
+                .unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "prefix", "--dir", "app"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                format!("{}\n", context.fenv_versions().join("2.0.0"))
+            );
+        })
+    }
+
     #[test]
     fn test_prefix_fails_with_prefix_if_specified_version_is_not_installed() {
         test_with_context(|context, output| {
@@ -177,4 +264,63 @@ mod tests {
             )
         })
     }
+
+    #[test]
+    fn test_prefix_with_validate_fails_if_installation_is_broken() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("3.19.6")
+                .create_dir_all()
+                .unwrap();
+
+            // execution
+            let err = try_run(
+                &["fenv", "prefix", "--validate", "3.19"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap_err();
+
+            // validation
+            assert!(err.downcast_ref::<BrokenInstallation>().is_some());
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "The Flutter SDK in `{}` is broken: do `fenv uninstall 3.19.6 && fenv install 3.19.6`",
+                    context.fenv_versions().join("3.19.6")
+                )
+            );
+            assert!(output.stdout_to_string().is_empty());
+        })
+    }
+
+    #[test]
+    fn test_prefix_with_validate_succeeds_if_flutter_exists() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("3.19.6/bin/flutter")
+                .writeln("")
+                .unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "prefix", "--validate", "3.19"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                format!("{}\n", context.fenv_versions().join("3.19.6"))
+            );
+        })
+    }
 }
@@ -0,0 +1 @@
+pub mod print_cache_key_service;
@@ -0,0 +1,189 @@
+use crate::{
+    args::FenvStartDirArgs,
+    context::FenvContext,
+    sdk_service::sdk_service::SdkService,
+    service::{
+        service::Service, start_dir::StartDir,
+        version_name::version_name_service::resolve_selected_sdk,
+    },
+    util::io::ConsoleOutput,
+};
+
+pub struct FenvPrintCacheKeyService {
+    pub args: FenvStartDirArgs,
+}
+
+impl FenvPrintCacheKeyService {
+    pub fn new(args: FenvStartDirArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvPrintCacheKeyService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let start_dir = self.args.resolve_start_dir(context);
+
+        let selected = resolve_selected_sdk(context, sdk_service, output, &start_dir)?;
+        let version_or_channel = selected.sdk.to_string();
+        let sha = sdk_service.read_sdk_commit_sha(&context.fenv_sdk_root(&version_or_channel))?;
+        writeln!(
+            output.stdout(),
+            "{}",
+            cache_key_of(&version_or_channel, &sha)
+        )?;
+        anyhow::Ok(())
+    }
+}
+
+/// Builds a cache key such as `flutter-3.7.12-<sha>`.
+///
+/// Every character that is not allowed in a Docker image tag is replaced with `_`,
+/// so that `3.7.12+hotfix.1` becomes `3.7.12_hotfix.1`.
+fn cache_key_of(version_or_channel: &str, sha: &str) -> String {
+    format!("flutter-{version_or_channel}-{sha}")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::FenvContext, define_mock_valid_git_command,
+        external::flutter_command::FlutterCommandImpl, sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context, try_run, util::chrono_wrapper::SystemClock,
+    };
+
+    define_mock_valid_git_command!();
+
+    #[test]
+    fn test_print_cache_key_succeeds_if_local_version_is_installed() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("3.7.12")
+                .create_dir_all()
+                .unwrap();
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("3.7")
+                .unwrap();
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(&["fenv", "print-cache-key"], context, &sdk_service, output).unwrap();
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                "flutter-3.7.12-135454af32477f815a7525073027a3ff9eff1bfd\n"
+            );
+            assert!(output.stderr_to_string().is_empty());
+        })
+    }
+
+    #[test]
+    fn test_print_cache_key_selects_fenv_version() {
+        test_with_context(|context, output| {
+            // setup
+            for version in ["3.7.12", "3.10.6"] {
+                context
+                    .fenv_versions()
+                    .join(version)
+                    .create_dir_all()
+                    .unwrap();
+            }
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("3.7")
+                .unwrap();
+            let context = context.clone().with_shell_version("3.10");
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(&["fenv", "print-cache-key"], &context, &sdk_service, output).unwrap();
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                "flutter-3.10.6-135454af32477f815a7525073027a3ff9eff1bfd\n"
+            );
+        })
+    }
+
+    #[test]
+    fn test_print_cache_key_replaces_characters_not_allowed_in_docker_tags() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("1.22.0+hotfix.1")
+                .create_dir_all()
+                .unwrap();
+            context.fenv_global_version_file().writeln("1").unwrap();
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(&["fenv", "print-cache-key"], context, &sdk_service, output).unwrap();
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                "flutter-1.22.0_hotfix.1-135454af32477f815a7525073027a3ff9eff1bfd\n"
+            );
+        })
+    }
+
+    #[test]
+    fn test_print_cache_key_fails_if_no_version_file_is_found() {
+        test_with_context(|context, output| {
+            // setup
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            let result = try_run(&["fenv", "print-cache-key"], context, &sdk_service, output);
+
+            // validation
+            assert!(result.is_err());
+            assert_eq!(
+                result.err().unwrap().to_string(),
+                "Could not find a version file"
+            );
+            assert!(output.stdout_to_string().is_empty());
+        })
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{
+    config::FenvProjectConfig, context::FenvContext, sdk_service::sdk_service::SdkService,
+    service::service::Service, util::io::ConsoleOutput,
+};
+
+/// Prints the environment variables configured by `[env]` of the nearest `.fenv.toml` as
+/// `KEY=VALUE`, one variable per line.
+///
+/// This is a plumbing command for the shims.
+#[derive(Default)]
+pub struct FenvProjectEnvService;
+
+impl FenvProjectEnvService {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvProjectEnvService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        _: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let config = FenvProjectConfig::load(context)?;
+        for (key, value) in &config.env {
+            writeln!(output.stdout(), "{key}={value}")?;
+        }
+        anyhow::Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::FenvContext, sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context, try_run,
+    };
+    use indoc::indoc;
+
+    #[test]
+    fn test_print_env_of_nearest_project_config() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_dir()
+                .join(".fenv.toml")
+                .write(indoc! {r#"
+                    [env]
+                    PUB_HOSTED_URL = "https://pub.example.com"
+                    FLUTTER_STORAGE_BASE_URL = "https://flutter-storage.example.com"
+                "#})
+                .unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "project-env"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                "FLUTTER_STORAGE_BASE_URL=https://flutter-storage.example.com\n\
+                PUB_HOSTED_URL=https://pub.example.com\n"
+            );
+        })
+    }
+}
@@ -0,0 +1,337 @@
+use crate::{
+    args::FenvPruneArgs,
+    context::FenvContext,
+    sdk_service::{
+        results::LookupResult, sdk_service::SdkService, version_file::TOOL_VERSIONS_FILE_NAME,
+    },
+    service::{rehash::rehash_service::rehash, service::Service},
+    util::{io::ConsoleOutput, path_like::PathLike},
+    workspaces,
+};
+use anyhow::{bail, Context};
+use log::debug;
+use std::collections::BTreeSet;
+
+/// The directories which are never scanned for the version files, because they are generated
+/// or hold the dependencies of the project.
+const SKIPPED_DIRECTORIES: [&str; 7] = [
+    ".git",
+    ".dart_tool",
+    ".symlinks",
+    ".pub-cache",
+    "build",
+    "node_modules",
+    "Pods",
+];
+
+/// Uninstalls the Flutter SDKs which are not referenced by any of the given projects, the
+/// global version, or `$FENV_VERSION`.
+///
+/// Without the projects, the workspaces recorded by `fenv workspace` are scanned.
+pub struct FenvPruneService {
+    pub args: FenvPruneArgs,
+}
+
+impl FenvPruneService {
+    pub fn new(args: FenvPruneArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvPruneService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let projects = self.projects(context)?;
+        let referenced = find_referenced_sdks(context, sdk_service, &projects)?;
+        let unreferenced: Vec<String> = sdk_service
+            .get_installed_sdk_list(context)?
+            .iter()
+            .map(|sdk| sdk.to_string())
+            .filter(|name| !is_symlink(&context.fenv_sdk_root(name)))
+            .filter(|name| !referenced.contains(name))
+            .collect();
+        if unreferenced.is_empty() {
+            output.warning("Could not find any unreferenced installation")?;
+            return anyhow::Ok(());
+        }
+        for name in &unreferenced {
+            if self.args.dry_run {
+                writeln!(output.stdout(), "Would uninstall `{name}`")?;
+                continue;
+            }
+            sdk_service.uninstall_by_name(context, name)?;
+            writeln!(output.stdout(), "{name}")?;
+        }
+        if self.args.dry_run {
+            return anyhow::Ok(());
+        }
+        rehash(context)
+    }
+}
+
+impl FenvPruneService {
+    /// The project roots to scan: the given ones, or the recorded workspaces if none is given.
+    fn projects(&self, context: &impl FenvContext) -> anyhow::Result<Vec<PathLike>> {
+        if self.args.projects.is_empty() {
+            let projects = workspaces::recorded_workspaces(context);
+            if projects.is_empty() {
+                bail!(
+                    "No project to scan: specify the project directories, \
+                    or run `fenv workspace` in them first"
+                )
+            }
+            return anyhow::Ok(projects);
+        }
+        let projects: Vec<PathLike> = self
+            .args
+            .projects
+            .iter()
+            .map(|project| context.fenv_dir().join(project))
+            .collect();
+        if let Some(project) = projects.iter().find(|project| !project.is_dir()) {
+            bail!("Not a directory: `{project}`")
+        }
+        anyhow::Ok(projects)
+    }
+}
+
+/// Collects the names of the installed Flutter SDKs which are selected by the version files in
+/// or above the `projects`, the global version, or `$FENV_VERSION`.
+///
+/// Fails if any of the version files cannot be read, because the Flutter SDK it selects would be
+/// uninstalled otherwise.
+fn find_referenced_sdks(
+    context: &impl FenvContext,
+    sdk_service: &impl SdkService,
+    projects: &[PathLike],
+) -> anyhow::Result<BTreeSet<String>> {
+    let mut version_files = vec![];
+    for project in projects {
+        collect_version_files(project, &mut version_files);
+        if let LookupResult::Found(version_file) =
+            sdk_service.find_nearest_local_version_file(project)
+        {
+            version_files.push(version_file);
+        }
+    }
+    let global_version_file = context.fenv_global_version_file();
+    if global_version_file.is_file() {
+        version_files.push(global_version_file);
+    }
+
+    let mut prefixes = BTreeSet::new();
+    for version_file in &version_files {
+        let prefix = sdk_service
+            .read_version_prefix(version_file)
+            .with_context(|| {
+                format!(
+                    "Failed to read `{version_file}`: fix or remove it to prune the Flutter SDKs"
+                )
+            })?;
+        if !prefix.is_empty() {
+            prefixes.insert(prefix);
+        }
+    }
+    prefixes.extend(context.shell_version());
+
+    let mut referenced = BTreeSet::new();
+    for prefix in &prefixes {
+        if let LookupResult::Found(sdk) = sdk_service.find_latest_local(context, prefix) {
+            let name = sdk.to_string();
+            // Keeps the installation which the referenced symbolic link points to.
+            if let Ok(target) = std::fs::read_link(context.fenv_sdk_root(&name)) {
+                if let Some(target) = target.file_name().and_then(|target| target.to_str()) {
+                    referenced.insert(target.to_owned());
+                }
+            }
+            debug!("find_referenced_sdks(): `{prefix}` refers to `{name}`");
+            referenced.insert(name);
+        }
+    }
+    anyhow::Ok(referenced)
+}
+
+/// Collects the `.flutter-version` and `.tool-versions` files under `dir` recursively, not
+/// following the symbolic links.
+fn collect_version_files(dir: &PathLike, version_files: &mut Vec<PathLike>) {
+    let entries = match dir.read_dir() {
+        Ok(entries) => entries,
+        Err(e) => {
+            debug!("collect_version_files(): Failed to read `{dir}`: {e}");
+            return;
+        }
+    };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let Some(name) = entry.file_name().to_str().map(|name| name.to_owned()) else {
+            continue;
+        };
+        if file_type.is_dir() {
+            if !SKIPPED_DIRECTORIES.contains(&name.as_str()) {
+                collect_version_files(&dir.join(&name), version_files);
+            }
+        } else if file_type.is_file()
+            && (name == ".flutter-version" || name == TOOL_VERSIONS_FILE_NAME)
+        {
+            version_files.push(dir.join(&name));
+        }
+    }
+}
+
+fn is_symlink(path: &PathLike) -> bool {
+    path.path()
+        .symlink_metadata()
+        .is_ok_and(|metadata| metadata.is_symlink())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::FenvContext, sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context, try_run,
+    };
+
+    #[test]
+    fn test_prune_keeps_referenced_sdks() {
+        test_with_context(|context, output| {
+            // setup
+            for version in ["3.7.12", "3.19.6", "3.22.2", "stable"] {
+                context
+                    .fenv_sdk_root(version)
+                    .join("bin")
+                    .join("flutter")
+                    .writeln("")
+                    .unwrap();
+            }
+            let app = context.fenv_dir().join("app");
+            app.join("packages")
+                .join("ui")
+                .join(".flutter-version")
+                .writeln("3.19")
+                .unwrap();
+            app.join(".tool-versions")
+                .writeln("flutter 3.7.12-stable")
+                .unwrap();
+            app.join("build")
+                .join(".flutter-version")
+                .writeln("3.22.2")
+                .unwrap();
+            context
+                .fenv_global_version_file()
+                .writeln("stable")
+                .unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "prune", "app"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "3.22.2\n");
+            assert!(!context.fenv_sdk_root("3.22.2").exists());
+            for version in ["3.7.12", "3.19.6", "stable"] {
+                assert!(context.fenv_sdk_root(version).exists());
+            }
+        })
+    }
+
+    #[test]
+    fn test_prune_with_dry_run_and_recorded_workspaces() {
+        test_with_context(|context, output| {
+            // setup
+            for version in ["3.19.6", "3.22.2"] {
+                context
+                    .fenv_sdk_root(version)
+                    .join("bin")
+                    .join("flutter")
+                    .writeln("")
+                    .unwrap();
+            }
+            let app = context.fenv_dir().join("app");
+            app.join(".flutter-version").writeln("3.22").unwrap();
+            crate::workspaces::record_workspace(context, &app).unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "prune", "--dry-run"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "Would uninstall `3.19.6`\n");
+            assert!(context.fenv_sdk_root("3.19.6").exists());
+        })
+    }
+
+    #[test]
+    fn test_prune_fails_on_unreadable_version_file() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_sdk_root("3.19.6")
+                .join("bin")
+                .join("flutter")
+                .writeln("")
+                .unwrap();
+            let app = context.fenv_dir().join("app");
+            app.create_dir_all().unwrap();
+            let version_file = app.join(".flutter-version");
+            std::fs::write(version_file.path(), [0xDE, 0xED, 0xBE, 0xEF]).unwrap();
+
+            // execution
+            let error = try_run(
+                &["fenv", "prune", "app"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap_err();
+
+            // validation
+            assert_eq!(
+                error.to_string(),
+                format!(
+                    "Failed to read `{version_file}`: fix or remove it to prune the Flutter SDKs"
+                )
+            );
+            assert!(context.fenv_sdk_root("3.19.6").exists());
+        })
+    }
+
+    #[test]
+    fn test_prune_fails_without_projects() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_sdk_root("3.19.6")
+                .join("bin")
+                .join("flutter")
+                .writeln("")
+                .unwrap();
+
+            // execution
+            let result = try_run(&["fenv", "prune"], context, &RealSdkService::new(), output);
+
+            // validation
+            assert!(result.is_err());
+            assert!(context.fenv_sdk_root("3.19.6").exists());
+        })
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{
+    args::FenvStartDirArgs,
+    context::FenvContext,
+    sdk_service::{results::LookupResult, sdk_service::SdkService},
+    service::{rehash::rehash_service::SHIMS, service::Service, start_dir::StartDir},
+    util::io::ConsoleOutput,
+};
+use anyhow::{bail, Context};
+use is_executable::is_executable;
+
+/// Succeeds only if the Flutter SDK selected in the given directory is installed and the shims
+/// are placed, so that `flutter` and `dart` are ready to run.
+///
+/// This is a readiness probe for containers such as Docker `HEALTHCHECK` and init containers.
+/// It prints nothing on success and never accesses the remote repository.
+pub struct FenvReadyService {
+    pub args: FenvStartDirArgs,
+}
+
+impl FenvReadyService {
+    pub fn new(args: FenvStartDirArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvReadyService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        _: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let start_dir = self.args.resolve_start_dir(context);
+
+        let path_to_version_file = match sdk_service.find_nearest_version_file(context, &start_dir)
+        {
+            LookupResult::Found(path) => path,
+            LookupResult::Err(err) => return Err(err),
+            LookupResult::None => bail!("Not ready: could not find a version file"),
+        };
+        let version_prefix = sdk_service
+            .read_version_prefix(&path_to_version_file)
+            .with_context(|| {
+                format!(
+                    "Not ready: could not read the version file (set by `{path_to_version_file}`)"
+                )
+            })?;
+        match sdk_service.find_latest_local(context, &version_prefix) {
+            LookupResult::Found(_) => {}
+            LookupResult::Err(err) => return Err(err),
+            LookupResult::None => bail!(
+                "Not ready: `{version_prefix}` is not installed (set by `{path_to_version_file}`)"
+            ),
+        }
+
+        for (shim, _) in SHIMS {
+            let shim_path = context.fenv_shims().join(shim);
+            if !is_executable(&shim_path) {
+                bail!("Not ready: the shim is not placed: `{shim_path}`");
+            }
+        }
+        anyhow::Ok(())
+    }
+}
+
+#[cfg(unix)]
+#[cfg(test)]
+mod tests_unix {
+    use crate::{
+        context::FenvContext, sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context, try_run,
+    };
+    use std::os::unix::prelude::PermissionsExt;
+
+    fn place_shims(context: &impl FenvContext) {
+        for shim in ["flutter", "dart"] {
+            let shim_path = context.fenv_shims().join(shim);
+            shim_path.writeln("").unwrap();
+            std::fs::set_permissions(&shim_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+    }
+
+    fn install_sdk(context: &impl FenvContext, name: &str) {
+        context
+            .fenv_sdk_root(name)
+            .join("bin")
+            .create_dir_all()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_ready() {
+        test_with_context(|context, output| {
+            // setup
+            install_sdk(context, "3.7.12");
+            place_shims(context);
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("3.7")
+                .unwrap();
+
+            // execution
+            try_run(&["fenv", "ready"], context, &RealSdkService::new(), output).unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "");
+        })
+    }
+
+    #[test]
+    fn test_not_ready_if_selected_version_is_not_installed() {
+        test_with_context(|context, output| {
+            // setup
+            install_sdk(context, "3.3.10");
+            place_shims(context);
+            let version_file = context.fenv_dir().join(".flutter-version");
+            version_file.writeln("3.7").unwrap();
+
+            // execution
+            let result = try_run(&["fenv", "ready"], context, &RealSdkService::new(), output);
+
+            // validation
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                format!("Not ready: `3.7` is not installed (set by `{version_file}`)")
+            );
+        })
+    }
+
+    #[test]
+    fn test_not_ready_if_shims_are_not_placed() {
+        test_with_context(|context, output| {
+            // setup
+            install_sdk(context, "3.7.12");
+            context
+                .fenv_global_version_file()
+                .writeln("3.7.12")
+                .unwrap();
+
+            // execution
+            let result = try_run(&["fenv", "ready"], context, &RealSdkService::new(), output);
+
+            // validation
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                format!(
+                    "Not ready: the shim is not placed: `{}`",
+                    context.fenv_shims().join("flutter")
+                )
+            );
+        })
+    }
+}
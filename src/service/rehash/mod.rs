@@ -0,0 +1 @@
+pub mod rehash_service;
@@ -0,0 +1,430 @@
+use crate::{
+    config::{FenvConfig, ShimKind},
+    context::FenvContext,
+    sdk_service::sdk_service::SdkService,
+    service::service::Service,
+    util::{io::ConsoleOutput, path_like::PathLike},
+};
+use anyhow::Context;
+use is_executable::is_executable;
+use log::debug;
+use std::{
+    collections::BTreeSet,
+    time::{Duration, SystemTime},
+};
+
+/// The shim scripts to be placed in `{fenv_shims}`, paired with their names.
+pub const SHIMS: [(&str, &str); 2] = [
+    ("flutter", include_str!("../../../shims/flutter")),
+    ("dart", include_str!("../../../shims/dart")),
+];
+
+/// The shim script placed for every other executable of the installed Flutter SDKs, such as
+/// `dartaotruntime`, which runs the executable through `fenv exec`.
+pub const GENERIC_SHIM: &str = include_str!("../../../shims/generic");
+
+/// The copy of the `fenv` binary in `{fenv_shims}`, which the native shims are hardlinks to.
+pub const NATIVE_SHIM_BINARY: &str = ".fenv-shim";
+
+/// How often [`heal_shims`] regenerates the shims at most.
+const AUTO_REHASH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Regenerates the shims in `{fenv_shims}`, which `fenv init -` puts on `PATH`.
+///
+/// `fenv install` and `fenv uninstall` also regenerate the shims, and `fenv exec` and `fenv which`
+/// do by [`heal_shims`], so this is required only if the shims are removed or modified by hand.
+#[derive(Default)]
+pub struct FenvRehashService;
+
+impl FenvRehashService {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvRehashService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        _: &impl SdkService,
+        _: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        rehash(context)
+    }
+}
+
+/// Writes the shims into `{fenv_shims}` unless they are up to date, and makes them executable.
+///
+/// Besides [`SHIMS`], a [`GENERIC_SHIM`] is written for every executable of the installed
+/// Flutter SDKs, and the generic shims which no installed SDK provides anymore are removed.
+///
+/// If `shimKind` of `config.json` is `native`, the shims are hardlinks to
+/// [`NATIVE_SHIM_BINARY`] copied from the running `fenv` instead of the scripts.
+pub fn rehash(context: &impl FenvContext) -> anyhow::Result<()> {
+    let executables = list_installed_executables(context);
+    let shim_kind = FenvConfig::load(context)?.shim_kind;
+    match shim_kind {
+        ShimKind::Script => {
+            for (name, script) in SHIMS {
+                write_shim(context, name, script)?;
+            }
+            for name in &executables {
+                write_shim(context, name, GENERIC_SHIM)?;
+            }
+        }
+        ShimKind::Native => {
+            let fenv = std::env::current_exe().context("Failed to locate the `fenv` binary")?;
+            write_native_shims(context, &PathLike::from(fenv.as_path()), &executables)?;
+        }
+    }
+    remove_stale_shims(context, &executables, shim_kind)
+}
+
+fn write_shim(context: &impl FenvContext, name: &str, script: &str) -> anyhow::Result<()> {
+    let shim = context.fenv_shims().join(name);
+    if shim.read_to_string().is_ok_and(|content| content == script) {
+        debug!("rehash(): `{shim}` is up to date");
+    } else {
+        debug!("rehash(): Writing `{shim}`");
+        // Writing into a native shim would overwrite the `fenv` binary it is linked to.
+        if shim.exists() {
+            shim.remove_file()
+                .with_context(|| format!("Failed to remove the shim: `{shim}`"))?;
+        }
+        shim.write(script)
+            .with_context(|| format!("Failed to write the shim: `{shim}`"))?;
+    }
+    make_executable(&shim)
+}
+
+/// Copies `fenv` into `{fenv_shims}` as [`NATIVE_SHIM_BINARY`], and links [`SHIMS`] and
+/// `executables` to it.
+fn write_native_shims(
+    context: &impl FenvContext,
+    fenv: &PathLike,
+    executables: &BTreeSet<String>,
+) -> anyhow::Result<()> {
+    let fenv_shims = context.fenv_shims();
+    fenv_shims
+        .create_dir_all()
+        .with_context(|| format!("Failed to create `{fenv_shims}`"))?;
+    let binary = fenv_shims.join(NATIVE_SHIM_BINARY);
+    // `fenv` is the binary itself if `fenv rehash` runs through a native shim.
+    if !is_same_file(fenv, &binary) {
+        let is_up_to_date = std::fs::read(&binary)
+            .ok()
+            .is_some_and(|content| std::fs::read(fenv).is_ok_and(|fenv| fenv == content));
+        if is_up_to_date {
+            debug!("rehash(): `{binary}` is up to date");
+        } else {
+            debug!("rehash(): Copying `{fenv}` into `{binary}`");
+            // The native shims linked to the outdated binary are linked again below.
+            if binary.exists() {
+                binary
+                    .remove_file()
+                    .with_context(|| format!("Failed to remove `{binary}`"))?;
+            }
+            std::fs::copy(fenv, &binary)
+                .with_context(|| format!("Failed to copy `{fenv}` into `{binary}`"))?;
+        }
+    }
+    make_executable(&binary)?;
+
+    let names = SHIMS
+        .iter()
+        .map(|(name, _)| *name)
+        .chain(executables.iter().map(String::as_str));
+    for name in names {
+        let shim = fenv_shims.join(name);
+        if is_same_file(&shim, &binary) {
+            debug!("rehash(): `{shim}` is up to date");
+            continue;
+        }
+        debug!("rehash(): Linking `{shim}` to `{binary}`");
+        if shim.exists() {
+            shim.remove_file()
+                .with_context(|| format!("Failed to remove the shim: `{shim}`"))?;
+        }
+        // A filesystem without hardlinks gets the copies instead.
+        std::fs::hard_link(&binary, &shim)
+            .or_else(|e| {
+                debug!("rehash(): Failed to link `{shim}`: {e}. Copying `{binary}` instead");
+                std::fs::copy(&binary, &shim).map(|_| ())
+            })
+            .with_context(|| format!("Failed to write the shim: `{shim}`"))?;
+    }
+    anyhow::Ok(())
+}
+
+fn make_executable(path: &PathLike) -> anyhow::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::prelude::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+            .with_context(|| format!("Failed to make the shim executable: `{path}`"))?;
+    }
+    anyhow::Ok(())
+}
+
+/// Whether `a` and `b` are the same file, such as a native shim and [`NATIVE_SHIM_BINARY`].
+fn is_same_file(a: &PathLike, b: &PathLike) -> bool {
+    let (Ok(a), Ok(b)) = (a.path().metadata(), b.path().metadata()) else {
+        return false;
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        a.dev() == b.dev() && a.ino() == b.ino()
+    }
+    #[cfg(not(unix))]
+    {
+        a.len() == b.len() && a.modified().ok() == b.modified().ok()
+    }
+}
+
+/// Whether the shim of `name` in [`SHIMS`] is placed as `shimKind` of `config.json` requires.
+pub fn is_shim_up_to_date(context: &impl FenvContext, name: &str) -> anyhow::Result<bool> {
+    let shim = context.fenv_shims().join(name);
+    anyhow::Ok(match FenvConfig::load(context)?.shim_kind {
+        ShimKind::Script => SHIMS.iter().any(|(shim_name, script)| {
+            *shim_name == name
+                && shim
+                    .read_to_string()
+                    .is_ok_and(|content| content == *script)
+        }),
+        ShimKind::Native => is_same_file(&shim, &context.fenv_shims().join(NATIVE_SHIM_BINARY)),
+    })
+}
+
+/// Removes the generic shims whose executables are not in `executables`, and
+/// [`NATIVE_SHIM_BINARY`] unless `shim_kind` is [`ShimKind::Native`].
+fn remove_stale_shims(
+    context: &impl FenvContext,
+    executables: &BTreeSet<String>,
+    shim_kind: ShimKind,
+) -> anyhow::Result<()> {
+    let fenv_shims = context.fenv_shims();
+    let Ok(entries) = fenv_shims.read_dir() else {
+        return anyhow::Ok(());
+    };
+    let binary = fenv_shims.join(NATIVE_SHIM_BINARY);
+    // The native shims are recognized only while the binary exists, so it is removed last.
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str().map(String::from))
+        .filter(|name| name != NATIVE_SHIM_BINARY)
+        .collect();
+    if shim_kind != ShimKind::Native {
+        names.push(String::from(NATIVE_SHIM_BINARY));
+    }
+    for name in names {
+        let shim = fenv_shims.join(&name);
+        let is_stale = (name == NATIVE_SHIM_BINARY && shim.exists())
+            || (!executables.contains(&name)
+                && SHIMS.iter().all(|(shim_name, _)| *shim_name != name)
+                && (is_same_file(&shim, &binary)
+                    || shim
+                        .read_to_string()
+                        .is_ok_and(|content| content == GENERIC_SHIM)));
+        if is_stale {
+            debug!("rehash(): Removing the stale shim `{shim}`");
+            shim.remove_file()
+                .with_context(|| format!("Failed to remove the shim: `{shim}`"))?;
+        }
+    }
+    anyhow::Ok(())
+}
+
+/// Lists the executables of all installed Flutter SDKs except [`SHIMS`].
+fn list_installed_executables(context: &impl FenvContext) -> BTreeSet<String> {
+    let Ok(entries) = context.fenv_versions().read_dir() else {
+        return BTreeSet::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .flat_map(|entry| list_sdk_executables(&PathLike::from(entry.path().as_path())))
+        .collect()
+}
+
+/// Lists the executables in `bin` and `bin/cache/dart-sdk/bin` of the Flutter SDK at
+/// `sdk_root` except [`SHIMS`].
+fn list_sdk_executables(sdk_root: &PathLike) -> BTreeSet<String> {
+    let sdk_bin = sdk_root.join("bin");
+    let dart_sdk_bin = sdk_bin.join("cache").join("dart-sdk").join("bin");
+    [sdk_bin, dart_sdk_bin]
+        .iter()
+        .filter_map(|dir| dir.read_dir().ok())
+        .flat_map(|entries| entries.flatten())
+        .filter(|entry| entry.path().is_file() && is_executable(entry.path()))
+        .filter_map(|entry| entry.file_name().to_str().map(String::from))
+        .filter(|name| !name.starts_with('.') && SHIMS.iter().all(|(shim, _)| shim != name))
+        .collect()
+}
+
+/// Regenerates the shims if an executable of the Flutter SDK at `sdk_root` has no shim, such as
+/// a tool shipped only by a newer Flutter SDK or by the Dart SDK downloaded on the first run.
+///
+/// Runs once per [`AUTO_REHASH_INTERVAL`] at most, recorded by `{fenv_cache}/auto_rehash`.
+/// Failures are only logged because the shims are regenerated by `fenv rehash` anyway.
+pub fn heal_shims(context: &impl FenvContext, sdk_root: &PathLike) {
+    let fenv_shims = context.fenv_shims();
+    let executables = list_sdk_executables(sdk_root);
+    let Some(missing) = executables
+        .iter()
+        .find(|name| !fenv_shims.join(name).is_file())
+    else {
+        return;
+    };
+    let stamp = context.fenv_cache().join("auto_rehash");
+    let is_rate_limited = stamp
+        .path()
+        .metadata()
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|elapsed| elapsed < AUTO_REHASH_INTERVAL);
+    if is_rate_limited {
+        debug!("heal_shims(): `{missing}` has no shim, but the shims were regenerated recently");
+        return;
+    }
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    if let Err(e) = stamp.writeln(now.as_secs().to_string()) {
+        debug!("heal_shims(): Failed to write `{stamp}`: {e}");
+        return;
+    }
+    debug!("heal_shims(): `{missing}` has no shim. Regenerating the shims");
+    if let Err(e) = rehash(context) {
+        debug!("heal_shims(): Failed to regenerate the shims: {e}");
+    }
+}
+
+#[cfg(unix)]
+#[cfg(test)]
+mod tests_unix {
+    use super::{
+        heal_shims, is_same_file, rehash, write_native_shims, GENERIC_SHIM, NATIVE_SHIM_BINARY,
+        SHIMS,
+    };
+    use crate::{
+        context::FenvContext, sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context, try_run, util::path_like::PathLike,
+    };
+    use is_executable::is_executable;
+    use std::{collections::BTreeSet, os::unix::prelude::PermissionsExt};
+
+    fn place_executable(path: &PathLike) {
+        path.writeln("#!/bin/sh").unwrap();
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn test_rehash_places_shims() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_shims()
+                .join("flutter")
+                .writeln("outdated")
+                .unwrap();
+
+            // execution
+            try_run(&["fenv", "rehash"], context, &RealSdkService::new(), output).unwrap();
+
+            // validation
+            for (name, script) in SHIMS {
+                let shim = context.fenv_shims().join(name);
+                assert_eq!(shim.read_to_string().unwrap(), script);
+                assert!(is_executable(&shim));
+            }
+            assert!(output.stdout_to_string().is_empty());
+        })
+    }
+
+    #[test]
+    fn test_rehash_places_generic_shims_for_sdk_executables() {
+        test_with_context(|context, _| {
+            // setup
+            let sdk_root = context.fenv_sdk_root("3.22.2");
+            place_executable(&sdk_root.join("bin/flutter"));
+            place_executable(&sdk_root.join("bin/cache/dart-sdk/bin/dartaotruntime"));
+            sdk_root.join("bin/flutter.bat").writeln("").unwrap();
+            let stale = context.fenv_shims().join("removed-tool");
+            stale.write(GENERIC_SHIM).unwrap();
+
+            // execution
+            rehash(context).unwrap();
+
+            // validation
+            let shim = context.fenv_shims().join("dartaotruntime");
+            assert_eq!(shim.read_to_string().unwrap(), GENERIC_SHIM);
+            assert!(is_executable(&shim));
+            assert!(!context.fenv_shims().join("flutter.bat").exists());
+            assert!(!stale.exists());
+        })
+    }
+
+    #[test]
+    fn test_native_shims_are_hardlinks_to_fenv_binary() {
+        test_with_context(|context, _| {
+            // setup
+            let fenv = context.fenv_root().join("bin/fenv");
+            fenv.writeln("fenv binary").unwrap();
+            context
+                .fenv_shims()
+                .join("flutter")
+                .write(SHIMS[0].1)
+                .unwrap();
+            let stale = context.fenv_shims().join("removed-tool");
+            let executables = BTreeSet::from([String::from("dartaotruntime")]);
+
+            // execution
+            write_native_shims(context, &fenv, &executables).unwrap();
+            std::fs::hard_link(context.fenv_shims().join(NATIVE_SHIM_BINARY), &stale).unwrap();
+            let binary = context.fenv_shims().join(NATIVE_SHIM_BINARY);
+            let linked: Vec<bool> = ["flutter", "dart", "dartaotruntime"]
+                .iter()
+                .map(|name| is_same_file(&context.fenv_shims().join(name), &binary))
+                .collect();
+            rehash(context).unwrap();
+
+            // validation
+            assert_eq!(linked, [true, true, true]);
+            for (name, script) in SHIMS {
+                let shim = context.fenv_shims().join(name);
+                assert_eq!(shim.read_to_string().unwrap(), script);
+            }
+            assert_eq!(fenv.read_to_string().unwrap(), "fenv binary\n");
+            assert!(!binary.exists());
+            assert!(!stale.exists());
+        })
+    }
+
+    #[test]
+    fn test_heal_shims_is_rate_limited() {
+        test_with_context(|context, _| {
+            // setup
+            let sdk_root = context.fenv_sdk_root("3.22.2");
+            place_executable(&sdk_root.join("bin/flutter"));
+            rehash(context).unwrap();
+            let first = sdk_root.join("bin/cache/dart-sdk/bin/dartaotruntime");
+            let second = sdk_root.join("bin/cache/dart-sdk/bin/dartdev");
+
+            // execution
+            place_executable(&first);
+            heal_shims(context, &sdk_root);
+            place_executable(&second);
+            heal_shims(context, &sdk_root);
+
+            // validation
+            assert!(context.fenv_shims().join("dartaotruntime").is_file());
+            assert!(!context.fenv_shims().join("dartdev").exists());
+        })
+    }
+}
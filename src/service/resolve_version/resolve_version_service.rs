@@ -0,0 +1,197 @@
+use crate::{
+    args::FenvStartDirArgs,
+    context::FenvContext,
+    sdk_service::{model::flutter_sdk::FlutterSdk, results::LookupResult, sdk_service::SdkService},
+    service::{service::Service, start_dir::StartDir},
+    util::io::ConsoleOutput,
+};
+use anyhow::{bail, Context};
+
+/// Resolves the Flutter SDK version selected in the given directory and prints it as
+/// `key=value` lines in the fixed order of `version`, `origin`, `installed`, and `path`.
+///
+/// This is a plumbing command for the shims, shell prompts, and third-party tools.
+/// Unlike `fenv version`, it never accesses the remote repository and its output format is
+/// kept stable.
+pub struct FenvResolveVersionService {
+    pub args: FenvStartDirArgs,
+}
+
+impl FenvResolveVersionService {
+    pub fn new(args: FenvStartDirArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvResolveVersionService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let start_dir = self.args.resolve_start_dir(context);
+
+        let path_to_version_file = match sdk_service.find_nearest_version_file(context, &start_dir)
+        {
+            LookupResult::Found(path) => path,
+            LookupResult::Err(err) => return Err(err),
+            LookupResult::None => bail!("Could not find a version file"),
+        };
+        let version_prefix = sdk_service
+            .read_version_prefix(&path_to_version_file)
+            .with_context(|| {
+                format!("Could not read the version file (set by `{path_to_version_file}`)")
+            })?;
+        let (version, installed, path) =
+            match sdk_service.find_latest_local(context, &version_prefix) {
+                LookupResult::Found(sdk) => (
+                    sdk.display_name(),
+                    true,
+                    context.fenv_sdk_root(&sdk.display_name()).to_string(),
+                ),
+                LookupResult::Err(err) => return Err(err),
+                LookupResult::None => (version_prefix, false, String::new()),
+            };
+
+        let stdout = output.stdout();
+        writeln!(stdout, "version={version}")?;
+        writeln!(stdout, "origin={path_to_version_file}")?;
+        writeln!(stdout, "installed={installed}")?;
+        writeln!(stdout, "path={path}")?;
+        anyhow::Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::FenvContext, sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context, try_run, write_invalid_utf8,
+    };
+    use indoc::formatdoc;
+    use std::io::Write;
+
+    #[test]
+    fn test_resolve_installed_local_version() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("3.7.12")
+                .create_dir_all()
+                .unwrap();
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("3.7")
+                .unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "__resolve-version"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                formatdoc! {"
+                    version=3.7.12
+                    origin={origin}
+                    installed=true
+                    path={path}
+                    ",
+                    origin = context.fenv_dir().join(".flutter-version"),
+                    path = context.fenv_versions().join("3.7.12"),
+                }
+            );
+            assert!(output.stderr_to_string().is_empty());
+        })
+    }
+
+    #[test]
+    fn test_resolve_uninstalled_global_version_without_accessing_remote() {
+        test_with_context(|context, output| {
+            // setup
+            context.fenv_global_version_file().writeln("3.10").unwrap();
+
+            // execution
+            // `RealSdkService::new()` would fail if it accessed the remote repository offline.
+            try_run(
+                &["fenv", "__resolve-version", &context.fenv_dir().to_string()],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                formatdoc! {"
+                    version=3.10
+                    origin={origin}
+                    installed=false
+                    path=
+                    ",
+                    origin = context.fenv_global_version_file(),
+                }
+            );
+        })
+    }
+
+    #[test]
+    fn test_resolve_fails_if_no_version_file_is_found() {
+        test_with_context(|context, output| {
+            // execution
+            let result = try_run(
+                &["fenv", "__resolve-version"],
+                context,
+                &RealSdkService::new(),
+                output,
+            );
+
+            // validation
+            assert!(result.is_err());
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                "Could not find a version file"
+            );
+            assert!(output.stdout_to_string().is_empty());
+        })
+    }
+
+    #[test]
+    fn test_resolve_fails_if_version_file_is_not_utf8() {
+        test_with_context(|context, output| {
+            // setup
+            write_invalid_utf8!(context.fenv_dir().join(".flutter-version"));
+
+            // execution
+            let result = try_run(
+                &["fenv", "__resolve-version"],
+                context,
+                &RealSdkService::new(),
+                output,
+            );
+
+            // validation
+            assert!(result.is_err());
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                format!(
+                    "Could not read the version file (set by `{}`)",
+                    context.fenv_dir().join(".flutter-version")
+                )
+            );
+        })
+    }
+}
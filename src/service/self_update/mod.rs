@@ -0,0 +1 @@
+pub mod self_update_service;
@@ -0,0 +1,333 @@
+use crate::{
+    args::FenvSelfUpdateArgs,
+    context::FenvContext,
+    external::downloader::{DownloadOptions, Downloader, DownloaderImpl},
+    sdk_service::sdk_service::SdkService,
+    service::service::Service,
+    util::{
+        checksum::sha256_of_file,
+        io::{ConsoleOutput, SilentFailure},
+        offline::ensure_online,
+        path_like::PathLike,
+        platform::{Architecture, OperatingSystem},
+    },
+};
+use anyhow::{bail, Context};
+use log::debug;
+use serde::Deserialize;
+
+/// The GitHub API which describes the latest release of `fenv`.
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/fenv-org/fenv/releases/latest";
+
+/// The base URL of the assets of the GitHub releases of `fenv`.
+const RELEASE_DOWNLOAD_URL: &str = "https://github.com/fenv-org/fenv/releases/download";
+
+/// Replaces the current `fenv` executable with the latest release on GitHub.
+///
+/// Each release has the executables named `fenv-{target}` such as `fenv-aarch64-apple-darwin`
+/// and their checksum files `fenv-{target}.sha256`. The executable is downloaded into
+/// `{fenv_bin}` first, and replaces the current executable only if its checksum matches.
+pub struct FenvSelfUpdateService {
+    pub args: FenvSelfUpdateArgs,
+}
+
+impl FenvSelfUpdateService {
+    pub fn new(args: FenvSelfUpdateArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvSelfUpdateService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        _: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        ensure_online(context, "Updating `fenv`")?;
+        let current_exe =
+            std::env::current_exe().context("Failed to locate the current `fenv` executable")?;
+        self_update(
+            context,
+            &DownloaderImpl::new(),
+            output,
+            self.args.check,
+            &PathLike::from(&current_exe),
+            env!("CARGO_PKG_VERSION"),
+        )
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+}
+
+fn self_update<OUT: std::io::Write, ERR: std::io::Write>(
+    context: &impl FenvContext,
+    downloader: &impl Downloader,
+    output: &mut dyn ConsoleOutput<OUT, ERR>,
+    check: bool,
+    current_exe: &PathLike,
+    current_version: &str,
+) -> anyhow::Result<()> {
+    let (os, arch) = (context.operating_system(), context.architecture());
+    let Some(target) = release_target(os, arch) else {
+        bail!("fenv is not released for `{os:?}` on `{arch:?}`")
+    };
+    let options = DownloadOptions::resolve(context)?;
+    let release: Release = serde_json::from_str(&downloader.fetch(LATEST_RELEASE_URL, &options)?)
+        .context("Failed to parse the latest release of fenv")?;
+    let latest_version = release.tag_name.trim_start_matches('v');
+    if !is_newer(latest_version, current_version) {
+        writeln!(output.stdout(), "fenv {current_version} is up to date")?;
+        return anyhow::Ok(());
+    }
+    if check {
+        writeln!(
+            output.stdout(),
+            "fenv {latest_version} is available (current: {current_version})"
+        )?;
+        return Err(SilentFailure(1).into());
+    }
+
+    let asset = format!("fenv-{target}");
+    let url = format!("{RELEASE_DOWNLOAD_URL}/{}/{asset}", release.tag_name);
+    let fenv_bin = context.fenv_bin();
+    fenv_bin
+        .create_dir_all()
+        .with_context(|| format!("Failed to create `{fenv_bin}`"))?;
+    let download = fenv_bin.join(format!(".{asset}.download"));
+    debug!("self_update(): Downloading `{url}` into `{download}`");
+    downloader.download(&url, &download.to_string(), &options)?;
+    let result = downloader
+        .fetch(&format!("{url}.sha256"), &options)
+        .and_then(|checksum| verify_checksum(&download, &asset, &checksum))
+        .and_then(|_| replace_executable(&download, current_exe));
+    if download.exists() {
+        if let Err(e) = download.remove_file() {
+            debug!("self_update(): Failed to remove `{download}`: {e}");
+        }
+    }
+    result?;
+    writeln!(
+        output.stdout(),
+        "fenv is updated from {current_version} to {latest_version}"
+    )?;
+    anyhow::Ok(())
+}
+
+/// The target triple of the release asset for the given platform.
+fn release_target(os: OperatingSystem, arch: Architecture) -> Option<&'static str> {
+    match (os, arch) {
+        (OperatingSystem::Linux, Architecture::X86_64) => Some("x86_64-unknown-linux-gnu"),
+        (OperatingSystem::Linux, Architecture::Aarch64) => Some("aarch64-unknown-linux-gnu"),
+        (OperatingSystem::MacOs, Architecture::X86_64) => Some("x86_64-apple-darwin"),
+        (OperatingSystem::MacOs, Architecture::Aarch64) => Some("aarch64-apple-darwin"),
+        _ => None,
+    }
+}
+
+/// Whether `latest` such as `0.2.0` is newer than `current` such as `0.2.0-SNAPSHOT`.
+///
+/// A pre-release is older than the release of the same version. If either of them is not
+/// in the form of `major.minor.patch`, `latest` is never regarded as newer, so that a malformed
+/// release never replaces a working `fenv`.
+fn is_newer(latest: &str, current: &str) -> bool {
+    fn parse(version: &str) -> Option<(Vec<u64>, bool)> {
+        let (numbers, pre_release) = match version.split_once('-') {
+            Some((numbers, _)) => (numbers, true),
+            None => (version, false),
+        };
+        let numbers = numbers
+            .split('.')
+            .map(|number| number.parse().ok())
+            .collect::<Option<Vec<u64>>>()?;
+        (numbers.len() == 3).then_some((numbers, pre_release))
+    }
+
+    match (parse(latest), parse(current)) {
+        (Some((latest, latest_pre)), Some((current, current_pre))) => {
+            latest > current || (latest == current && current_pre && !latest_pre)
+        }
+        _ => false,
+    }
+}
+
+/// Fails unless the SHA-256 of `file` matches the first field of the given `checksum` line,
+/// which is formatted as `sha256sum` does.
+fn verify_checksum(file: &PathLike, asset: &str, checksum: &str) -> anyhow::Result<()> {
+    let Some(expected) = checksum.split_whitespace().next() else {
+        bail!("The checksum of `{asset}` is empty")
+    };
+    let actual = sha256_of_file(file)?;
+    if !actual.eq_ignore_ascii_case(expected) {
+        bail!("The checksum of `{asset}` does not match: expected `{expected}`, but `{actual}`")
+    }
+    anyhow::Ok(())
+}
+
+/// Replaces `current_exe` with `new_exe` atomically by renaming.
+fn replace_executable(new_exe: &PathLike, current_exe: &PathLike) -> anyhow::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::prelude::PermissionsExt;
+        std::fs::set_permissions(new_exe, std::fs::Permissions::from_mode(0o755))
+            .with_context(|| format!("Failed to make `{new_exe}` executable"))?;
+    }
+    if std::fs::rename(new_exe, current_exe).is_ok() {
+        return anyhow::Ok(());
+    }
+    // `rename` does not work across the file systems, so copies it next to `current_exe` first.
+    let staged = PathLike::from(current_exe.path().with_extension("new").as_path());
+    std::fs::copy(new_exe, &staged)
+        .with_context(|| format!("Failed to copy `{new_exe}` to `{staged}`"))?;
+    std::fs::rename(&staged, current_exe)
+        .with_context(|| format!("Failed to replace `{current_exe}`"))
+}
+
+#[cfg(unix)]
+#[cfg(test)]
+mod tests_unix {
+    use super::{is_newer, self_update};
+    use crate::{
+        context::FenvContext, external::downloader::MockDownloader,
+        service::macros::test_with_context, util::io::SilentFailure,
+    };
+    use is_executable::is_executable;
+
+    const LATEST_RELEASE: &str = r#"{"tag_name": "v0.3.0"}"#;
+
+    /// SHA-256 of `new fenv\n`.
+    const NEW_FENV_SHA256: &str =
+        "0d7cf09f6559fff3f32803858c57f5c7b7d0f1ccd5b87f555373fff623ca2e54";
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("0.3.0", "0.2.0"));
+        assert!(is_newer("0.2.0", "0.2.0-SNAPSHOT"));
+        assert!(is_newer("1.0.0", "0.10.0"));
+        assert!(!is_newer("0.2.0", "0.2.0"));
+        assert!(!is_newer("0.2.0-beta", "0.2.0"));
+        assert!(!is_newer("0.1.9", "0.2.0"));
+        assert!(!is_newer("nightly", "0.2.0"));
+        assert!(!is_newer("0.3.0", "0.2"));
+    }
+
+    #[test]
+    fn test_self_update_reports_up_to_date() {
+        test_with_context(|context, output| {
+            // setup
+            let mut downloader = MockDownloader::new();
+            downloader
+                .expect_fetch()
+                .times(1)
+                .returning(|_, _| anyhow::Ok(String::from(LATEST_RELEASE)));
+            let current_exe = context.fenv_bin().join("fenv");
+
+            // execution
+            self_update(context, &downloader, output, false, &current_exe, "0.3.0").unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "fenv 0.3.0 is up to date\n");
+        })
+    }
+
+    #[test]
+    fn test_self_update_check_fails_if_newer_release_exists() {
+        test_with_context(|context, output| {
+            // setup
+            let mut downloader = MockDownloader::new();
+            downloader
+                .expect_fetch()
+                .times(1)
+                .returning(|_, _| anyhow::Ok(String::from(LATEST_RELEASE)));
+            let current_exe = context.fenv_bin().join("fenv");
+
+            // execution
+            let err =
+                self_update(context, &downloader, output, true, &current_exe, "0.2.0").unwrap_err();
+
+            // validation
+            assert_eq!(err.downcast_ref::<SilentFailure>().unwrap().0, 1);
+            assert_eq!(
+                output.stdout_to_string(),
+                "fenv 0.3.0 is available (current: 0.2.0)\n"
+            );
+        })
+    }
+
+    fn mock_downloader(checksum: &'static str) -> MockDownloader {
+        let mut downloader = MockDownloader::new();
+        downloader
+            .expect_fetch()
+            .withf(|url, _| url.ends_with("/releases/latest"))
+            .returning(|_, _| anyhow::Ok(String::from(LATEST_RELEASE)));
+        downloader
+            .expect_fetch()
+            .withf(|url, _| url.ends_with(".sha256"))
+            .returning(move |url, _| {
+                let asset = url.rsplit('/').next().unwrap().trim_end_matches(".sha256");
+                anyhow::Ok(format!("{checksum}  {asset}\n"))
+            });
+        downloader
+            .expect_download()
+            .withf(|url, _, _| url.contains("/releases/download/v0.3.0/fenv-"))
+            .times(1)
+            .returning(|_, destination, _| {
+                std::fs::write(destination, "new fenv\n").map_err(|e| anyhow::anyhow!(e))
+            });
+        downloader
+    }
+
+    #[test]
+    fn test_self_update_replaces_current_executable() {
+        test_with_context(|context, output| {
+            // setup
+            let current_exe = context.fenv_bin().join("fenv");
+            current_exe.writeln("old fenv").unwrap();
+            let downloader = mock_downloader(NEW_FENV_SHA256);
+
+            // execution
+            self_update(context, &downloader, output, false, &current_exe, "0.2.0").unwrap();
+
+            // validation
+            assert_eq!(current_exe.read_to_string().unwrap(), "new fenv\n");
+            assert!(is_executable(&current_exe));
+            assert_eq!(
+                context.fenv_bin().read_dir().unwrap().count(),
+                1,
+                "the downloaded file must be moved"
+            );
+            assert_eq!(
+                output.stdout_to_string(),
+                "fenv is updated from 0.2.0 to 0.3.0\n"
+            );
+        })
+    }
+
+    #[test]
+    fn test_self_update_fails_if_checksum_does_not_match() {
+        test_with_context(|context, output| {
+            // setup
+            let current_exe = context.fenv_bin().join("fenv");
+            current_exe.writeln("old fenv").unwrap();
+            let downloader = mock_downloader("0123456789abcdef");
+
+            // execution
+            let result = self_update(context, &downloader, output, false, &current_exe, "0.2.0");
+
+            // validation
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .contains("does not match: expected `0123456789abcdef`"));
+            assert_eq!(current_exe.read_to_string().unwrap(), "old fenv\n");
+            assert_eq!(context.fenv_bin().read_dir().unwrap().count(), 1);
+        })
+    }
+}
@@ -0,0 +1,2 @@
+mod pubspec_yaml;
+pub mod setup_service;
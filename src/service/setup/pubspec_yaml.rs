@@ -0,0 +1,107 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// Reads the Flutter SDK constraint in the `environment` section of `pubspec.yaml`.
+///
+/// Only the block style such as below is supported since `pubspec.yaml` is formatted so in
+/// practice.
+///
+/// ```yaml
+/// environment:
+///   sdk: ">=2.19.0 <3.0.0"
+///   flutter: ">=3.7.0 <4.0.0"
+/// ```
+pub fn read_flutter_constraint(raw_yaml: &str) -> Option<String> {
+    lazy_static! {
+        static ref FLUTTER_PATTERN: Regex =
+            Regex::new(r#"^\s+flutter\s*:\s*["']?([^"'#]*?)["']?\s*(?:#.*)?$"#).unwrap();
+    }
+    let mut in_environment = false;
+    for line in raw_yaml.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) {
+            in_environment = line.trim_end() == "environment:";
+            continue;
+        }
+        if in_environment {
+            if let Some(captures) = FLUTTER_PATTERN.captures(line) {
+                return Some(captures[1].trim().to_string());
+            }
+        }
+    }
+    None
+}
+
+/// Converts the given Flutter SDK constraint to a version prefix.
+///
+/// Supports an exact version such as `3.7.12`, a caret constraint such as `^3.7.0`, and a range
+/// constraint within a major version such as `>=3.7.0 <4.0.0`. The latter two are converted to
+/// the major version, whose latest version always satisfies them.
+pub fn constraint_to_prefix(constraint: &str) -> Option<String> {
+    lazy_static! {
+        static ref EXACT_PATTERN: Regex =
+            Regex::new(r"^(\d+\.\d+\.\d+(?:[+-]hotfix\.\d+)?)$").unwrap();
+        static ref CARET_PATTERN: Regex = Regex::new(r"^\^([1-9]\d*)\.\d+\.\d+$").unwrap();
+        static ref RANGE_PATTERN: Regex =
+            Regex::new(r"^>=\s*([1-9]\d*)\.\d+\.\d+\s+<\s*(\d+)\.0\.0$").unwrap();
+    }
+    if let Some(captures) = EXACT_PATTERN.captures(constraint) {
+        return Some(captures[1].to_string());
+    }
+    if let Some(captures) = CARET_PATTERN.captures(constraint) {
+        return Some(captures[1].to_string());
+    }
+    if let Some(captures) = RANGE_PATTERN.captures(constraint) {
+        let lower_major: u32 = captures[1].parse().ok()?;
+        let upper_major: u32 = captures[2].parse().ok()?;
+        if upper_major == lower_major + 1 {
+            return Some(lower_major.to_string());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{constraint_to_prefix, read_flutter_constraint};
+    use indoc::indoc;
+
+    #[test]
+    fn test_read_flutter_constraint() {
+        let raw_yaml = indoc! {r#"
+            name: app
+            # flutter: "1.0.0"
+            dependencies:
+              flutter:
+                sdk: flutter
+
+            environment:
+              sdk: ">=2.19.0 <3.0.0"
+              flutter: ">=3.7.0 <4.0.0" # comment
+        "#};
+        assert_eq!(
+            read_flutter_constraint(raw_yaml),
+            Some(String::from(">=3.7.0 <4.0.0"))
+        );
+        assert_eq!(read_flutter_constraint("name: app\n"), None);
+    }
+
+    #[test]
+    fn test_constraint_to_prefix() {
+        assert_eq!(constraint_to_prefix("3.7.12"), Some(String::from("3.7.12")));
+        assert_eq!(
+            constraint_to_prefix("1.22.0+hotfix.1"),
+            Some(String::from("1.22.0+hotfix.1"))
+        );
+        assert_eq!(constraint_to_prefix("^3.7.0"), Some(String::from("3")));
+        assert_eq!(
+            constraint_to_prefix(">=3.7.0 <4.0.0"),
+            Some(String::from("3"))
+        );
+        assert_eq!(constraint_to_prefix(">=2.10.0 <4.0.0"), None);
+        assert_eq!(constraint_to_prefix(">=3.7.0"), None);
+        assert_eq!(constraint_to_prefix("any"), None);
+    }
+}
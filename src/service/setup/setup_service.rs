@@ -0,0 +1,277 @@
+use crate::{
+    args::{FenvSetupArgs, FenvWorkspaceArgs},
+    context::FenvContext,
+    sdk_service::{
+        model::{flutter_sdk::FlutterSdk, local_flutter_sdk::LocalFlutterSdk},
+        results::LookupResult,
+        sdk_service::{InstallOptions, SdkService},
+    },
+    service::{
+        service::Service,
+        setup::pubspec_yaml::{constraint_to_prefix, read_flutter_constraint},
+        workspace::workspace_service::FenvWorkspaceService,
+    },
+    util::{io::ConsoleOutput, path_like::PathLike},
+};
+use anyhow::{bail, Context};
+
+/// Bootstraps a Flutter project in one go: resolves the Flutter SDK version of the project,
+/// installs it if missing, and generates the workspace files for IDEs.
+pub struct FenvSetupService {
+    pub args: FenvSetupArgs,
+}
+
+impl FenvSetupService {
+    pub fn new(args: FenvSetupArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvSetupService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let project_path = context.fenv_dir().join(&self.args.project);
+        let pubspec_yaml_path = project_path.join("pubspec.yaml");
+        if !pubspec_yaml_path.is_file() {
+            bail!("Specify a project path that contains `pubspec.yaml` file: `{project_path}`");
+        }
+
+        let (prefix, origin) =
+            resolve_version_prefix(sdk_service, &project_path, &pubspec_yaml_path)?;
+        writeln!(
+            output.stdout(),
+            "Use Flutter `{prefix}` (set by `{origin}`)"
+        )?;
+
+        let sdk = match find_installed_sdk(context, sdk_service, &prefix)? {
+            Some(sdk) => sdk,
+            None => {
+                sdk_service.install_sdk(
+                    context,
+                    &prefix,
+                    &InstallOptions {
+                        should_doctor: true,
+                        should_precache: self.args.should_precache,
+                        ..Default::default()
+                    },
+                    output.stderr(),
+                )?;
+                match find_installed_sdk(context, sdk_service, &prefix)? {
+                    Some(sdk) => sdk,
+                    None => bail!("Failed to install Flutter `{prefix}`"),
+                }
+            }
+        };
+
+        FenvWorkspaceService::new(FenvWorkspaceArgs {
+            workspace: project_path.to_string(),
+            prefix: Some(sdk.display_name()),
+            should_pub_get: self.args.should_pub_get,
+            force: false,
+            emit_path_file: None,
+            clean: false,
+        })
+        .execute(context, sdk_service, output)
+    }
+}
+
+/// Resolves the version prefix of the project from the nearest local version file, or
+/// the Flutter SDK constraint of `pubspec.yaml` if no local version file exists.
+///
+/// Returns the prefix and the file where it comes from.
+fn resolve_version_prefix(
+    sdk_service: &impl SdkService,
+    project_path: &PathLike,
+    pubspec_yaml_path: &PathLike,
+) -> anyhow::Result<(String, PathLike)> {
+    match sdk_service.find_nearest_local_version_file(project_path) {
+        LookupResult::Found(path_to_version_file) => {
+            let prefix = sdk_service
+                .read_version_prefix(&path_to_version_file)
+                .with_context(|| format!("Could not read `{path_to_version_file}`"))?;
+            return anyhow::Ok((prefix, path_to_version_file));
+        }
+        LookupResult::Err(err) => return Err(err),
+        LookupResult::None => {}
+    }
+
+    let raw_yaml = pubspec_yaml_path
+        .read_to_string()
+        .with_context(|| format!("Could not read `{pubspec_yaml_path}`"))?;
+    let Some(constraint) = read_flutter_constraint(&raw_yaml) else {
+        bail!(
+            "Could not find any local version file nor Flutter SDK constraint in \
+            `{pubspec_yaml_path}`: run `fenv local <VERSION>` in `{project_path}` first"
+        )
+    };
+    match constraint_to_prefix(&constraint) {
+        Some(prefix) => anyhow::Ok((prefix, pubspec_yaml_path.clone())),
+        None => bail!(
+            "Could not pick a Flutter SDK version from the constraint `{constraint}` in \
+            `{pubspec_yaml_path}`: run `fenv local <VERSION>` in `{project_path}` first"
+        ),
+    }
+}
+
+pub(crate) fn find_installed_sdk(
+    context: &impl FenvContext,
+    sdk_service: &impl SdkService,
+    prefix: &str,
+) -> anyhow::Result<Option<LocalFlutterSdk>> {
+    match sdk_service.find_latest_local(context, prefix) {
+        LookupResult::Found(sdk) => anyhow::Ok(Some(sdk)),
+        LookupResult::Err(err) => Err(err),
+        LookupResult::None => anyhow::Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::FenvContext,
+        define_mock_flutter_command,
+        external::git_command::MockGitCommand,
+        sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context,
+        try_run,
+        util::{chrono_wrapper::SystemClock, path_like::PathLike},
+    };
+    use indoc::{formatdoc, indoc};
+
+    define_mock_flutter_command!();
+
+    fn prepare_project(context: &impl FenvContext, pubspec_yaml: &str) -> PathLike {
+        let project = context.fenv_dir().join("app");
+        project.join("pubspec.yaml").write(pubspec_yaml).unwrap();
+        project
+    }
+
+    fn prepare_dart_sdk_lib(sdk_root: &str) {
+        let dart_sdk_lib = PathLike::from(sdk_root)
+            .join("bin")
+            .join("cache")
+            .join("dart-sdk")
+            .join("lib");
+        dart_sdk_lib.join("core").create_dir_all().unwrap();
+    }
+
+    #[test]
+    fn test_setup_with_installed_version_from_version_file() {
+        test_with_context(|context, output| {
+            // setup
+            let project = prepare_project(context, "name: app\n");
+            project.join(".flutter-version").writeln("3.7").unwrap();
+            prepare_dart_sdk_lib(&context.fenv_sdk_root("3.7.12").to_string());
+
+            // execution
+            try_run(
+                &["fenv", "setup", "app"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                formatdoc! {"
+                    Use Flutter `3.7` (set by `{project}/.flutter-version`)
+                    `{project}/.dart_tool/package_config.json` is generated
+                    `{project}/.idea/libraries/Dart_SDK.xml` is generated
+                    ",
+                    project = project,
+                }
+            );
+        })
+    }
+
+    #[test]
+    fn test_setup_installs_missing_version_from_pubspec_yaml() {
+        test_with_context(|context, output| {
+            // setup
+            let project = prepare_project(
+                context,
+                indoc! {r#"
+                    name: app
+                    environment:
+                      flutter: "3.7.12"
+                "#},
+            );
+            let mut git_command = MockGitCommand::new();
+            git_command
+                .expect_list_remote_sdks_by_tags()
+                .returning(|_| {
+                    anyhow::Ok(String::from(
+                        "fd9d2d5d2e5dc3a7e4e5a5d2e5dc3a7e4e5a5d2e\trefs/tags/3.7.12",
+                    ))
+                });
+            git_command
+                .expect_list_remote_sdks_by_branches()
+                .returning(|_| anyhow::Ok(String::new()));
+            git_command
+                .expect_clone_flutter_sdk_by_version()
+                .times(1)
+                .returning(|_, destination, _| {
+                    prepare_dart_sdk_lib(destination);
+                    anyhow::Ok(())
+                });
+            let sdk_service =
+                RealSdkService::from(git_command, SystemClock::new(), MockFlutterCommand);
+
+            // execution
+            try_run(&["fenv", "setup", "app"], context, &sdk_service, output).unwrap();
+
+            // validation
+            assert!(context.fenv_sdk_root("3.7.12").is_dir());
+            assert!(project
+                .join(".dart_tool")
+                .join("package_config.json")
+                .is_file());
+            assert!(output.stdout_to_string().starts_with(&format!(
+                "Use Flutter `3.7.12` (set by `{project}/pubspec.yaml`)\n"
+            )));
+        })
+    }
+
+    #[test]
+    fn test_setup_fails_if_version_is_not_resolvable() {
+        test_with_context(|context, output| {
+            // setup
+            let project = prepare_project(
+                context,
+                indoc! {r#"
+                    name: app
+                    environment:
+                      flutter: ">=2.10.0"
+                "#},
+            );
+
+            // execution
+            let result = try_run(
+                &["fenv", "setup", "app"],
+                context,
+                &RealSdkService::new(),
+                output,
+            );
+
+            // validation
+            assert!(result.is_err());
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                format!(
+                    "Could not pick a Flutter SDK version from the constraint `>=2.10.0` in \
+                    `{project}/pubspec.yaml`: run `fenv local <VERSION>` in `{project}` first"
+                )
+            );
+        })
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{
+    args::FenvShellArgs,
+    context::FenvContext,
+    sdk_service::{results::LookupResult, sdk_service::SdkService},
+    service::{init::init_service::detect_shell, service::Service},
+    util::io::ConsoleOutput,
+};
+use anyhow::{bail, Context};
+
+/// Prints the shell code which sets or clears `$FENV_VERSION` of the current shell session.
+///
+/// A child process cannot change the environment variables of its parent shell, so the shell
+/// function `fenv` defined by `fenv init -` evaluates the printed code.
+pub struct FenvShellService {
+    pub args: FenvShellArgs,
+}
+
+impl FenvShellService {
+    pub fn new(args: FenvShellArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvShellService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let shell = match &self.args.shell {
+            Some(shell) => String::from(shell),
+            None => detect_shell(context).context("Failed to detect the current shell")?,
+        };
+        let is_fish = shell == "fish";
+
+        let prefix = match &self.args.prefix {
+            Some(prefix) => prefix,
+            // `--unset` is given.
+            None => {
+                let code = if is_fish {
+                    "set -e FENV_VERSION"
+                } else {
+                    "unset FENV_VERSION"
+                };
+                writeln!(output.stdout(), "{code}")?;
+                return anyhow::Ok(());
+            }
+        };
+        match sdk_service.find_latest_local(context, prefix) {
+            LookupResult::Found(_) => {}
+            LookupResult::None => {
+                bail!(
+                    "The specified version `{prefix}` is not installed: do `fenv install {prefix}`"
+                )
+            }
+            LookupResult::Err(err) => return Err(err),
+        }
+        if is_fish {
+            writeln!(
+                output.stdout(),
+                "set -gx FENV_VERSION {}",
+                quote_fish(prefix)
+            )?;
+        } else {
+            writeln!(
+                output.stdout(),
+                "export FENV_VERSION={}",
+                quote_posix(prefix)
+            )?;
+        }
+        anyhow::Ok(())
+    }
+}
+
+/// Quotes `value` as a single-quoted string of bash, zsh, and ksh.
+pub fn quote_posix(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Quotes `value` as a single-quoted string of fish.
+pub fn quote_fish(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::FenvContext, sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context, try_run,
+    };
+
+    #[test]
+    fn test_shell_exports_version_prefix() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("3.7.12")
+                .create_dir_all()
+                .unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "shell", "--shell", "bash", "3.7"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "export FENV_VERSION='3.7'\n");
+        })
+    }
+
+    #[test]
+    fn test_shell_sets_version_prefix_for_fish() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("stable")
+                .create_dir_all()
+                .unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "shell", "--shell", "fish", "stable"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "set -gx FENV_VERSION 'stable'\n");
+        })
+    }
+
+    #[test]
+    fn test_shell_unsets_version_prefix() {
+        test_with_context(|context, output| {
+            // execution
+            try_run(
+                &["fenv", "shell", "--shell", "zsh", "--unset"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "unset FENV_VERSION\n");
+        })
+    }
+
+    #[test]
+    fn test_shell_fails_if_version_is_not_installed() {
+        test_with_context(|context, output| {
+            // execution
+            let result = try_run(
+                &["fenv", "shell", "--shell", "bash", "3.7"],
+                context,
+                &RealSdkService::new(),
+                output,
+            );
+
+            // validation
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                "The specified version `3.7` is not installed: do `fenv install 3.7`"
+            );
+            assert!(output.stdout_to_string().is_empty());
+        })
+    }
+}
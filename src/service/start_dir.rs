@@ -0,0 +1,94 @@
+use crate::{
+    args::{FenvInstallArgs, FenvStartDirArgs, FenvStartDirOption},
+    context::FenvContext,
+    util::path_like::PathLike,
+};
+
+/// Arguments which specify the directory where to start finding the nearest version file.
+///
+/// Every command reading the nearest version file resolves the directory through
+/// [`StartDir::resolve_start_dir`], so that `--dir` has the same meaning for all of them.
+pub trait StartDir {
+    /// The directory given by the user, if any.
+    fn start_dir(&self) -> Option<&str>;
+
+    /// Resolves the directory where to start finding the nearest version file.
+    ///
+    /// A relative path is resolved from `{fenv_dir}`, and `{fenv_dir}` itself is used if no
+    /// directory is given.
+    fn resolve_start_dir(&self, context: &impl FenvContext) -> PathLike {
+        match self.start_dir() {
+            Some(dir) => context.fenv_dir().join(dir),
+            None => context.fenv_dir(),
+        }
+    }
+}
+
+impl StartDir for FenvStartDirOption {
+    fn start_dir(&self) -> Option<&str> {
+        self.start_dir.as_deref()
+    }
+}
+
+impl StartDir for FenvStartDirArgs {
+    fn start_dir(&self) -> Option<&str> {
+        self.dir.as_deref().or(self.option.start_dir())
+    }
+}
+
+impl StartDir for FenvInstallArgs {
+    fn start_dir(&self) -> Option<&str> {
+        self.dir.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StartDir;
+    use crate::{
+        args::{FenvStartDirArgs, FenvStartDirOption},
+        context::FenvContext,
+        service::macros::test_with_context,
+    };
+
+    #[test]
+    fn test_resolve_start_dir_defaults_to_fenv_dir() {
+        test_with_context(|context, _| {
+            assert_eq!(
+                FenvStartDirArgs::default().resolve_start_dir(context),
+                context.fenv_dir()
+            );
+        })
+    }
+
+    #[test]
+    fn test_resolve_start_dir_from_fenv_dir() {
+        test_with_context(|context, _| {
+            let option = FenvStartDirOption {
+                start_dir: Some(String::from("app")),
+            };
+            let args = FenvStartDirArgs {
+                dir: Some(String::from("app")),
+                ..Default::default()
+            };
+            assert_eq!(
+                option.resolve_start_dir(context),
+                context.fenv_dir().join("app")
+            );
+            assert_eq!(
+                args.resolve_start_dir(context),
+                context.fenv_dir().join("app")
+            );
+        })
+    }
+
+    #[test]
+    fn test_resolve_start_dir_keeps_absolute_path() {
+        test_with_context(|context, _| {
+            let option = FenvStartDirOption {
+                start_dir: Some(context.home().to_string()),
+            };
+            assert_eq!(option.resolve_start_dir(context), context.home());
+        })
+    }
+}
@@ -1,11 +1,18 @@
+use anyhow::bail;
 use log::debug;
 
 use crate::{
     args::FenvUninstallArgs,
     context::FenvContext,
-    sdk_service::{results::LookupResult, sdk_service::SdkService},
-    service::service::Service,
-    util::io::ConsoleOutput,
+    sdk_service::{
+        alias, model::local_flutter_sdk::LocalFlutterSdk, results::LookupResult,
+        sdk_service::SdkService, version_prefix_match::matches_prefix,
+    },
+    service::{rehash::rehash_service::rehash, service::Service},
+    util::{
+        io::ConsoleOutput,
+        size::{disk_usage, human_size},
+    },
 };
 
 pub struct FenvUninstallService {
@@ -29,11 +36,136 @@ where
         sdk_service: &impl SdkService,
         output: &mut dyn ConsoleOutput<OUT, ERR>,
     ) -> anyhow::Result<()> {
+        if self.args.broken {
+            uninstall_broken_installations(context, sdk_service, output)?
+        }
+        if self.args.all || !self.args.all_except.is_empty() {
+            uninstall_all_except(context, sdk_service, output, &self.args.all_except)?
+        }
         for prefix in &self.args.prefixes {
-            uninstall_version(context, sdk_service, output, prefix)?
+            if prefix.contains(std::path::MAIN_SEPARATOR) {
+                uninstall_path(context, sdk_service, output, prefix)?
+            } else {
+                uninstall_version(context, sdk_service, output, prefix)?
+            }
+        }
+        rehash(context)
+    }
+}
+
+fn uninstall_broken_installations<OUT, ERR>(
+    context: &impl FenvContext,
+    sdk_service: &impl SdkService,
+    output: &mut dyn ConsoleOutput<OUT, ERR>,
+) -> anyhow::Result<()>
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    let names = sdk_service.find_broken_installations(context)?;
+    if names.is_empty() {
+        output.warning("Could not find any broken installation")?;
+        return anyhow::Ok(());
+    }
+    for name in names {
+        debug!("Found broken installation: `{}`", name);
+        if sdk_service.uninstall_broken_installation(context, &name)? {
+            writeln!(output.stdout(), "{}", name)?;
+        }
+    }
+    anyhow::Ok(())
+}
+
+/// Uninstalls every installed Flutter SDK except the ones matching any of `excluded_prefixes`,
+/// and reports the total size of the removed directories.
+fn uninstall_all_except<OUT, ERR>(
+    context: &impl FenvContext,
+    sdk_service: &impl SdkService,
+    output: &mut dyn ConsoleOutput<OUT, ERR>,
+    excluded_prefixes: &[String],
+) -> anyhow::Result<()>
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    let installed = sdk_service.get_installed_sdk_list(context)?;
+    let mut excluded: Vec<LocalFlutterSdk> = vec![];
+    for prefix in excluded_prefixes {
+        let matched = matches_prefix(&installed, &alias::resolve(context, prefix));
+        if matched.is_empty() {
+            output.warning(&format!("Could not find any installed sdk: `{prefix}`"))?;
         }
-        Ok(())
+        excluded.extend(matched);
     }
+    let names: Vec<String> = installed
+        .iter()
+        .filter(|sdk| !excluded.contains(sdk))
+        .map(|sdk| sdk.to_string())
+        // The compatibility symlinks are removed together with their targets.
+        .filter(|name| {
+            !context
+                .fenv_sdk_root(name)
+                .path()
+                .symlink_metadata()
+                .is_ok_and(|metadata| metadata.is_symlink())
+        })
+        .collect();
+    if names.is_empty() {
+        output.warning("Could not find any installation to uninstall")?;
+        return anyhow::Ok(());
+    }
+    let mut reclaimed = 0;
+    for name in &names {
+        let size = disk_usage(&context.fenv_sdk_root(name));
+        sdk_service.uninstall_by_name(context, name)?;
+        reclaimed += size;
+        writeln!(output.stdout(), "{name}")?;
+    }
+    output.info(&format!(
+        "Reclaimed {} by uninstalling {} Flutter SDK(s)",
+        human_size(reclaimed),
+        names.len()
+    ))?;
+    anyhow::Ok(())
+}
+
+/// Uninstalls the installation at the given `path`, which must be a directory right under
+/// `{fenv_root}/versions`. A relative path is resolved from the current directory first, and
+/// then from `{fenv_root}`.
+fn uninstall_path<OUT, ERR>(
+    context: &impl FenvContext,
+    sdk_service: &impl SdkService,
+    output: &mut dyn ConsoleOutput<OUT, ERR>,
+    path: &str,
+) -> anyhow::Result<()>
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    let versions_directory = context.fenv_versions();
+    let canonical_versions_directory = std::fs::canonicalize(&versions_directory).ok();
+    for candidate in [
+        context.fenv_dir().join(path),
+        context.fenv_root().join(path),
+    ] {
+        let candidate = candidate.path();
+        let is_directory = candidate
+            .symlink_metadata()
+            .map(|metadata| metadata.is_dir())
+            .unwrap_or(false);
+        let is_in_versions_directory = candidate
+            .parent()
+            .and_then(|parent| std::fs::canonicalize(parent).ok())
+            .is_some_and(|parent| Some(parent) == canonical_versions_directory);
+        let name = candidate.file_name().and_then(|name| name.to_str());
+        if let (true, true, Some(name)) = (is_directory, is_in_versions_directory, name) {
+            debug!("Found installation `{}` at `{}`", name, path);
+            sdk_service.uninstall_by_name(context, name)?;
+            writeln!(output.stdout(), "{}", name)?;
+            return anyhow::Ok(());
+        }
+    }
+    bail!("`{path}` is not an installation in `{versions_directory}`")
 }
 
 fn uninstall_version<OUT, ERR>(
@@ -49,11 +181,7 @@ where
     debug!("Attempting to uninstall `{}`", prefix);
     let mut lookup_result = sdk_service.find_latest_local(context, prefix);
     if let LookupResult::None = lookup_result {
-        writeln!(
-            output.stderr(),
-            "Could not find any installed sdk: `{}`",
-            prefix
-        )?;
+        output.warning(&format!("Could not find any installed sdk: `{prefix}`"))?;
         return anyhow::Ok(());
     }
 
@@ -126,6 +254,56 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_uninstall_all_except_keeps_matched_sdks() {
+        test_with_context(|context, output| {
+            // setup
+            let sdks = ["3.7.12", "3.10.0", "3.22.0", "3.22.2", "beta", "stable"];
+            for version in &sdks {
+                context
+                    .fenv_sdk_root(version)
+                    .join("bin")
+                    .join("flutter")
+                    .write("x".repeat(1024))
+                    .unwrap();
+            }
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(
+                &[
+                    "fenv",
+                    "uninstall",
+                    "--all-except",
+                    "3.22",
+                    "--all-except",
+                    "stable",
+                ],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "3.7.12\n3.10.0\nbeta\n");
+            assert_eq!(
+                output.stderr_to_string(),
+                "fenv: info: Reclaimed 3.0 KiB by uninstalling 3 Flutter SDK(s)\n"
+            );
+            for version in ["3.7.12", "3.10.0", "beta"] {
+                assert!(!context.fenv_sdk_root(version).exists());
+            }
+            for version in ["3.22.0", "3.22.2", "stable"] {
+                assert!(context.fenv_sdk_root(version).exists());
+            }
+        })
+    }
+
     #[test]
     fn test_uninstall_version_does_not_fails_if_attempts_to_uninstall_nonexistent_sdk() {
         test_with_context(|context, output| {
@@ -149,8 +327,179 @@ mod tests {
             assert!(output.stdout_to_string().is_empty());
             assert_eq!(
                 output.stderr_to_string(),
-                "Could not find any installed sdk: `stable`\n"
+                "fenv: warning: Could not find any installed sdk: `stable`\n"
+            );
+        })
+    }
+
+    #[test]
+    fn test_uninstall_version_removes_compatibility_symlink() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("1.7.8+hotfix.4")
+                .create_dir_all()
+                .unwrap();
+            std::os::unix::fs::symlink(
+                "1.7.8+hotfix.4",
+                context.fenv_versions().join("v1.7.8+hotfix.4"),
+            )
+            .unwrap();
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(
+                &["fenv", "uninstall", "1.7.8+hotfix.4"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert!(!context.fenv_versions().join("1.7.8+hotfix.4").exists());
+            assert!(context
+                .fenv_versions()
+                .join("v1.7.8+hotfix.4")
+                .path()
+                .symlink_metadata()
+                .is_err());
+        })
+    }
+
+    #[test]
+    fn test_uninstall_broken_installations() {
+        test_with_context(|context, output| {
+            // setup
+            // `3.7.12` is healthy, `3.10.0` is being installed, and `stable` lacks `bin/flutter`.
+            for version in ["3.7.12", "3.10.0"] {
+                context
+                    .fenv_sdk_root(version)
+                    .join("bin")
+                    .join("flutter")
+                    .write("")
+                    .unwrap();
+            }
+            context
+                .fenv_versions()
+                .join(".install_3.10.0")
+                .write("")
+                .unwrap();
+            context.fenv_sdk_root("stable").create_dir_all().unwrap();
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(
+                &["fenv", "uninstall", "--broken"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "3.10.0\nstable\n");
+            assert!(context.fenv_sdk_root("3.7.12").is_dir());
+            assert!(!context.fenv_sdk_root("3.10.0").exists());
+            assert!(!context.fenv_versions().join(".install_3.10.0").exists());
+            assert!(!context.fenv_sdk_root("stable").exists());
+        })
+    }
+
+    #[test]
+    fn test_uninstall_broken_installations_removes_orphaned_markers() {
+        test_with_context(|context, output| {
+            // setup
+            // `3.13.0` has been removed by hand during its installation.
+            context
+                .fenv_versions()
+                .join(".install_3.13.0")
+                .write("")
+                .unwrap();
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(
+                &["fenv", "uninstall", "--broken"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "3.13.0\n");
+            assert!(!context.fenv_versions().join(".install_3.13.0").exists());
+        })
+    }
+
+    #[test]
+    fn test_uninstall_by_path_removes_installation_with_invalid_name() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_sdk_root("my-flutter")
+                .create_dir_all()
+                .unwrap();
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(
+                &["fenv", "uninstall", "versions/my-flutter/"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "my-flutter\n");
+            assert!(!context.fenv_sdk_root("my-flutter").exists());
+        })
+    }
+
+    #[test]
+    fn test_uninstall_by_path_fails_if_path_is_out_of_versions_directory() {
+        test_with_context(|context, output| {
+            // setup
+            context.fenv_dir().join("flutter").create_dir_all().unwrap();
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            let path = context.fenv_dir().join("flutter").to_string();
+            let result = try_run(&["fenv", "uninstall", &path], context, &sdk_service, output);
+
+            // validation
+            assert!(result.is_err());
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                format!(
+                    "`{path}` is not an installation in `{}`",
+                    context.fenv_versions()
+                )
             );
+            assert!(context.fenv_dir().join("flutter").is_dir());
         })
     }
 }
@@ -0,0 +1,181 @@
+use crate::{
+    args::FenvUpgradeArgs,
+    context::FenvContext,
+    sdk_service::{
+        model::{flutter_channel::FlutterChannel, local_flutter_sdk::LocalFlutterSdk},
+        results::LookupResult,
+        sdk_service::SdkService,
+    },
+    service::{rehash::rehash_service::rehash, service::Service},
+    util::io::ConsoleOutput,
+};
+use anyhow::bail;
+
+/// Fast-forwards the installed channels such as `stable` to the latest commits of their
+/// upstream branches, and reports the commits before and after.
+///
+/// The versions such as `3.22.2` are never upgraded because they are pinned to their tags.
+pub struct FenvUpgradeService {
+    pub args: FenvUpgradeArgs,
+}
+
+impl FenvUpgradeService {
+    pub fn new(args: FenvUpgradeArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvUpgradeService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let channels = self.channels(context, sdk_service)?;
+        for channel in &channels {
+            let name = channel.channel_name();
+            let (old_sha, new_sha) = sdk_service.upgrade_channel(
+                context,
+                channel,
+                self.args.precache,
+                output.stderr(),
+            )?;
+            if old_sha == new_sha {
+                writeln!(output.stdout(), "{name}: already up to date ({new_sha})")?;
+            } else {
+                writeln!(output.stdout(), "{name}: {old_sha} -> {new_sha}")?;
+            }
+        }
+        // The upgraded channels may ship new executables.
+        rehash(context)
+    }
+}
+
+impl FenvUpgradeService {
+    /// The channels to upgrade: the given ones, or every installed channel if none is given.
+    fn channels(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+    ) -> anyhow::Result<Vec<FlutterChannel>> {
+        if self.args.channels.is_empty() {
+            let channels: Vec<FlutterChannel> = sdk_service
+                .get_installed_sdk_list(context)?
+                .into_iter()
+                .filter_map(|sdk| match sdk {
+                    LocalFlutterSdk::Channel(channel) => Some(channel),
+                    LocalFlutterSdk::Version { .. } | LocalFlutterSdk::Commit(_) => None,
+                })
+                .collect();
+            if channels.is_empty() {
+                bail!("No channel is installed: do `fenv install stable` to install one")
+            }
+            return anyhow::Ok(channels);
+        }
+        let mut channels = vec![];
+        for prefix in &self.args.channels {
+            match sdk_service.find_latest_local(context, prefix) {
+                LookupResult::Found(LocalFlutterSdk::Channel(channel)) => channels.push(channel),
+                LookupResult::Found(sdk) => {
+                    bail!("`{sdk}` is not a channel: only the channels such as `stable` can be upgraded")
+                }
+                LookupResult::None => {
+                    bail!("`{prefix}` is not installed: do `fenv install {prefix}`")
+                }
+                LookupResult::Err(e) => return Err(e),
+            }
+        }
+        anyhow::Ok(channels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::FenvContext, define_mock_flutter_command, external::git_command::MockGitCommand,
+        sdk_service::sdk_service::RealSdkService, service::macros::test_with_context, try_run,
+        util::chrono_wrapper::SystemClock,
+    };
+    use mockall::Sequence;
+
+    define_mock_flutter_command!();
+
+    fn install_sdk(context: &impl FenvContext, name: &str) {
+        context
+            .fenv_sdk_root(name)
+            .join("bin")
+            .join("flutter")
+            .writeln("")
+            .unwrap();
+    }
+
+    #[test]
+    fn test_upgrade_installed_channels() {
+        test_with_context(|context, output| {
+            // setup
+            install_sdk(context, "stable");
+            install_sdk(context, "3.22.2");
+            let stable = context.fenv_sdk_root("stable").to_string();
+            let mut sequence = Sequence::new();
+            let mut git_command = MockGitCommand::new();
+            git_command
+                .expect_rev_parse_head()
+                .times(1)
+                .in_sequence(&mut sequence)
+                .returning(|_| anyhow::Ok(String::from("1111111")));
+            git_command
+                .expect_pull_ff_only()
+                .withf(move |working_dir, _, _| working_dir == stable)
+                .times(1)
+                .in_sequence(&mut sequence)
+                .returning(|_, _, _| anyhow::Ok(()));
+            git_command
+                .expect_rev_parse_head()
+                .times(1)
+                .in_sequence(&mut sequence)
+                .returning(|_| anyhow::Ok(String::from("2222222")));
+            let sdk_service =
+                RealSdkService::from(git_command, SystemClock::new(), MockFlutterCommand);
+
+            // execution
+            try_run(
+                &["fenv", "upgrade", "--precache"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "stable: 1111111 -> 2222222\n");
+            assert!(context.fenv_shims().join("flutter").is_file());
+        })
+    }
+
+    #[test]
+    fn test_upgrade_fails_with_version() {
+        test_with_context(|context, output| {
+            // setup
+            install_sdk(context, "3.22.2");
+            let sdk_service = RealSdkService::from(
+                MockGitCommand::new(),
+                SystemClock::new(),
+                MockFlutterCommand,
+            );
+
+            // execution
+            let result = try_run(&["fenv", "upgrade", "3.22"], context, &sdk_service, output);
+
+            // validation
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                "`3.22.2` is not a channel: only the channels such as `stable` can be upgraded"
+            );
+        })
+    }
+}
@@ -0,0 +1,155 @@
+use crate::{
+    args::FenvVerifyArgs,
+    context::FenvContext,
+    sdk_service::{
+        model::{flutter_sdk::FlutterSdk, local_flutter_sdk::LocalFlutterSdk},
+        results::LookupResult,
+        sdk_service::SdkService,
+    },
+    service::service::Service,
+    util::io::ConsoleOutput,
+};
+use anyhow::bail;
+
+/// Verifies that the latest installed Flutter SDK matching the given prefix is complete and
+/// checked out at the commit of its tag or branch.
+pub struct FenvVerifyService {
+    pub args: FenvVerifyArgs,
+}
+
+impl FenvVerifyService {
+    pub fn new(args: FenvVerifyArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl<OUT, ERR> Service<OUT, ERR> for FenvVerifyService
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn execute(
+        &self,
+        context: &impl FenvContext,
+        sdk_service: &impl SdkService,
+        output: &mut dyn ConsoleOutput<OUT, ERR>,
+    ) -> anyhow::Result<()> {
+        let prefix = &self.args.prefix;
+        let sdk = match sdk_service.find_latest_local(context, prefix) {
+            LookupResult::Found(sdk) => sdk,
+            LookupResult::Err(err) => return Err(err),
+            // The incomplete installation is not listed, but can be verified by its exact name.
+            LookupResult::None if context.fenv_sdk_root(prefix).is_dir() => {
+                LocalFlutterSdk::parse(prefix)?
+            }
+            LookupResult::None => {
+                bail!(
+                    "The specified version `{prefix}` is not installed: do `fenv install {prefix}`"
+                )
+            }
+        };
+        let name = sdk.display_name();
+        let problems = sdk_service.verify_sdk(context, &sdk);
+        if problems.is_empty() {
+            writeln!(output.stdout(), "`{name}` is verified")?;
+            return anyhow::Ok(());
+        }
+        for problem in &problems {
+            writeln!(output.stderr(), "  - {problem}")?;
+        }
+        bail!("`{name}` is broken: do `fenv uninstall {name} && fenv install {name}`")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::FenvContext,
+        define_mock_flutter_command,
+        external::git_command::MockGitCommand,
+        sdk_service::sdk_service::RealSdkService,
+        service::macros::test_with_context,
+        try_run,
+        util::{chrono_wrapper::SystemClock, path_like::PathLike},
+    };
+
+    define_mock_flutter_command!();
+
+    fn prepare_intact_sdk(sdk_root: &PathLike) {
+        for path in ["bin/flutter", "bin/dart", "bin/cache/dart-sdk/bin/dart"] {
+            sdk_root.join(path).writeln("").unwrap();
+        }
+        sdk_root.join(".git").create_dir_all().unwrap();
+    }
+
+    #[test]
+    fn test_verify_intact_sdk() {
+        test_with_context(|context, output| {
+            // setup
+            prepare_intact_sdk(&context.fenv_sdk_root("1.17.5"));
+            let mut git_command = MockGitCommand::new();
+            git_command
+                .expect_rev_parse_head()
+                .returning(|_| anyhow::Ok(String::from("a")));
+            git_command
+                .expect_rev_parse_commit()
+                .withf(|_, rev| rev == "refs/tags/1.17.5")
+                .returning(|_, _| anyhow::bail!("not found"));
+            git_command
+                .expect_rev_parse_commit()
+                .withf(|_, rev| rev == "refs/tags/v1.17.5")
+                .returning(|_, _| anyhow::Ok(String::from("a")));
+            let sdk_service =
+                RealSdkService::from(git_command, SystemClock::new(), MockFlutterCommand);
+
+            // execution
+            try_run(&["fenv", "verify", "1"], context, &sdk_service, output).unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "`1.17.5` is verified\n");
+        })
+    }
+
+    #[test]
+    fn test_verify_broken_sdk() {
+        test_with_context(|context, output| {
+            // setup
+            let sdk_root = context.fenv_sdk_root("stable");
+            prepare_intact_sdk(&sdk_root);
+            sdk_root.join("bin/dart").remove_file().unwrap();
+            context
+                .fenv_versions()
+                .join(".install_stable")
+                .writeln("")
+                .unwrap();
+            let mut git_command = MockGitCommand::new();
+            git_command
+                .expect_rev_parse_head()
+                .returning(|_| anyhow::Ok(String::from("a")));
+            git_command
+                .expect_rev_parse_commit()
+                .withf(|_, rev| rev == "refs/heads/stable")
+                .returning(|_, _| anyhow::Ok(String::from("b")));
+            let sdk_service =
+                RealSdkService::from(git_command, SystemClock::new(), MockFlutterCommand);
+
+            // execution
+            let result = try_run(&["fenv", "verify", "stable"], context, &sdk_service, output);
+
+            // validation
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                "`stable` is broken: do `fenv uninstall stable && fenv install stable`"
+            );
+            assert_eq!(
+                output.stderr_to_string(),
+                format!(
+                    "  - The installation was not completed: `{}/.install_stable` remains\n  \
+                    - Missing `{sdk_root}/bin/dart`\n  \
+                    - The git HEAD `a` is not at `refs/heads/stable` (`b`)\n",
+                    context.fenv_versions()
+                )
+            );
+        })
+    }
+}
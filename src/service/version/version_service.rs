@@ -1,14 +1,22 @@
 use crate::{
-    args::FenvStartDirArgs, context::FenvContext, invoke_command,
-    sdk_service::sdk_service::SdkService, service::service::Service, util::io::ConsoleOutput,
+    args::FenvVersionArgs,
+    context::FenvContext,
+    invoke_command,
+    sdk_service::{results::LookupResult, sdk_service::SdkService},
+    service::{
+        service::Service, start_dir::StartDir,
+        version_name::version_name_service::resolve_selected_sdk,
+    },
+    util::{io::ConsoleOutput, path_like::PathLike},
 };
+use anyhow::bail;
 
 pub struct FenvVersionService {
-    pub args: FenvStartDirArgs,
+    pub args: FenvVersionArgs,
 }
 
 impl FenvVersionService {
-    pub fn new(args: FenvStartDirArgs) -> Self {
+    pub fn new(args: FenvVersionArgs) -> Self {
         Self { args }
     }
 }
@@ -24,11 +32,20 @@ where
         sdk_service: &impl SdkService,
         output: &mut dyn ConsoleOutput<OUT, ERR>,
     ) -> anyhow::Result<()> {
-        let dir: String = if let Some(dir) = &self.args.dir {
-            dir.clone()
-        } else {
-            context.fenv_dir().to_string()
-        };
+        let start_dir = self.args.start_dir.resolve_start_dir(context);
+        if self.args.version_only {
+            return print_version_only(context, sdk_service, output, &start_dir);
+        }
+        if self.args.json {
+            let selected = resolve_selected_sdk(context, sdk_service, output, &start_dir)?;
+            writeln!(
+                output.stdout(),
+                "{}",
+                serde_json::to_string_pretty(&selected.to_json())?
+            )?;
+            return anyhow::Ok(());
+        }
+        let dir = start_dir.to_string();
         let version_name = invoke_command!(context, sdk_service, output, "version-name", &dir)?;
         let version_file = invoke_command!(context, sdk_service, output, "version-file", &dir)?;
         writeln!(output.stdout(), "{version_name} (set by `{version_file}`)")?;
@@ -36,6 +53,27 @@ where
     }
 }
 
+/// Prints the version written in the nearest version file from `start_dir` as it is.
+fn print_version_only<OUT, ERR>(
+    context: &impl FenvContext,
+    sdk_service: &impl SdkService,
+    output: &mut dyn ConsoleOutput<OUT, ERR>,
+    start_dir: &PathLike,
+) -> anyhow::Result<()>
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    let path_to_version_file = match sdk_service.find_nearest_version_file(context, start_dir) {
+        LookupResult::Found(path) => path,
+        LookupResult::Err(err) => return Err(err),
+        LookupResult::None => bail!("Could not find a version file"),
+    };
+    let version_prefix = sdk_service.read_version_prefix(&path_to_version_file)?;
+    writeln!(output.stdout(), "{version_prefix}")?;
+    anyhow::Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -46,6 +84,45 @@ mod tests {
 
     define_mock_valid_git_command!();
 
+    #[test]
+    fn test_show_version_of_shell_in_json() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("3.7.12")
+                .create_dir_all()
+                .unwrap();
+            let context = context.clone().with_shell_version("3.7");
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(
+                &["fenv", "version", "--json"],
+                &context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            let json: serde_json::Value = serde_json::from_str(&output.stdout_to_string()).unwrap();
+            assert_eq!(
+                json,
+                serde_json::json!({
+                    "version": "3.7.12",
+                    "prefix": "3.7",
+                    "version_file": null,
+                    "is_global": false,
+                })
+            );
+        })
+    }
+
     #[test]
     fn test_show_version_succeeds_if_global_version_is_set_and_installed() {
         test_with_context(|context, output| {
@@ -125,6 +202,46 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_show_version_with_dir_option_is_same_as_positional_dir() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("v1.0.0")
+                .create_dir_all()
+                .unwrap();
+            context
+                .fenv_dir()
+                .join("a")
+                .join(".flutter-version")
+                .writeln("1.0.0")
+                .unwrap();
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(&["fenv", "version", "a"], context, &sdk_service, output).unwrap();
+            try_run(
+                &["fenv", "version", "--dir", "a"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            let expected = format!(
+                "v1.0.0 (set by `{}/a/.flutter-version`)\n",
+                context.fenv_dir()
+            );
+            assert_eq!(output.stdout_to_string(), expected.repeat(2));
+        })
+    }
+
     #[test]
     fn test_show_version_fails_if_any_version_file_cannot_be_found() {
         test_with_context(|context, output| {
@@ -172,4 +289,88 @@ mod tests {
             )
         })
     }
+
+    #[test]
+    fn test_show_version_only_without_resolving_it() {
+        test_with_context(|context, output| {
+            // setup
+            // `3.7` is neither installed nor resolved.
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("3.7")
+                .unwrap();
+
+            // execution
+            // `RealSdkService::new()` would fail if it accessed the remote repository offline.
+            try_run(
+                &["fenv", "version", "--version-only"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "3.7\n");
+            assert!(output.stderr_to_string().is_empty());
+        })
+    }
+
+    #[test]
+    fn test_show_version_only_reads_tool_versions_if_no_flutter_version_exists() {
+        test_with_context(|context, output| {
+            // setup
+            // `.flutter-version` in the nearer directory takes precedence over `.tool-versions`.
+            let project = context.fenv_dir().join("project");
+            project.create_dir_all().unwrap();
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("3.3")
+                .unwrap();
+            project
+                .join(".tool-versions")
+                .writeln("nodejs 20.0.0\nflutter 3.7.12-stable")
+                .unwrap();
+            // `.tool-versions` without `flutter` is ignored.
+            project
+                .join("sub")
+                .join(".tool-versions")
+                .writeln("nodejs 20.0.0")
+                .unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "version", "--version-only", "project/sub"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "3.7.12\n");
+        })
+    }
+
+    #[test]
+    fn test_show_version_only_fails_if_no_version_file_is_found() {
+        test_with_context(|context, output| {
+            // execution
+            let result = try_run(
+                &["fenv", "version", "--version-only"],
+                context,
+                &RealSdkService::new(),
+                output,
+            );
+
+            // validation
+            assert!(result.is_err());
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                "Could not find a version file"
+            );
+        })
+    }
 }
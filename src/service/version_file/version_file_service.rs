@@ -2,8 +2,8 @@ use crate::{
     args::FenvStartDirArgs,
     context::FenvContext,
     sdk_service::sdk_service::SdkService,
-    service::service::Service,
-    util::{io::ConsoleOutput, path_like::PathLike},
+    service::{service::Service, start_dir::StartDir},
+    util::io::ConsoleOutput,
 };
 use anyhow::{bail, Ok};
 use log::debug;
@@ -29,16 +29,8 @@ where
         sdk_service: &impl SdkService,
         output: &mut dyn ConsoleOutput<OUT, ERR>,
     ) -> anyhow::Result<()> {
-        let start_dir = match &self.args.dir {
-            Some(dir) => {
-                debug!("Start looking for version file from `{dir}`");
-                PathLike::from(dir.as_str())
-            }
-            None => {
-                debug!("Start looking for version file from the current directory");
-                context.fenv_dir().to_owned()
-            }
-        };
+        let start_dir = self.args.resolve_start_dir(context);
+        debug!("Start looking for version file from `{start_dir}`");
         if !start_dir.exists() {
             bail!("`{start_dir}` does not exist");
         }
@@ -108,6 +100,7 @@ mod tests {
             lookup_dir.create_dir_all().unwrap();
             let args = FenvStartDirArgs {
                 dir: Some(lookup_dir.to_string()),
+                ..Default::default()
             };
             let service = FenvVersionFileService::new(args);
 
@@ -142,6 +135,7 @@ mod tests {
             lookup_dir.create_dir_all().unwrap();
             let args = FenvStartDirArgs {
                 dir: Some(lookup_dir.to_string()),
+                ..Default::default()
             };
             let service = FenvVersionFileService::new(args);
 
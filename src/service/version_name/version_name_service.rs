@@ -1,17 +1,21 @@
 use crate::{
-    args::FenvStartDirArgs,
+    args::FenvVersionNameArgs,
     context::FenvContext,
-    sdk_service::sdk_service::SdkService,
-    service::service::Service,
+    sdk_service::{
+        latest_keyword, model::local_flutter_sdk::LocalFlutterSdk, results::LookupResult,
+        sdk_service::SdkService,
+    },
+    service::{service::Service, start_dir::StartDir},
     util::{io::ConsoleOutput, path_like::PathLike},
 };
+use anyhow::bail;
 
 pub struct FenvVersionNameService {
-    pub args: FenvStartDirArgs,
+    pub args: FenvVersionNameArgs,
 }
 
 impl FenvVersionNameService {
-    pub fn new(args: FenvStartDirArgs) -> Self {
+    pub fn new(args: FenvVersionNameArgs) -> Self {
         Self { args }
     }
 }
@@ -27,16 +31,82 @@ where
         sdk_service: &impl SdkService,
         output: &mut dyn ConsoleOutput<OUT, ERR>,
     ) -> anyhow::Result<()> {
-        let start_dir = match &self.args.dir {
-            Some(start_dir) => PathLike::from(start_dir.as_str()),
-            None => context.fenv_dir(),
+        let start_dir = self.args.start_dir.resolve_start_dir(context);
+        let selected = resolve_selected_sdk(context, sdk_service, output, &start_dir)?;
+        if self.args.json {
+            writeln!(
+                output.stdout(),
+                "{}",
+                serde_json::to_string_pretty(&selected.to_json())?
+            )?;
+        } else {
+            writeln!(output.stdout(), "{}", selected.sdk)?;
+        }
+        anyhow::Ok(())
+    }
+}
+
+/// The installed Flutter SDK selected by `$FENV_VERSION` or the nearest version file.
+pub struct SelectedSdk {
+    pub sdk: LocalFlutterSdk,
+    /// The version prefix as it is written in `$FENV_VERSION` or the version file.
+    pub prefix: String,
+    /// `None` if selected by `$FENV_VERSION`.
+    pub version_file: Option<PathLike>,
+    pub is_global: bool,
+}
+
+impl SelectedSdk {
+    /// The object of `--json` output.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "version": self.sdk.to_string(),
+            "prefix": self.prefix,
+            "version_file": self.version_file.as_ref().map(|path| path.to_string()),
+            "is_global": self.is_global,
+        })
+    }
+}
+
+/// Resolves the installed Flutter SDK selected from `start_dir`.
+///
+/// Triggers a failure if no version file exists or the selected one is not installed.
+pub fn resolve_selected_sdk<OUT: std::io::Write, ERR: std::io::Write>(
+    context: &impl FenvContext,
+    sdk_service: &impl SdkService,
+    output: &mut dyn ConsoleOutput<OUT, ERR>,
+    start_dir: &PathLike,
+) -> anyhow::Result<SelectedSdk> {
+    if let Some(prefix) = context.shell_version() {
+        return match sdk_service.find_latest_local(context, &prefix) {
+            LookupResult::Found(sdk) => anyhow::Ok(SelectedSdk {
+                sdk,
+                prefix,
+                version_file: None,
+                is_global: false,
+            }),
+            LookupResult::None => bail!(
+                "The specified version `{prefix}` is not installed (set by `$FENV_VERSION`): do `fenv install {prefix}`"
+            ),
+            LookupResult::Err(err) => Err(err),
         };
+    }
 
-        let result = sdk_service.read_nearest_version_file(context, &start_dir);
-        let summary = sdk_service.ensure_sdk_is_available(&result)?;
-        writeln!(output.stdout(), "{}", summary.latest_local_sdk)?;
-        anyhow::Ok(())
+    let result = sdk_service.read_nearest_version_file(context, start_dir);
+    let summary = sdk_service.ensure_sdk_is_available(&result)?;
+    if latest_keyword::is_latest(&summary.store_version_prefix) {
+        output.warning(&format!(
+            "{} (set by `{}`)",
+            latest_keyword::warning_of(&summary.store_version_prefix),
+            summary.path_to_version_file
+        ))?;
     }
+    anyhow::Ok(SelectedSdk {
+        sdk: summary.latest_local_sdk.clone(),
+        prefix: summary.store_version_prefix.clone(),
+        version_file: Some(summary.path_to_version_file.clone()),
+        is_global: summary.is_global,
+    })
 }
 
 #[cfg(test)]
@@ -106,6 +176,83 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_show_version_name_resolves_version_range() {
+        test_with_context(|context, output| {
+            // setup
+            for version in ["3.7.12", "3.16.9", "3.19.6", "4.0.0"] {
+                context
+                    .fenv_versions()
+                    .join(version)
+                    .create_dir_all()
+                    .unwrap();
+            }
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln(">=3.7 <3.19 # Waiting for the migration to 3.19")
+                .unwrap();
+            context.fenv_global_version_file().writeln("^3.10").unwrap();
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(&["fenv", "version-name"], context, &sdk_service, output).unwrap();
+            try_run(
+                &["fenv", "version-name", "--dir", "/"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // verification
+            assert_eq!(output.stdout_to_string(), "3.16.9\n3.19.6\n");
+        })
+    }
+
+    #[test]
+    fn test_show_version_name_in_json() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("1.0.0")
+                .create_dir_all()
+                .unwrap();
+            context.fenv_global_version_file().writeln("1").unwrap();
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(
+                &["fenv", "version-name", "--json"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // verification
+            let json: serde_json::Value = serde_json::from_str(&output.stdout_to_string()).unwrap();
+            assert_eq!(
+                json,
+                serde_json::json!({
+                    "version": "1.0.0",
+                    "prefix": "1",
+                    "version_file": context.fenv_global_version_file().to_string(),
+                    "is_global": true,
+                })
+            );
+        })
+    }
+
     #[test]
     fn test_show_version_name_fails_if_no_version_name_is_found() {
         test_with_context(|context, output| {
@@ -256,4 +403,60 @@ mod tests {
             )
         })
     }
+
+    #[test]
+    fn test_show_version_name_prefers_shell_version_to_version_files() {
+        test_with_context(|context, output| {
+            // setup
+            context
+                .fenv_versions()
+                .join("1.0.0")
+                .create_dir_all()
+                .unwrap();
+            context
+                .fenv_versions()
+                .join("master")
+                .create_dir_all()
+                .unwrap();
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("1")
+                .unwrap();
+            let context = &context.clone().with_shell_version("m");
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            try_run(&["fenv", "version-name"], context, &sdk_service, output).unwrap();
+
+            // verification
+            assert_eq!(output.stdout_to_string(), "master\n");
+        })
+    }
+
+    #[test]
+    fn test_show_version_name_fails_if_shell_version_is_not_installed() {
+        test_with_context(|context, output| {
+            // setup
+            let context = &context.clone().with_shell_version("3.7");
+            let sdk_service = RealSdkService::from(
+                MockValidGitCommand,
+                SystemClock::new(),
+                FlutterCommandImpl::new(),
+            );
+
+            // execution
+            let result = try_run(&["fenv", "version-name"], context, &sdk_service, output);
+
+            // validation
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                "The specified version `3.7` is not installed (set by `$FENV_VERSION`): do `fenv install 3.7`"
+            );
+        })
+    }
 }
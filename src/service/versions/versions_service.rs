@@ -1,15 +1,31 @@
 use crate::{
+    args::FenvVersionsArgs,
     context::FenvContext,
-    sdk_service::{model::flutter_sdk::FlutterSdk, sdk_service::SdkService},
+    sdk_service::{
+        model::{flutter_sdk::FlutterSdk, local_flutter_sdk::LocalFlutterSdk},
+        results::LookupResult,
+        sdk_metadata,
+        sdk_service::SdkService,
+    },
     service::service::Service,
     util::io::ConsoleOutput,
 };
+use chrono::SecondsFormat;
 
-pub struct FenvVersionsService {}
+/// The format version of `--machine` output, printed on the first line. The version is bumped
+/// only if the columns change incompatibly; new columns are appended without bumping it.
+const MACHINE_FORMAT: &str = "#fenv-versions/v1";
+
+/// The header line of `--machine` output, whose columns match the ones of the data lines.
+const MACHINE_HEADER: &str = "name\tkind\tpath\tinstalled_at\tdart_version";
+
+pub struct FenvVersionsService {
+    pub args: FenvVersionsArgs,
+}
 
 impl FenvVersionsService {
-    pub fn new() -> FenvVersionsService {
-        FenvVersionsService {}
+    pub fn new(args: FenvVersionsArgs) -> FenvVersionsService {
+        FenvVersionsService { args }
     }
 }
 
@@ -24,14 +40,142 @@ where
         sdk_service: &impl SdkService,
         output: &mut dyn ConsoleOutput<OUT, ERR>,
     ) -> anyhow::Result<()> {
-        let sdks = sdk_service.get_installed_sdk_list(context)?;
+        // The equivalent installations such as `v1.17.5` and `1.17.5` are listed only once.
+        let sdks = LocalFlutterSdk::dedup_equivalents(sdk_service.get_installed_sdk_list(context)?);
+        if self.args.json {
+            let active_sdk = find_active_sdk(context, sdk_service);
+            let objects: Vec<serde_json::Value> = sdks
+                .iter()
+                .map(|sdk| json_object_of(context, sdk, active_sdk.as_ref()))
+                .collect();
+            writeln!(
+                output.stdout(),
+                "{}",
+                serde_json::to_string_pretty(&objects)?
+            )?;
+            return anyhow::Ok(());
+        }
+        if self.args.tree {
+            let active_sdk = find_active_sdk(context, sdk_service);
+            for line in tree_lines_of(&sdks, active_sdk.as_ref()) {
+                writeln!(output.stdout(), "{line}")?;
+            }
+            return anyhow::Ok(());
+        }
+        if self.args.machine {
+            writeln!(output.stdout(), "{MACHINE_FORMAT}")?;
+            writeln!(output.stdout(), "{MACHINE_HEADER}")?;
+        }
         for sdk in sdks {
-            writeln!(output.stdout(), "{}", &sdk.display_name())?;
+            if self.args.machine {
+                writeln!(output.stdout(), "{}", machine_line_of(context, &sdk))?;
+            } else {
+                writeln!(output.stdout(), "{}", &sdk.display_name())?;
+            }
         }
         anyhow::Ok(())
     }
 }
 
+/// Finds the installed Flutter SDK selected by `$FENV_VERSION` or the nearest version file.
+fn find_active_sdk(
+    context: &impl FenvContext,
+    sdk_service: &impl SdkService,
+) -> Option<LocalFlutterSdk> {
+    let prefix = match context.shell_version() {
+        Some(prefix) => prefix,
+        None => match sdk_service.find_nearest_version_file(context, &context.fenv_dir()) {
+            LookupResult::Found(path) => sdk_service.read_version_prefix(&path).ok()?,
+            LookupResult::None | LookupResult::Err(_) => return None,
+        },
+    };
+    match sdk_service.find_latest_local(context, &prefix) {
+        LookupResult::Found(sdk) => Some(sdk),
+        LookupResult::None | LookupResult::Err(_) => None,
+    }
+}
+
+/// An object of `--json` output for the given installed `sdk`.
+fn json_object_of(
+    context: &impl FenvContext,
+    sdk: &LocalFlutterSdk,
+    active_sdk: Option<&LocalFlutterSdk>,
+) -> serde_json::Value {
+    let name = sdk.display_name();
+    let kind = match sdk {
+        LocalFlutterSdk::Channel(_) => "channel",
+        LocalFlutterSdk::Version { .. } => "version",
+        LocalFlutterSdk::Commit(_) => "commit",
+    };
+    serde_json::json!({
+        "name": name,
+        "kind": kind,
+        "path": context.fenv_sdk_root(&name).to_string(),
+        // The equivalent installations such as `v1.17.5` and `1.17.5` are active together.
+        "is_active": active_sdk.is_some_and(|active_sdk| active_sdk.is_equivalent(sdk)),
+    })
+}
+
+/// The lines of `--tree` output for the given sorted `sdks`, where the versions of the same
+/// minor version are grouped under it.
+fn tree_lines_of(sdks: &[LocalFlutterSdk], active_sdk: Option<&LocalFlutterSdk>) -> Vec<String> {
+    let name_of = |sdk: &LocalFlutterSdk| {
+        let marker = if active_sdk.is_some_and(|active_sdk| active_sdk.is_equivalent(sdk)) {
+            "*"
+        } else {
+            ""
+        };
+        format!("{}{marker}", sdk.display_name())
+    };
+    let mut lines = vec![];
+    let mut versions = sdks
+        .iter()
+        .filter_map(|sdk| match sdk {
+            LocalFlutterSdk::Version { version, .. } => Some((version, sdk)),
+            LocalFlutterSdk::Channel(_) | LocalFlutterSdk::Commit(_) => None,
+        })
+        .peekable();
+    while let Some((version, sdk)) = versions.next() {
+        let minor = (version.major, version.minor);
+        let mut group = vec![sdk];
+        while let Some((_, sdk)) = versions.next_if(|(next, _)| (next.major, next.minor) == minor) {
+            group.push(sdk);
+        }
+        lines.push(format!("{}.{}", version.major, version.minor));
+        for (index, sdk) in group.iter().enumerate() {
+            let branch = if index + 1 == group.len() {
+                "└─"
+            } else {
+                "├─"
+            };
+            lines.push(format!("{branch} {}", name_of(sdk)));
+        }
+    }
+    lines.extend(
+        sdks.iter()
+            .filter(|sdk| !matches!(sdk, LocalFlutterSdk::Version { .. }))
+            .map(name_of),
+    );
+    lines
+}
+
+/// A tab-separated line of `--machine` output for the given installed `sdk`.
+fn machine_line_of(context: &impl FenvContext, sdk: &LocalFlutterSdk) -> String {
+    let name = sdk.display_name();
+    let kind = match sdk {
+        LocalFlutterSdk::Channel(_) => "channel",
+        LocalFlutterSdk::Version { .. } => "release",
+        LocalFlutterSdk::Commit(_) => "commit",
+    };
+    let path = context.fenv_sdk_root(&name);
+    let installed_at = sdk_metadata::read_installed_at(&path)
+        .map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .unwrap_or_else(|| String::from("-"));
+    let dart_version =
+        sdk_metadata::read_dart_sdk_version(&path).unwrap_or_else(|| String::from("-"));
+    format!("{name}\t{kind}\t{path}\t{installed_at}\t{dart_version}")
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -41,6 +185,29 @@ mod tests {
     use indoc::formatdoc;
     use std::fs;
 
+    #[test]
+    fn test_list_equivalent_installations_once() {
+        test_with_context(|context, output| {
+            // setup
+            let fenv_versions = context.fenv_versions();
+            fs::create_dir_all(fenv_versions.join("v1.17.5")).unwrap();
+            fs::create_dir_all(fenv_versions.join("1.17.5")).unwrap();
+            fs::create_dir_all(fenv_versions.join("v1.4.19")).unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "versions"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), "v1.4.19\n1.17.5\n");
+        })
+    }
+
     #[test]
     fn test_sorted_order_of_list_installed_sdks() {
         test_with_context(|context, output| {
@@ -84,6 +251,136 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_list_installed_sdks_in_machine_format() {
+        test_with_context(|context, output| {
+            // setup
+            let fenv_versions = context.fenv_versions();
+            fenv_versions
+                .join("3.7.12/bin/cache/dart-sdk/version")
+                .writeln("2.19.6")
+                .unwrap();
+            fs::create_dir_all(fenv_versions.join("stable")).unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "versions", "--machine"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            let stdout = output.stdout_to_string();
+            let lines: Vec<Vec<&str>> = stdout
+                .lines()
+                .map(|line| line.split('\t').collect())
+                .collect();
+            assert_eq!(lines[0], ["#fenv-versions/v1"]);
+            assert_eq!(
+                lines[1],
+                ["name", "kind", "path", "installed_at", "dart_version"]
+            );
+            assert_eq!(lines.len(), 4);
+            let sdk_root = context.fenv_sdk_root("3.7.12").to_string();
+            assert_eq!(lines[2][..3], ["3.7.12", "release", &sdk_root]);
+            assert_eq!(lines[2][4], "2.19.6");
+            let sdk_root = context.fenv_sdk_root("stable").to_string();
+            assert_eq!(lines[3][..3], ["stable", "channel", &sdk_root]);
+            assert_eq!(lines[3][4], "-");
+            for line in &lines[2..] {
+                assert_eq!(line.len(), lines[1].len());
+                assert!(chrono::DateTime::parse_from_rfc3339(line[3]).is_ok());
+            }
+        })
+    }
+
+    #[test]
+    fn test_list_installed_sdks_in_json() {
+        test_with_context(|context, output| {
+            // setup
+            let fenv_versions = context.fenv_versions();
+            fs::create_dir_all(fenv_versions.join("v3.7.12")).unwrap();
+            fs::create_dir_all(fenv_versions.join("3.7.12")).unwrap();
+            fs::create_dir_all(fenv_versions.join("stable")).unwrap();
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("3.7")
+                .unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "versions", "--json"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            let json: serde_json::Value = serde_json::from_str(&output.stdout_to_string()).unwrap();
+            assert_eq!(
+                json,
+                serde_json::json!([
+                    {
+                        "name": "3.7.12",
+                        "kind": "version",
+                        "path": context.fenv_sdk_root("3.7.12").to_string(),
+                        "is_active": true,
+                    },
+                    {
+                        "name": "stable",
+                        "kind": "channel",
+                        "path": context.fenv_sdk_root("stable").to_string(),
+                        "is_active": false,
+                    },
+                ])
+            );
+        })
+    }
+
+    #[test]
+    fn test_list_installed_sdks_in_tree() {
+        test_with_context(|context, output| {
+            // setup
+            let fenv_versions = context.fenv_versions();
+            for name in ["3.19.6", "3.22.0", "3.22.2", "stable"] {
+                fs::create_dir_all(fenv_versions.join(name)).unwrap();
+            }
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("3.22")
+                .unwrap();
+
+            // execution
+            try_run(
+                &["fenv", "versions", "--tree"],
+                context,
+                &RealSdkService::new(),
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                formatdoc! {
+                    "
+                    3.19
+                    └─ 3.19.6
+                    3.22
+                    ├─ 3.22.0
+                    └─ 3.22.2*
+                    stable
+                    "
+                }
+            );
+        })
+    }
+
     #[test]
     fn test_filter_out_installing_markers() {
         test_with_context(|context, output| {
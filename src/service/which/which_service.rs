@@ -2,8 +2,12 @@ use crate::{
     args::FenvWhichArgs,
     context::FenvContext,
     invoke_command,
-    sdk_service::{results::LookupResult, sdk_service::SdkService},
-    service::service::Service,
+    sdk_service::{model::flutter_sdk::FlutterSdk, results::LookupResult, sdk_service::SdkService},
+    service::{
+        rehash::rehash_service::{heal_shims, is_shim_up_to_date, SHIMS},
+        service::Service,
+        start_dir::StartDir,
+    },
     util::{io::ConsoleOutput, path_like::PathLike},
 };
 use anyhow::bail;
@@ -31,66 +35,223 @@ where
         output: &mut dyn ConsoleOutput<OUT, ERR>,
     ) -> anyhow::Result<()> {
         let executable = &self.args.executable;
-        let command_path_or_none =
-            lookup_executable_in_sdks(context, sdk_service, output, executable)?
-                .or_else(|| lookup_executable_in_pub_cache(context, executable));
-
-        match command_path_or_none {
-            Some(command_path) => {
-                writeln!(output.stdout(), "{}", command_path)?;
-                anyhow::Ok(())
-            }
-            None => bail!("Could not find the specified executable: `{executable}`"),
+        if self.args.all {
+            return show_all_executables(context, sdk_service, output, executable);
+        }
+        let start_dir = self.args.start_dir.resolve_start_dir(context);
+        let found = lookup_executable_in_sdks(
+            context,
+            sdk_service,
+            output,
+            &start_dir,
+            executable,
+            self.args.validate,
+        )?
+        .or_else(|| lookup_executable_in_pub_cache(context, executable))
+        .or_else(|| lookup_executable_in_system(context, executable));
+        let Some(found) = found else {
+            bail!("Could not find the specified executable: `{executable}`")
+        };
+
+        let path = if self.args.shim && is_shim(executable) {
+            find_healthy_shim(context, executable)?
+        } else {
+            found.path
+        };
+        if self.args.json {
+            let object = serde_json::json!({
+                "path": path.to_string(),
+                "origin": found.origin.name(),
+                "version": found.version,
+                "executable": executable,
+            });
+            writeln!(
+                output.stdout(),
+                "{}",
+                serde_json::to_string_pretty(&object)?
+            )?;
+        } else {
+            writeln!(output.stdout(), "{path}")?;
+        }
+        anyhow::Ok(())
+    }
+}
+
+/// Where the executable found by `fenv which` comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExecutableOrigin {
+    /// `bin` of the selected Flutter SDK.
+    Sdk,
+    /// `$PUB_CACHE/bin`, which must be on `PATH` for the executable to run.
+    PubCache,
+    /// A directory on `PATH` other than `$FENV_ROOT/shims`.
+    System,
+}
+
+impl ExecutableOrigin {
+    /// The name reported as `origin` by `--json`.
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Sdk => "sdk",
+            Self::PubCache => "pub-cache",
+            Self::System => "system",
         }
     }
 }
 
+/// An executable found by `fenv which`.
+struct FoundExecutable {
+    path: PathLike,
+    origin: ExecutableOrigin,
+    /// The name of the selected Flutter SDK if the executable is in it.
+    version: Option<String>,
+}
+
+/// Prints every installed Flutter SDK which has `executable` in `bin` or
+/// `bin/cache/dart-sdk/bin`, with the full path to it.
+fn show_all_executables<OUT: std::io::Write, ERR: std::io::Write>(
+    context: &impl FenvContext,
+    sdk_service: &impl SdkService,
+    output: &mut dyn ConsoleOutput<OUT, ERR>,
+    executable: &str,
+) -> anyhow::Result<()> {
+    let found: Vec<(String, PathLike)> = sdk_service
+        .get_installed_sdk_list(context)?
+        .iter()
+        .filter_map(|sdk| {
+            let name = sdk.display_name();
+            let sdk_bin = context.fenv_sdk_root(&name).join("bin");
+            let dart_sdk_bin = sdk_bin.join("cache").join("dart-sdk").join("bin");
+            [sdk_bin, dart_sdk_bin]
+                .iter()
+                .map(|dir| dir.join(executable))
+                .find(|command_path| is_executable(command_path))
+                .map(|command_path| (name, command_path))
+        })
+        .collect();
+    if found.is_empty() {
+        bail!(
+            "Could not find the specified executable in any installed Flutter SDK: `{executable}`"
+        )
+    }
+    let width = found.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    for (name, command_path) in &found {
+        writeln!(output.stdout(), "{name:width$} {command_path}")?;
+    }
+    anyhow::Ok(())
+}
+
 fn lookup_executable_in_sdks<OUT: std::io::Write, ERR: std::io::Write>(
     context: &impl FenvContext,
     sdk_service: &impl SdkService,
     output: &mut dyn ConsoleOutput<OUT, ERR>,
+    start_dir: &PathLike,
     executable: &str,
-) -> anyhow::Result<Option<PathLike>> {
-    let version_or_channel = match invoke_command!(context, sdk_service, output, "version-name") {
-        Ok(version_or_channel) => version_or_channel,
-        Err(err) => {
-            if let LookupResult::None =
-                sdk_service.find_nearest_version_file(context, &context.fenv_dir())
-            {
-                return anyhow::Ok(None);
-            } else {
-                return anyhow::Result::Err(err);
+    validate: bool,
+) -> anyhow::Result<Option<FoundExecutable>> {
+    let dir = start_dir.to_string();
+    let version_or_channel =
+        match invoke_command!(context, sdk_service, output, "version-name", &dir) {
+            Ok(version_or_channel) => version_or_channel,
+            Err(err) => {
+                // `$FENV_VERSION` selects a Flutter SDK even if no version file exists.
+                if context.shell_version().is_none()
+                    && matches!(
+                        sdk_service.find_nearest_version_file(context, start_dir),
+                        LookupResult::None
+                    )
+                {
+                    return anyhow::Ok(None);
+                } else {
+                    return anyhow::Result::Err(err);
+                }
             }
-        }
-    };
+        };
 
-    let prefix = invoke_command!(context, sdk_service, output, "prefix", &version_or_channel)?;
-    let command_path = PathLike::from(prefix.as_str()).join("bin").join(executable);
+    let prefix = if validate {
+        invoke_command!(
+            context,
+            sdk_service,
+            output,
+            "prefix",
+            "--validate",
+            &version_or_channel
+        )?
+    } else {
+        invoke_command!(context, sdk_service, output, "prefix", &version_or_channel)?
+    };
+    let sdk_root = PathLike::from(prefix.as_str());
+    heal_shims(context, &sdk_root);
+    let command_path = sdk_root.join("bin").join(executable);
     if is_executable(&command_path) {
-        anyhow::Ok(Some(command_path))
+        anyhow::Ok(Some(FoundExecutable {
+            path: command_path,
+            origin: ExecutableOrigin::Sdk,
+            version: Some(version_or_channel),
+        }))
     } else {
         anyhow::Ok(None)
     }
 }
 
+fn is_shim(executable: &str) -> bool {
+    SHIMS.iter().any(|(name, _)| *name == executable)
+}
+
+/// Returns the path to the shim of `executable` if it is placed, executable, and up to date.
+fn find_healthy_shim(context: &impl FenvContext, executable: &str) -> anyhow::Result<PathLike> {
+    let shim_path = context.fenv_shims().join(executable);
+    if !is_executable(&shim_path) || !is_shim_up_to_date(context, executable)? {
+        bail!("The shim is missing or outdated: `{shim_path}`. Run `fenv rehash` to regenerate it")
+    }
+    anyhow::Ok(shim_path)
+}
+
 fn lookup_executable_in_pub_cache(
     context: &impl FenvContext,
     executable: &str,
-) -> Option<PathLike> {
+) -> Option<FoundExecutable> {
     let command_path = context.pub_cache().join("bin").join(executable);
     if is_executable(&command_path) {
-        Some(command_path)
+        Some(FoundExecutable {
+            path: command_path,
+            origin: ExecutableOrigin::PubCache,
+            version: None,
+        })
     } else {
         None
     }
 }
 
+/// Finds `executable` on `PATH`, skipping `$FENV_ROOT/shims` which would run `fenv` again.
+fn lookup_executable_in_system(
+    context: &impl FenvContext,
+    executable: &str,
+) -> Option<FoundExecutable> {
+    let fenv_shims = context.fenv_shims();
+    context
+        .path()
+        .iter()
+        .filter(|dir| **dir != fenv_shims)
+        .map(|dir| dir.join(executable))
+        .find(|command_path| is_executable(command_path))
+        .map(|command_path| FoundExecutable {
+            path: command_path,
+            origin: ExecutableOrigin::System,
+            version: None,
+        })
+}
+
 #[cfg(unix)]
 #[cfg(test)]
 mod tests_unix {
     use crate::{
-        context::FenvContext, sdk_service::sdk_service::RealSdkService,
-        service::macros::test_with_context, try_run, write_invalid_utf8,
+        context::FenvContext,
+        sdk_service::{results::BrokenInstallation, sdk_service::RealSdkService},
+        service::{macros::test_with_context, rehash::rehash_service::rehash},
+        try_run,
+        util::io::ConsoleOutput,
+        write_invalid_utf8,
     };
     use std::io::Write;
     use std::os::unix::prelude::PermissionsExt;
@@ -123,6 +284,210 @@ mod tests_unix {
         })
     }
 
+    #[test]
+    fn test_show_executable_as_json_with_origin() {
+        test_with_context(|context, output| {
+            // setup
+            let flutter_path = context.fenv_versions().join("3.7.12/bin/flutter");
+            let melos_path = context.pub_cache().join("bin").join("melos");
+            let system_bin = context.home().join("usr").join("bin");
+            let git_path = system_bin.join("git");
+            for path in [&flutter_path, &melos_path, &git_path] {
+                path.writeln("").unwrap();
+                std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+            }
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("3")
+                .unwrap();
+            let context = context
+                .clone()
+                .with_path(&[context.fenv_shims(), system_bin]);
+            let sdk_service = RealSdkService::new();
+            let mut which_as_json = |executable: &str| {
+                output.stdout().clear();
+                try_run(
+                    &["fenv", "which", "--json", executable],
+                    &context,
+                    &sdk_service,
+                    output,
+                )
+                .unwrap();
+                serde_json::from_str::<serde_json::Value>(&output.stdout_to_string()).unwrap()
+            };
+
+            // execution
+            let flutter = which_as_json("flutter");
+            let melos = which_as_json("melos");
+            let git = which_as_json("git");
+
+            // validation
+            assert_eq!(
+                flutter,
+                serde_json::json!({
+                    "path": flutter_path.to_string(),
+                    "origin": "sdk",
+                    "version": "3.7.12",
+                    "executable": "flutter",
+                })
+            );
+            assert_eq!(melos["path"], melos_path.to_string());
+            assert_eq!(melos["origin"], "pub-cache");
+            assert_eq!(melos["version"], serde_json::Value::Null);
+            assert_eq!(git["path"], git_path.to_string());
+            assert_eq!(git["origin"], "system");
+        })
+    }
+
+    #[test]
+    fn test_show_all_sdks_providing_executable() {
+        test_with_context(|context, output| {
+            // setup
+            let melos_of_3_7 = context.fenv_versions().join("3.7.12/bin/melos");
+            let melos_of_stable = context
+                .fenv_versions()
+                .join("stable/bin/cache/dart-sdk/bin/melos");
+            for melos in [&melos_of_3_7, &melos_of_stable] {
+                melos.writeln("").unwrap();
+                std::fs::set_permissions(melos, std::fs::Permissions::from_mode(0o755)).unwrap();
+            }
+            context
+                .fenv_versions()
+                .join("3.22.2/bin/flutter")
+                .writeln("")
+                .unwrap();
+            let sdk_service = RealSdkService::new();
+
+            // execution
+            try_run(
+                &["fenv", "which", "--all", "melos"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+            let result = try_run(
+                &["fenv", "which", "--all", "flutter"],
+                context,
+                &sdk_service,
+                output,
+            );
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                format!("3.7.12 {melos_of_3_7}\nstable {melos_of_stable}\n")
+            );
+            // A non-executable file is not counted.
+            assert!(result.is_err());
+        })
+    }
+
+    #[test]
+    fn test_show_flutter_shim_path_if_shims_are_healthy() {
+        test_with_context(|context, output| {
+            // setup
+            let flutter_path = context.fenv_versions().join("3.7.12/bin/flutter");
+            flutter_path.writeln("").unwrap();
+            std::fs::set_permissions(&flutter_path, std::fs::Permissions::from_mode(0o755))
+                .unwrap();
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("3")
+                .unwrap();
+            rehash(context).unwrap();
+            let sdk_service = RealSdkService::new();
+
+            // execution
+            try_run(
+                &["fenv", "which", "--shim", "flutter"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                output.stdout_to_string(),
+                format!("{}\n", context.fenv_shims().join("flutter"))
+            );
+        })
+    }
+
+    #[test]
+    fn test_fails_to_show_flutter_shim_path_if_shim_is_outdated() {
+        test_with_context(|context, output| {
+            // setup
+            let flutter_path = context.fenv_versions().join("3.7.12/bin/flutter");
+            flutter_path.writeln("").unwrap();
+            std::fs::set_permissions(&flutter_path, std::fs::Permissions::from_mode(0o755))
+                .unwrap();
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("3")
+                .unwrap();
+            rehash(context).unwrap();
+            let shim_path = context.fenv_shims().join("flutter");
+            shim_path.writeln("#!/bin/sh").unwrap();
+            let sdk_service = RealSdkService::new();
+
+            // execution
+            let result = try_run(
+                &["fenv", "which", "--shim", "flutter"],
+                context,
+                &sdk_service,
+                output,
+            );
+
+            // validation
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                format!(
+                    "The shim is missing or outdated: `{shim_path}`. \
+                    Run `fenv rehash` to regenerate it"
+                )
+            );
+            assert!(output.stdout_to_string().is_empty());
+        })
+    }
+
+    #[test]
+    fn test_show_flutter_filepath_in_given_dir() {
+        test_with_context(|context, output| {
+            // setup
+            // prepare the `flutter` CLI for 3.7.12
+            let flutter_path = context.fenv_versions().join("3.7.12/bin/flutter");
+            flutter_path.writeln("").unwrap();
+            let mut permissions = flutter_path.path().metadata().unwrap().permissions();
+            permissions.set_mode(0o755);
+            std::fs::set_permissions(&flutter_path, permissions).unwrap();
+            // prepare the `.flutter-version` file only in `app`
+            context
+                .fenv_dir()
+                .join("app")
+                .join(".flutter-version")
+                .writeln("3")
+                .unwrap();
+            let sdk_service = RealSdkService::new();
+
+            // execution
+            try_run(
+                &["fenv", "which", "--dir", "app", "flutter"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), format!("{}\n", flutter_path));
+        })
+    }
+
     #[test]
     fn test_fails_to_show_flutter_filepath_if_flutter_cli_is_not_executable() {
         test_with_context(|context, output| {
@@ -152,6 +517,46 @@ mod tests_unix {
         })
     }
 
+    #[test]
+    fn test_fails_to_show_flutter_filepath_if_installation_is_broken() {
+        test_with_context(|context, output| {
+            // setup
+            // the installation of 3.7.12 was interrupted before `bin/flutter` is checked out
+            context
+                .fenv_versions()
+                .join("3.7.12")
+                .create_dir_all()
+                .unwrap();
+            context
+                .fenv_dir()
+                .join(".flutter-version")
+                .writeln("3")
+                .unwrap();
+            let sdk_service = RealSdkService::new();
+
+            // execution
+            let err =
+                try_run(&["fenv", "which", "flutter"], context, &sdk_service, output).unwrap_err();
+
+            // validation
+            assert!(err.downcast_ref::<BrokenInstallation>().is_some());
+
+            // execution
+            let result = try_run(
+                &["fenv", "which", "--no-validate", "flutter"],
+                context,
+                &sdk_service,
+                output,
+            );
+
+            // validation
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                "Could not find the specified executable: `flutter`"
+            );
+        })
+    }
+
     #[test]
     fn test_fails_to_show_flutter_filepath_if_version_file_is_non_utf8() {
         test_with_context(|context, output| {
@@ -224,4 +629,24 @@ mod tests_unix {
             assert!(output.stderr_to_string().is_empty());
         })
     }
+
+    #[test]
+    fn test_show_flutter_filepath_of_shell_version_without_version_file() {
+        test_with_context(|context, output| {
+            // setup
+            let flutter_path = context.fenv_versions().join("3.7.12/bin/flutter");
+            flutter_path.writeln("").unwrap();
+            let mut permissions = flutter_path.path().metadata().unwrap().permissions();
+            permissions.set_mode(0o755);
+            std::fs::set_permissions(&flutter_path, permissions).unwrap();
+            let context = &context.clone().with_shell_version("3.7");
+            let sdk_service = RealSdkService::new();
+
+            // execution
+            try_run(&["fenv", "which", "flutter"], context, &sdk_service, output).unwrap();
+
+            // validation
+            assert_eq!(output.stdout_to_string(), format!("{}\n", flutter_path));
+        })
+    }
 }
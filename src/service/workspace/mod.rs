@@ -1,3 +1,4 @@
 mod dart_sdk_xml;
 mod package_config_json;
+mod workspace_manifest;
 pub mod workspace_service;
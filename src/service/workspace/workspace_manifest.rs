@@ -0,0 +1,139 @@
+use crate::util::{checksum::sha256_of_file, path_like::PathLike};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+/// A definition of format of `.fenv/workspace-manifest.json` file, which lists the files
+/// generated by `fenv workspace` with their SHA-256 hashes at the time of the generation.
+///
+/// The paths are relative to the workspace directory unless the files are out of it.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WorkspaceManifest {
+    pub files: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ManifestEntry {
+    pub path: String,
+    pub sha256: String,
+}
+
+impl WorkspaceManifest {
+    /// `{workspace}/.fenv/workspace-manifest.json`.
+    pub fn path_of(workspace_path: &PathLike) -> PathLike {
+        workspace_path.join(".fenv").join("workspace-manifest.json")
+    }
+
+    /// Reads the manifest of the given workspace.
+    ///
+    /// An empty manifest is returned if the manifest does not exist or is not readable,
+    /// so that every existing file is regarded as not generated by `fenv`.
+    pub fn load(workspace_path: &PathLike) -> Self {
+        std::fs::read_to_string(Self::path_of(workspace_path))
+            .ok()
+            .and_then(|content| Self::parse(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn parse(raw_json: &str) -> anyhow::Result<Self> {
+        serde_json::from_str(raw_json)
+            .with_context(|| "Failed to parse the given `workspace-manifest.json`")
+    }
+
+    pub fn stringify(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn save(&self, workspace_path: &PathLike) -> anyhow::Result<()> {
+        let manifest_path = Self::path_of(workspace_path);
+        manifest_path
+            .writeln(self.stringify())
+            .with_context(|| format!("Failed to write `{manifest_path}`"))
+    }
+
+    /// Records the current content of the given `file` as generated by `fenv`.
+    pub fn record(&mut self, workspace_path: &PathLike, file: &PathLike) -> anyhow::Result<()> {
+        let sha256 = sha256_of_file(file)?;
+        let path = relative_path_of(workspace_path, file);
+        self.forget(workspace_path, file);
+        self.files.push(ManifestEntry { path, sha256 });
+        self.files.sort_by(|a, b| a.path.cmp(&b.path));
+        anyhow::Ok(())
+    }
+
+    /// Stops regarding the given `file` as generated by `fenv`.
+    pub fn forget(&mut self, workspace_path: &PathLike, file: &PathLike) {
+        let path = relative_path_of(workspace_path, file);
+        self.files.retain(|entry| entry.path != path);
+    }
+
+    /// Whether the given `file` was generated by `fenv` and has been modified since then.
+    pub fn is_modified(&self, workspace_path: &PathLike, file: &PathLike) -> bool {
+        let path = relative_path_of(workspace_path, file);
+        match self.files.iter().find(|entry| entry.path == path) {
+            Some(entry) => {
+                file.is_file() && sha256_of_file(file).ok().as_ref() != Some(&entry.sha256)
+            }
+            None => false,
+        }
+    }
+}
+
+/// The path of `file` relative to the workspace, or the path as is if `file` is out of it.
+///
+/// Both paths are expected to be resolved against the working directory of the context.
+fn relative_path_of(workspace_path: &PathLike, file: &PathLike) -> String {
+    match file.path().strip_prefix(workspace_path.path()) {
+        Ok(relative_path) => relative_path.to_string_lossy().to_string(),
+        Err(_) => file.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WorkspaceManifest;
+    use crate::{context::FenvContext, service::macros::test_with_context};
+
+    #[test]
+    fn test_record_and_detect_modification() {
+        test_with_context(|context, _| {
+            // setup
+            let workspace = context.fenv_dir().join("workspace");
+            let file = workspace
+                .join(".idea")
+                .join("libraries")
+                .join("Dart_SDK.xml");
+            file.writeln("<component />").unwrap();
+            let mut manifest = WorkspaceManifest::default();
+
+            // execution
+            manifest.record(&workspace, &file).unwrap();
+
+            // validation
+            assert_eq!(manifest.files.len(), 1);
+            assert_eq!(manifest.files[0].path, ".idea/libraries/Dart_SDK.xml");
+            assert!(!manifest.is_modified(&workspace, &file));
+            file.writeln("<component name=\"modified\" />").unwrap();
+            assert!(manifest.is_modified(&workspace, &file));
+        })
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        test_with_context(|context, _| {
+            // setup
+            let workspace = context.fenv_dir().join("workspace");
+            let file = context.fenv_dir().join("sdk_path.txt");
+            file.writeln("/home/user/.fenv/versions/3.7.12").unwrap();
+            let mut manifest = WorkspaceManifest::default();
+            manifest.record(&workspace, &file).unwrap();
+
+            // execution
+            manifest.save(&workspace).unwrap();
+
+            // validation
+            let loaded = WorkspaceManifest::load(&workspace);
+            assert_eq!(loaded, manifest);
+            assert_eq!(loaded.files[0].path, file.to_string());
+        })
+    }
+}
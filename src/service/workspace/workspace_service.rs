@@ -7,14 +7,14 @@ use crate::{
         workspace::{
             dart_sdk_xml::{Classes, DartSdkXml, Library, LibraryEntry, Root},
             package_config_json::{Package, PackageConfigJson},
+            workspace_manifest::WorkspaceManifest,
         },
     },
-    spawn_and_wait,
     util::{io::ConsoleOutput, path_like::PathLike},
+    workspaces,
 };
 use anyhow::{bail, Context};
 use log::{debug, info};
-use std::process::Command;
 
 pub struct FenvWorkspaceService {
     pub args: FenvWorkspaceArgs,
@@ -37,9 +37,13 @@ where
         sdk_service: &impl SdkService,
         output: &mut dyn ConsoleOutput<OUT, ERR>,
     ) -> anyhow::Result<()> {
-        let workspace = &self.args.workspace[..];
-        let workspace_path = PathLike::from(workspace);
+        // Resolved against the working directory of the context, as the paths in the manifest are.
+        let workspace_path = context.fenv_dir().join(&self.args.workspace);
         ensure_pubspec_yaml_contains(&workspace_path)?;
+        let mut manifest = WorkspaceManifest::load(&workspace_path);
+        if self.args.clean {
+            return clean_workspace(output, &workspace_path, &manifest);
+        }
         let prefix = self.args.prefix.as_ref().map(|s| &s[..]);
         let sdk_root_path = find_sdk_root_path(context, sdk_service, &workspace_path, prefix)?;
 
@@ -47,21 +51,48 @@ where
         if !self.args.should_pub_get {
             generate_package_config_json_manually(
                 output,
+                &mut manifest,
                 &workspace_path,
                 &sdk_root_path,
                 self.args.force,
             )?;
         } else {
-            generate_package_config_json_by_pub_get(&workspace_path, &sdk_root_path)?;
+            debug!("`dart pub get` is started on `{workspace_path}`");
+            sdk_service.pub_get(&sdk_root_path, &workspace_path)?;
+            // `package_config.json` is owned by `dart pub get` from now on.
+            manifest.forget(
+                &workspace_path,
+                &workspace_path
+                    .join(".dart_tool")
+                    .join("package_config.json"),
+            );
         }
 
         support_intellij_dart_plugin(
             output,
+            &mut manifest,
             &workspace_path,
             &sdk_root_path,
             &context.home(),
             self.args.force,
-        )
+        )?;
+
+        if let Some(path_file) = &self.args.emit_path_file {
+            emit_sdk_path_file(
+                output,
+                &mut manifest,
+                &workspace_path,
+                &context.fenv_dir().join(path_file),
+                &sdk_root_path,
+                self.args.force,
+            )?;
+        }
+        manifest.save(&workspace_path)?;
+        // `fenv prune` scans the recorded workspaces for the version files.
+        if let Err(e) = workspaces::record_workspace(context, &workspace_path) {
+            debug!("Failed to record `{workspace_path}`: {e}");
+        }
+        anyhow::Ok(())
     }
 }
 
@@ -73,20 +104,74 @@ fn ensure_pubspec_yaml_contains(workspace_path: &PathLike) -> anyhow::Result<()>
     anyhow::Ok(())
 }
 
+/// Removes the files which `fenv workspace` generated and the manifest which lists them.
+///
+/// The files modified since the generation are left with warnings.
+fn clean_workspace<OUT: std::io::Write, ERR: std::io::Write>(
+    output: &mut dyn ConsoleOutput<OUT, ERR>,
+    workspace_path: &PathLike,
+    manifest: &WorkspaceManifest,
+) -> anyhow::Result<()> {
+    for entry in &manifest.files {
+        let file = workspace_path.join(&entry.path);
+        if !file.is_file() {
+            debug!("`{file}` is already removed");
+            continue;
+        }
+        if keeps_modified_file(output, manifest, workspace_path, &file)? {
+            continue;
+        }
+        file.remove_file()
+            .with_context(|| format!("Failed to remove `{file}`"))?;
+        writeln!(output.stdout(), "`{file}` is removed")?;
+    }
+    let manifest_path = WorkspaceManifest::path_of(workspace_path);
+    if manifest_path.is_file() {
+        manifest_path
+            .remove_file()
+            .with_context(|| format!("Failed to remove `{manifest_path}`"))?;
+    }
+    // Leaves `.fenv` if the user put anything else in it.
+    if let Some(manifest_dir) = manifest_path.parent() {
+        let _ = std::fs::remove_dir(&manifest_dir);
+    }
+    anyhow::Ok(())
+}
+
+/// Whether the given `file` must be kept because it has been modified since `fenv` generated it.
+fn keeps_modified_file<OUT: std::io::Write, ERR: std::io::Write>(
+    output: &mut dyn ConsoleOutput<OUT, ERR>,
+    manifest: &WorkspaceManifest,
+    workspace_path: &PathLike,
+    file: &PathLike,
+) -> anyhow::Result<bool> {
+    if manifest.is_modified(workspace_path, file) {
+        output.warning(&format!(
+            "`{file}` has been modified since fenv generated it. Skipped it"
+        ))?;
+        return anyhow::Ok(true);
+    }
+    anyhow::Ok(false)
+}
+
 /// Generates `.dart_tool/package_config.json` manually to set `flutter`'s version to the given
 /// `sdk_root_path`.
 ///
 /// If the `.dart_tool/package_config.json` already exists and has the same `flutter` package, it will not be
-/// regenerated.
+/// regenerated. With `force`, it is regenerated unless it has been modified since `fenv` generated it.
 fn generate_package_config_json_manually<OUT: std::io::Write, ERR: std::io::Write>(
     output: &mut dyn ConsoleOutput<OUT, ERR>,
+    manifest: &mut WorkspaceManifest,
     workspace_path: &PathLike,
     sdk_root_path: &PathLike,
     force: bool,
 ) -> anyhow::Result<()> {
     let dart_tool_dir = workspace_path.join(".dart_tool");
-    let flutter_package_path = sdk_root_path.join("packages").join("flutter");
+    let flutter_package_uri = flutter_package_uri(sdk_root_path);
     let package_config_json_path = dart_tool_dir.join("package_config.json");
+    if force && keeps_modified_file(output, manifest, workspace_path, &package_config_json_path)? {
+        return anyhow::Ok(());
+    }
 
     // If an existing `package_config.json` has the same `flutter` package,
     // we don't need to re-generate it.
@@ -98,7 +183,7 @@ fn generate_package_config_json_manually<OUT: std::io::Write, ERR: std::io::Writ
                 .iter()
                 .find(|p| p.name == "flutter");
             if let Some(flutter_package) = flutter_package {
-                if &flutter_package.root_uri == &format!("file://{}", flutter_package_path) {
+                if flutter_package.root_uri == flutter_package_uri {
                     info!("`{}` is already generated", &package_config_json_path);
                     writeln!(
                         output.stdout(),
@@ -115,20 +200,17 @@ fn generate_package_config_json_manually<OUT: std::io::Write, ERR: std::io::Writ
         debug!("Removing the existing `{dart_tool_dir}`");
         dart_tool_dir.remove_dir_all()?;
     }
-    debug!("Generating `{dart_tool_dir}/package_config.json` with `{flutter_package_path}`");
+    debug!("Generating `{dart_tool_dir}/package_config.json` with `{flutter_package_uri}`");
     package_config_json_path
         .writeln(
             PackageConfigJson {
                 config_version: 2,
-                packages: vec![Package::new(
-                    "flutter",
-                    &format!("file://{}", flutter_package_path),
-                    "lib/",
-                )],
+                packages: vec![Package::new("flutter", &flutter_package_uri, "lib/")],
             }
             .stringify(),
         )
         .with_context(|| anyhow::anyhow!("Failed to write package_config.json"))?;
+    manifest.record(workspace_path, &package_config_json_path)?;
 
     writeln!(
         output.stdout(),
@@ -138,20 +220,58 @@ fn generate_package_config_json_manually<OUT: std::io::Write, ERR: std::io::Writ
     anyhow::Ok(())
 }
 
-/// Generates `.dart_tool/package_config.json` by running `dart pub get`.
-fn generate_package_config_json_by_pub_get(
+/// The `rootUri` of the `flutter` package of `.dart_tool/package_config.json`.
+fn flutter_package_uri(sdk_root_path: &PathLike) -> String {
+    format!("file://{}", sdk_root_path.join("packages").join("flutter"))
+}
+
+/// `sdk_root_path` whose home directory is replaced with `$USER_HOME$` as IntelliJ does.
+fn user_home_replaced(sdk_root_path: &PathLike, home_path: &PathLike) -> String {
+    sdk_root_path
+        .to_string()
+        .replace(home_path.to_string().as_str(), "$USER_HOME$")
+}
+
+/// The URL of the `lib/core` library of `.idea/libraries/Dart_SDK.xml`.
+fn dart_core_library_uri(sdk_root_path: &PathLike, home_path: &PathLike) -> String {
+    format!(
+        "file://{}/bin/cache/dart-sdk/lib/core",
+        user_home_replaced(sdk_root_path, home_path)
+    )
+}
+
+/// Lists the IDE files generated in `workspace_path`, which refer to another Flutter SDK than
+/// the one at `sdk_root_path`. The missing or unreadable files are not listed, and neither is
+/// `package_config.json` without the `flutter` package, which a pure Dart package has.
+pub fn find_stale_files(
     workspace_path: &PathLike,
     sdk_root_path: &PathLike,
-) -> anyhow::Result<()> {
-    debug!("`dart pub get` is started on `{workspace_path}`");
-    let dart_cli_path = sdk_root_path.join("bin").join("dart");
-    let mut command = Command::new(dart_cli_path.path());
-    spawn_and_wait!(
-        command.current_dir(workspace_path).args(["pub", "get"]),
-        "generate_package_config_json_by_pub_get",
-        "Failed to execute `dart pub get`"
-    );
-    anyhow::Ok(())
+    home_path: &PathLike,
+) -> Vec<PathLike> {
+    let mut stale_files = vec![];
+    let package_config_json_path = workspace_path
+        .join(".dart_tool")
+        .join("package_config.json");
+    if let Ok(package_config_json) = PackageConfigJson::read(&package_config_json_path) {
+        let expected = flutter_package_uri(sdk_root_path);
+        if package_config_json
+            .packages
+            .iter()
+            .any(|package| package.name == "flutter" && package.root_uri != expected)
+        {
+            stale_files.push(package_config_json_path);
+        }
+    }
+    let dart_sdk_xml_path = workspace_path
+        .join(".idea")
+        .join("libraries")
+        .join("Dart_SDK.xml");
+    if let Ok(xml) = DartSdkXml::read(&dart_sdk_xml_path) {
+        if !xml.has_library(&dart_core_library_uri(sdk_root_path, home_path)) {
+            stale_files.push(dart_sdk_xml_path);
+        }
+    }
+    stale_files
 }
 
 fn find_sdk_root_path(
@@ -178,6 +298,7 @@ fn find_sdk_root_path(
 
 fn support_intellij_dart_plugin<OUT: std::io::Write, ERR: std::io::Write>(
     output: &mut dyn ConsoleOutput<OUT, ERR>,
+    manifest: &mut WorkspaceManifest,
     workspace_path: &PathLike,
     sdk_root_path: &PathLike,
     home_path: &PathLike,
@@ -187,13 +308,13 @@ fn support_intellij_dart_plugin<OUT: std::io::Write, ERR: std::io::Write>(
         .join(".idea")
         .join("libraries")
         .join("Dart_SDK.xml");
-    let user_home_replaced_sdk_root_path = sdk_root_path
-        .to_string()
-        .replace(home_path.to_string().as_str(), "$USER_HOME$");
+    if force && keeps_modified_file(output, manifest, workspace_path, &dart_sdk_xml_path)? {
+        return anyhow::Ok(());
+    }
+    let user_home_replaced_sdk_root_path = user_home_replaced(sdk_root_path, home_path);
     // The `lib/core` package is very important for `intellij-dart`.
     // The `intellij-dart` plugin uses this package to find the location of Dart SDK.
-    let dart_core_package_uri =
-        format!("file://{user_home_replaced_sdk_root_path}/bin/cache/dart-sdk/lib/core");
+    let dart_core_package_uri = dart_core_library_uri(sdk_root_path, home_path);
 
     // If an existing `Dart_SDK.xml` has the same `lib/core` package,
     // we don't need to re-generate it.
@@ -232,10 +353,45 @@ fn support_intellij_dart_plugin<OUT: std::io::Write, ERR: std::io::Write>(
     dart_sdk_xml_path
         .write(dart_sdk_xml.stringify())
         .map_err(|err| anyhow::anyhow!("Failed to write `{dart_sdk_xml_path}`: {err}"))?;
+    manifest.record(workspace_path, &dart_sdk_xml_path)?;
     writeln!(output.stdout(), "`{dart_sdk_xml_path}` is generated",)?;
     anyhow::Ok(())
 }
 
+/// Writes the given `sdk_root_path` into the plain file `path_file`.
+///
+/// If the `path_file` already contains the same `sdk_root_path`, it will not be regenerated.
+fn emit_sdk_path_file<OUT: std::io::Write, ERR: std::io::Write>(
+    output: &mut dyn ConsoleOutput<OUT, ERR>,
+    manifest: &mut WorkspaceManifest,
+    workspace_path: &PathLike,
+    path_file: &PathLike,
+    sdk_root_path: &PathLike,
+    force: bool,
+) -> anyhow::Result<()> {
+    if force && keeps_modified_file(output, manifest, workspace_path, path_file)? {
+        return anyhow::Ok(());
+    }
+    if !force && path_file.is_file() {
+        if let Ok(existing_sdk_root_path) = path_file.read_to_string() {
+            if existing_sdk_root_path.trim_end() == sdk_root_path.to_string() {
+                info!("`{path_file}` already contains `{sdk_root_path}`");
+                writeln!(output.stdout(), "No need to re-generate `{path_file}`")?;
+                return anyhow::Ok(());
+            }
+        }
+        info!("Need to re-write the existing file `{path_file}`")
+    }
+
+    debug!("Writing `{sdk_root_path}` to `{path_file}`...");
+    path_file
+        .writeln(sdk_root_path.to_string())
+        .map_err(|err| anyhow::anyhow!("Failed to write `{path_file}`: {err}"))?;
+    manifest.record(workspace_path, path_file)?;
+    writeln!(output.stdout(), "`{path_file}` is generated")?;
+    anyhow::Ok(())
+}
+
 fn list_dart_libs(sdk_root_path: &PathLike) -> anyhow::Result<Vec<String>> {
     let dart_sdk_path = sdk_root_path
         .join("bin")
@@ -270,8 +426,12 @@ fn list_dart_libs(sdk_root_path: &PathLike) -> anyhow::Result<Vec<String>> {
 #[cfg(test)]
 mod tests {
     use crate::{
-        context::FenvContext, sdk_service::sdk_service::RealSdkService,
-        service::macros::test_with_context, try_run, util::path_like::PathLike,
+        context::FenvContext,
+        external::dart_command::MockDartCommand,
+        sdk_service::sdk_service::RealSdkService,
+        service::{macros::test_with_context, workspace::workspace_manifest::WorkspaceManifest},
+        try_run,
+        util::{io::BufferedOutput, path_like::PathLike},
     };
 
     fn prepare_valid_workspace(context: &impl FenvContext) {
@@ -477,6 +637,49 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_pub_get_generates_package_config_json_by_dart() {
+        test_with_context(|context, output| {
+            // setup
+            prepare_valid_workspace(context);
+            prepare_flutter_sdk(context, "stable");
+            let workspace = context.fenv_dir().join("workspace");
+            let mut dart_command = MockDartCommand::new();
+            let expected_sdk_root = context.fenv_sdk_root("stable").to_string();
+            let expected_working_dir = workspace.to_string();
+            dart_command
+                .expect_pub_get()
+                .withf(move |sdk_root, working_dir| {
+                    sdk_root == expected_sdk_root && working_dir == expected_working_dir
+                })
+                .times(1)
+                .returning(|_, _| anyhow::Ok(()));
+            let sdk_service = RealSdkService::new().with_dart_command(dart_command);
+
+            // execution
+            try_run(
+                &[
+                    "fenv",
+                    "workspace",
+                    "--pub-get",
+                    &workspace.to_string(),
+                    "s",
+                ],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert!(read_package_config_json(context).is_err());
+            assert_eq!(
+                output.stdout_to_string(),
+                format!("`{workspace}/.idea/libraries/Dart_SDK.xml` is generated\n"),
+            );
+        })
+    }
+
     #[test]
     fn test_skip_regenerating_files_if_not_needed() {
         test_with_context(|context, output| {
@@ -594,4 +797,244 @@ mod tests {
             assert!(output.stderr_to_string().is_empty());
         })
     }
+
+    #[test]
+    fn test_emit_path_file_if_option_is_given() {
+        test_with_context(|context, output| {
+            // setup
+            prepare_valid_workspace(context);
+            prepare_flutter_sdk(context, "3.7.12");
+            context.fenv_root().join("version").write("3").unwrap();
+            let path_file = context.fenv_dir().join("workspace/.flutter_sdk_path");
+            let sdk_service = RealSdkService::new();
+
+            // execution
+            try_run(
+                &[
+                    "fenv",
+                    "workspace",
+                    &format!("{}/workspace", context.fenv_dir()),
+                    "--emit-path-file",
+                    &path_file.to_string(),
+                ],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                path_file.read_to_string().unwrap(),
+                format!("{}\n", context.fenv_versions().join("3.7.12"))
+            );
+            assert!(output
+                .stdout_to_string()
+                .ends_with(&format!("`{path_file}` is generated\n")));
+            assert!(output.stderr_to_string().is_empty());
+        })
+    }
+
+    #[test]
+    fn test_emit_path_file_relative_to_working_directory() {
+        test_with_context(|context, output| {
+            // setup
+            prepare_valid_workspace(context);
+            prepare_flutter_sdk(context, "3.7.12");
+            context.fenv_root().join("version").write("3").unwrap();
+            let sdk_service = RealSdkService::new();
+
+            // execution
+            try_run(
+                &[
+                    "fenv",
+                    "workspace",
+                    "workspace",
+                    "--emit-path-file",
+                    "sdk_path.txt",
+                ],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            let path_file = context.fenv_dir().join("sdk_path.txt");
+            assert_eq!(
+                path_file.read_to_string().unwrap(),
+                format!("{}\n", context.fenv_versions().join("3.7.12"))
+            );
+            let manifest = WorkspaceManifest::load(&context.fenv_dir().join("workspace"));
+            let paths: Vec<&str> = manifest
+                .files
+                .iter()
+                .map(|entry| entry.path.as_str())
+                .collect();
+            assert!(paths.contains(&path_file.to_string().as_str()));
+        })
+    }
+
+    #[test]
+    fn test_skip_regenerating_path_file_if_not_needed() {
+        test_with_context(|context, output| {
+            // setup
+            prepare_valid_workspace(context);
+            prepare_flutter_sdk(context, "3.7.12");
+            context.fenv_root().join("version").write("3").unwrap();
+            let path_file = context.fenv_dir().join("workspace/.flutter_sdk_path");
+            path_file
+                .writeln(context.fenv_versions().join("3.7.12").to_string())
+                .unwrap();
+            let sdk_service = RealSdkService::new();
+
+            // execution
+            try_run(
+                &[
+                    "fenv",
+                    "workspace",
+                    &format!("{}/workspace", context.fenv_dir()),
+                    "--emit-path-file",
+                    &path_file.to_string(),
+                ],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert!(output
+                .stdout_to_string()
+                .ends_with(&format!("No need to re-generate `{path_file}`\n")));
+        })
+    }
+
+    #[test]
+    fn test_regenerating_path_file_if_version_is_changed() {
+        test_with_context(|context, output| {
+            // setup
+            prepare_valid_workspace(context);
+            prepare_flutter_sdk(context, "3.7.12");
+            context.fenv_root().join("version").write("3").unwrap();
+            let path_file = context.fenv_dir().join("workspace/.flutter_sdk_path");
+            path_file
+                .writeln(context.fenv_versions().join("stable").to_string())
+                .unwrap();
+            let sdk_service = RealSdkService::new();
+
+            // execution
+            try_run(
+                &[
+                    "fenv",
+                    "workspace",
+                    &format!("{}/workspace", context.fenv_dir()),
+                    "--emit-path-file",
+                    &path_file.to_string(),
+                ],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(
+                path_file.read_to_string().unwrap(),
+                format!("{}\n", context.fenv_versions().join("3.7.12"))
+            );
+            assert!(output
+                .stdout_to_string()
+                .ends_with(&format!("`{path_file}` is generated\n")));
+        })
+    }
+
+    #[test]
+    fn test_clean_removes_only_unmodified_generated_files() {
+        test_with_context(|context, output| {
+            // setup
+            prepare_valid_workspace(context);
+            prepare_flutter_sdk(context, "stable");
+            let workspace = context.fenv_dir().join("workspace");
+            let sdk_service = RealSdkService::new();
+            try_run(
+                &["fenv", "workspace", &workspace.to_string(), "s"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+            let manifest = workspace.join(".fenv/workspace-manifest.json");
+            assert!(manifest.is_file());
+            write_dart_sdk_xml(context, "<component />").unwrap();
+            let mut output = BufferedOutput::new();
+
+            // execution
+            try_run(
+                &["fenv", "workspace", "--clean", &workspace.to_string()],
+                context,
+                &sdk_service,
+                &mut output,
+            )
+            .unwrap();
+
+            // validation
+            assert!(!workspace.join(".dart_tool/package_config.json").exists());
+            assert_eq!(read_dart_sdk_xml(context).unwrap(), "<component />");
+            assert!(!workspace.join(".fenv").exists());
+            assert!(workspace.join("pubspec.yaml").is_file());
+            assert_eq!(
+                output.stdout_to_string(),
+                format!("`{workspace}/.dart_tool/package_config.json` is removed\n")
+            );
+            assert_eq!(
+                output.stderr_to_string(),
+                format!(
+                    "fenv: warning: `{workspace}/.idea/libraries/Dart_SDK.xml` has been modified since fenv generated it. Skipped it\n"
+                )
+            );
+        })
+    }
+
+    #[test]
+    fn test_force_skips_modified_files() {
+        test_with_context(|context, output| {
+            // setup
+            prepare_valid_workspace(context);
+            prepare_flutter_sdk(context, "stable");
+            let workspace = context.fenv_dir().join("workspace");
+            let sdk_service = RealSdkService::new();
+            try_run(
+                &["fenv", "workspace", &workspace.to_string(), "s"],
+                context,
+                &sdk_service,
+                output,
+            )
+            .unwrap();
+            write_dart_sdk_xml(context, "<component />").unwrap();
+            let mut output = BufferedOutput::new();
+
+            // execution
+            try_run(
+                &["fenv", "workspace", "--force", &workspace.to_string(), "s"],
+                context,
+                &sdk_service,
+                &mut output,
+            )
+            .unwrap();
+
+            // validation
+            assert_eq!(read_dart_sdk_xml(context).unwrap(), "<component />");
+            assert_eq!(
+                output.stdout_to_string(),
+                format!("`{workspace}/.dart_tool/package_config.json` is generated\n")
+            );
+            assert_eq!(
+                output.stderr_to_string(),
+                format!(
+                    "fenv: warning: `{workspace}/.idea/libraries/Dart_SDK.xml` has been modified since fenv generated it. Skipped it\n"
+                )
+            );
+        })
+    }
 }
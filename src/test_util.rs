@@ -0,0 +1,254 @@
+//! Runs `fenv` commands in an isolated environment, so that the tools which wrap `fenv` can be
+//! tested against its behavior without the network or the real `$HOME`.
+//!
+//! Enabled by the `test-util` feature.
+
+use crate::{
+    context::{FenvContext, RealFenvContext},
+    external::{
+        dart_command::DartCommandImpl,
+        downloader::OfflineDownloader,
+        flutter_command::{FlutterCommand, PrecachePlatform},
+        git_command::{GitCommand, GitRemote, GitTlsOptions},
+        proxy::ProxyOptions,
+    },
+    sdk_service::sdk_service::{RealSdkService, SdkService},
+    try_run,
+    util::{chrono_wrapper::SystemClock, path_like::PathLike, retry::RetryPolicy},
+};
+use anyhow::Context;
+use std::{io::Write, time::Duration};
+use tempfile::TempDir;
+
+pub use crate::{
+    external::{dart_command::MockDartCommand, git_command::MockGitCommand},
+    util::io::BufferedOutput,
+};
+
+/// The commit which `FakeGitCommand` reports for every revision.
+pub const FAKE_COMMIT_SHA: &str = "135454af32477f815a7525073027a3ff9eff1bfd";
+
+/// `$HOME`, `$FENV_ROOT`, `$FENV_DIR`, and `$PUB_CACHE` in the temporary directories, which are
+/// removed when this is dropped.
+pub struct TestEnvironment {
+    context: RealFenvContext,
+    _home: TempDir,
+    _fenv_dir: TempDir,
+}
+
+impl TestEnvironment {
+    pub fn new() -> anyhow::Result<Self> {
+        let home = tempfile::tempdir().context("Failed to create a temporary `$HOME`")?;
+        let fenv_dir = tempfile::tempdir().context("Failed to create a temporary `$FENV_DIR`")?;
+        let home_path = PathLike::from(home.path());
+        // A failure of the network fails at once instead of waiting for the retries.
+        let context = RealFenvContext::new(
+            &home_path.join(".fenv").to_string(),
+            &PathLike::from(fenv_dir.path()).to_string(),
+            &home_path.to_string(),
+            "/bin/bash",
+            &home_path.join(".pub-cache").to_string(),
+        )
+        .with_retry_policy(RetryPolicy {
+            attempts: 1,
+            backoff: Duration::ZERO,
+        });
+        anyhow::Ok(Self {
+            context,
+            _home: home,
+            _fenv_dir: fenv_dir,
+        })
+    }
+
+    pub fn context(&self) -> &RealFenvContext {
+        &self.context
+    }
+
+    /// Creates `{fenv_versions}/{name}` with the empty `bin/flutter` and `bin/dart`, which is
+    /// enough for `fenv` to regard it as installed.
+    pub fn install_fake_sdk(&self, name: &str) -> anyhow::Result<PathLike> {
+        let sdk_root = self.context.fenv_sdk_root(name);
+        for executable in ["flutter", "dart"] {
+            let path = sdk_root.join("bin").join(executable);
+            path.writeln("#!/bin/sh")
+                .with_context(|| format!("Failed to write `{path}`"))?;
+        }
+        anyhow::Ok(sdk_root)
+    }
+
+    /// Runs `fenv` with `args`, which do not include `fenv` itself.
+    pub fn run(
+        &self,
+        args: &[&str],
+        sdk_service: &impl SdkService,
+        output: &mut BufferedOutput,
+    ) -> anyhow::Result<()> {
+        let args = std::iter::once("fenv").chain(args.iter().copied());
+        try_run(args, &self.context, sdk_service, output)
+    }
+}
+
+/// Calls `lambda` with the context of a new `TestEnvironment` and an empty `BufferedOutput`.
+pub fn test_with_context<F>(lambda: F)
+where
+    F: FnOnce(&RealFenvContext, &mut BufferedOutput),
+{
+    let environment = TestEnvironment::new().unwrap();
+    let mut output = BufferedOutput::new();
+    lambda(environment.context(), &mut output);
+}
+
+/// A `SdkService` which never accesses the network: see `FakeGitCommand` and
+/// `FakeFlutterCommand`.
+pub fn fake_sdk_service() -> RealSdkService<
+    FakeGitCommand,
+    SystemClock,
+    FakeFlutterCommand,
+    DartCommandImpl,
+    OfflineDownloader,
+> {
+    RealSdkService::from(FakeGitCommand, SystemClock::new(), FakeFlutterCommand)
+}
+
+/// A `GitCommand` which never accesses the network.
+///
+/// Cloning creates an empty directory, the remote repository has the tags and branches of the
+/// Flutter repository as of Flutter 3.7, and every revision is at `FAKE_COMMIT_SHA`.
+pub struct FakeGitCommand;
+
+impl GitCommand for FakeGitCommand {
+    fn clone_flutter_sdk_by_channel(
+        &self,
+        _: &str,
+        destination: &str,
+        _: &GitRemote,
+    ) -> anyhow::Result<()> {
+        std::fs::create_dir(destination)
+            .with_context(|| format!("Failed to create `{destination}`"))
+    }
+
+    fn clone_flutter_sdk_by_version(
+        &self,
+        _: &str,
+        destination: &str,
+        _: &GitRemote,
+    ) -> anyhow::Result<()> {
+        std::fs::create_dir(destination)
+            .with_context(|| format!("Failed to create `{destination}`"))
+    }
+
+    fn clone_flutter_sdk_by_commit(
+        &self,
+        _: &str,
+        destination: &str,
+        _: &GitRemote,
+    ) -> anyhow::Result<()> {
+        std::fs::create_dir(destination)
+            .with_context(|| format!("Failed to create `{destination}`"))
+    }
+
+    fn resume_flutter_sdk_by_channel(&self, _: &str, _: &str, _: &GitRemote) -> anyhow::Result<()> {
+        anyhow::Ok(())
+    }
+
+    fn resume_flutter_sdk_by_version(&self, _: &str, _: &str, _: &GitRemote) -> anyhow::Result<()> {
+        anyhow::Ok(())
+    }
+
+    fn list_remote_sdks_by_tags(&self, _: &GitRemote) -> anyhow::Result<String> {
+        anyhow::Ok(String::from(include_str!(
+            "../resources/test/install_service/git_lf-remote_tags.txt"
+        )))
+    }
+
+    fn list_remote_sdks_by_branches(&self, _: &GitRemote) -> anyhow::Result<String> {
+        anyhow::Ok(String::from(include_str!(
+            "../resources/test/install_service/git_lf-remote_heads.txt"
+        )))
+    }
+
+    fn rev_parse_head(&self, _: &str) -> anyhow::Result<String> {
+        anyhow::Ok(String::from(FAKE_COMMIT_SHA))
+    }
+
+    fn rev_parse_commit(&self, _: &str, _: &str) -> anyhow::Result<String> {
+        anyhow::Ok(String::from(FAKE_COMMIT_SHA))
+    }
+
+    fn pull_ff_only(&self, _: &str, _: &GitTlsOptions, _: &ProxyOptions) -> anyhow::Result<()> {
+        anyhow::Ok(())
+    }
+}
+
+/// A `FlutterCommand` which does nothing but writing `pubspec.yaml` on `create`.
+pub struct FakeFlutterCommand;
+
+impl FlutterCommand for FakeFlutterCommand {
+    fn doctor(&self, _: &str, _: Option<&str>, _: &mut dyn Write) -> anyhow::Result<()> {
+        anyhow::Ok(())
+    }
+
+    fn precache(
+        &self,
+        _: &str,
+        _: &[PrecachePlatform],
+        _: Option<&str>,
+        _: &mut dyn Write,
+    ) -> anyhow::Result<()> {
+        anyhow::Ok(())
+    }
+
+    fn accept_android_licenses(
+        &self,
+        _: &str,
+        _: Option<&str>,
+        _: &mut dyn Write,
+    ) -> anyhow::Result<()> {
+        anyhow::Ok(())
+    }
+
+    fn create(
+        &self,
+        _: &str,
+        project_dir: &str,
+        _: &[String],
+        _: &mut dyn Write,
+    ) -> anyhow::Result<()> {
+        let pubspec_yaml = PathLike::from(project_dir).join("pubspec.yaml");
+        pubspec_yaml
+            .writeln("name: app")
+            .with_context(|| format!("Failed to write `{pubspec_yaml}`"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fake_sdk_service, BufferedOutput, TestEnvironment};
+    use crate::context::FenvContext;
+
+    #[test]
+    fn test_environment_runs_commands_in_isolation() {
+        // setup
+        let environment = TestEnvironment::new().unwrap();
+        environment.install_fake_sdk("3.19.6").unwrap();
+        let sdk_service = fake_sdk_service();
+        let mut output = BufferedOutput::new();
+
+        // execution
+        environment
+            .run(&["install", "3.7.12"], &sdk_service, &mut output)
+            .unwrap();
+        environment
+            .run(&["local", "3.19"], &sdk_service, &mut output)
+            .unwrap();
+        environment
+            .run(&["version-name"], &sdk_service, &mut output)
+            .unwrap();
+
+        // validation
+        let context = environment.context();
+        assert!(context.fenv_sdk_root("3.7.12").is_dir());
+        assert!(context.fenv_shims().join("flutter").is_file());
+        assert!(output.stdout_to_string().ends_with("3.19.6\n"));
+    }
+}
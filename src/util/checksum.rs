@@ -0,0 +1,45 @@
+use crate::util::path_like::PathLike;
+use anyhow::Context;
+use sha2::{Digest, Sha256};
+
+/// The SHA-256 of `file` in lowercase hex.
+///
+/// The content is streamed into the hasher, so that the archive of a Flutter SDK, which is
+/// hundreds of megabytes, is never held in memory.
+pub fn sha256_of_file(file: &PathLike) -> anyhow::Result<String> {
+    let mut reader =
+        std::fs::File::open(file).with_context(|| format!("Failed to open `{file}`"))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut reader, &mut hasher).with_context(|| format!("Failed to read `{file}`"))?;
+    anyhow::Ok(
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sha256_of_file;
+    use crate::{context::FenvContext, service::macros::test_with_context};
+
+    #[test]
+    fn test_sha256_of_file() {
+        test_with_context(|context, _| {
+            // setup
+            let file = context.fenv_root().join("hello.txt");
+            file.writeln("hello").unwrap();
+
+            // execution
+            let actual = sha256_of_file(&file).unwrap();
+
+            // validation
+            assert_eq!(
+                actual,
+                "5891b5b522d5df086d0ff0b110fbd9d21bb4fc7163af34d08286a2e846f6be03"
+            );
+        })
+    }
+}
@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 
-pub trait Clock {
+pub trait Clock: Sync {
     fn utc_now(&self) -> DateTime<Utc>;
 }
 
@@ -1,3 +1,5 @@
+use std::sync::{Arc, Mutex};
+
 pub trait ConsoleOutput<OUT, ERR>
 where
     OUT: std::io::Write,
@@ -5,11 +7,99 @@ where
 {
     fn stdout<'a>(&'a mut self) -> &'a mut OUT;
     fn stderr<'a>(&'a mut self) -> &'a mut ERR;
+    fn options(&self) -> &OutputOptions;
+    fn options_mut(&mut self) -> &mut OutputOptions;
+
+    /// Prints `fenv: warning: {message}` to stderr unless `--quiet` is given.
+    fn warning(&mut self, message: &str) -> std::io::Result<()> {
+        self.diagnostic(DiagnosticLevel::Warning, message)
+    }
+
+    /// Prints `fenv: info: {message}` to stderr unless `--quiet` is given.
+    fn info(&mut self, message: &str) -> std::io::Result<()> {
+        self.diagnostic(DiagnosticLevel::Info, message)
+    }
+
+    /// Prints `fenv: warning: {message}` to stderr even if `--quiet` is given, for the warnings
+    /// which must not be missed such as the insecure settings.
+    fn security_warning(&mut self, message: &str) -> std::io::Result<()> {
+        self.write_diagnostic(DiagnosticLevel::Warning, message)
+    }
+
+    /// Prints a diagnostic message to stderr in the format given by `--error-format`
+    /// unless `--quiet` is given.
+    fn diagnostic(&mut self, level: DiagnosticLevel, message: &str) -> std::io::Result<()> {
+        if self.options().quiet {
+            return Ok(());
+        }
+        self.write_diagnostic(level, message)
+    }
+
+    /// Prints a diagnostic message to stderr in the format given by `--error-format`.
+    fn write_diagnostic(&mut self, level: DiagnosticLevel, message: &str) -> std::io::Result<()> {
+        match self.options().error_format {
+            ErrorFormat::Human => writeln!(self.stderr(), "fenv: {}: {message}", level.name()),
+            ErrorFormat::Json => writeln!(
+                self.stderr(),
+                "{}",
+                serde_json::json!({ "level": level.name(), "message": message })
+            ),
+        }
+    }
+}
+
+/// The format of diagnostic messages, given by `--error-format`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ErrorFormat {
+    /// `fenv: warning: {message}`
+    #[default]
+    Human,
+
+    /// `{"level":"warning","message":"{message}"}`, one JSON object per line.
+    Json,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticLevel {
+    Warning,
+    Info,
+}
+
+impl DiagnosticLevel {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DiagnosticLevel::Warning => "warning",
+            DiagnosticLevel::Info => "info",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct OutputOptions {
+    /// Given by `--quiet`. No diagnostic message is printed.
+    pub quiet: bool,
+    pub error_format: ErrorFormat,
+}
+
+/// An error which makes `fenv` exit with the given non-zero code without printing any message.
+///
+/// Returned when the failure is expected to be handled by the exit code only,
+/// such as `fenv latest --quiet --exit-code` and the failure of the command run by `fenv exec`.
+#[derive(Debug)]
+pub struct SilentFailure(pub i32);
+
+impl std::fmt::Display for SilentFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed silently with exit code {}", self.0)
+    }
+}
+
+impl std::error::Error for SilentFailure {}
+
 pub struct StdOutput {
     stdout: std::io::Stdout,
     stderr: std::io::Stderr,
+    options: OutputOptions,
 }
 
 impl StdOutput {
@@ -17,6 +107,7 @@ impl StdOutput {
         Self {
             stdout: std::io::stdout(),
             stderr: std::io::stderr(),
+            options: OutputOptions::default(),
         }
     }
 }
@@ -29,11 +120,20 @@ impl ConsoleOutput<std::io::Stdout, std::io::Stderr> for StdOutput {
     fn stderr<'a>(&'a mut self) -> &'a mut std::io::Stderr {
         &mut self.stderr
     }
+
+    fn options(&self) -> &OutputOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut OutputOptions {
+        &mut self.options
+    }
 }
 
 pub struct BufferedOutput {
     stdout: Vec<u8>,
     stderr: Vec<u8>,
+    options: OutputOptions,
 }
 
 impl BufferedOutput {
@@ -41,6 +141,14 @@ impl BufferedOutput {
         Self {
             stdout: Vec::new(),
             stderr: Vec::new(),
+            options: OutputOptions::default(),
+        }
+    }
+
+    pub fn with_options(options: OutputOptions) -> Self {
+        Self {
+            options,
+            ..Self::new()
         }
     }
 
@@ -61,11 +169,170 @@ impl ConsoleOutput<Vec<u8>, Vec<u8>> for BufferedOutput {
     fn stderr<'a>(&'a mut self) -> &'a mut Vec<u8> {
         &mut self.stderr
     }
+
+    fn options(&self) -> &OutputOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut OutputOptions {
+        &mut self.options
+    }
+}
+
+/// A [`ConsoleOutput`] shared by the jobs running concurrently, such as the parallel installs.
+///
+/// Each job writes into its own [`JobOutput`], which prefixes every line with the job label such
+/// as `[3.19.6] ` and writes the whole line into the shared stream at once, so that the lines of
+/// the jobs are interleaved but never broken.
+pub struct MultiplexedOutput<OUT, ERR>
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    stdout: Arc<Mutex<OUT>>,
+    stderr: Arc<Mutex<ERR>>,
+    options: OutputOptions,
+}
+
+impl<OUT, ERR> MultiplexedOutput<OUT, ERR>
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    pub fn new(stdout: OUT, stderr: ERR, options: OutputOptions) -> Self {
+        Self {
+            stdout: Arc::new(Mutex::new(stdout)),
+            stderr: Arc::new(Mutex::new(stderr)),
+            options,
+        }
+    }
+
+    /// Creates the output of the job named `label`, which can be sent to another thread.
+    pub fn job(&self, label: &str) -> JobOutput<OUT, ERR> {
+        let prefix = format!("[{label}] ");
+        JobOutput {
+            stdout: PrefixedWriter::new(&prefix, self.stdout.clone()),
+            stderr: PrefixedWriter::new(&prefix, self.stderr.clone()),
+            options: self.options,
+        }
+    }
+
+    /// Returns the underlying streams, or `None` if any [`JobOutput`] is still alive.
+    pub fn into_inner(self) -> Option<(OUT, ERR)> {
+        let stdout = Arc::try_unwrap(self.stdout).ok()?;
+        let stderr = Arc::try_unwrap(self.stderr).ok()?;
+        Some((
+            stdout.into_inner().unwrap_or_else(|e| e.into_inner()),
+            stderr.into_inner().unwrap_or_else(|e| e.into_inner()),
+        ))
+    }
+}
+
+/// The output of a job of [`MultiplexedOutput`].
+pub struct JobOutput<OUT, ERR>
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    stdout: PrefixedWriter<OUT>,
+    stderr: PrefixedWriter<ERR>,
+    options: OutputOptions,
+}
+
+impl<OUT, ERR> ConsoleOutput<PrefixedWriter<OUT>, PrefixedWriter<ERR>> for JobOutput<OUT, ERR>
+where
+    OUT: std::io::Write,
+    ERR: std::io::Write,
+{
+    fn stdout(&mut self) -> &mut PrefixedWriter<OUT> {
+        &mut self.stdout
+    }
+
+    fn stderr(&mut self) -> &mut PrefixedWriter<ERR> {
+        &mut self.stderr
+    }
+
+    fn options(&self) -> &OutputOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut OutputOptions {
+        &mut self.options
+    }
+}
+
+/// A writer which buffers a line, and writes it with `prefix` into the shared `inner` at once
+/// when the line ends.
+///
+/// A line is rewritten in place by a carriage return as the progress spinner does, so only the
+/// text after the last one is written, without the erase-line sequence.
+/// The incomplete line is kept on flush, and written when dropped.
+pub struct PrefixedWriter<W: std::io::Write> {
+    prefix: String,
+    inner: Arc<Mutex<W>>,
+    line: Vec<u8>,
+}
+
+impl<W: std::io::Write> PrefixedWriter<W> {
+    pub fn new(prefix: &str, inner: Arc<Mutex<W>>) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            inner,
+            line: Vec::new(),
+        }
+    }
+
+    fn write_line(&mut self) -> std::io::Result<()> {
+        const ERASE_LINE: &[u8] = b"\x1b[2K";
+        let mut line = match self.line.iter().rposition(|&byte| byte == b'\r') {
+            Some(position) => &self.line[position + 1..],
+            None => &self.line[..],
+        };
+        if let Some(rest) = line.strip_prefix(ERASE_LINE) {
+            line = rest;
+        }
+        let mut bytes = Vec::with_capacity(self.prefix.len() + line.len() + 1);
+        bytes.extend_from_slice(self.prefix.as_bytes());
+        bytes.extend_from_slice(line);
+        bytes.push(b'\n');
+        self.line.clear();
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.write_all(&bytes)?;
+        inner.flush()
+    }
+}
+
+impl<W: std::io::Write> std::io::Write for PrefixedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        for &byte in buf {
+            if byte == b'\n' {
+                self.write_line()?;
+            } else {
+                self.line.push(byte);
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        // The incomplete line is kept so that the rest of it follows the prefix.
+        self.inner.lock().unwrap_or_else(|e| e.into_inner()).flush()
+    }
+}
+
+impl<W: std::io::Write> Drop for PrefixedWriter<W> {
+    fn drop(&mut self) {
+        if !self.line.is_empty() {
+            let _ = self.write_line();
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::util::io::ConsoleOutput;
+    use crate::util::io::{
+        BufferedOutput, ConsoleOutput, ErrorFormat, MultiplexedOutput, OutputOptions,
+    };
     use std::io::Write;
 
     #[test]
@@ -74,4 +341,105 @@ mod tests {
         writeln!(output.stdout(), "Hello world!").unwrap();
         writeln!(output.stderr(), "Hello world!").unwrap();
     }
+
+    #[test]
+    fn test_print_warning_in_human_format() {
+        let mut output = BufferedOutput::new();
+        output.warning("something went wrong").unwrap();
+        output.info("something happened").unwrap();
+        assert_eq!(
+            output.stderr_to_string(),
+            "fenv: warning: something went wrong\nfenv: info: something happened\n"
+        );
+        assert!(output.stdout_to_string().is_empty());
+    }
+
+    #[test]
+    fn test_print_warning_in_json_format() {
+        let mut output = BufferedOutput::with_options(OutputOptions {
+            error_format: ErrorFormat::Json,
+            ..OutputOptions::default()
+        });
+        output.warning("`3.7` is \"missing\"").unwrap();
+        assert_eq!(
+            output.stderr_to_string(),
+            "{\"level\":\"warning\",\"message\":\"`3.7` is \\\"missing\\\"\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_quiet_suppresses_warnings() {
+        let mut output = BufferedOutput::with_options(OutputOptions {
+            quiet: true,
+            ..OutputOptions::default()
+        });
+        output.warning("something went wrong").unwrap();
+        output.info("something happened").unwrap();
+        assert!(output.stderr_to_string().is_empty());
+    }
+
+    #[test]
+    fn test_quiet_does_not_suppress_security_warnings() {
+        let mut output = BufferedOutput::with_options(OutputOptions {
+            quiet: true,
+            ..OutputOptions::default()
+        });
+        output.security_warning("something is insecure").unwrap();
+        assert_eq!(
+            output.stderr_to_string(),
+            "fenv: warning: something is insecure\n"
+        );
+    }
+
+    #[test]
+    fn test_multiplexed_output_prefixes_whole_lines() {
+        let output = MultiplexedOutput::new(vec![], vec![], OutputOptions::default());
+        let jobs: Vec<_> = ["3.19.6", "3.22.2"]
+            .into_iter()
+            .map(|label| {
+                let mut job = output.job(label);
+                std::thread::spawn(move || {
+                    for i in 0..100 {
+                        write!(job.stdout(), "line ").unwrap();
+                        writeln!(job.stdout(), "{i}").unwrap();
+                    }
+                    job.warning("done").unwrap();
+                })
+            })
+            .collect();
+        for job in jobs {
+            job.join().unwrap();
+        }
+
+        let (stdout, stderr) = output.into_inner().unwrap();
+        let stdout = String::from_utf8(stdout).unwrap();
+        for label in ["3.19.6", "3.22.2"] {
+            let lines: Vec<&str> = stdout
+                .lines()
+                .filter_map(|line| line.strip_prefix(&format!("[{label}] ")))
+                .collect();
+            let expected: Vec<String> = (0..100).map(|i| format!("line {i}")).collect();
+            assert_eq!(lines, expected);
+        }
+        assert_eq!(stdout.lines().count(), 200);
+        let stderr = String::from_utf8(stderr).unwrap();
+        assert!(stderr.contains("[3.19.6] fenv: warning: done\n"));
+        assert!(stderr.contains("[3.22.2] fenv: warning: done\n"));
+    }
+
+    #[test]
+    fn test_prefixed_writer_keeps_last_state_of_rewritten_line() {
+        let output = MultiplexedOutput::new(vec![], vec![], OutputOptions::default());
+        let mut job = output.job("stable");
+        write!(job.stderr(), "\r\x1b[2K| flutter precache (0s)").unwrap();
+        job.stderr().flush().unwrap();
+        write!(job.stderr(), "\r\x1b[2KDownloading\nincomplete").unwrap();
+        drop(job);
+
+        let (_, stderr) = output.into_inner().unwrap();
+        assert_eq!(
+            String::from_utf8(stderr).unwrap(),
+            "[stable] Downloading\n[stable] incomplete\n"
+        );
+    }
 }
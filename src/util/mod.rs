@@ -1,3 +1,9 @@
+pub mod checksum;
 pub mod chrono_wrapper;
 pub mod io;
+pub mod network_fs;
+pub mod offline;
 pub mod path_like;
+pub mod platform;
+pub mod retry;
+pub mod size;
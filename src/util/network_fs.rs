@@ -0,0 +1,371 @@
+//! Works around the network file systems such as NFS and SMB, where `$FENV_ROOT` is placed by
+//! the teams sharing their home directories.
+//!
+//! There, renaming a directory may fail while the files in it are open or be denied by the
+//! server, and `flock` may be ignored or be local to each client.
+
+use super::path_like::PathLike;
+use anyhow::Context;
+use log::debug;
+use std::{
+    fs::File,
+    io::Write,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// How long a lease is valid without being renewed. An older lease is regarded as left by a
+/// crashed `fenv` and is taken over, which is long enough to tolerate the clock skew between the
+/// clients sharing the file system.
+const LEASE_DURATION: Duration = Duration::from_secs(120);
+
+/// How often the holder of a lease renews it.
+const LEASE_RENEWAL_INTERVAL: Duration = Duration::from_secs(20);
+
+/// How often a lock held by another process is retried.
+const RETRY_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How often the renewal thread checks whether the lease is released.
+const TICK: Duration = Duration::from_millis(100);
+
+/// Whether `path`, or its nearest existing ancestor, is on a network file system.
+pub fn is_network_filesystem(path: &PathLike) -> bool {
+    match path.path().ancestors().find(|ancestor| ancestor.exists()) {
+        Some(existing) => is_network_filesystem_at(existing),
+        None => false,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn is_network_filesystem_at(path: &Path) -> bool {
+    // The magic numbers of NFS, SMB, CIFS, and SMB2 listed in `man 2 statfs`.
+    const NETWORK_FILESYSTEMS: [u32; 4] = [0x6969, 0x517b, 0xff53_4d42, 0xfe53_4d42];
+    match nix::sys::statfs::statfs(path) {
+        Ok(stat) => NETWORK_FILESYSTEMS.contains(&(stat.filesystem_type().0 as u32)),
+        Err(e) => {
+            debug!("is_network_filesystem(): Failed to get the file system of {path:?}: {e}");
+            false
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn is_network_filesystem_at(path: &Path) -> bool {
+    const NETWORK_FILESYSTEMS: [&str; 4] = ["nfs", "smbfs", "afpfs", "webdav"];
+    match nix::sys::statfs::statfs(path) {
+        Ok(stat) => NETWORK_FILESYSTEMS.contains(&stat.filesystem_type_name()),
+        Err(e) => {
+            debug!("is_network_filesystem(): Failed to get the file system of {path:?}: {e}");
+            false
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn is_network_filesystem_at(_: &Path) -> bool {
+    false
+}
+
+/// Moves the directory `source` to `destination`, which must not exist.
+///
+/// If renaming fails on a network file system, `source` is copied file by file instead, each
+/// file being synced to the server before `source` is removed, which may take long for a Flutter
+/// SDK.
+pub fn move_dir(source: &PathLike, destination: &PathLike) -> anyhow::Result<()> {
+    let error = match std::fs::rename(source, destination) {
+        Ok(_) => return anyhow::Ok(()),
+        Err(e) => e,
+    };
+    if !is_network_filesystem(destination) {
+        return Err(error).with_context(|| format!("Failed to move `{source}` to `{destination}`"));
+    }
+    debug!("move_dir(): Failed to rename `{source}` to `{destination}`, so copies it: {error}");
+    copy_dir_synced(source.path(), destination.path())
+        .with_context(|| format!("Failed to copy `{source}` to `{destination}`"))?;
+    source
+        .remove_dir_all()
+        .with_context(|| format!("Failed to remove `{source}`"))
+}
+
+fn copy_dir_synced(source: &Path, destination: &Path) -> std::io::Result<()> {
+    std::fs::create_dir(destination)?;
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        let target = destination.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_dir_synced(&entry.path(), &target)?;
+        } else if file_type.is_symlink() {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(std::fs::read_link(entry.path())?, &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+            File::open(&target)?.sync_all()?;
+        }
+    }
+    File::open(destination)?.sync_all()
+}
+
+/// An exclusive lock of a file among the processes, which is released when dropped.
+///
+/// `flock` is used on a local file system. On a network file system, the file is created
+/// exclusively as a lease instead, which the holder renews in background and the others take
+/// over once it expires.
+pub enum FileLock {
+    #[cfg(unix)]
+    Flock(nix::fcntl::Flock<File>),
+    Lease(Lease),
+}
+
+impl FileLock {
+    /// Waits until no other process locks `path`, and locks it.
+    pub fn acquire(path: &PathLike) -> anyhow::Result<Self> {
+        if let Some(parent) = path.parent() {
+            parent
+                .create_dir_all()
+                .with_context(|| format!("Failed to create `{parent}`"))?;
+        }
+        if is_network_filesystem(path) {
+            return Lease::acquire(path).map(Self::Lease);
+        }
+        #[cfg(unix)]
+        {
+            use nix::fcntl::{Flock, FlockArg};
+            let file = File::options()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(path)
+                .with_context(|| format!("Failed to open `{path}`"))?;
+            Flock::lock(file, FlockArg::LockExclusive)
+                .map(Self::Flock)
+                .map_err(|(_, errno)| anyhow::anyhow!("Failed to lock `{path}`: {errno}"))
+        }
+        #[cfg(not(unix))]
+        Lease::acquire(path).map(Self::Lease)
+    }
+}
+
+/// A lock file created exclusively, whose modified time is renewed while it is held.
+///
+/// The file holds the token of its holder, so that neither the takeover nor the release removes
+/// the lease of another holder.
+pub struct Lease {
+    path: PathLike,
+    token: String,
+    released: Arc<AtomicBool>,
+    renewal: Option<JoinHandle<()>>,
+}
+
+impl Lease {
+    fn acquire(path: &PathLike) -> anyhow::Result<Self> {
+        let token = new_token();
+        loop {
+            match File::options().write(true).create_new(true).open(path) {
+                Ok(mut file) => {
+                    writeln!(file, "{token}")
+                        .and_then(|_| file.sync_all())
+                        .with_context(|| format!("Failed to write `{path}`"))?;
+                    break;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let stale = path.read_to_string().unwrap_or_default();
+                    if is_expired(path) {
+                        // The other processes may take it over at once, and one of them may
+                        // have created a new lease already, which must not be removed.
+                        debug!("Lease::acquire(): Taking over the expired `{path}`");
+                        remove_if(path, &token, |file| {
+                            holds_token(file, stale.trim()) && is_expired(file)
+                        });
+                        continue;
+                    }
+                    std::thread::sleep(RETRY_INTERVAL);
+                }
+                Err(e) => return Err(e).with_context(|| format!("Failed to create `{path}`")),
+            }
+        }
+        let released = Arc::new(AtomicBool::new(false));
+        let renewal = {
+            let path = path.clone();
+            let released = released.clone();
+            std::thread::spawn(move || renew_until_released(&path, &released))
+        };
+        anyhow::Ok(Self {
+            path: path.clone(),
+            token,
+            released,
+            renewal: Some(renewal),
+        })
+    }
+}
+
+impl Drop for Lease {
+    fn drop(&mut self) {
+        self.released.store(true, Ordering::SeqCst);
+        if let Some(renewal) = self.renewal.take() {
+            let _ = renewal.join();
+        }
+        // The lease may have been taken over if the renewal was stalled.
+        let token = &self.token;
+        if !remove_if(&self.path, token, |file| holds_token(file, token)) {
+            debug!("Lease::drop(): `{}` is not held anymore", self.path);
+        }
+    }
+}
+
+/// A token unique to the holder of a lease, which is the process ID and a number unique in the
+/// process because its threads may hold the leases too.
+fn new_token() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos());
+    let count = COUNTER.fetch_add(1, Ordering::SeqCst);
+    format!("{} {nanos}-{count}", std::process::id())
+}
+
+fn holds_token(path: &PathLike, token: &str) -> bool {
+    path.read_to_string()
+        .is_ok_and(|content| content.trim() == token)
+}
+
+/// Removes the lease at `path` if `is_removable` holds for it, and returns whether it is removed.
+///
+/// The file is renamed aside to a name unique to `token` before `is_removable` is checked, which
+/// only one process can do. If it turns out to be a lease created by another process in the
+/// meantime, it is linked back unless yet another lease is created there.
+fn remove_if(path: &PathLike, token: &str, is_removable: impl Fn(&PathLike) -> bool) -> bool {
+    let aside = PathLike::from(format!("{path}.{}", token.replace(' ', "-")).as_str());
+    if std::fs::rename(path, &aside).is_err() {
+        return false;
+    }
+    let is_removed = is_removable(&aside);
+    if !is_removed {
+        if let Err(e) = std::fs::hard_link(&aside, path) {
+            debug!("remove_if(): Failed to restore `{path}`: {e}");
+        }
+    }
+    if let Err(e) = aside.remove_file() {
+        debug!("remove_if(): Failed to remove `{aside}`: {e}");
+    }
+    is_removed
+}
+
+fn is_expired(path: &PathLike) -> bool {
+    let Ok(modified) = path
+        .path()
+        .metadata()
+        .and_then(|metadata| metadata.modified())
+    else {
+        return false;
+    };
+    SystemTime::now()
+        .duration_since(modified)
+        .is_ok_and(|elapsed| elapsed > LEASE_DURATION)
+}
+
+fn renew_until_released(path: &PathLike, released: &AtomicBool) {
+    let mut renewed_at = Instant::now();
+    while !released.load(Ordering::SeqCst) {
+        std::thread::sleep(TICK);
+        if renewed_at.elapsed() < LEASE_RENEWAL_INTERVAL {
+            continue;
+        }
+        let result = File::options()
+            .write(true)
+            .open(path)
+            .and_then(|file| file.set_modified(SystemTime::now()));
+        if let Err(e) = result {
+            debug!("renew_until_released(): Failed to renew `{path}`: {e}");
+        }
+        renewed_at = Instant::now();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{move_dir, FileLock, Lease};
+    use crate::util::path_like::PathLike;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::{Duration, SystemTime},
+    };
+
+    #[test]
+    fn test_move_dir() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = PathLike::from(temp_dir.path());
+        let source = root.join("staging");
+        source
+            .join("bin")
+            .join("flutter")
+            .writeln("#!/bin/sh")
+            .unwrap();
+
+        move_dir(&source, &root.join("3.22.2")).unwrap();
+
+        assert!(!source.exists());
+        assert!(root.join("3.22.2").join("bin").join("flutter").is_file());
+    }
+
+    #[test]
+    fn test_file_lock_excludes_other_holders() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = PathLike::from(temp_dir.path())
+            .join("versions")
+            .join(".lock_3.22.2");
+        let running = AtomicUsize::new(0);
+        let max_running = AtomicUsize::new(0);
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    let _lock = FileLock::acquire(&path).unwrap();
+                    let current = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_running.fetch_max(current, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(20));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+        assert_eq!(max_running.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_lease_takes_over_expired_one() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = PathLike::from(temp_dir.path()).join(".lock_3.22.2");
+        path.writeln("12345").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(600))
+            .unwrap();
+
+        let lease = Lease::acquire(&path).unwrap();
+        assert_eq!(path.read_to_string().unwrap(), format!("{}\n", lease.token));
+        assert!(lease.token.starts_with(&format!("{} ", std::process::id())));
+
+        drop(lease);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_lease_keeps_one_taken_over_by_another_holder() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = PathLike::from(temp_dir.path()).join(".lock_3.22.2");
+        let lease = Lease::acquire(&path).unwrap();
+
+        // Another holder has taken it over while the renewal was stalled.
+        path.writeln("12345 0-0").unwrap();
+        drop(lease);
+
+        assert_eq!(path.read_to_string().unwrap(), "12345 0-0\n");
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+}
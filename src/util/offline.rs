@@ -0,0 +1,11 @@
+use crate::context::FenvContext;
+use anyhow::bail;
+
+/// Fails if `--offline` is given, because `action` such as `Upgrading `stable`` accesses the
+/// network.
+pub fn ensure_online(context: &impl FenvContext, action: &str) -> anyhow::Result<()> {
+    if context.is_offline() {
+        bail!("{action} requires the network, which `--offline` forbids")
+    }
+    anyhow::Ok(())
+}
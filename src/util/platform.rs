@@ -0,0 +1,39 @@
+/// The operating system where `fenv` runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperatingSystem {
+    Linux,
+    MacOs,
+    Windows,
+    Unknown,
+}
+
+impl OperatingSystem {
+    /// The operating system which the current executable is built for.
+    pub fn current() -> Self {
+        match std::env::consts::OS {
+            "linux" => Self::Linux,
+            "macos" => Self::MacOs,
+            "windows" => Self::Windows,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// The CPU architecture where `fenv` runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Architecture {
+    X86_64,
+    Aarch64,
+    Unknown,
+}
+
+impl Architecture {
+    /// The CPU architecture which the current executable is built for.
+    pub fn current() -> Self {
+        match std::env::consts::ARCH {
+            "x86_64" => Self::X86_64,
+            "aarch64" => Self::Aarch64,
+            _ => Self::Unknown,
+        }
+    }
+}
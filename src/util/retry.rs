@@ -0,0 +1,140 @@
+use crate::{
+    config::FenvConfig,
+    context::FenvContext,
+    sdk_service::results::{InstallFailure, InstallFailureReason},
+};
+use log::info;
+use std::time::Duration;
+
+/// How many times a network operation is attempted, and how long to wait between the attempts,
+/// so that a transient DNS or TLS failure does not abort `fenv install` halfway.
+///
+/// The wait is doubled after every failed attempt. Only the failures classified as
+/// [`InstallFailureReason::Network`] are retried, because the others such as a mismatched
+/// checksum fail again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The number of the attempts including the first one, which is at least 1.
+    pub attempts: u32,
+
+    /// The wait before the first retry.
+    pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Resolves the policy from `retry` of `{fenv_root}/config.json`, unless the context
+    /// overrides it.
+    pub fn resolve(context: &impl FenvContext) -> anyhow::Result<Self> {
+        if let Some(policy) = context.retry_policy() {
+            return anyhow::Ok(policy);
+        }
+        let config = FenvConfig::load(context)?.retry;
+        let default = Self::default();
+        anyhow::Ok(Self {
+            attempts: config.attempts.unwrap_or(default.attempts).max(1),
+            backoff: config
+                .backoff_millis
+                .map(Duration::from_millis)
+                .unwrap_or(default.backoff),
+        })
+    }
+
+    /// Runs `operation` until it succeeds, fails by other than the network, or the attempts run
+    /// out, and returns the last result. Every retry is logged with `--info`.
+    pub fn run<T>(
+        &self,
+        name: &str,
+        mut operation: impl FnMut() -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        let mut backoff = self.backoff;
+        let mut attempt = 1;
+        loop {
+            match operation() {
+                Ok(value) => return Ok(value),
+                Err(e)
+                    if attempt < self.attempts
+                        && InstallFailure::reason_of(&e) == Some(InstallFailureReason::Network) =>
+                {
+                    info!(
+                        "{name}(): Retrying ({attempt}/{}) in {backoff:?} after the failure: {e}",
+                        self.attempts - 1
+                    );
+                    std::thread::sleep(backoff);
+                    backoff = backoff.saturating_mul(2);
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RetryPolicy;
+    use crate::sdk_service::results::InstallFailureReason;
+    use std::time::Duration;
+
+    #[test]
+    fn test_run_retries_until_success() {
+        let policy = RetryPolicy {
+            attempts: 3,
+            backoff: Duration::ZERO,
+        };
+        let mut calls = 0;
+
+        let result = policy.run("test", || {
+            calls += 1;
+            match calls {
+                1 | 2 => Err(InstallFailureReason::Network
+                    .wrap(anyhow::anyhow!("Temporary failure in name resolution"))),
+                _ => anyhow::Ok(calls),
+            }
+        });
+
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[test]
+    fn test_run_returns_last_failure() {
+        let policy = RetryPolicy {
+            attempts: 2,
+            backoff: Duration::ZERO,
+        };
+        let mut calls = 0;
+
+        let result: anyhow::Result<()> = policy.run("test", || {
+            calls += 1;
+            Err(InstallFailureReason::Network.wrap(anyhow::anyhow!("failure {calls}")))
+        });
+
+        assert_eq!(result.unwrap_err().to_string(), "failure 2");
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn test_run_does_not_retry_other_than_network() {
+        let policy = RetryPolicy {
+            attempts: 3,
+            backoff: Duration::ZERO,
+        };
+        let mut calls = 0;
+
+        let result: anyhow::Result<()> = policy.run("test", || {
+            calls += 1;
+            Err(InstallFailureReason::Checksum.wrap(anyhow::anyhow!("checksum mismatch")))
+        });
+
+        assert_eq!(result.unwrap_err().to_string(), "checksum mismatch");
+        assert_eq!(calls, 1);
+    }
+}
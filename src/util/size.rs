@@ -0,0 +1,45 @@
+use super::path_like::PathLike;
+
+/// The total size of the files under `path`, not following the symbolic links.
+pub fn disk_usage(path: &PathLike) -> u64 {
+    let metadata = match path.path().symlink_metadata() {
+        Ok(metadata) => metadata,
+        Err(_) => return 0,
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    match path.read_dir() {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| disk_usage(&PathLike::from(entry.path().as_path())))
+            .sum(),
+        Err(_) => 0,
+    }
+}
+
+/// Formats `bytes` in the binary units such as `1.5 KiB`.
+pub fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+/// Formats `duration` in the largest unit such as `3m` and `2d`.
+pub fn human_duration(duration: std::time::Duration) -> String {
+    let seconds = duration.as_secs();
+    match seconds {
+        0..=59 => format!("{seconds}s"),
+        60..=3599 => format!("{}m", seconds / 60),
+        3600..=86399 => format!("{}h", seconds / 3600),
+        _ => format!("{}d", seconds / 86400),
+    }
+}
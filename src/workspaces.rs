@@ -0,0 +1,85 @@
+use crate::{context::FenvContext, util::path_like::PathLike};
+use anyhow::Context;
+use std::collections::BTreeSet;
+
+/// The file where the workspaces set up by `fenv workspace` are recorded, one absolute path
+/// per line.
+///
+/// `{fenv_root}/workspaces`.
+fn workspaces_file(context: &impl FenvContext) -> PathLike {
+    context.fenv_root().join("workspaces")
+}
+
+/// Records the given `workspace` so that `fenv prune` can find the Flutter SDKs it refers to.
+pub fn record_workspace(context: &impl FenvContext, workspace: &PathLike) -> anyhow::Result<()> {
+    let workspace = std::fs::canonicalize(workspace)
+        .with_context(|| format!("Failed to resolve `{workspace}`"))?;
+    let workspace = PathLike::from(&workspace).to_string();
+    let mut workspaces: BTreeSet<String> = read_lines(context).into_iter().collect();
+    if !workspaces.insert(workspace) {
+        return anyhow::Ok(());
+    }
+    let file = workspaces_file(context);
+    file.write(
+        workspaces
+            .iter()
+            .map(|workspace| format!("{workspace}\n"))
+            .collect::<String>(),
+    )
+    .with_context(|| format!("Failed to record the workspace: `{file}`"))
+}
+
+/// Lists the workspaces which have been recorded by [`record_workspace`] and still exist.
+pub fn recorded_workspaces(context: &impl FenvContext) -> Vec<PathLike> {
+    read_lines(context)
+        .iter()
+        .map(|line| PathLike::from(line.as_str()))
+        .filter(|workspace| workspace.is_dir())
+        .collect()
+}
+
+fn read_lines(context: &impl FenvContext) -> Vec<String> {
+    workspaces_file(context)
+        .read_to_string()
+        .map(|content| {
+            content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| line.to_owned())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{record_workspace, recorded_workspaces};
+    use crate::{context::FenvContext, service::macros::test_with_context};
+
+    #[test]
+    fn test_record_workspace_without_duplication() {
+        test_with_context(|context, _| {
+            // setup
+            let app = context.fenv_dir().join("app");
+            app.create_dir_all().unwrap();
+            let removed = context.fenv_dir().join("removed");
+            removed.create_dir_all().unwrap();
+
+            // execution
+            record_workspace(context, &app).unwrap();
+            record_workspace(context, &app).unwrap();
+            record_workspace(context, &removed).unwrap();
+            removed.remove_dir_all().unwrap();
+
+            // validation
+            let workspaces: Vec<String> = recorded_workspaces(context)
+                .iter()
+                .map(|workspace| workspace.to_string())
+                .collect();
+            assert_eq!(
+                workspaces,
+                [std::fs::canonicalize(&app).unwrap().to_str().unwrap()]
+            );
+        })
+    }
+}